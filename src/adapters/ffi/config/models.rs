@@ -0,0 +1,330 @@
+use crate::adapters::ffi::file_cache::models::FfiCacheChannelOptions;
+use crate::domain::models::http_models::HttpRetryPolicy;
+use crate::service::config::{
+    CircuitBreakerConfig, CookieBackend, CookieConfig, CookiePolicy, CookiePolicyRule,
+    DomainOverride, FileCacheConfig, HttpAuditConfig, HttpConfig, IpPreference, RateLimitConfig,
+    RuntimeConfig,
+};
+use std::time::Duration;
+
+// the part of RuntimeConfig that can be built from dart, providers and other trait objects
+// stay unset
+#[derive(Clone)]
+pub struct FfiRuntimeConfig {
+    // worker threads of the tokio runtime created by initialize_ffi, one per core when None
+    pub worker_threads: Option<usize>,
+    pub http: Option<FfiHttpConfig>,
+    pub cookie: Option<FfiCookieConfig>,
+    pub file_cache: Option<FfiFileCacheConfig>,
+    pub http_audit: Option<FfiHttpAuditConfig>,
+    pub strict_init: bool,
+}
+
+#[derive(Clone)]
+pub struct FfiHttpAuditConfig {
+    pub channel: String,
+    pub records_per_segment: usize,
+    pub max_segments: usize,
+}
+
+#[derive(Clone)]
+pub struct FfiHttpConfig {
+    pub connect_timeout_millis: u64,
+    pub request_timeout_millis: u64,
+    pub pool_idle_timeout_millis: u64,
+    pub pool_max_idle_per_host: usize,
+    pub max_connections_per_host: usize,
+    pub tcp_keepalive_millis: Option<u64>,
+    pub tcp_nodelay: bool,
+    pub http2_keep_alive_interval_millis: Option<u64>,
+    pub all_proxy: Option<String>,
+    pub host_proxy: Option<Vec<(String, String)>>,
+    pub tls_danger_accept_invalid_hostnames: bool,
+    pub tls_danger_accept_invalid_certs: bool,
+    pub circuit_breaker: Option<FfiCircuitBreakerConfig>,
+    pub rate_limit: Option<FfiRateLimitConfig>,
+    pub ip_preference: FfiIpPreference,
+    pub per_domain: Vec<FfiDomainOverride>,
+}
+
+#[derive(Clone)]
+pub struct FfiHttpRetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff_millis: u64,
+    pub max_backoff_millis: u64,
+    pub retry_non_idempotent: bool,
+}
+
+#[derive(Clone)]
+pub struct FfiDomainOverride {
+    pub domain: String,
+    pub connect_timeout_millis: Option<u64>,
+    pub request_timeout_millis: Option<u64>,
+    pub retry_policy: Option<FfiHttpRetryPolicy>,
+    pub proxy: Option<String>,
+    pub default_headers: Vec<(String, String)>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Clone)]
+pub enum FfiIpPreference {
+    Auto,
+    V4Only,
+    V6Only,
+    PreferV4,
+}
+
+#[derive(Clone)]
+pub struct FfiCircuitBreakerConfig {
+    pub window_millis: u64,
+    pub minimum_requests: usize,
+    pub failure_rate_threshold: f32,
+    pub open_duration_millis: u64,
+    pub half_open_max_probes: usize,
+}
+
+#[derive(Clone)]
+pub struct FfiRateLimitConfig {
+    pub max_requests: usize,
+    pub period_millis: u64,
+}
+
+#[derive(Clone)]
+pub enum FfiCookieBackend {
+    File,
+    Memory,
+    Sqlite,
+}
+
+#[derive(Clone)]
+pub enum FfiCookiePolicyRule {
+    AcceptAll,
+    RejectAll,
+    FirstPartyOnly { first_party_domains: Vec<String> },
+}
+
+#[derive(Clone)]
+pub struct FfiCookiePolicy {
+    pub domain_pattern: String,
+    pub rule: FfiCookiePolicyRule,
+}
+
+#[derive(Clone)]
+pub struct FfiCookieConfig {
+    pub backend: FfiCookieBackend,
+    pub cookie_path: Option<String>,
+    pub debounce_delay_millis: u64,
+    pub auto_save_interval_millis: Option<u64>,
+    pub public_suffix_list_path: Option<String>,
+    pub max_cookies: Option<usize>,
+    pub max_cookies_per_domain: Option<usize>,
+    pub policies: Vec<FfiCookiePolicy>,
+    pub max_cookie_lifetime_millis: Option<u64>,
+    pub session_only: bool,
+}
+
+#[derive(Clone)]
+pub struct FfiFileCacheChannelConfig {
+    pub name: String,
+    pub extension: Option<String>,
+    pub options: FfiCacheChannelOptions,
+}
+
+#[derive(Clone)]
+pub struct FfiFileCacheConfig {
+    pub base_path: String,
+    pub auto_save_interval_millis: u64,
+    pub channels: Option<Vec<FfiFileCacheChannelConfig>>,
+    pub auto_create_channels: bool,
+}
+
+impl From<FfiHttpAuditConfig> for HttpAuditConfig {
+    fn from(value: FfiHttpAuditConfig) -> Self {
+        Self {
+            channel: value.channel,
+            records_per_segment: value.records_per_segment,
+            max_segments: value.max_segments,
+        }
+    }
+}
+
+impl From<FfiCircuitBreakerConfig> for CircuitBreakerConfig {
+    fn from(value: FfiCircuitBreakerConfig) -> Self {
+        Self {
+            window: Duration::from_millis(value.window_millis),
+            minimum_requests: value.minimum_requests,
+            failure_rate_threshold: value.failure_rate_threshold,
+            open_duration: Duration::from_millis(value.open_duration_millis),
+            half_open_max_probes: value.half_open_max_probes,
+        }
+    }
+}
+
+impl From<FfiRateLimitConfig> for RateLimitConfig {
+    fn from(value: FfiRateLimitConfig) -> Self {
+        Self {
+            max_requests: value.max_requests,
+            period: Duration::from_millis(value.period_millis),
+        }
+    }
+}
+
+impl From<FfiIpPreference> for IpPreference {
+    fn from(value: FfiIpPreference) -> Self {
+        match value {
+            FfiIpPreference::Auto => IpPreference::Auto,
+            FfiIpPreference::V4Only => IpPreference::V4Only,
+            FfiIpPreference::V6Only => IpPreference::V6Only,
+            FfiIpPreference::PreferV4 => IpPreference::PreferV4,
+        }
+    }
+}
+
+impl From<FfiHttpRetryPolicy> for HttpRetryPolicy {
+    fn from(value: FfiHttpRetryPolicy) -> Self {
+        Self {
+            max_attempts: value.max_attempts,
+            initial_backoff: Duration::from_millis(value.initial_backoff_millis),
+            max_backoff: Duration::from_millis(value.max_backoff_millis),
+            retry_non_idempotent: value.retry_non_idempotent,
+        }
+    }
+}
+
+impl From<FfiDomainOverride> for DomainOverride {
+    fn from(value: FfiDomainOverride) -> Self {
+        Self {
+            domain: value.domain,
+            connect_timeout: value.connect_timeout_millis.map(Duration::from_millis),
+            request_timeout: value.request_timeout_millis.map(Duration::from_millis),
+            retry_policy: value.retry_policy.map(HttpRetryPolicy::from),
+            proxy: value.proxy,
+            default_headers: value.default_headers,
+            user_agent: value.user_agent,
+        }
+    }
+}
+
+impl From<FfiHttpConfig> for HttpConfig {
+    fn from(value: FfiHttpConfig) -> Self {
+        Self {
+            connect_timeout: Duration::from_millis(value.connect_timeout_millis),
+            request_timeout: Duration::from_millis(value.request_timeout_millis),
+            pool_idle_timeout: Duration::from_millis(value.pool_idle_timeout_millis),
+            pool_max_idle_per_host: value.pool_max_idle_per_host,
+            max_connections_per_host: value.max_connections_per_host,
+            tcp_keepalive: value.tcp_keepalive_millis.map(Duration::from_millis),
+            tcp_nodelay: value.tcp_nodelay,
+            http2_keep_alive_interval: value
+                .http2_keep_alive_interval_millis
+                .map(Duration::from_millis),
+            // the runtime hands its own cookie store to the client
+            cookie_config: None,
+            encryption_provider: None,
+            decryption_provider: None,
+            all_proxy: value.all_proxy,
+            host_proxy: value.host_proxy,
+            tls_danger_accept_invalid_hostnames: value.tls_danger_accept_invalid_hostnames,
+            tls_danger_accept_invalid_certs: value.tls_danger_accept_invalid_certs,
+            response_validators: None,
+            user_agent_provider: None,
+            circuit_breaker: value.circuit_breaker.map(CircuitBreakerConfig::from),
+            rate_limit: value.rate_limit.map(RateLimitConfig::from),
+            logger: None,
+            ip_preference: value.ip_preference.into(),
+            per_domain: value
+                .per_domain
+                .into_iter()
+                .map(DomainOverride::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<FfiCookiePolicy> for CookiePolicy {
+    fn from(value: FfiCookiePolicy) -> Self {
+        let rule = match value.rule {
+            FfiCookiePolicyRule::AcceptAll => CookiePolicyRule::AcceptAll,
+            FfiCookiePolicyRule::RejectAll => CookiePolicyRule::RejectAll,
+            FfiCookiePolicyRule::FirstPartyOnly {
+                first_party_domains,
+            } => CookiePolicyRule::FirstPartyOnly {
+                first_party_domains,
+            },
+        };
+        Self {
+            domain_pattern: value.domain_pattern,
+            rule,
+        }
+    }
+}
+
+impl FfiCookieConfig {
+    // fails for the sqlite backend when the feature is off
+    pub fn into_config(self) -> Result<CookieConfig, String> {
+        let backend = match self.backend {
+            FfiCookieBackend::File => CookieBackend::File,
+            FfiCookieBackend::Memory => CookieBackend::Memory,
+            #[cfg(feature = "sqlite")]
+            FfiCookieBackend::Sqlite => CookieBackend::Sqlite,
+            #[cfg(not(feature = "sqlite"))]
+            FfiCookieBackend::Sqlite => {
+                return Err("the sqlite cookie backend requires the sqlite feature".to_string());
+            }
+        };
+        Ok(CookieConfig {
+            backend,
+            cookie_path: self.cookie_path,
+            debounce_delay: Duration::from_millis(self.debounce_delay_millis),
+            auto_save_interval: self.auto_save_interval_millis.map(Duration::from_millis),
+            initial_cookies: None,
+            public_suffix_list_path: self.public_suffix_list_path,
+            max_cookies: self.max_cookies,
+            max_cookies_per_domain: self.max_cookies_per_domain,
+            policies: self.policies.into_iter().map(CookiePolicy::from).collect(),
+            max_cookie_lifetime: self.max_cookie_lifetime_millis.map(Duration::from_millis),
+            session_only: self.session_only,
+        })
+    }
+}
+
+impl FfiFileCacheConfig {
+    // fails when a channel has an encryption key that is not 32 bytes
+    pub fn into_config(self) -> Result<FileCacheConfig, String> {
+        let channels = match self.channels {
+            Some(channels) => Some(
+                channels
+                    .into_iter()
+                    .map(|channel| {
+                        channel
+                            .options
+                            .into_channel_config(channel.name, channel.extension)
+                    })
+                    .collect::<Result<Vec<_>, String>>()?,
+            ),
+            None => None,
+        };
+        Ok(FileCacheConfig {
+            base_path: self.base_path,
+            auto_save_interval: Duration::from_millis(self.auto_save_interval_millis),
+            channels,
+            auto_create_channels: self.auto_create_channels,
+        })
+    }
+}
+
+impl FfiRuntimeConfig {
+    pub fn into_config(self) -> Result<RuntimeConfig, String> {
+        Ok(RuntimeConfig {
+            http: self.http.map(HttpConfig::from),
+            cookie: self.cookie.map(FfiCookieConfig::into_config).transpose()?,
+            file_cache_config: self
+                .file_cache
+                .map(FfiFileCacheConfig::into_config)
+                .transpose()?,
+            http_audit: self.http_audit.map(HttpAuditConfig::from),
+            strict_init: self.strict_init,
+            ..RuntimeConfig::default()
+        })
+    }
+}
@@ -0,0 +1,82 @@
+use crate::domain::models::cookie_models::{Cookie, CookieLoadReport, SameSite};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone)]
+pub enum FfiSameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+#[derive(Clone)]
+pub struct FfiCookie {
+    pub domain: String,
+    pub path: String,
+    pub name: String,
+    pub value: String,
+    // None for a session cookie
+    pub expires_millis: Option<u64>,
+    pub creation_time_millis: u64,
+    pub last_access_time_millis: u64,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: Option<FfiSameSite>,
+    pub persistent: bool,
+    pub host_only: bool,
+}
+
+#[derive(Clone)]
+pub struct FfiCookieLoadReport {
+    pub loaded: usize,
+    pub expired: usize,
+    pub malformed: usize,
+    pub error: Option<String>,
+    pub is_clean: bool,
+}
+
+fn to_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+impl From<SameSite> for FfiSameSite {
+    fn from(value: SameSite) -> Self {
+        match value {
+            SameSite::Strict => FfiSameSite::Strict,
+            SameSite::Lax => FfiSameSite::Lax,
+            SameSite::None => FfiSameSite::None,
+        }
+    }
+}
+
+impl From<Cookie> for FfiCookie {
+    fn from(value: Cookie) -> Self {
+        Self {
+            domain: value.key.domain,
+            path: value.key.path,
+            name: value.key.name,
+            value: value.value,
+            expires_millis: value.expires.map(to_millis),
+            creation_time_millis: to_millis(value.creation_time),
+            last_access_time_millis: to_millis(value.last_access_time),
+            secure: value.secure,
+            http_only: value.http_only,
+            same_site: value.same_site.map(FfiSameSite::from),
+            persistent: value.persistent,
+            host_only: value.host_only,
+        }
+    }
+}
+
+impl From<CookieLoadReport> for FfiCookieLoadReport {
+    fn from(value: CookieLoadReport) -> Self {
+        Self {
+            is_clean: value.is_clean(),
+            loaded: value.loaded,
+            expired: value.expired,
+            malformed: value.malformed,
+            error: value.error,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::domain::models::crash_models::CrashReport;
+
+#[derive(Clone)]
+pub struct FfiCrashReport {
+    pub id: String,
+    pub timestamp_millis: u64,
+    pub thread: Option<String>,
+    pub task: Option<String>,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+}
+
+impl From<CrashReport> for FfiCrashReport {
+    fn from(value: CrashReport) -> Self {
+        Self {
+            id: value.id,
+            timestamp_millis: value.timestamp,
+            thread: value.thread,
+            task: value.task,
+            message: value.message,
+            location: value.location,
+            backtrace: value.backtrace,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::domain::models::database_models::{
+    DatabaseStatement, DatabaseValue, ExecuteResult, QueryResult,
+};
+
+#[derive(Clone)]
+pub enum FfiDatabaseValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+#[derive(Clone)]
+pub struct FfiDatabaseStatement {
+    pub sql: String,
+    pub params: Vec<FfiDatabaseValue>,
+}
+
+#[derive(Clone)]
+pub struct FfiQueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<FfiDatabaseValue>>,
+}
+
+#[derive(Clone)]
+pub struct FfiExecuteResult {
+    pub rows_affected: u64,
+    pub last_insert_id: i64,
+}
+
+impl FfiDatabaseStatement {
+    pub fn new(sql: String, params: Vec<FfiDatabaseValue>) -> Self {
+        Self { sql, params }
+    }
+}
+
+impl From<FfiDatabaseValue> for DatabaseValue {
+    fn from(value: FfiDatabaseValue) -> Self {
+        match value {
+            FfiDatabaseValue::Null => DatabaseValue::Null,
+            FfiDatabaseValue::Integer(value) => DatabaseValue::Integer(value),
+            FfiDatabaseValue::Real(value) => DatabaseValue::Real(value),
+            FfiDatabaseValue::Text(value) => DatabaseValue::Text(value),
+            FfiDatabaseValue::Blob(value) => DatabaseValue::Blob(value),
+        }
+    }
+}
+
+impl From<DatabaseValue> for FfiDatabaseValue {
+    fn from(value: DatabaseValue) -> Self {
+        match value {
+            DatabaseValue::Null => FfiDatabaseValue::Null,
+            DatabaseValue::Integer(value) => FfiDatabaseValue::Integer(value),
+            DatabaseValue::Real(value) => FfiDatabaseValue::Real(value),
+            DatabaseValue::Text(value) => FfiDatabaseValue::Text(value),
+            DatabaseValue::Blob(value) => FfiDatabaseValue::Blob(value),
+        }
+    }
+}
+
+impl From<FfiDatabaseStatement> for DatabaseStatement {
+    fn from(value: FfiDatabaseStatement) -> Self {
+        DatabaseStatement {
+            sql: value.sql,
+            params: value.params.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<QueryResult> for FfiQueryResult {
+    fn from(value: QueryResult) -> Self {
+        Self {
+            columns: value.columns,
+            rows: value
+                .rows
+                .into_iter()
+                .map(|row| row.into_iter().map(FfiDatabaseValue::from).collect())
+                .collect(),
+        }
+    }
+}
+
+impl From<ExecuteResult> for FfiExecuteResult {
+    fn from(value: ExecuteResult) -> Self {
+        Self {
+            rows_affected: value.rows_affected,
+            last_insert_id: value.last_insert_id,
+        }
+    }
+}
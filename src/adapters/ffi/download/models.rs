@@ -0,0 +1,111 @@
+use crate::domain::models::download_models::{
+    DownloadEvent, DownloadRequest, DownloadState, DownloadTask,
+};
+
+#[derive(Clone)]
+pub struct FfiDownloadRequest {
+    pub url: String,
+    pub target_path: String,
+    pub priority: i32,
+    pub headers: Vec<(String, String)>,
+}
+
+#[derive(Clone)]
+pub enum FfiDownloadState {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Clone)]
+pub struct FfiDownloadTask {
+    pub id: String,
+    pub url: String,
+    pub target_path: String,
+    pub priority: i32,
+    pub state: FfiDownloadState,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub attempts: u32,
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct FfiDownloadEvent {
+    pub id: String,
+    pub state: FfiDownloadState,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl FfiDownloadRequest {
+    pub fn new(
+        url: String,
+        target_path: String,
+        priority: i32,
+        headers: Vec<(String, String)>,
+    ) -> Self {
+        Self {
+            url,
+            target_path,
+            priority,
+            headers,
+        }
+    }
+}
+
+impl From<FfiDownloadRequest> for DownloadRequest {
+    fn from(value: FfiDownloadRequest) -> Self {
+        DownloadRequest {
+            url: value.url,
+            target_path: value.target_path,
+            priority: value.priority,
+            headers: value.headers,
+        }
+    }
+}
+
+impl From<DownloadState> for FfiDownloadState {
+    fn from(value: DownloadState) -> Self {
+        match value {
+            DownloadState::Queued => FfiDownloadState::Queued,
+            DownloadState::Running => FfiDownloadState::Running,
+            DownloadState::Paused => FfiDownloadState::Paused,
+            DownloadState::Completed => FfiDownloadState::Completed,
+            DownloadState::Failed => FfiDownloadState::Failed,
+            DownloadState::Cancelled => FfiDownloadState::Cancelled,
+        }
+    }
+}
+
+impl From<DownloadTask> for FfiDownloadTask {
+    fn from(value: DownloadTask) -> Self {
+        Self {
+            id: value.id,
+            url: value.url,
+            target_path: value.target_path,
+            priority: value.priority,
+            state: value.state.into(),
+            downloaded: value.downloaded,
+            total: value.total,
+            attempts: value.attempts,
+            error: value.error,
+        }
+    }
+}
+
+impl From<DownloadEvent> for FfiDownloadEvent {
+    fn from(value: DownloadEvent) -> Self {
+        Self {
+            id: value.id,
+            state: value.state.into(),
+            downloaded: value.downloaded,
+            total: value.total,
+            error: value.error,
+        }
+    }
+}
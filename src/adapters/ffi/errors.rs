@@ -10,6 +10,12 @@ pub enum FfiAdapterError {
     Serialization(String),
     #[error("Configuration error: {0}")]
     Configuration(String),
+    // the overall_timeout_millis of the call ran out
+    #[error("Overall timeout after {0} ms")]
+    OverallTimeout(u64),
+    // dart stopped listening to a stream pushed into a StreamSink
+    #[error("Stream closed")]
+    StreamClosed,
 }
 
 impl FfiAdapterError {
@@ -32,6 +38,15 @@ impl FfiAdapterError {
             HttpClientError::Crypto(msg) => {
                 FfiAdapterError::DomainError(format!("Crypto: {}", msg))
             }
+            HttpClientError::Business { code, message } => {
+                FfiAdapterError::DomainError(format!("Business {}: {}", code, message))
+            }
+            HttpClientError::CircuitOpen(host) => {
+                FfiAdapterError::DomainError(format!("Circuit open: {}", host))
+            }
+            HttpClientError::Status(status) => {
+                FfiAdapterError::DomainError(format!("Unexpected status {}", status))
+            }
         }
     }
 }
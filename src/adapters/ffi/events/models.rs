@@ -0,0 +1,82 @@
+use crate::adapters::ffi::task::models::FfiTaskOutcome;
+use crate::domain::models::cookie_models::CookieChange;
+use crate::domain::models::event_models::RuntimeEvent;
+
+#[derive(Clone)]
+pub enum FfiCookieChange {
+    Set {
+        domain: String,
+        path: String,
+        name: String,
+        value: String,
+    },
+    Removed {
+        domain: String,
+        path: String,
+        name: String,
+    },
+    Cleared,
+}
+
+#[derive(Clone)]
+pub enum FfiRuntimeEvent {
+    CookieChanged(FfiCookieChange),
+    CacheEvicted {
+        channel: String,
+        tag: String,
+        size: usize,
+    },
+    AutoSaveFailed {
+        source: String,
+        error: String,
+    },
+    ConnectivityChanged {
+        online: bool,
+    },
+    TaskFinished {
+        id: u64,
+        name: String,
+        outcome: FfiTaskOutcome,
+    },
+}
+
+impl From<CookieChange> for FfiCookieChange {
+    fn from(value: CookieChange) -> Self {
+        match value {
+            CookieChange::Set(cookie) => FfiCookieChange::Set {
+                domain: cookie.key.domain,
+                path: cookie.key.path,
+                name: cookie.key.name,
+                value: cookie.value,
+            },
+            CookieChange::Removed(key) => FfiCookieChange::Removed {
+                domain: key.domain,
+                path: key.path,
+                name: key.name,
+            },
+            CookieChange::Cleared => FfiCookieChange::Cleared,
+        }
+    }
+}
+
+impl From<RuntimeEvent> for FfiRuntimeEvent {
+    fn from(value: RuntimeEvent) -> Self {
+        match value {
+            RuntimeEvent::CookieChanged(change) => FfiRuntimeEvent::CookieChanged(change.into()),
+            RuntimeEvent::CacheEvicted { channel, tag, size } => {
+                FfiRuntimeEvent::CacheEvicted { channel, tag, size }
+            }
+            RuntimeEvent::AutoSaveFailed { source, error } => {
+                FfiRuntimeEvent::AutoSaveFailed { source, error }
+            }
+            RuntimeEvent::ConnectivityChanged { online } => {
+                FfiRuntimeEvent::ConnectivityChanged { online }
+            }
+            RuntimeEvent::TaskFinished { id, name, outcome } => FfiRuntimeEvent::TaskFinished {
+                id,
+                name,
+                outcome: outcome.into(),
+            },
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod models;pub mod observer;
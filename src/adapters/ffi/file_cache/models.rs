@@ -0,0 +1,235 @@
+use crate::domain::models::file_cache_models::{
+    CacheDiagnostic, CacheRecord, CacheRecordFilter, CacheRefresh, CacheStats, CacheTask,
+    CompressionKind,
+};
+use crate::service::config::FileCacheChannelConfig;
+use std::time::Duration;
+
+#[derive(Clone)]
+pub struct FfiCacheRecord {
+    pub tag: String,
+    pub filename: String,
+    pub size: usize,
+    pub sentence: String,
+    pub expires_at_millis: Option<u64>,
+    pub last_access_millis: u64,
+    pub cached_at_millis: u64,
+    pub metadata: Vec<(String, String)>,
+    pub pinned: bool,
+}
+
+#[derive(Clone)]
+pub enum FfiCacheRefresh {
+    NotModified(FfiCacheRecord),
+    Updated(FfiCacheRecord),
+}
+
+#[derive(Clone)]
+pub struct FfiCacheStats {
+    pub channel: String,
+    pub entries: usize,
+    pub total_bytes: u64,
+    pub dirty: bool,
+    pub last_persist_millis: Option<u64>,
+    pub memory_hits: u64,
+    pub memory_misses: u64,
+    pub memory_bytes: u64,
+}
+
+#[derive(Clone)]
+pub struct FfiCacheRecordFilter {
+    pub tag_prefix: Option<String>,
+    pub min_size: Option<usize>,
+    pub max_size: Option<usize>,
+    pub older_than_millis: Option<u64>,
+    pub newer_than_millis: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct FfiCacheDiagnostic {
+    pub channel: String,
+    pub task: String,
+    pub error: String,
+    pub at_millis: u64,
+}
+
+// size is the entry count for persist events
+#[derive(Clone)]
+pub struct FfiCacheEvent {
+    pub channel: String,
+    pub kind: String,
+    pub tag: Option<String>,
+    pub size: usize,
+}
+
+#[derive(Clone)]
+pub enum FfiCompressionKind {
+    Zstd,
+    Gzip,
+}
+
+#[derive(Clone)]
+pub struct FfiCacheChannelOptions {
+    pub default_ttl_millis: Option<u64>,
+    pub max_bytes: Option<u64>,
+    pub max_entries: Option<usize>,
+    pub compression: Option<FfiCompressionKind>,
+    pub encryption_key: Option<Vec<u8>>,
+    pub memory_budget: Option<u64>,
+    pub schema_version: Option<u32>,
+}
+
+impl From<CacheRecord> for FfiCacheRecord {
+    fn from(value: CacheRecord) -> Self {
+        FfiCacheRecord {
+            tag: value.tag,
+            filename: value.filename,
+            size: value.size,
+            sentence: value.sentence,
+            expires_at_millis: value.expires_at,
+            last_access_millis: value.last_access,
+            cached_at_millis: value.cached_at,
+            metadata: value.metadata,
+            pinned: value.pinned,
+        }
+    }
+}
+
+impl From<CacheRefresh> for FfiCacheRefresh {
+    fn from(value: CacheRefresh) -> Self {
+        match value {
+            CacheRefresh::NotModified(record) => FfiCacheRefresh::NotModified(record.into()),
+            CacheRefresh::Updated(record) => FfiCacheRefresh::Updated(record.into()),
+        }
+    }
+}
+
+impl From<CacheDiagnostic> for FfiCacheDiagnostic {
+    fn from(value: CacheDiagnostic) -> Self {
+        let task = match value.task {
+            CacheTask::Recovery => "recovery",
+            CacheTask::Sweep => "sweep",
+            CacheTask::Persist => "persist",
+        };
+        FfiCacheDiagnostic {
+            channel: value.channel,
+            task: task.to_string(),
+            error: value.error,
+            at_millis: value.at,
+        }
+    }
+}
+
+impl From<CacheStats> for FfiCacheStats {
+    fn from(value: CacheStats) -> Self {
+        FfiCacheStats {
+            channel: value.channel,
+            entries: value.entries,
+            total_bytes: value.total_bytes,
+            dirty: value.dirty,
+            last_persist_millis: value.last_persist,
+            memory_hits: value.memory_hits,
+            memory_misses: value.memory_misses,
+            memory_bytes: value.memory_bytes,
+        }
+    }
+}
+
+impl From<FfiCacheRecordFilter> for CacheRecordFilter {
+    fn from(value: FfiCacheRecordFilter) -> Self {
+        CacheRecordFilter {
+            tag_prefix: value.tag_prefix,
+            min_size: value.min_size,
+            max_size: value.max_size,
+            older_than: value.older_than_millis.map(Duration::from_millis),
+            newer_than: value.newer_than_millis.map(Duration::from_millis),
+        }
+    }
+}
+
+impl FfiCacheRecordFilter {
+    pub fn new(
+        tag_prefix: Option<String>,
+        min_size: Option<usize>,
+        max_size: Option<usize>,
+        older_than_millis: Option<u64>,
+        newer_than_millis: Option<u64>,
+    ) -> Self {
+        Self {
+            tag_prefix,
+            min_size,
+            max_size,
+            older_than_millis,
+            newer_than_millis,
+        }
+    }
+}
+
+impl From<FfiCompressionKind> for CompressionKind {
+    fn from(value: FfiCompressionKind) -> Self {
+        match value {
+            FfiCompressionKind::Zstd => CompressionKind::Zstd,
+            FfiCompressionKind::Gzip => CompressionKind::Gzip,
+        }
+    }
+}
+
+impl FfiCacheEvent {
+    pub fn new(channel: String, kind: String, tag: Option<String>, size: usize) -> Self {
+        Self {
+            channel,
+            kind,
+            tag,
+            size,
+        }
+    }
+}
+
+impl FfiCacheChannelOptions {
+    pub fn new(
+        default_ttl_millis: Option<u64>,
+        max_bytes: Option<u64>,
+        max_entries: Option<usize>,
+        compression: Option<FfiCompressionKind>,
+        encryption_key: Option<Vec<u8>>,
+        memory_budget: Option<u64>,
+        schema_version: Option<u32>,
+    ) -> Self {
+        Self {
+            default_ttl_millis,
+            max_bytes,
+            max_entries,
+            compression,
+            encryption_key,
+            memory_budget,
+            schema_version,
+        }
+    }
+
+    pub fn into_channel_config(
+        self,
+        name: String,
+        extension: Option<String>,
+    ) -> Result<FileCacheChannelConfig, String> {
+        let encryption_key = match self.encryption_key {
+            Some(key) => Some(
+                <[u8; 32]>::try_from(key.as_slice())
+                    .map_err(|_| "encryption key must be 32 bytes".to_string())?,
+            ),
+            None => None,
+        };
+
+        Ok(FileCacheChannelConfig {
+            name,
+            extension,
+            default_ttl: self.default_ttl_millis.map(Duration::from_millis),
+            max_bytes: self.max_bytes,
+            max_entries: self.max_entries,
+            compression: self.compression.map(|compression| compression.into()),
+            encryption_key,
+            memory_budget: self.memory_budget,
+            schema_version: self.schema_version,
+            migration: None,
+        })
+    }
+}
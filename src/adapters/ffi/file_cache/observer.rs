@@ -0,0 +1,42 @@
+use crate::adapters::ffi::file_cache::models::FfiCacheEvent;
+use crate::adapters::ffi::stream_sink::StreamSink;
+use crate::domain::traits::file_cache_traits::FileCacheObserver;
+
+// pushes observer callbacks into the sink of the dart stream
+pub struct SinkCacheObserver {
+    sink: StreamSink<FfiCacheEvent>,
+}
+
+impl SinkCacheObserver {
+    pub fn new(sink: StreamSink<FfiCacheEvent>) -> Self {
+        Self { sink }
+    }
+
+    fn send(&self, channel: &str, kind: &str, tag: Option<&str>, size: usize) {
+        // a closed stream only means nobody is listening anymore
+        let _ = self.sink.add(FfiCacheEvent::new(
+            channel.to_string(),
+            kind.to_string(),
+            tag.map(str::to_string),
+            size,
+        ));
+    }
+}
+
+impl FileCacheObserver for SinkCacheObserver {
+    fn on_cache(&self, channel: &str, tag: &str, size: usize) {
+        self.send(channel, "cache", Some(tag), size);
+    }
+
+    fn on_evict(&self, channel: &str, tag: &str, size: usize) {
+        self.send(channel, "evict", Some(tag), size);
+    }
+
+    fn on_flush(&self, channel: &str, tag: &str, size: usize) {
+        self.send(channel, "flush", Some(tag), size);
+    }
+
+    fn on_persist(&self, channel: &str, entries: usize) {
+        self.send(channel, "persist", None, entries);
+    }
+}
@@ -1 +1,2 @@
 pub mod models;
+pub mod providers;
\ No newline at end of file
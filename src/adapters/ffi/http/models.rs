@@ -1,26 +1,31 @@
-use crate::adapters::ffi::errors::FfiAdapterError;
-use crate::domain::models::http_models::{HttpClientError, HttpEndpoint, HttpMethod, HttpResponse, HttpStreamResponse};
+use crate::domain::models::http_models::{HttpAuditRecord, HttpClientError, HttpEndpoint, HttpMethod, HttpResponse, HttpStreamResponse, LongPollOptions, ProxySelection, QueryArrayEncoding, QueryValue};
 use std::time::Duration;
 use bytes::Bytes;
+use flutter_rust_bridge::frb;
 use futures_util::stream::BoxStream;
-use futures_util::TryStreamExt;
 
 #[derive(Clone)]
 pub struct FfiHttpEndpoint {
     pub path: String,
     pub domain: String,
     pub body: Option<Vec<u8>>,
-    pub timeout_millis: u64,
+    // None takes the request_timeout of the domain override, else the default of 30s
+    pub timeout_millis: Option<u64>,
 
     pub headers: Option<Vec<(String, String)>>,
     pub path_params: Option<Vec<(String, String)>>,
-    pub query_params: Option<Vec<(String, String)>>,
+    pub query_params: Option<Vec<(String, FfiQueryValue)>>,
+    pub query_array_encoding: FfiQueryArrayEncoding,
 
     pub method: FfiHttpMethod,
     pub requires_encryption: bool,
     pub requires_decryption: bool,
     pub user_agent: Option<String>,
     pub content_type: Option<String>,
+    pub cookie_profile: Option<String>,
+    pub proxy_override: Option<FfiProxySelection>,
+    // bounds the whole call including retries and validation, on top of timeout_millis
+    pub overall_timeout_millis: Option<u64>,
 }
 
 #[derive(Clone)]
@@ -28,48 +33,170 @@ pub struct FfiHttpResponse {
     pub status: u16,
     pub headers: Vec<(String, String)>,
     pub body: Vec<u8>,
+    pub final_url: Option<String>,
+    pub redirects: Vec<String>,
+    pub trailers: Vec<(String, String)>,
+}
+
+// what a dart response validator reports for a rejected response
+#[derive(Clone)]
+pub struct FfiValidationFailure {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Clone)]
+pub struct FfiLongPollOptions {
+    pub poll_interval_millis: u64,
+    pub initial_backoff_millis: u64,
+    pub max_backoff_millis: u64,
+    pub max_consecutive_errors: Option<u32>,
+}
+
+#[derive(Clone)]
+pub struct FfiHttpAuditRecord {
+    pub timestamp_millis: u64,
+    pub method: String,
+    pub url: String,
+    pub status: Option<u16>,
+    pub duration_millis: u64,
+    pub request_size: u64,
+    pub response_size: Option<u64>,
+    pub error: Option<String>,
+}
+
+// one entry of a batch, failing requests do not fail the batch
+#[derive(Clone)]
+pub enum FfiHttpBatchResult {
+    Response(FfiHttpResponse),
+    Error(String),
 }
 
 pub struct FfiHttpStreamResponse {
     pub status: u16,
     pub headers: Vec<(String, String)>,
+    pub final_url: Option<String>,
+    pub redirects: Vec<String>,
     pub stream: BoxStream<'static, Result<Bytes, HttpClientError>>
 }
 
+// the head comes first, then the body in the chunks it arrived in
+#[derive(Clone)]
+pub enum FfiHttpStreamEvent {
+    Head {
+        status: u16,
+        headers: Vec<(String, String)>,
+        final_url: Option<String>,
+        redirects: Vec<String>,
+    },
+    Chunk {
+        data: Vec<u8>,
+    },
+}
+
+#[derive(Clone)]
+pub enum FfiQueryValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+    Array(Vec<FfiQueryValue>),
+}
+
+#[derive(Clone)]
+pub enum FfiQueryArrayEncoding {
+    Repeat,
+    Brackets,
+    Comma,
+}
+
+#[derive(Clone)]
+pub enum FfiProxySelection {
+    UseDefault,
+    Direct,
+    Specific(String),
+}
+
 #[derive(Clone)]
 pub enum FfiHttpMethod {
     Get,
     Post,
     Put,
     Delete,
+    Head,
+    Patch,
 }
 
-impl Into<HttpMethod> for FfiHttpMethod {
-    fn into(self) -> HttpMethod {
-        match self {
+impl From<FfiHttpMethod> for HttpMethod {
+    fn from(value: FfiHttpMethod) -> Self {
+        match value {
             FfiHttpMethod::Get => HttpMethod::Get,
             FfiHttpMethod::Post => HttpMethod::Post,
             FfiHttpMethod::Put => HttpMethod::Put,
-            FfiHttpMethod::Delete => HttpMethod::Delete
+            FfiHttpMethod::Delete => HttpMethod::Delete,
+            FfiHttpMethod::Head => HttpMethod::Head,
+            FfiHttpMethod::Patch => HttpMethod::Patch,
         }
     }
 }
 
-impl Into<HttpEndpoint> for FfiHttpEndpoint {
-    fn into(self) -> HttpEndpoint {
+impl From<FfiQueryValue> for QueryValue {
+    fn from(value: FfiQueryValue) -> Self {
+        match value {
+            FfiQueryValue::String(value) => QueryValue::String(value),
+            FfiQueryValue::Integer(value) => QueryValue::Integer(value),
+            FfiQueryValue::Float(value) => QueryValue::Float(value),
+            FfiQueryValue::Bool(value) => QueryValue::Bool(value),
+            FfiQueryValue::Array(values) => {
+                QueryValue::Array(values.into_iter().map(QueryValue::from).collect())
+            }
+        }
+    }
+}
+
+impl From<FfiQueryArrayEncoding> for QueryArrayEncoding {
+    fn from(value: FfiQueryArrayEncoding) -> Self {
+        match value {
+            FfiQueryArrayEncoding::Repeat => QueryArrayEncoding::Repeat,
+            FfiQueryArrayEncoding::Brackets => QueryArrayEncoding::Brackets,
+            FfiQueryArrayEncoding::Comma => QueryArrayEncoding::Comma,
+        }
+    }
+}
+
+impl From<FfiProxySelection> for ProxySelection {
+    fn from(value: FfiProxySelection) -> Self {
+        match value {
+            FfiProxySelection::UseDefault => ProxySelection::UseDefault,
+            FfiProxySelection::Direct => ProxySelection::Direct,
+            FfiProxySelection::Specific(url) => ProxySelection::Specific(url),
+        }
+    }
+}
+
+impl From<FfiHttpEndpoint> for HttpEndpoint {
+    fn from(value: FfiHttpEndpoint) -> Self {
         HttpEndpoint {
-            path: self.path,
-            domain: self.domain,
-            body: self.body,
-            timeout: Duration::from_millis(self.timeout_millis),
-            headers: self.headers,
-            path_params: self.path_params,
-            query_params: self.query_params,
-            method: self.method.into(),
-            requires_encryption: self.requires_encryption,
-            requires_decryption: self.requires_decryption,
-            user_agent: self.user_agent,
-            content_type: self.content_type,
+            path: value.path,
+            domain: value.domain,
+            body: value.body,
+            timeout: value.timeout_millis.map(Duration::from_millis),
+            headers: value.headers,
+            path_params: value.path_params,
+            query_params: value.query_params.map(|query_params| {
+                query_params
+                    .into_iter()
+                    .map(|(key, value)| (key, value.into()))
+                    .collect()
+            }),
+            query_array_encoding: value.query_array_encoding.into(),
+            method: value.method.into(),
+            requires_encryption: value.requires_encryption,
+            requires_decryption: value.requires_decryption,
+            user_agent: value.user_agent,
+            content_type: value.content_type,
+            cookie_profile: value.cookie_profile,
+            proxy_override: value.proxy_override.map(ProxySelection::from),
         }
     }
 }
@@ -80,6 +207,51 @@ impl From<HttpResponse> for FfiHttpResponse {
             status: domain_resp.status,
             headers: domain_resp.headers,
             body: domain_resp.body,
+            final_url: domain_resp.final_url,
+            redirects: domain_resp.redirects,
+            trailers: domain_resp.trailers,
+        }
+    }
+}
+
+impl FfiHttpResponse {
+    // the body decoded with the charset of Content-Type, see HttpResponse::text
+    #[frb(sync)]
+    pub fn text(&self) -> String {
+        let response = HttpResponse {
+            status: self.status,
+            headers: self.headers.clone(),
+            body: self.body.clone(),
+            final_url: self.final_url.clone(),
+            redirects: self.redirects.clone(),
+            trailers: self.trailers.clone(),
+        };
+        response.text()
+    }
+}
+
+impl From<FfiLongPollOptions> for LongPollOptions {
+    fn from(value: FfiLongPollOptions) -> Self {
+        Self {
+            poll_interval: Duration::from_millis(value.poll_interval_millis),
+            initial_backoff: Duration::from_millis(value.initial_backoff_millis),
+            max_backoff: Duration::from_millis(value.max_backoff_millis),
+            max_consecutive_errors: value.max_consecutive_errors,
+        }
+    }
+}
+
+impl From<HttpAuditRecord> for FfiHttpAuditRecord {
+    fn from(value: HttpAuditRecord) -> Self {
+        Self {
+            timestamp_millis: value.timestamp,
+            method: value.method,
+            url: value.url,
+            status: value.status,
+            duration_millis: value.duration_millis,
+            request_size: value.request_size,
+            response_size: value.response_size,
+            error: value.error,
         }
     }
 }
@@ -89,27 +261,34 @@ impl From<HttpStreamResponse> for FfiHttpStreamResponse {
         FfiHttpStreamResponse {
             status: value.status,
             headers: value.headers,
+            final_url: value.final_url,
+            redirects: value.redirects,
             stream: value.stream
         }
     }
 }
 
 impl FfiHttpEndpoint {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         path: String,
         domain: String,
         body: Option<Vec<u8>>,
-        timeout_millis: u64,
+        timeout_millis: Option<u64>,
 
         headers: Option<Vec<(String, String)>>,
         path_params: Option<Vec<(String, String)>>,
-        query_params: Option<Vec<(String, String)>>,
+        query_params: Option<Vec<(String, FfiQueryValue)>>,
+        query_array_encoding: FfiQueryArrayEncoding,
 
         method: FfiHttpMethod,
         requires_encryption: bool,
         requires_decryption: bool,
         user_agent: Option<String>,
         content_type: Option<String>,
+        cookie_profile: Option<String>,
+        proxy_override: Option<FfiProxySelection>,
+        overall_timeout_millis: Option<u64>,
     ) -> FfiHttpEndpoint {
         FfiHttpEndpoint {
             path,
@@ -119,11 +298,15 @@ impl FfiHttpEndpoint {
             headers,
             path_params,
             query_params,
+            query_array_encoding,
             method,
             requires_encryption,
             requires_decryption,
             user_agent,
             content_type,
+            cookie_profile,
+            proxy_override,
+            overall_timeout_millis,
         }
     }
 }
@@ -0,0 +1,140 @@
+use crate::adapters::ffi::http::models::{FfiHttpResponse, FfiValidationFailure};
+use crate::domain::models::http_models::{HttpClientError, HttpResponse};
+use crate::domain::traits::http_traits::{
+    DecryptionProvider, EncryptionProvider, ResponseValidator,
+};
+use flutter_rust_bridge::DartFnFuture;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::Thread;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+pub type FfiCryptoCallback =
+    Arc<dyn Fn(Vec<u8>) -> DartFnFuture<Result<Vec<u8>, String>> + Send + Sync + 'static>;
+
+pub type FfiValidatorCallback = Arc<
+    dyn Fn(FfiHttpResponse) -> DartFnFuture<Option<FfiValidationFailure>> + Send + Sync + 'static,
+>;
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn park_on<T>(mut future: DartFnFuture<T>) -> T {
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut context = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+// the providers are called synchronously, dart completes the future on its own thread while
+// a runtime worker hands its other tasks over until then
+fn wait<T>(future: DartFnFuture<T>) -> T {
+    let multi_thread = Handle::try_current()
+        .is_ok_and(|handle| handle.runtime_flavor() == RuntimeFlavor::MultiThread);
+    if multi_thread {
+        tokio::task::block_in_place(|| park_on(future))
+    } else {
+        park_on(future)
+    }
+}
+
+pub struct DartEncryptionProvider {
+    callback: FfiCryptoCallback,
+}
+
+impl DartEncryptionProvider {
+    pub fn new(callback: FfiCryptoCallback) -> Self {
+        Self { callback }
+    }
+}
+
+impl EncryptionProvider for DartEncryptionProvider {
+    fn encrypt(&self, bytes: &[u8]) -> Result<Vec<u8>, HttpClientError> {
+        wait((self.callback)(bytes.to_vec())).map_err(HttpClientError::Crypto)
+    }
+}
+
+pub struct DartDecryptionProvider {
+    callback: FfiCryptoCallback,
+}
+
+impl DartDecryptionProvider {
+    pub fn new(callback: FfiCryptoCallback) -> Self {
+        Self { callback }
+    }
+}
+
+impl DecryptionProvider for DartDecryptionProvider {
+    fn decrypt(&self, bytes: &[u8]) -> Result<Vec<u8>, HttpClientError> {
+        wait((self.callback)(bytes.to_vec())).map_err(HttpClientError::Crypto)
+    }
+}
+
+pub struct DartResponseValidator {
+    callback: FfiValidatorCallback,
+}
+
+impl DartResponseValidator {
+    pub fn new(callback: FfiValidatorCallback) -> Self {
+        Self { callback }
+    }
+}
+
+impl ResponseValidator for DartResponseValidator {
+    fn validate(&self, response: &HttpResponse) -> Result<(), HttpClientError> {
+        match wait((self.callback)(FfiHttpResponse::from(response.clone()))) {
+            None => Ok(()),
+            Some(failure) => Err(HttpClientError::Business {
+                code: failure.code,
+                message: failure.message,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::ffi::http::providers::DartEncryptionProvider;
+    use crate::domain::models::http_models::HttpClientError;
+    use crate::domain::traits::http_traits::EncryptionProvider;
+    use flutter_rust_bridge::DartFnFuture;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    // stands in for dart answering from a thread of its own
+    fn answer_later(bytes: Vec<u8>) -> DartFnFuture<Result<Vec<u8>, String>> {
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            if bytes.is_empty() {
+                let _ = sender.send(Err("nothing to encrypt".to_string()));
+            } else {
+                let _ = sender.send(Ok(bytes.iter().rev().cloned().collect()));
+            }
+        });
+        Box::pin(async move { receiver.await.unwrap() })
+    }
+
+    #[test]
+    fn test_dart_provider_waits_for_callback() {
+        let provider = DartEncryptionProvider::new(Arc::new(answer_later));
+        assert_eq!(provider.encrypt(&[1, 2, 3]).unwrap(), vec![3, 2, 1]);
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(async move {
+            tokio::spawn(async move { provider.encrypt(&Vec::new()) })
+                .await
+                .unwrap()
+        });
+        assert!(matches!(result, Err(HttpClientError::Crypto(_))));
+    }
+}
@@ -0,0 +1 @@
+pub mod models;
\ No newline at end of file
@@ -0,0 +1,33 @@
+use crate::domain::models::init_models::{SubsystemReport, SubsystemStatus};
+
+#[derive(Clone)]
+pub enum FfiSubsystemStatus {
+    Started,
+    Degraded,
+    Failed,
+    Disabled,
+}
+
+#[derive(Clone)]
+pub struct FfiSubsystemReport {
+    pub name: String,
+    pub status: FfiSubsystemStatus,
+    // why it degraded or failed
+    pub reason: Option<String>,
+}
+
+impl From<SubsystemReport> for FfiSubsystemReport {
+    fn from(value: SubsystemReport) -> Self {
+        let (status, reason) = match value.status {
+            SubsystemStatus::Started => (FfiSubsystemStatus::Started, None),
+            SubsystemStatus::Degraded(reason) => (FfiSubsystemStatus::Degraded, Some(reason)),
+            SubsystemStatus::Failed(reason) => (FfiSubsystemStatus::Failed, Some(reason)),
+            SubsystemStatus::Disabled => (FfiSubsystemStatus::Disabled, None),
+        };
+        Self {
+            name: value.name,
+            status,
+            reason,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use crate::domain::models::logging_models::{LogLevel, LogRecord};
+
+#[derive(Clone)]
+pub enum FfiLogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Clone)]
+pub struct FfiLogRecord {
+    pub timestamp_millis: u64,
+    pub level: FfiLogLevel,
+    pub target: String,
+    pub message: String,
+    pub fields: Vec<(String, String)>,
+}
+
+impl From<LogLevel> for FfiLogLevel {
+    fn from(value: LogLevel) -> Self {
+        match value {
+            LogLevel::Trace => FfiLogLevel::Trace,
+            LogLevel::Debug => FfiLogLevel::Debug,
+            LogLevel::Info => FfiLogLevel::Info,
+            LogLevel::Warn => FfiLogLevel::Warn,
+            LogLevel::Error => FfiLogLevel::Error,
+        }
+    }
+}
+
+impl From<LogRecord> for FfiLogRecord {
+    fn from(value: LogRecord) -> Self {
+        Self {
+            timestamp_millis: value.timestamp,
+            level: value.level.into(),
+            target: value.target,
+            message: value.message,
+            fields: value.fields,
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use crate::domain::models::metrics_models::{
+    CounterSample, GaugeSample, HistogramSample, MetricsSnapshot,
+};
+
+#[derive(Clone)]
+pub struct FfiCounterSample {
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+    pub value: u64,
+}
+
+#[derive(Clone)]
+pub struct FfiGaugeSample {
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+    pub value: f64,
+}
+
+#[derive(Clone)]
+pub struct FfiHistogramSample {
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+    pub count: u64,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+    pub bounds: Vec<f64>,
+    pub counts: Vec<u64>,
+}
+
+#[derive(Clone)]
+pub struct FfiMetricsSnapshot {
+    pub timestamp_millis: u64,
+    pub counters: Vec<FfiCounterSample>,
+    pub gauges: Vec<FfiGaugeSample>,
+    pub histograms: Vec<FfiHistogramSample>,
+}
+
+impl From<CounterSample> for FfiCounterSample {
+    fn from(value: CounterSample) -> Self {
+        Self {
+            name: value.name,
+            labels: value.labels,
+            value: value.value,
+        }
+    }
+}
+
+impl From<GaugeSample> for FfiGaugeSample {
+    fn from(value: GaugeSample) -> Self {
+        Self {
+            name: value.name,
+            labels: value.labels,
+            value: value.value,
+        }
+    }
+}
+
+impl From<HistogramSample> for FfiHistogramSample {
+    fn from(value: HistogramSample) -> Self {
+        Self {
+            name: value.name,
+            labels: value.labels,
+            count: value.count,
+            sum: value.sum,
+            min: value.min,
+            max: value.max,
+            bounds: value.bounds,
+            counts: value.counts,
+        }
+    }
+}
+
+impl From<MetricsSnapshot> for FfiMetricsSnapshot {
+    fn from(value: MetricsSnapshot) -> Self {
+        Self {
+            timestamp_millis: value.timestamp,
+            counters: value.counters.into_iter().map(Into::into).collect(),
+            gauges: value.gauges.into_iter().map(Into::into).collect(),
+            histograms: value.histograms.into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -2,4 +2,18 @@ pub mod http;
 pub mod errors;
 pub mod service_ffi_adapter;
 pub mod service_exporter_ffi_adapter;
-pub mod storage;
\ No newline at end of file
+pub mod stream_sink;
+pub mod storage;
+pub mod file_cache;
+pub mod database;
+pub mod scheduler;
+pub mod download;
+pub mod upload;
+pub mod logging;
+pub mod metrics;
+pub mod crash;
+pub mod task;
+pub mod init;
+pub mod config;
+pub mod events;
+pub mod cookie;
\ No newline at end of file
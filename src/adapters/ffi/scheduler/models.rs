@@ -0,0 +1,33 @@
+use crate::domain::models::scheduler_models::{JobInfo, JobSchedule};
+
+#[derive(Clone)]
+pub struct FfiJobInfo {
+    pub name: String,
+    // exactly one of interval_millis and cron is set
+    pub interval_millis: Option<u64>,
+    pub cron: Option<String>,
+    pub paused: bool,
+    pub running: bool,
+    pub last_run_millis: Option<u64>,
+    pub next_run_millis: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+impl From<JobInfo> for FfiJobInfo {
+    fn from(value: JobInfo) -> Self {
+        let (interval_millis, cron) = match value.schedule {
+            JobSchedule::Interval(interval) => (Some(interval.as_millis() as u64), None),
+            JobSchedule::Cron(expression) => (None, Some(expression)),
+        };
+        Self {
+            name: value.name,
+            interval_millis,
+            cron,
+            paused: value.paused,
+            running: value.running,
+            last_run_millis: value.last_run,
+            next_run_millis: value.next_run,
+            last_error: value.last_error,
+        }
+    }
+}
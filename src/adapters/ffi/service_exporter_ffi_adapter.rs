@@ -1,11 +1,18 @@
+use crate::adapters::ffi::config::models::FfiRuntimeConfig;
 use crate::adapters::ffi::service_ffi_adapter::ServiceFfiAdapter;
 use crate::service::config::RuntimeConfig;
 use crate::service::service_runtime::{InitError, ServiceRuntime};
-use std::panic::AssertUnwindSafe;
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
-use crate::domain::traits::monitor_traits::Monitor;
 
+lazy_static! {
+    // the runtime dart talks to when it does not hold an adapter itself
+    static ref GLOBAL_ADAPTER: RwLock<Option<Arc<ServiceExporterFfiAdapter>>> = RwLock::new(None);
+}
+
+#[derive(Clone)]
 pub struct ServiceExporterFfiAdapter {
     runtime: Arc<ServiceRuntime>,
 }
@@ -31,3 +38,124 @@ pub fn create_service_exporter_ffi_adapter_with_tokio_runtime(
     let runtime = ServiceRuntime::with_tokio_runtime(config, tokio_runtime)?;
     Ok(ServiceExporterFfiAdapter::new(runtime))
 }
+
+// the entry point for dart, which has no tokio runtime to hand over
+pub fn initialize_ffi(config: FfiRuntimeConfig) -> Result<ServiceExporterFfiAdapter, String> {
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    builder
+        .enable_all()
+        .thread_name("strawberry-background-worker");
+    if let Some(worker_threads) = config.worker_threads {
+        builder.worker_threads(worker_threads);
+    }
+    let tokio_runtime = builder
+        .build()
+        .map_err(|e| InitError::TokioInit(e.to_string()).to_string())?;
+    let config = config.into_config()?;
+    create_service_exporter_ffi_adapter_with_tokio_runtime(config, Arc::new(tokio_runtime))
+        .map_err(|e| e.to_string())
+}
+
+// a second call fails until dispose_global, the running runtime is left untouched
+pub fn init_global(config: FfiRuntimeConfig) -> Result<(), String> {
+    let mut global = GLOBAL_ADAPTER.write();
+    if global.is_some() {
+        return Err(
+            "the global runtime is already initialized, call dispose_global first".to_string(),
+        );
+    }
+    *global = Some(Arc::new(initialize_ffi(config)?));
+    Ok(())
+}
+
+pub fn global_adapter() -> Result<ServiceFfiAdapter, String> {
+    GLOBAL_ADAPTER
+        .read()
+        .as_ref()
+        .map(|adapter| adapter.runtime_ffi_adapter())
+        .ok_or_else(|| "the global runtime is not initialized, call init_global first".to_string())
+}
+
+// blocks until the runtime shut down, so it must not be called from inside it, adapters
+// taken earlier keep their runtime alive but it no longer serves requests
+pub fn dispose_global() -> Result<(), String> {
+    // held throughout so init_global waits for the shutdown to finish
+    let mut global = GLOBAL_ADAPTER.write();
+    let Some(adapter) = global.take() else {
+        return Ok(());
+    };
+    let runtime = adapter.runtime().clone();
+    runtime
+        .clone()
+        .execute_block(async move { runtime.shutdown().await })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::ffi::config::models::FfiRuntimeConfig;
+    use crate::adapters::ffi::service_exporter_ffi_adapter::{
+        dispose_global, global_adapter, init_global, initialize_ffi,
+    };
+
+    #[test]
+    fn test_global_lifecycle() {
+        let config = FfiRuntimeConfig {
+            worker_threads: Some(1),
+            http: None,
+            cookie: None,
+            file_cache: None,
+            http_audit: None,
+            strict_init: true,
+        };
+        assert!(global_adapter().is_err());
+        init_global(config.clone()).unwrap();
+        assert!(init_global(config.clone()).is_err());
+        let adapter = global_adapter().unwrap();
+        assert!(adapter.list_tasks().is_empty());
+
+        dispose_global().unwrap();
+        assert!(global_adapter().is_err());
+        // nothing left to dispose
+        dispose_global().unwrap();
+        init_global(config).unwrap();
+        dispose_global().unwrap();
+    }
+
+    #[test]
+    fn test_clones_are_used_concurrently() {
+        let exporter = initialize_ffi(FfiRuntimeConfig {
+            worker_threads: Some(2),
+            http: None,
+            cookie: None,
+            file_cache: None,
+            http_audit: None,
+            strict_init: true,
+        })
+        .unwrap();
+        let adapter = exporter.runtime_ffi_adapter();
+
+        // each thread stands in for an isolate holding its own clone
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let adapter = adapter.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        assert!(adapter.list_tasks().is_empty());
+                        assert!(!adapter.init_report().is_empty());
+                        assert!(adapter.downloads().is_err());
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let runtime = exporter.runtime().clone();
+        runtime
+            .clone()
+            .execute_block(async move { runtime.shutdown().await })
+            .unwrap();
+    }
+}
@@ -1,146 +1,1649 @@
-use crate::adapters::ffi::http::models::{FfiHttpEndpoint, FfiHttpResponse, FfiHttpStreamResponse};
-use crate::adapters::ffi::storage::models::{FfiReadFile, FfiWriteFile};
+use crate::adapters::ffi::database::models::{
+    FfiDatabaseStatement, FfiDatabaseValue, FfiExecuteResult, FfiQueryResult,
+};
+use crate::adapters::ffi::download::models::{
+    FfiDownloadEvent, FfiDownloadRequest, FfiDownloadTask,
+};
+use crate::adapters::ffi::file_cache::models::{
+    FfiCacheChannelOptions, FfiCacheDiagnostic, FfiCacheEvent, FfiCacheRecord,
+    FfiCacheRecordFilter, FfiCacheRefresh, FfiCacheStats,
+};
+use crate::adapters::ffi::errors::FfiAdapterError;
+use crate::adapters::ffi::events::models::FfiRuntimeEvent;
+use crate::adapters::ffi::file_cache::observer::SinkCacheObserver;
+use crate::adapters::ffi::http::models::{
+    FfiHttpAuditRecord, FfiHttpBatchResult, FfiHttpEndpoint, FfiHttpResponse, FfiHttpStreamEvent,
+    FfiHttpStreamResponse, FfiLongPollOptions, FfiValidationFailure,
+};
+use crate::adapters::ffi::http::providers::{
+    DartDecryptionProvider, DartEncryptionProvider, DartResponseValidator,
+};
+use crate::adapters::ffi::cookie::models::{FfiCookie, FfiCookieLoadReport};
+use crate::adapters::ffi::crash::models::FfiCrashReport;
+use crate::adapters::ffi::init::models::FfiSubsystemReport;
+use crate::adapters::ffi::logging::models::FfiLogRecord;
+use crate::adapters::ffi::metrics::models::FfiMetricsSnapshot;
+use crate::adapters::ffi::scheduler::models::FfiJobInfo;
+use crate::adapters::ffi::task::models::FfiTaskInfo;
+use crate::adapters::ffi::stream_sink::StreamSink;
+use crate::adapters::ffi::storage::models::{
+    FfiDeleteFile, FfiDirEntry, FfiDiskUsage, FfiFileHash, FfiFileMetadata, FfiHashAlgorithm,
+    FfiProgress, FfiReadFile, FfiReadResult, FfiStorageEvent, FfiTransferFile, FfiWriteFile,
+    FfiWriteResult,
+};
+use crate::adapters::ffi::upload::models::{FfiUploadEvent, FfiUploadRequest, FfiUploadTask};
+use crate::domain::models::download_models::{DownloadEvent, DownloadState};
 use crate::domain::models::storage_models::WriteFile;
+use crate::service::config::{
+    CookieConfig, FileCacheConfig, HttpConfig, HttpReconfiguration, RuntimeReconfiguration,
+};
+use crate::domain::traits::http_traits::ResponseValidator;
 use crate::service::service_runtime::ServiceRuntime;
+use crate::superstructure::task_registry::TaskHandle;
+use bytes::Bytes;
+use flutter_rust_bridge::{DartFnFuture, frb};
+use futures_util::{Stream, StreamExt};
+use futures_util::stream::BoxStream;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::io::StreamReader;
+use tokio_util::sync::CancellationToken;
 
+// ends the wait with a distinct error once overall_timeout_millis ran out, whatever the lower
+// timeouts allow, dropping the future stops the work
+async fn within_overall_timeout<T>(
+    overall_timeout_millis: Option<u64>,
+    future: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    let Some(millis) = overall_timeout_millis else {
+        return future.await;
+    };
+    match tokio::time::timeout(Duration::from_millis(millis), future).await {
+        Ok(result) => result,
+        Err(_) => Err(FfiAdapterError::OverallTimeout(millis).to_string()),
+    }
+}
+
+// like within_overall_timeout for work spawned as a task, which keeps running unless cancelled
+async fn await_task<T>(
+    runtime: &ServiceRuntime,
+    handle: TaskHandle<T>,
+    overall_timeout_millis: Option<u64>,
+) -> Result<T, String> {
+    let Some(millis) = overall_timeout_millis else {
+        return handle.await.map_err(|e| e.to_string());
+    };
+    let task_id = handle.id;
+    match tokio::time::timeout(Duration::from_millis(millis), handle).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => {
+            // the task may have ended meanwhile
+            let _ = runtime.cancel_task(task_id);
+            Err(FfiAdapterError::OverallTimeout(millis).to_string())
+        }
+    }
+}
+
+// pushes the items into the sink on the runtime until the stream ended, an error ends it as the
+// last item and dart no longer listening ends it early
+fn forward_stream<T, S>(runtime: &ServiceRuntime, stream: S, sink: StreamSink<T>)
+where
+    T: Send + 'static,
+    S: Stream<Item = Result<T, String>> + Send + 'static,
+{
+    runtime.available_runtime().spawn(async move {
+        let mut stream = std::pin::pin!(stream);
+        while let Some(item) = stream.next().await {
+            let added = match item {
+                Ok(value) => sink.add(value),
+                Err(e) => {
+                    let _ = sink.add_error(e);
+                    return;
+                }
+            };
+            if added.is_err() {
+                return;
+            }
+        }
+    });
+}
+
+// pushes what the receiver gets into the sink on the runtime until the sender is gone or dart no
+// longer listens, a lagging listener skips what it fell behind on
+fn forward_broadcast<E, T>(
+    runtime: &ServiceRuntime,
+    mut receiver: broadcast::Receiver<E>,
+    sink: StreamSink<T>,
+    map: impl Fn(E) -> T + Send + 'static,
+) where
+    E: Clone + Send + 'static,
+    T: Send + 'static,
+{
+    runtime.available_runtime().spawn(async move {
+        loop {
+            let item = match receiver.recv().await {
+                Ok(item) => item,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            };
+            if sink.add(map(item)).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+// cancels the followed download when download_with_progress is cancelled before it ended
+struct CancelDownloadOnAbort {
+    runtime: Arc<ServiceRuntime>,
+    id: Option<String>,
+}
+
+impl Drop for CancelDownloadOnAbort {
+    fn drop(&mut self) {
+        let Some(id) = self.id.take() else {
+            return;
+        };
+        let runtime = self.runtime.clone();
+        self.runtime.available_runtime().spawn(async move {
+            let _ = runtime.cancel_download(&id).await;
+        });
+    }
+}
+
+// a handle to the runtime, clones share it and every method takes &self, so each dart
+// isolate may hold its own clone and call into it at the same time, the frb(sync) ones only
+// read in-memory state and are answered on the calling isolate's own thread
+#[derive(Clone)]
 pub struct ServiceFfiAdapter {
     runtime: Arc<ServiceRuntime>,
 }
 
-impl ServiceFfiAdapter {
-    pub fn new(runtime: Arc<ServiceRuntime>) -> Self {
-        Self { runtime }
+// handed across threads and isolates, this fails to build once that no longer holds
+const _: fn() = || {
+    fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+    assert_shareable::<ServiceFfiAdapter>();
+};
+
+impl ServiceFfiAdapter {
+    pub fn new(runtime: Arc<ServiceRuntime>) -> Self {
+        Self { runtime }
+    }
+
+    pub async fn shutdown(&self) -> Result<(), String> {
+        self.runtime.shutdown().await.map_err(|e| e.to_string())
+    }
+
+    pub async fn enable_cookie_store(&self, config: CookieConfig) -> Result<(), String> {
+        self.runtime
+            .enable_cookie_store(config)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn enable_http(&self, config: HttpConfig) -> Result<(), String> {
+        self.runtime.enable_http(config).map_err(|e| e.to_string())
+    }
+
+    pub async fn enable_file_cache(&self, config: FileCacheConfig) -> Result<(), String> {
+        self.runtime
+            .enable_file_cache(config)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn reconfigure(&self, config: RuntimeReconfiguration) -> Result<(), String> {
+        self.runtime
+            .reconfigure(config)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn reconfigure_http(&self, http: HttpReconfiguration) -> Result<(), String> {
+        self.reconfigure(RuntimeReconfiguration {
+            http: Some(http),
+            ..Default::default()
+        })
+        .await
+    }
+
+    // the callback receives the request body and returns it encrypted
+    pub async fn set_encryption_provider(
+        &self,
+        encrypt: impl Fn(Vec<u8>) -> DartFnFuture<Result<Vec<u8>, String>> + Send + Sync + 'static,
+    ) -> Result<(), String> {
+        self.reconfigure_http(HttpReconfiguration {
+            encryption_provider: Some(Some(Arc::new(DartEncryptionProvider::new(Arc::new(
+                encrypt,
+            ))))),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn remove_encryption_provider(&self) -> Result<(), String> {
+        self.reconfigure_http(HttpReconfiguration {
+            encryption_provider: Some(None),
+            ..Default::default()
+        })
+        .await
+    }
+
+    // the callback receives the response body and returns it decrypted
+    pub async fn set_decryption_provider(
+        &self,
+        decrypt: impl Fn(Vec<u8>) -> DartFnFuture<Result<Vec<u8>, String>> + Send + Sync + 'static,
+    ) -> Result<(), String> {
+        self.reconfigure_http(HttpReconfiguration {
+            decryption_provider: Some(Some(Arc::new(DartDecryptionProvider::new(Arc::new(
+                decrypt,
+            ))))),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn remove_decryption_provider(&self) -> Result<(), String> {
+        self.reconfigure_http(HttpReconfiguration {
+            decryption_provider: Some(None),
+            ..Default::default()
+        })
+        .await
+    }
+
+    // one callback validates the responses of every given domain, replacing earlier
+    // validators, it returns a failure to reject the response
+    pub async fn set_response_validator(
+        &self,
+        domains: Vec<String>,
+        validate: impl Fn(FfiHttpResponse) -> DartFnFuture<Option<FfiValidationFailure>>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Result<(), String> {
+        let validator: Arc<dyn ResponseValidator> =
+            Arc::new(DartResponseValidator::new(Arc::new(validate)));
+        let validators = domains
+            .into_iter()
+            .map(|domain| (domain, validator.clone()))
+            .collect();
+        self.reconfigure_http(HttpReconfiguration {
+            response_validators: Some(Some(validators)),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn remove_response_validators(&self) -> Result<(), String> {
+        self.reconfigure_http(HttpReconfiguration {
+            response_validators: Some(None),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn on_background(&self) -> Result<(), String> {
+        self.runtime.on_background().await.map_err(|e| e.to_string())
+    }
+
+    pub fn on_foreground(&self) {
+        self.runtime.on_foreground();
+    }
+
+    pub fn file_cache_diagnostics(
+        &self,
+        sink: StreamSink<FfiCacheDiagnostic>,
+    ) -> Result<(), String> {
+        let receiver = self
+            .runtime
+            .file_cache_diagnostics()
+            .map_err(|e| e.to_string())?;
+        // lagging behind only drops old diagnostics, the stream ends when the factory is gone
+        forward_broadcast(&self.runtime, receiver, sink, FfiCacheDiagnostic::from);
+        Ok(())
+    }
+
+    pub fn file_cache_events(&self, sink: StreamSink<FfiCacheEvent>) -> Result<(), String> {
+        self.runtime
+            .file_cache_add_observer(Arc::new(SinkCacheObserver::new(sink)))
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    // runs the work as a listed task under a reserved id, so dart can cancel it while it
+    // awaits the result
+    async fn cancelable<T, F>(
+        &self,
+        task_id: u64,
+        name: impl Into<String>,
+        overall_timeout_millis: Option<u64>,
+        future: F,
+    ) -> Result<T, String>
+    where
+        F: Future<Output = Result<T, String>> + Send + 'static,
+        T: Send + 'static,
+    {
+        let handle = self.runtime.execute_async_as(task_id, name, future);
+        await_task(&self.runtime, handle, overall_timeout_millis).await?
+    }
+
+    // the id one of the calls taking a task_id runs under, known before that call is awaited
+    // so cancel(task_id) stops it even before it started, each id is meant for a single call
+    #[frb(sync)]
+    pub fn reserve_task_id(&self) -> u64 {
+        self.runtime.reserve_task_id()
+    }
+
+    pub fn cancel(&self, task_id: u64) -> Result<(), String> {
+        self.runtime.cancel_task(task_id).map_err(|e| e.to_string())
+    }
+
+    pub async fn execute_http_endpoint(
+        &self,
+        task_id: u64,
+        ffi_endpoint: FfiHttpEndpoint,
+    ) -> Result<FfiHttpResponse, String> {
+        let overall_timeout_millis = ffi_endpoint.overall_timeout_millis;
+        let domain_endpoint = ffi_endpoint.into();
+        let handle = self
+            .runtime
+            .execute_http_as(task_id, domain_endpoint)
+            .map_err(|e| e.to_string())?;
+
+        let domain_response = await_task(&self.runtime, handle, overall_timeout_millis)
+            .await?
+            .map_err(|e| e.to_string())?;
+        Ok(FfiHttpResponse::from(domain_response))
+    }
+
+    // overall_timeout_millis bounds the whole batch, the ones of the endpoints are not used
+    pub async fn execute_http_batch(
+        &self,
+        task_id: u64,
+        ffi_endpoints: Vec<FfiHttpEndpoint>,
+        max_concurrency: usize,
+        overall_timeout_millis: Option<u64>,
+    ) -> Result<Vec<FfiHttpBatchResult>, String> {
+        let domain_endpoints = ffi_endpoints.into_iter().map(Into::into).collect();
+        let handle = self
+            .runtime
+            .execute_http_batch_as(task_id, domain_endpoints, max_concurrency)
+            .map_err(|e| e.to_string())?;
+
+        let domain_results = await_task(&self.runtime, handle, overall_timeout_millis).await?;
+        Ok(domain_results
+            .into_iter()
+            .map(|result| match result {
+                Ok(response) => FfiHttpBatchResult::Response(response.into()),
+                Err(e) => FfiHttpBatchResult::Error(e.to_string()),
+            })
+            .collect())
+    }
+
+    // polls until cancel(task_id) or until dart stops listening, the overall timeout of the
+    // endpoint is not used
+    pub fn execute_long_poll(
+        &self,
+        task_id: u64,
+        ffi_endpoint: FfiHttpEndpoint,
+        options: FfiLongPollOptions,
+        sink: StreamSink<FfiHttpResponse>,
+    ) -> Result<(), String> {
+        let name = format!("long poll {}{}", ffi_endpoint.domain, ffi_endpoint.path);
+        let mut responses = self
+            .runtime
+            .execute_long_poll(ffi_endpoint.into(), options.into(), CancellationToken::new())
+            .map_err(|e| e.to_string())?;
+        self.runtime.execute_async_as(task_id, name, async move {
+            // failed polls arrive as errors, the polling goes on after the backoff
+            while let Some(result) = responses.next().await {
+                let added = match result {
+                    Ok(response) => sink.add(FfiHttpResponse::from(response)),
+                    Err(e) => sink.add_error(e.to_string()),
+                };
+                if added.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    // cancelling only covers the head, the body is read by the caller
+    pub async fn execute_stream_http_endpoint(
+        &self,
+        task_id: u64,
+        ffi_endpoint: FfiHttpEndpoint,
+    ) -> Result<FfiHttpStreamResponse, String> {
+        let overall_timeout_millis = ffi_endpoint.overall_timeout_millis;
+        let domain_endpoint = ffi_endpoint.into();
+        let handle = self
+            .runtime
+            .execute_stream_http_as(task_id, domain_endpoint)
+            .map_err(|e| e.to_string())?;
+
+        let domain_response = await_task(&self.runtime, handle, overall_timeout_millis)
+            .await?
+            .map_err(|e| e.to_string())?;
+        Ok(FfiHttpStreamResponse::from(domain_response))
+    }
+
+    // the body is read on the runtime and pushed into the sink as it arrives, the stream ends
+    // after the last chunk and a failure ends it as an error
+    pub fn execute_http_stream(
+        &self,
+        ffi_endpoint: FfiHttpEndpoint,
+        sink: StreamSink<FfiHttpStreamEvent>,
+    ) {
+        let runtime = self.runtime.clone();
+        self.runtime.available_runtime().spawn(async move {
+            // the overall timeout covers the head only, the body may take as long as it takes
+            let overall_timeout_millis = ffi_endpoint.overall_timeout_millis;
+            let response = match runtime.execute_stream_http(ffi_endpoint.into()) {
+                Ok(handle) => match await_task(&runtime, handle, overall_timeout_millis).await {
+                    Ok(response) => response.map_err(|e| e.to_string()),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e.to_string()),
+            };
+            let mut response = match response {
+                Ok(response) => response,
+                Err(e) => {
+                    let _ = sink.add_error(e);
+                    return;
+                }
+            };
+            let head = FfiHttpStreamEvent::Head {
+                status: response.status,
+                headers: response.headers,
+                final_url: response.final_url,
+                redirects: response.redirects,
+            };
+            if sink.add(head).is_err() {
+                return;
+            }
+            // dart no longer listening stops the transfer
+            while let Some(chunk) = response.stream.next().await {
+                let added = match chunk {
+                    Ok(data) => sink.add(FfiHttpStreamEvent::Chunk {
+                        data: data.to_vec(),
+                    }),
+                    Err(e) => {
+                        let _ = sink.add_error(e.to_string());
+                        return;
+                    }
+                };
+                if added.is_err() {
+                    return;
+                }
+            }
+        });
+    }
+
+    pub async fn http_cached(
+        &self,
+        task_id: u64,
+        ffi_endpoint: FfiHttpEndpoint,
+        channel: &str,
+        tag: String,
+    ) -> Result<FfiHttpResponse, String> {
+        let runtime = self.runtime.clone();
+        let channel = channel.to_string();
+        let name = format!("http_cached {}/{}", channel, tag);
+        let overall_timeout_millis = ffi_endpoint.overall_timeout_millis;
+        self.cancelable(task_id, name, overall_timeout_millis, async move {
+            let domain_endpoint = ffi_endpoint.into();
+            let domain_response = runtime
+                .http_cached(domain_endpoint, &channel, tag)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            Ok(FfiHttpResponse::from(domain_response))
+        })
+        .await
+    }
+
+    pub async fn http_to_cache(
+        &self,
+        task_id: u64,
+        ffi_endpoint: FfiHttpEndpoint,
+        channel: &str,
+        tag: String,
+        sentence: String,
+    ) -> Result<FfiCacheRecord, String> {
+        let runtime = self.runtime.clone();
+        let channel = channel.to_string();
+        let name = format!("http_to_cache {}/{}", channel, tag);
+        let overall_timeout_millis = ffi_endpoint.overall_timeout_millis;
+        self.cancelable(task_id, name, overall_timeout_millis, async move {
+            let record = runtime
+                .http_to_cache(ffi_endpoint.into(), &channel, tag, sentence)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            Ok(FfiCacheRecord::from(record))
+        })
+        .await
+    }
+
+    pub async fn http_refresh_cache(
+        &self,
+        task_id: u64,
+        ffi_endpoint: FfiHttpEndpoint,
+        channel: &str,
+        tag: String,
+        sentence: String,
+    ) -> Result<FfiCacheRefresh, String> {
+        let runtime = self.runtime.clone();
+        let channel = channel.to_string();
+        let name = format!("http_refresh_cache {}/{}", channel, tag);
+        let overall_timeout_millis = ffi_endpoint.overall_timeout_millis;
+        self.cancelable(task_id, name, overall_timeout_millis, async move {
+            let refresh = runtime
+                .http_refresh_cache(ffi_endpoint.into(), &channel, tag, sentence)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            Ok(FfiCacheRefresh::from(refresh))
+        })
+        .await
+    }
+
+    pub async fn read_file(&self, ffi_read_file: FfiReadFile) -> Result<Vec<u8>, String> {
+        let overall_timeout_millis = ffi_read_file.overall_timeout_millis;
+        let domain_read_file = ffi_read_file.into();
+        within_overall_timeout(overall_timeout_millis, async {
+            self.runtime
+                .read_file(domain_read_file)
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    pub async fn write_file(&self, ffi_write_file: FfiWriteFile) -> Result<(), String> {
+        let domain_write_file = WriteFile::from(&ffi_write_file);
+        within_overall_timeout(ffi_write_file.overall_timeout_millis, async {
+            self.runtime
+                .write_file(domain_write_file)
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    pub async fn read_files(
+        &self,
+        ffi_read_files: Vec<FfiReadFile>,
+        parallelism: usize,
+    ) -> Result<Vec<FfiReadResult>, String> {
+        let domain_read_files = ffi_read_files.into_iter().map(|file| file.into()).collect();
+        let results = self
+            .runtime
+            .read_files(domain_read_files, parallelism)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(results.into_iter().map(FfiReadResult::from).collect())
+    }
+
+    pub async fn write_files(
+        &self,
+        ffi_write_files: Vec<FfiWriteFile>,
+        parallelism: usize,
+    ) -> Result<Vec<FfiWriteResult>, String> {
+        let domain_write_files = ffi_write_files.iter().map(WriteFile::from).collect();
+        let results = self
+            .runtime
+            .write_files(domain_write_files, parallelism)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(results.into_iter().map(FfiWriteResult::from).collect())
+    }
+
+    pub async fn read_file_verified(
+        &self,
+        ffi_read_file: FfiReadFile,
+        expected: FfiFileHash,
+    ) -> Result<Vec<u8>, String> {
+        let expected = expected.into_file_hash()?;
+        let overall_timeout_millis = ffi_read_file.overall_timeout_millis;
+        within_overall_timeout(overall_timeout_millis, async {
+            self.runtime
+                .read_file_verified(ffi_read_file.into(), expected)
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    pub async fn write_file_hashed(
+        &self,
+        ffi_write_file: FfiWriteFile,
+        algorithm: FfiHashAlgorithm,
+    ) -> Result<FfiFileHash, String> {
+        let domain_write_file = WriteFile::from(&ffi_write_file);
+        let file_hash = within_overall_timeout(ffi_write_file.overall_timeout_millis, async {
+            self.runtime
+                .write_file_hashed(domain_write_file, algorithm.into())
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await?;
+
+        Ok(file_hash.into())
+    }
+
+    pub async fn delete_file(&self, ffi_delete_file: FfiDeleteFile) -> Result<(), String> {
+        let overall_timeout_millis = ffi_delete_file.overall_timeout_millis;
+        within_overall_timeout(overall_timeout_millis, async {
+            self.runtime
+                .delete_file(ffi_delete_file.into())
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    pub async fn rename_file(&self, ffi_transfer_file: FfiTransferFile) -> Result<(), String> {
+        let overall_timeout_millis = ffi_transfer_file.overall_timeout_millis;
+        within_overall_timeout(overall_timeout_millis, async {
+            self.runtime
+                .rename_file(ffi_transfer_file.into())
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    pub async fn copy_file(&self, ffi_transfer_file: FfiTransferFile) -> Result<(), String> {
+        let overall_timeout_millis = ffi_transfer_file.overall_timeout_millis;
+        within_overall_timeout(overall_timeout_millis, async {
+            self.runtime
+                .copy_file(ffi_transfer_file.into())
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    pub async fn move_file(&self, ffi_transfer_file: FfiTransferFile) -> Result<(), String> {
+        let overall_timeout_millis = ffi_transfer_file.overall_timeout_millis;
+        within_overall_timeout(overall_timeout_millis, async {
+            self.runtime
+                .move_file(ffi_transfer_file.into())
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    // the stream ends after the copy finished, a failure ends it as an error
+    pub fn copy_file_with_progress(
+        &self,
+        ffi_transfer_file: FfiTransferFile,
+        sink: StreamSink<FfiProgress>,
+    ) {
+        let runtime = self.runtime.clone();
+        let progress_sink = sink.clone();
+        self.runtime.available_runtime().spawn(async move {
+            let progress_sink = Box::new(move |value, total, delta| {
+                let _ = progress_sink.add(FfiProgress {
+                    value,
+                    total,
+                    delta,
+                });
+            });
+            let overall_timeout_millis = ffi_transfer_file.overall_timeout_millis;
+            let result = within_overall_timeout(overall_timeout_millis, async {
+                runtime
+                    .copy_file_with_progress(ffi_transfer_file.into(), progress_sink)
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+            .await;
+            if let Err(e) = result {
+                let _ = sink.add_error(e);
+            }
+        });
+    }
+
+    pub async fn create_dir(&self, path: String, recursive: bool) -> Result<(), String> {
+        self.runtime
+            .create_dir(path, recursive)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn list_dir(&self, path: String) -> Result<Vec<FfiDirEntry>, String> {
+        let entries = self
+            .runtime
+            .list_dir(path)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(entries.into_iter().map(FfiDirEntry::from).collect())
+    }
+
+    pub async fn remove_dir(&self, path: String, recursive: bool) -> Result<(), String> {
+        self.runtime
+            .remove_dir(path, recursive)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn read_file_range(
+        &self,
+        path: String,
+        offset: u64,
+        len: u64,
+    ) -> Result<Vec<u8>, String> {
+        let data = self
+            .runtime
+            .read_file_range(path, offset, len)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(data)
+    }
+
+    pub async fn read_file_stream(
+        &self,
+        path: String,
+        chunk_size: usize,
+        sink: StreamSink<Vec<u8>>,
+    ) -> Result<(), String> {
+        let stream = self
+            .runtime
+            .read_file_stream(path, chunk_size)
+            .await
+            .map_err(|e| e.to_string())?;
+        let chunks = stream.map(|chunk| {
+            chunk.map(|chunk| chunk.to_vec()).map_err(|e| e.to_string())
+        });
+        forward_stream(&self.runtime, chunks, sink);
+        Ok(())
+    }
+
+    pub async fn write_file_stream(
+        &self,
+        path: String,
+        chunks: BoxStream<'static, Vec<u8>>,
+    ) -> Result<u64, String> {
+        let written = self
+            .runtime
+            .write_file_stream(path, Box::pin(chunks.map(|chunk| Ok(Bytes::from(chunk)))))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(written)
+    }
+
+    pub async fn append_line(&self, path: String, line: Vec<u8>) -> Result<(), String> {
+        self.runtime
+            .append_line(path, line)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    // lines from start up to but excluding end
+    pub async fn read_lines(
+        &self,
+        path: String,
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<Vec<u8>>, String> {
+        let lines = self
+            .runtime
+            .read_lines(path, start..end)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(lines)
+    }
+
+    pub async fn enqueue_download(&self, request: FfiDownloadRequest) -> Result<String, String> {
+        let id = self
+            .runtime
+            .enqueue_download(request.into())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(id)
+    }
+
+    // enqueues the download and follows it, the stream ends once it completed, failed or was
+    // cancelled, a paused download keeps the stream open, cancelling the task cancels the
+    // download while dart no longer listening only stops following it
+    pub fn download_with_progress(
+        &self,
+        task_id: u64,
+        request: FfiDownloadRequest,
+        sink: StreamSink<FfiDownloadEvent>,
+    ) {
+        let runtime = self.runtime.clone();
+        self.runtime.execute_async_as(task_id, "download", async move {
+            // subscribed first so no event of the new download is missed
+            let mut events = match runtime.download_events() {
+                Ok(events) => events,
+                Err(e) => {
+                    let _ = sink.add_error(e.to_string());
+                    return;
+                }
+            };
+            let id = match runtime.enqueue_download(request.into()).await {
+                Ok(id) => id,
+                Err(e) => {
+                    let _ = sink.add_error(e.to_string());
+                    return;
+                }
+            };
+            let mut cancel_on_abort = CancelDownloadOnAbort {
+                runtime: runtime.clone(),
+                id: Some(id.clone()),
+            };
+            let is_finished = |state: DownloadState| {
+                matches!(
+                    state,
+                    DownloadState::Completed | DownloadState::Failed | DownloadState::Cancelled
+                )
+            };
+            loop {
+                let event = match events.recv().await {
+                    Ok(event) => event,
+                    // the dropped events may have held the last one, the task state tells
+                    Err(RecvError::Lagged(_)) => {
+                        let task = match runtime.downloads() {
+                            Ok(tasks) => tasks.into_iter().find(|task| task.id == id),
+                            Err(_) => None,
+                        };
+                        match task {
+                            Some(task) if is_finished(task.state) => DownloadEvent {
+                                id: task.id,
+                                state: task.state,
+                                downloaded: task.downloaded,
+                                total: task.total,
+                                error: task.error,
+                            },
+                            Some(_) => continue,
+                            None => break,
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if event.id != id {
+                    continue;
+                }
+                let finished = is_finished(event.state);
+                if sink.add(FfiDownloadEvent::from(event)).is_err() || finished {
+                    break;
+                }
+            }
+            cancel_on_abort.id = None;
+        });
+    }
+
+    pub async fn pause_download(&self, id: String) -> Result<(), String> {
+        self.runtime
+            .pause_download(&id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn resume_download(&self, id: String) -> Result<(), String> {
+        self.runtime
+            .resume_download(&id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn cancel_download(&self, id: String) -> Result<(), String> {
+        self.runtime
+            .cancel_download(&id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn remove_download(&self, id: String) -> Result<(), String> {
+        self.runtime
+            .remove_download(&id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    #[frb(sync)]
+    pub fn downloads(&self) -> Result<Vec<FfiDownloadTask>, String> {
+        let tasks = self.runtime.downloads().map_err(|e| e.to_string())?;
+        Ok(tasks.into_iter().map(FfiDownloadTask::from).collect())
+    }
+
+    pub fn download_events(&self, sink: StreamSink<FfiDownloadEvent>) -> Result<(), String> {
+        let receiver = self
+            .runtime
+            .download_events()
+            .map_err(|e| e.to_string())?;
+        // a lagging listener misses intermediate progress, the next event carries the totals
+        forward_broadcast(&self.runtime, receiver, sink, FfiDownloadEvent::from);
+        Ok(())
+    }
+
+    pub async fn enqueue_upload(&self, request: FfiUploadRequest) -> Result<String, String> {
+        let id = self
+            .runtime
+            .enqueue_upload(request.into())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(id)
+    }
+
+    pub async fn pause_upload(&self, id: String) -> Result<(), String> {
+        self.runtime
+            .pause_upload(&id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn resume_upload(&self, id: String) -> Result<(), String> {
+        self.runtime
+            .resume_upload(&id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn cancel_upload(&self, id: String) -> Result<(), String> {
+        self.runtime
+            .cancel_upload(&id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn remove_upload(&self, id: String) -> Result<(), String> {
+        self.runtime
+            .remove_upload(&id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    #[frb(sync)]
+    pub fn uploads(&self) -> Result<Vec<FfiUploadTask>, String> {
+        let tasks = self.runtime.uploads().map_err(|e| e.to_string())?;
+        Ok(tasks.into_iter().map(FfiUploadTask::from).collect())
+    }
+
+    pub fn upload_events(&self, sink: StreamSink<FfiUploadEvent>) -> Result<(), String> {
+        let receiver = self.runtime.upload_events().map_err(|e| e.to_string())?;
+        forward_broadcast(&self.runtime, receiver, sink, FfiUploadEvent::from);
+        Ok(())
+    }
+
+    pub fn log_records(&self, sink: StreamSink<FfiLogRecord>) -> Result<(), String> {
+        let receiver = self.runtime.log_records().map_err(|e| e.to_string())?;
+        // records missed by a lagging listener are still in the log file
+        forward_broadcast(&self.runtime, receiver, sink, FfiLogRecord::from);
+        Ok(())
+    }
+
+    // path of a gzip compressed tar with the log files, for "send diagnostics" in the app
+    pub async fn collect_logs(&self, since_millis: Option<u64>) -> Result<String, String> {
+        let path = self
+            .runtime
+            .collect_logs(since_millis)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(path)
     }
 
-    pub async fn execute_http_endpoint(
+    pub async fn cookie_persist_now(&self) -> Result<(), String> {
+        self.runtime
+            .cookie_persist_now()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    #[frb(sync)]
+    pub fn cookie_has_unsaved_changes(&self) -> Result<bool, String> {
+        let dirty = self
+            .runtime
+            .cookie_has_unsaved_changes()
+            .map_err(|e| e.to_string())?;
+
+        Ok(dirty)
+    }
+
+    #[frb(sync)]
+    pub fn cookie_load_report(&self) -> Result<Option<FfiCookieLoadReport>, String> {
+        let report = self
+            .runtime
+            .cookie_load_report()
+            .map_err(|e| e.to_string())?;
+
+        Ok(report.map(FfiCookieLoadReport::from))
+    }
+
+    pub async fn cookie_clear_all(&self) -> Result<(), String> {
+        self.runtime
+            .cookie_clear_all()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn cookie_get_for_domain(&self, domain: String) -> Result<Vec<FfiCookie>, String> {
+        let cookies = self
+            .runtime
+            .cookie_get_for_domain(&domain)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(cookies.into_iter().map(FfiCookie::from).collect())
+    }
+
+    pub async fn http_audit_records(&self) -> Result<Vec<FfiHttpAuditRecord>, String> {
+        let records = self
+            .runtime
+            .http_audit_records()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(records.into_iter().map(FfiHttpAuditRecord::from).collect())
+    }
+
+    pub async fn take_pending_crash_reports(&self) -> Result<Vec<FfiCrashReport>, String> {
+        let reports = self
+            .runtime
+            .take_pending_crash_reports()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(reports.into_iter().map(FfiCrashReport::from).collect())
+    }
+
+    #[frb(sync)]
+    pub fn metrics_snapshot(&self) -> Result<FfiMetricsSnapshot, String> {
+        let snapshot = self
+            .runtime
+            .metrics_snapshot()
+            .map_err(|e| e.to_string())?;
+
+        Ok(snapshot.into())
+    }
+
+    #[frb(sync)]
+    pub fn init_report(&self) -> Vec<FfiSubsystemReport> {
+        self.runtime
+            .init_report()
+            .subsystems
+            .iter()
+            .cloned()
+            .map(FfiSubsystemReport::from)
+            .collect()
+    }
+
+    // the one stream to listen to for everything the runtime reports on its own, it lives as
+    // long as the runtime
+    pub fn subscribe_events(&self, sink: StreamSink<FfiRuntimeEvent>) {
+        let receiver = self.runtime.subscribe_events();
+        // a lagging listener misses the events it fell behind on
+        forward_broadcast(&self.runtime, receiver, sink, FfiRuntimeEvent::from);
+    }
+
+    #[frb(sync)]
+    pub fn list_tasks(&self) -> Vec<FfiTaskInfo> {
+        self.runtime
+            .list_tasks()
+            .into_iter()
+            .map(FfiTaskInfo::from)
+            .collect()
+    }
+
+    #[frb(sync)]
+    pub fn jobs(&self) -> Vec<FfiJobInfo> {
+        self.runtime.jobs().into_iter().map(FfiJobInfo::from).collect()
+    }
+
+    pub fn pause_job(&self, name: String) -> Result<(), String> {
+        self.runtime.pause_job(&name).map_err(|e| e.to_string())
+    }
+
+    pub fn resume_job(&self, name: String) -> Result<(), String> {
+        self.runtime.resume_job(&name).map_err(|e| e.to_string())
+    }
+
+    pub fn pause_jobs(&self) {
+        self.runtime.pause_jobs()
+    }
+
+    pub fn resume_jobs(&self) {
+        self.runtime.resume_jobs()
+    }
+
+    pub fn run_job_now(&self, name: String) -> Result<(), String> {
+        self.runtime.run_job_now(&name).map_err(|e| e.to_string())
+    }
+
+    pub async fn database_query(
         &self,
-        ffi_endpoint: FfiHttpEndpoint,
-    ) -> Result<FfiHttpResponse, String> {
-        let domain_endpoint = ffi_endpoint.into();
-        let domain_response = self
+        sql: String,
+        params: Vec<FfiDatabaseValue>,
+    ) -> Result<FfiQueryResult, String> {
+        let result = self
             .runtime
-            .execute_http(domain_endpoint)
-            .map_err(|e| e.to_string())?
+            .database_query(sql, params.into_iter().map(Into::into).collect())
             .await
-            .map_err(|e| e.to_string())?
             .map_err(|e| e.to_string())?;
 
-        Ok(FfiHttpResponse::from(domain_response))
+        Ok(result.into())
     }
 
-    pub async fn execute_stream_http_endpoint(
+    pub async fn database_execute(
         &self,
-        ffi_endpoint: FfiHttpEndpoint,
-    ) -> Result<FfiHttpStreamResponse, String> {
-        let domain_endpoint = ffi_endpoint.into();
-        let domain_response = self
+        sql: String,
+        params: Vec<FfiDatabaseValue>,
+    ) -> Result<FfiExecuteResult, String> {
+        let result = self
             .runtime
-            .execute_stream_http(domain_endpoint)
-            .map_err(|e| e.to_string())?
+            .database_execute(sql, params.into_iter().map(Into::into).collect())
             .await
-            .map_err(|e| e.to_string())?
             .map_err(|e| e.to_string())?;
 
-        Ok(FfiHttpStreamResponse::from(domain_response))
+        Ok(result.into())
     }
 
-    pub async fn read_file(&self, ffi_read_file: FfiReadFile) -> Result<Vec<u8>, String> {
-        let domain_read_file = ffi_read_file.into();
-        let data = self
+    pub async fn database_execute_batch(&self, sql: String) -> Result<(), String> {
+        self.runtime
+            .database_execute_batch(sql)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn database_transaction(
+        &self,
+        statements: Vec<FfiDatabaseStatement>,
+    ) -> Result<Vec<FfiExecuteResult>, String> {
+        let results = self
             .runtime
-            .read_file(domain_read_file)
+            .database_transaction(statements.into_iter().map(Into::into).collect())
             .await
-            .map_err(|e| e.to_string())?
             .map_err(|e| e.to_string())?;
 
-        Ok(data)
+        Ok(results.into_iter().map(FfiExecuteResult::from).collect())
     }
 
-    pub async fn write_file(&self, ffi_write_file: FfiWriteFile) -> Result<(), String> {
-        let domain_write_file = WriteFile::from(&ffi_write_file);
-        let data = self
+    pub async fn watch_path(
+        &self,
+        path: String,
+        debounce_millis: u64,
+        sink: StreamSink<FfiStorageEvent>,
+    ) -> Result<(), String> {
+        let stream = self
             .runtime
-            .write_file(domain_write_file)
+            .watch_path(path, Duration::from_millis(debounce_millis))
             .await
-            .map_err(|e| e.to_string())?
             .map_err(|e| e.to_string())?;
+        // the watch stops once dart no longer listens
+        let events = stream.map(|event| Ok(FfiStorageEvent::from(event)));
+        forward_stream(&self.runtime, events, sink);
+        Ok(())
+    }
 
-        Ok(data)
+    pub async fn disk_usage(&self, path: String) -> Result<FfiDiskUsage, String> {
+        let usage = self
+            .runtime
+            .disk_usage(path)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(usage.into())
+    }
+
+    pub async fn dir_size(&self, path: String) -> Result<u64, String> {
+        let size = self
+            .runtime
+            .dir_size(path)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(size)
+    }
+
+    pub async fn create_temp_file(
+        &self,
+        prefix: String,
+        extension: Option<String>,
+    ) -> Result<String, String> {
+        let path = self
+            .runtime
+            .create_temp_file(prefix, extension)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(path)
+    }
+
+    pub async fn unique_path(
+        &self,
+        dir: String,
+        base: String,
+        extension: Option<String>,
+    ) -> Result<String, String> {
+        let path = self
+            .runtime
+            .unique_path(dir, base, extension)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(path)
+    }
+
+    pub async fn file_exists(&self, path: String) -> Result<bool, String> {
+        let exists = self
+            .runtime
+            .file_exists(path)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(exists)
+    }
+
+    pub async fn file_metadata(&self, path: String) -> Result<FfiFileMetadata, String> {
+        let metadata = self
+            .runtime
+            .file_metadata(path)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(metadata.into())
+    }
+
+    pub async fn is_file(&self, path: String) -> Result<bool, String> {
+        let is_file = self
+            .runtime
+            .is_file(path)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(is_file)
+    }
+
+    pub async fn is_dir(&self, path: String) -> Result<bool, String> {
+        let is_dir = self
+            .runtime
+            .is_dir(path)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(is_dir)
+    }
+
+    pub async fn cleanup_temp_files(&self, older_than_millis: u64) -> Result<u64, String> {
+        let removed = self
+            .runtime
+            .cleanup_temp_files(Duration::from_millis(older_than_millis))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(removed)
     }
 
     pub async fn file_cache_cache(
         &self,
-        channel: &String,
+        channel: &str,
+        tag: String,
+        sentence: String,
+        bytes: &[u8],
+    ) -> Result<(), String> {
+        self.runtime
+            .file_cache_cache(channel, tag, sentence, bytes)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn file_cache_cache_with_ttl(
+        &self,
+        channel: &str,
         tag: String,
         sentence: String,
-        bytes: &Vec<u8>,
+        bytes: &[u8],
+        ttl_millis: Option<u64>,
     ) -> Result<(), String> {
+        self.runtime
+            .file_cache_cache_with_ttl(
+                channel,
+                tag,
+                sentence,
+                bytes,
+                ttl_millis.map(Duration::from_millis),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn file_cache_sweep_expired(&self, channel: &str) -> Result<usize, String> {
         let data = self
             .runtime
-            .file_cache_cache(channel, tag, sentence, bytes)
+            .file_cache_sweep_expired(channel)
             .await
-            .map_err(|e| e.to_string())?
             .map_err(|e| e.to_string())?;
         Ok(data)
     }
 
+    pub async fn file_cache_cache_stream(
+        &self,
+        channel: &str,
+        tag: String,
+        sentence: String,
+        chunks: BoxStream<'static, Vec<u8>>,
+    ) -> Result<(), String> {
+        let reader =
+            StreamReader::new(chunks.map(|chunk| Ok::<_, std::io::Error>(Bytes::from(chunk))));
+        self.runtime
+            .file_cache_cache_stream(channel, tag, sentence, Box::pin(reader))
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn file_cache_fetch_stream(
+        &self,
+        channel: &str,
+        tag: &str,
+        sink: StreamSink<Vec<u8>>,
+    ) -> Result<(), String> {
+        let stream = self
+            .runtime
+            .file_cache_fetch_stream(channel, tag)
+            .await
+            .map_err(|e| e.to_string())?;
+        let chunks = stream.map(|chunk| {
+            chunk.map(|chunk| chunk.to_vec()).map_err(|e| e.to_string())
+        });
+        forward_stream(&self.runtime, chunks, sink);
+        Ok(())
+    }
+
     pub async fn file_cache_should_update(
         &self,
-        channel: &String,
-        tag: &String,
-        sentence: &String,
+        channel: &str,
+        tag: &str,
+        sentence: &str,
     ) -> Result<bool, String> {
         let data = self
             .runtime
             .file_cache_should_update(channel, tag, sentence)
             .await
-            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+        Ok(data)
+    }
+
+    pub async fn file_cache_remove_if_stale(
+        &self,
+        channel: &str,
+        tag: &str,
+        sentence: &str,
+    ) -> Result<bool, String> {
+        let data = self
+            .runtime
+            .file_cache_remove_if_stale(channel, tag, sentence)
+            .await
             .map_err(|e| e.to_string())?;
         Ok(data)
     }
 
     pub async fn file_cache_fetch(
         &self,
-        channel: &String,
-        tag: &String,
+        channel: &str,
+        tag: &str,
     ) -> Result<Vec<u8>, String> {
         let data = self
             .runtime
             .file_cache_fetch(channel, tag)
             .await
-            .map_err(|e| e.to_string())?
             .map_err(|e| e.to_string())?;
         Ok(data)
     }
 
-    pub async fn file_cache_flush(&self, channel: &String, tag: &String) -> Result<(), String> {
+    pub async fn file_cache_flush(&self, channel: &str, tag: &str) -> Result<(), String> {
+        self.runtime
+            .file_cache_flush(channel, tag)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn file_cache_persist(&self, channel: &str) -> Result<(), String> {
+        self.runtime
+            .file_cache_persist(channel)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn file_cache_clear(&self, channel: &str) -> Result<(), String> {
+        self.runtime
+            .file_cache_clear(channel)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn file_cache_create_channel(
+        &self,
+        name: String,
+        extension: Option<String>,
+        options: FfiCacheChannelOptions,
+    ) -> Result<(), String> {
+        let channel_config = options.into_channel_config(name, extension)?;
+        self.runtime
+            .file_cache_create_channel(channel_config)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn file_cache_delete_channel(&self, channel: &str) -> Result<(), String> {
+        self.runtime
+            .file_cache_delete_channel(channel)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn file_cache_export_channel(
+        &self,
+        task_id: u64,
+        channel: &str,
+        path: &str,
+    ) -> Result<(), String> {
+        let runtime = self.runtime.clone();
+        let (channel, path) = (channel.to_string(), path.to_string());
+        let name = format!("file_cache_export_channel {}", channel);
+        self.cancelable(task_id, name, None, async move {
+            runtime
+                .file_cache_export_channel(&channel, &path)
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    pub async fn file_cache_import_channel(
+        &self,
+        task_id: u64,
+        path: &str,
+    ) -> Result<String, String> {
+        let runtime = self.runtime.clone();
+        let path = path.to_string();
+        self.cancelable(task_id, "file_cache_import_channel", None, async move {
+            runtime
+                .file_cache_import_channel(&path)
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    pub async fn file_cache_clear_all(&self, task_id: u64) -> Result<(), String> {
+        let runtime = self.runtime.clone();
+        self.cancelable(task_id, "file_cache_clear_all", None, async move {
+            runtime
+                .file_cache_clear_all()
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    pub async fn file_cache_verify_all(
+        &self,
+        task_id: u64,
+        channel: &str,
+        purge: bool,
+    ) -> Result<Vec<String>, String> {
+        let runtime = self.runtime.clone();
+        let channel = channel.to_string();
+        let name = format!("file_cache_verify_all {}", channel);
+        self.cancelable(task_id, name, None, async move {
+            runtime
+                .file_cache_verify_all(&channel, purge)
+                .await
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    pub async fn file_cache_record(
+        &self,
+        channel: &str,
+        tag: &str,
+    ) -> Result<FfiCacheRecord, String> {
         let data = self
             .runtime
-            .file_cache_flush(channel, tag)
+            .file_cache_record(channel, tag)
             .await
-            .map_err(|e| e.to_string())?
             .map_err(|e| e.to_string())?;
-        Ok(data)
+        Ok(FfiCacheRecord::from(data))
+    }
+
+    pub async fn file_cache_pin(&self, channel: &str, tag: &str) -> Result<(), String> {
+        self.runtime
+            .file_cache_pin(channel, tag)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn file_cache_unpin(&self, channel: &str, tag: &str) -> Result<(), String> {
+        self.runtime
+            .file_cache_unpin(channel, tag)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn file_cache_update_metadata(
+        &self,
+        channel: &str,
+        tag: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<(), String> {
+        self.runtime
+            .file_cache_update_metadata(channel, tag, metadata)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
     }
 
-    pub async fn file_cache_persist(&self, channel: &String) -> Result<(), String> {
+    pub async fn file_cache_stats(&self, channel: &str) -> Result<FfiCacheStats, String> {
         let data = self
             .runtime
-            .file_cache_persist(channel)
+            .file_cache_stats(channel)
             .await
-            .map_err(|e| e.to_string())?
             .map_err(|e| e.to_string())?;
-        Ok(data)
+        Ok(FfiCacheStats::from(data))
+    }
+
+    pub async fn file_cache_stats_all(&self) -> Result<Vec<FfiCacheStats>, String> {
+        let data = self
+            .runtime
+            .file_cache_stats_all()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(data.into_iter().map(FfiCacheStats::from).collect())
+    }
+
+    pub async fn file_cache_list(
+        &self,
+        channel: &str,
+        filter: Option<FfiCacheRecordFilter>,
+    ) -> Result<Vec<FfiCacheRecord>, String> {
+        let data = self
+            .runtime
+            .file_cache_list(channel, filter.map(|filter| filter.into()))
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(data.into_iter().map(FfiCacheRecord::from).collect())
     }
 
-    pub async fn file_cache_path(&self, channel: &String, tag: &String) -> Result<String, String> {
+    pub async fn file_cache_path(&self, channel: &str, tag: &str) -> Result<String, String> {
         let data = self
             .runtime
             .file_cache_path(channel, tag)
             .await
-            .map_err(|e| e.to_string())?
             .map_err(|e| e.to_string())?;
         Ok(data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::ffi::service_ffi_adapter::within_overall_timeout;
+
+    #[test]
+    fn test_overall_timeout() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let result = within_overall_timeout(Some(10), async {
+                tokio::time::sleep(std::time::Duration::from_secs(3600)).await;
+                Ok(())
+            })
+            .await;
+            assert_eq!(result.unwrap_err(), "Overall timeout after 10 ms");
+
+            let result = within_overall_timeout(Some(1000), async { Ok(7) }).await;
+            assert_eq!(result.unwrap(), 7);
+            let result: Result<(), String> =
+                within_overall_timeout(None, async { Err("failed".to_string()) }).await;
+            assert_eq!(result.unwrap_err(), "failed");
+        });
+    }
+}
@@ -1,11 +1,16 @@
-use crate::domain::models::storage_models::{EnsureMode, ReadFile, WriteFile, WriteMode};
-use std::sync::Arc;
+use crate::adapters::ffi::file_cache::models::FfiCompressionKind;
+use crate::domain::models::storage_models::{
+    DeleteFile, DirEntry, DiskUsage, EnsureMode, FileHash, FileMetadata, HashAlgorithm, ReadFile,
+    StorageError, StorageEvent, StorageEventKind, TransferFile, WriteFile, WriteMode,
+};
 use std::time::Duration;
 
 #[derive(Clone)]
 pub struct FfiReadFile {
     pub path: String,
     pub timeout_millis: u64,
+    // bounds the whole call, batches ignore it
+    pub overall_timeout_millis: Option<u64>,
 }
 
 #[derive(Clone)]
@@ -14,13 +19,102 @@ pub struct FfiWriteFile {
     pub mode: FfiWriteMode,
     pub timeout_millis: u64,
     pub ensure_mode: Option<FfiEnsureMode>,
+    pub compression: Option<FfiCompressionKind>,
     pub data: Vec<u8>,
+    // bounds the whole call, batches ignore it
+    pub overall_timeout_millis: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct FfiDeleteFile {
+    pub path: String,
+    pub timeout_millis: u64,
+    pub overall_timeout_millis: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct FfiTransferFile {
+    pub from: String,
+    pub to: String,
+    pub timeout_millis: u64,
+    pub overall_timeout_millis: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct FfiDirEntry {
+    pub name: String,
+    pub size: u64,
+    pub modified_millis: u64,
+    pub is_dir: bool,
+}
+
+#[derive(Clone)]
+pub struct FfiFileMetadata {
+    pub size: u64,
+    pub modified_millis: u64,
+    pub created_millis: u64,
+    pub readonly: bool,
+    pub is_dir: bool,
+}
+
+#[derive(Clone)]
+pub enum FfiHashAlgorithm {
+    Xxh3,
+    Sha256,
+}
+
+#[derive(Clone)]
+pub struct FfiFileHash {
+    pub algorithm: FfiHashAlgorithm,
+    // lowercase hex digest
+    pub hex: String,
+}
+
+#[derive(Clone)]
+pub struct FfiProgress {
+    pub value: u64,
+    pub total: u64,
+    pub delta: u64,
+}
+
+// one entry of a batch, exactly one of data and error is set
+#[derive(Clone)]
+pub struct FfiReadResult {
+    pub data: Option<Vec<u8>>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct FfiWriteResult {
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct FfiDiskUsage {
+    pub total: u64,
+    pub free: u64,
+    pub available: u64,
+}
+
+#[derive(Clone)]
+pub enum FfiStorageEventKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+#[derive(Clone)]
+pub struct FfiStorageEvent {
+    pub path: String,
+    pub kind: FfiStorageEventKind,
 }
 
 #[derive(Clone)]
 pub enum FfiWriteMode {
     Cover,
+    Truncate,
     Append,
+    Atomic,
 }
 
 #[derive(Clone)]
@@ -31,10 +125,11 @@ pub enum FfiEnsureMode {
 }
 
 impl FfiReadFile {
-    pub fn new(path: String, timeout_millis: u64) -> Self {
+    pub fn new(path: String, timeout_millis: u64, overall_timeout_millis: Option<u64>) -> Self {
         Self {
             path,
             timeout_millis,
+            overall_timeout_millis,
         }
     }
 }
@@ -45,30 +140,95 @@ impl FfiWriteFile {
         mode: FfiWriteMode,
         timeout_millis: u64,
         ensure_mode: Option<FfiEnsureMode>,
+        compression: Option<FfiCompressionKind>,
         data: Vec<u8>,
+        overall_timeout_millis: Option<u64>,
     ) -> Self {
         Self {
             path,
             mode,
             timeout_millis,
             ensure_mode,
+            compression,
             data,
+            overall_timeout_millis,
         }
     }
 }
 
-impl Into<WriteMode> for FfiWriteMode {
-    fn into(self) -> WriteMode {
-        match self {
+impl FfiDeleteFile {
+    pub fn new(path: String, timeout_millis: u64, overall_timeout_millis: Option<u64>) -> Self {
+        Self {
+            path,
+            timeout_millis,
+            overall_timeout_millis,
+        }
+    }
+}
+
+impl FfiTransferFile {
+    pub fn new(
+        from: String,
+        to: String,
+        timeout_millis: u64,
+        overall_timeout_millis: Option<u64>,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            timeout_millis,
+            overall_timeout_millis,
+        }
+    }
+}
+
+impl FfiFileHash {
+    pub fn new(algorithm: FfiHashAlgorithm, hex: String) -> Self {
+        Self { algorithm, hex }
+    }
+
+    pub fn into_file_hash(self) -> Result<FileHash, String> {
+        FileHash::from_hex(self.algorithm.into(), &self.hex.to_ascii_lowercase())
+            .ok_or_else(|| format!("invalid hex digest: {}", self.hex))
+    }
+}
+
+impl From<FfiHashAlgorithm> for HashAlgorithm {
+    fn from(value: FfiHashAlgorithm) -> Self {
+        match value {
+            FfiHashAlgorithm::Xxh3 => HashAlgorithm::Xxh3,
+            FfiHashAlgorithm::Sha256 => HashAlgorithm::Sha256,
+        }
+    }
+}
+
+impl From<FileHash> for FfiFileHash {
+    fn from(value: FileHash) -> Self {
+        let algorithm = match value.algorithm() {
+            HashAlgorithm::Xxh3 => FfiHashAlgorithm::Xxh3,
+            HashAlgorithm::Sha256 => FfiHashAlgorithm::Sha256,
+        };
+        FfiFileHash {
+            algorithm,
+            hex: value.to_hex(),
+        }
+    }
+}
+
+impl From<FfiWriteMode> for WriteMode {
+    fn from(value: FfiWriteMode) -> Self {
+        match value {
             FfiWriteMode::Cover => WriteMode::Cover,
+            FfiWriteMode::Truncate => WriteMode::Truncate,
             FfiWriteMode::Append => WriteMode::Append,
+            FfiWriteMode::Atomic => WriteMode::Atomic,
         }
     }
 }
 
-impl Into<EnsureMode> for FfiEnsureMode {
-    fn into(self) -> EnsureMode {
-        match self {
+impl From<FfiEnsureMode> for EnsureMode {
+    fn from(value: FfiEnsureMode) -> Self {
+        match value {
             FfiEnsureMode::Flush => EnsureMode::Flush,
             FfiEnsureMode::SyncData => EnsureMode::SyncData,
             FfiEnsureMode::SyncAll => EnsureMode::SyncAll,
@@ -76,11 +236,11 @@ impl Into<EnsureMode> for FfiEnsureMode {
     }
 }
 
-impl Into<ReadFile> for FfiReadFile {
-    fn into(self) -> ReadFile {
+impl From<FfiReadFile> for ReadFile {
+    fn from(value: FfiReadFile) -> Self {
         ReadFile {
-            path: self.path,
-            timeout: Duration::from_millis(self.timeout_millis),
+            path: value.path,
+            timeout: Duration::from_millis(value.timeout_millis),
         }
     }
 }
@@ -95,7 +255,100 @@ impl<'a> From<&'a FfiWriteFile> for WriteFile<'a> {
                 .clone()
                 .ensure_mode
                 .map(|ensure_mode| ensure_mode.into()),
+            compression: value
+                .clone()
+                .compression
+                .map(|compression| compression.into()),
             data: &value.data,
         }
     }
 }
+
+impl From<FfiDeleteFile> for DeleteFile {
+    fn from(value: FfiDeleteFile) -> Self {
+        DeleteFile {
+            path: value.path,
+            timeout: Duration::from_millis(value.timeout_millis),
+        }
+    }
+}
+
+impl From<FfiTransferFile> for TransferFile {
+    fn from(value: FfiTransferFile) -> Self {
+        TransferFile {
+            from: value.from,
+            to: value.to,
+            timeout: Duration::from_millis(value.timeout_millis),
+        }
+    }
+}
+
+impl From<DirEntry> for FfiDirEntry {
+    fn from(value: DirEntry) -> Self {
+        FfiDirEntry {
+            name: value.name,
+            size: value.size,
+            modified_millis: value.modified,
+            is_dir: value.is_dir,
+        }
+    }
+}
+
+impl From<FileMetadata> for FfiFileMetadata {
+    fn from(value: FileMetadata) -> Self {
+        FfiFileMetadata {
+            size: value.size,
+            modified_millis: value.modified,
+            created_millis: value.created,
+            readonly: value.readonly,
+            is_dir: value.is_dir,
+        }
+    }
+}
+
+impl From<StorageEvent> for FfiStorageEvent {
+    fn from(value: StorageEvent) -> Self {
+        let kind = match value.kind {
+            StorageEventKind::Created => FfiStorageEventKind::Created,
+            StorageEventKind::Modified => FfiStorageEventKind::Modified,
+            StorageEventKind::Removed => FfiStorageEventKind::Removed,
+        };
+        FfiStorageEvent {
+            path: value.path,
+            kind,
+        }
+    }
+}
+
+impl From<DiskUsage> for FfiDiskUsage {
+    fn from(value: DiskUsage) -> Self {
+        FfiDiskUsage {
+            total: value.total,
+            free: value.free,
+            available: value.available,
+        }
+    }
+}
+
+impl From<Result<Vec<u8>, StorageError>> for FfiReadResult {
+    fn from(value: Result<Vec<u8>, StorageError>) -> Self {
+        match value {
+            Ok(data) => FfiReadResult {
+                data: Some(data),
+                error: None,
+            },
+            Err(e) => FfiReadResult {
+                data: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+impl From<Result<(), StorageError>> for FfiWriteResult {
+    fn from(value: Result<(), StorageError>) -> Self {
+        FfiWriteResult {
+            error: value.err().map(|e| e.to_string()),
+        }
+    }
+}
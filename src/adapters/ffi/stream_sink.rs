@@ -0,0 +1,72 @@
+use crate::adapters::ffi::errors::FfiAdapterError;
+use std::sync::Arc;
+
+// the dart end of a stream the adapter pushes into, the bridge builds it around the sink dart
+// handed over, dropping the last clone closes the stream on the dart side
+pub struct StreamSink<T> {
+    add: Arc<dyn Fn(T) -> bool + Send + Sync>,
+    add_error: Arc<dyn Fn(String) -> bool + Send + Sync>,
+}
+
+impl<T> Clone for StreamSink<T> {
+    fn clone(&self) -> Self {
+        Self {
+            add: self.add.clone(),
+            add_error: self.add_error.clone(),
+        }
+    }
+}
+
+impl<T> StreamSink<T> {
+    // both return false once dart stopped listening
+    pub fn new(
+        add: impl Fn(T) -> bool + Send + Sync + 'static,
+        add_error: impl Fn(String) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            add: Arc::new(add),
+            add_error: Arc::new(add_error),
+        }
+    }
+
+    pub fn add(&self, value: T) -> Result<(), FfiAdapterError> {
+        if (self.add)(value) {
+            Ok(())
+        } else {
+            Err(FfiAdapterError::StreamClosed)
+        }
+    }
+
+    pub fn add_error(&self, error: String) -> Result<(), FfiAdapterError> {
+        if (self.add_error)(error) {
+            Ok(())
+        } else {
+            Err(FfiAdapterError::StreamClosed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapters::ffi::stream_sink::StreamSink;
+
+    #[test]
+    fn test_stream_sink() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let error_sender = sender.clone();
+        let sink = StreamSink::new(
+            move |value: u32| sender.send(Ok(value)).is_ok(),
+            move |error| error_sender.send(Err(error)).is_ok(),
+        );
+
+        sink.add(1).unwrap();
+        sink.clone().add_error("failed".to_string()).unwrap();
+        assert_eq!(receiver.try_recv().unwrap(), Ok(1));
+        assert_eq!(receiver.try_recv().unwrap(), Err("failed".to_string()));
+
+        // dart stopped listening
+        drop(receiver);
+        assert!(sink.add(2).is_err());
+        assert!(sink.add_error("failed".to_string()).is_err());
+    }
+}
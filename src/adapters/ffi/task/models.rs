@@ -0,0 +1,35 @@
+use crate::domain::models::task_models::{TaskInfo, TaskOutcome};
+
+#[derive(Clone)]
+pub struct FfiTaskInfo {
+    pub id: u64,
+    pub name: String,
+    pub started_millis: u64,
+}
+
+#[derive(Clone)]
+pub enum FfiTaskOutcome {
+    Completed,
+    Cancelled,
+    Panicked,
+}
+
+impl From<TaskInfo> for FfiTaskInfo {
+    fn from(value: TaskInfo) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            started_millis: value.started,
+        }
+    }
+}
+
+impl From<TaskOutcome> for FfiTaskOutcome {
+    fn from(value: TaskOutcome) -> Self {
+        match value {
+            TaskOutcome::Completed => FfiTaskOutcome::Completed,
+            TaskOutcome::Cancelled => FfiTaskOutcome::Cancelled,
+            TaskOutcome::Panicked => FfiTaskOutcome::Panicked,
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use crate::domain::models::upload_models::{
+    UploadEvent, UploadProtocol, UploadRequest, UploadState, UploadTask,
+};
+
+#[derive(Clone)]
+pub enum FfiUploadProtocol {
+    Multipart {
+        field_name: String,
+        file_name: Option<String>,
+    },
+    ChunkedRange {
+        chunk_size: u64,
+    },
+    Tus {
+        chunk_size: u64,
+    },
+}
+
+#[derive(Clone)]
+pub struct FfiUploadRequest {
+    pub url: String,
+    pub source_path: String,
+    pub protocol: FfiUploadProtocol,
+    pub priority: i32,
+    pub headers: Vec<(String, String)>,
+}
+
+#[derive(Clone)]
+pub enum FfiUploadState {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Clone)]
+pub struct FfiUploadTask {
+    pub id: String,
+    pub url: String,
+    pub source_path: String,
+    pub protocol: FfiUploadProtocol,
+    pub priority: i32,
+    pub state: FfiUploadState,
+    pub uploaded: u64,
+    pub total: Option<u64>,
+    pub attempts: u32,
+    pub error: Option<String>,
+    pub response_status: Option<u16>,
+    pub response_body: Option<Vec<u8>>,
+}
+
+#[derive(Clone)]
+pub struct FfiUploadEvent {
+    pub id: String,
+    pub state: FfiUploadState,
+    pub uploaded: u64,
+    pub total: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl FfiUploadRequest {
+    pub fn new(
+        url: String,
+        source_path: String,
+        protocol: FfiUploadProtocol,
+        priority: i32,
+        headers: Vec<(String, String)>,
+    ) -> Self {
+        Self {
+            url,
+            source_path,
+            protocol,
+            priority,
+            headers,
+        }
+    }
+}
+
+impl From<FfiUploadProtocol> for UploadProtocol {
+    fn from(value: FfiUploadProtocol) -> Self {
+        match value {
+            FfiUploadProtocol::Multipart {
+                field_name,
+                file_name,
+            } => UploadProtocol::Multipart {
+                field_name,
+                file_name,
+            },
+            FfiUploadProtocol::ChunkedRange { chunk_size } => {
+                UploadProtocol::ChunkedRange { chunk_size }
+            }
+            FfiUploadProtocol::Tus { chunk_size } => UploadProtocol::Tus { chunk_size },
+        }
+    }
+}
+
+impl From<UploadProtocol> for FfiUploadProtocol {
+    fn from(value: UploadProtocol) -> Self {
+        match value {
+            UploadProtocol::Multipart {
+                field_name,
+                file_name,
+            } => FfiUploadProtocol::Multipart {
+                field_name,
+                file_name,
+            },
+            UploadProtocol::ChunkedRange { chunk_size } => {
+                FfiUploadProtocol::ChunkedRange { chunk_size }
+            }
+            UploadProtocol::Tus { chunk_size } => FfiUploadProtocol::Tus { chunk_size },
+        }
+    }
+}
+
+impl From<FfiUploadRequest> for UploadRequest {
+    fn from(value: FfiUploadRequest) -> Self {
+        UploadRequest {
+            url: value.url,
+            source_path: value.source_path,
+            protocol: value.protocol.into(),
+            priority: value.priority,
+            headers: value.headers,
+        }
+    }
+}
+
+impl From<UploadState> for FfiUploadState {
+    fn from(value: UploadState) -> Self {
+        match value {
+            UploadState::Queued => FfiUploadState::Queued,
+            UploadState::Running => FfiUploadState::Running,
+            UploadState::Paused => FfiUploadState::Paused,
+            UploadState::Completed => FfiUploadState::Completed,
+            UploadState::Failed => FfiUploadState::Failed,
+            UploadState::Cancelled => FfiUploadState::Cancelled,
+        }
+    }
+}
+
+impl From<UploadTask> for FfiUploadTask {
+    fn from(value: UploadTask) -> Self {
+        Self {
+            id: value.id,
+            url: value.url,
+            source_path: value.source_path,
+            protocol: value.protocol.into(),
+            priority: value.priority,
+            state: value.state.into(),
+            uploaded: value.uploaded,
+            total: value.total,
+            attempts: value.attempts,
+            error: value.error,
+            response_status: value.response_status,
+            response_body: value.response_body,
+        }
+    }
+}
+
+impl From<UploadEvent> for FfiUploadEvent {
+    fn from(value: UploadEvent) -> Self {
+        Self {
+            id: value.id,
+            state: value.state.into(),
+            uploaded: value.uploaded,
+            total: value.total,
+            error: value.error,
+        }
+    }
+}
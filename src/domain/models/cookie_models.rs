@@ -19,6 +19,12 @@ pub struct Cookie {
     pub http_only: bool,
     pub same_site: Option<SameSite>,
     pub persistent: bool,
+    #[serde(default = "default_host_only")]
+    pub host_only: bool,
+}
+
+fn default_host_only() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +35,47 @@ pub enum SameSite {
     None,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum CookieFormat {
+    Netscape,
+    DartJson,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CookieStats {
+    pub total: usize,
+    pub persistent: usize,
+    pub session: usize,
+    pub expired: usize,
+    pub per_domain: Vec<(String, usize)>,
+    pub storage_size: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum CookieChange {
+    Set(Cookie),
+    Removed(CookieKey),
+    Cleared,
+}
+
+// what the last load found in the cookie file, a store that failed to load starts without the
+// cookies and holds the error
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CookieLoadReport {
+    pub loaded: usize,
+    pub expired: usize,
+    // cookies that could not be read and were dropped
+    pub malformed: usize,
+    pub error: Option<String>,
+}
+
+impl CookieLoadReport {
+    // false when cookies were lost, which usually signs the user out
+    pub fn is_clean(&self) -> bool {
+        self.malformed == 0 && self.error.is_none()
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum CookieError {
     #[error("Storage error: {0}")]
@@ -38,10 +85,13 @@ pub enum CookieError {
     #[error("IO error: {0}")]
     IO(String),
     #[error("Timeout error: {0}")]
-    Timeout(String)
+    Timeout(String),
+    #[error("Cookie rejected: {0}")]
+    Rejected(String),
 }
 
 impl Cookie {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         domain: String,
         path: String,
@@ -63,6 +113,7 @@ impl Cookie {
             http_only,
             same_site,
             persistent: expires.is_some(),
+            host_only: true,
         }
     }
     
@@ -86,6 +137,7 @@ impl Cookie {
             http_only,
             same_site,
             persistent: false,
+            host_only: true,
         }
     }
 
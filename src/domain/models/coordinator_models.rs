@@ -253,9 +253,9 @@ impl Hash for Identifier {
     }
 }
 
-impl Into<String> for Identifier {
-    fn into(self) -> String {
-        self.id
+impl From<Identifier> for String {
+    fn from(val: Identifier) -> Self {
+        val.id
     }
 }
 
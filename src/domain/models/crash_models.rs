@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    // milliseconds since the unix epoch
+    pub timestamp: u64,
+    pub thread: Option<String>,
+    // set when the panic happened inside a task started through guard_task
+    pub task: Option<String>,
+    pub message: String,
+    // file:line:column of the panic
+    pub location: Option<String>,
+    pub backtrace: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CrashError {
+    #[error("Storage error: {0}")]
+    Storage(String),
+    #[error("Crash report could not be serialized: {0}")]
+    Serialization(String),
+}
@@ -0,0 +1,48 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum DatabaseValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+// one statement with its positional parameters, bound as ?1, ?2, ...
+#[derive(Debug, Clone)]
+pub struct DatabaseStatement {
+    pub sql: String,
+    pub params: Vec<DatabaseValue>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    // every row holds one value per column, in column order
+    pub rows: Vec<Vec<DatabaseValue>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecuteResult {
+    pub rows_affected: u64,
+    pub last_insert_id: i64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DatabaseError {
+    #[error("SQLite Error: {0}")]
+    Sqlite(String),
+    #[error("Connection pool error: {0}")]
+    Pool(String),
+}
+
+impl DatabaseStatement {
+    pub fn new(sql: String, params: Vec<DatabaseValue>) -> Self {
+        Self { sql, params }
+    }
+}
+
+impl QueryResult {
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|column| column == name)
+    }
+}
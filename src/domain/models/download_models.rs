@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone)]
+pub struct DownloadRequest {
+    pub url: String,
+    pub target_path: String,
+    // higher runs first, equal priorities run in the order they were enqueued
+    pub priority: i32,
+    pub headers: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DownloadState {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+// persisted with the queue, the received bytes live in a ".part" file next to the target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadTask {
+    pub id: String,
+    pub url: String,
+    pub target_path: String,
+    pub priority: i32,
+    pub headers: Vec<(String, String)>,
+    pub state: DownloadState,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub attempts: u32,
+    pub error: Option<String>,
+    // sent as If-Range so a changed file is downloaded again instead of resumed
+    pub etag: Option<String>,
+    pub sequence: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadEvent {
+    pub id: String,
+    pub state: DownloadState,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DownloadError {
+    #[error("Download {0} does not exist")]
+    NotExist(String),
+    #[error("Download {id} cannot be {action} while {state:?}")]
+    InvalidState {
+        id: String,
+        action: String,
+        state: DownloadState,
+    },
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("Unexpected status {0}")]
+    Status(u16),
+    #[error("HTTP error: {0}")]
+    Http(String),
+    #[error("Storage error: {0}")]
+    Storage(String),
+    #[error("Download queue could not be persisted: {0}")]
+    Persistence(String),
+}
+
+impl DownloadRequest {
+    pub fn new(url: String, target_path: String) -> Self {
+        Self {
+            url,
+            target_path,
+            priority: 0,
+            headers: Vec::new(),
+        }
+    }
+}
+
+impl DownloadTask {
+    pub fn part_path(&self) -> String {
+        format!("{}.part", self.target_path)
+    }
+}
+
+impl From<&DownloadTask> for DownloadEvent {
+    fn from(value: &DownloadTask) -> Self {
+        Self {
+            id: value.id.clone(),
+            state: value.state,
+            downloaded: value.downloaded,
+            total: value.total,
+            error: value.error.clone(),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use crate::domain::models::cookie_models::CookieChange;
+use crate::domain::models::task_models::TaskOutcome;
+
+// everything the runtime announces on its own, delivered through one subscription
+#[derive(Debug, Clone)]
+pub enum RuntimeEvent {
+    CookieChanged(CookieChange),
+    CacheEvicted {
+        channel: String,
+        tag: String,
+        size: usize,
+    },
+    // source is the file cache channel or "cookie_store"
+    AutoSaveFailed {
+        source: String,
+        error: String,
+    },
+    // judged by whether http requests reach the network
+    ConnectivityChanged {
+        online: bool,
+    },
+    TaskFinished {
+        id: u64,
+        name: String,
+        outcome: TaskOutcome,
+    },
+}
@@ -1,9 +1,11 @@
 use rkyv::{Archive, Deserialize, Serialize, bytecheck::CheckBytes};
+use std::time::Duration;
 
 #[derive(Archive, Deserialize, Serialize, Debug, PartialEq, CheckBytes)]
 pub struct CacheChannel {
     pub name: String,
     pub extension: Option<String>,
+    pub version: u32,
     pub records: Vec<CacheRecord>,
 }
 
@@ -12,7 +14,236 @@ pub struct CacheRecord {
     pub tag: String,
     pub filename: String,
     pub size: usize,
-    pub sentence: String
+    pub sentence: String,
+    pub expires_at: Option<u64>,
+    pub last_access: u64,
+    pub cached_at: u64,
+    pub checksum: Option<u64>,
+    pub compression: Option<CompressionKind>,
+    pub encrypted: bool,
+    pub metadata: Vec<(String, String)>,
+    pub pinned: bool,
+}
+
+#[derive(Archive, Deserialize, Serialize, Debug, PartialEq, CheckBytes, Clone)]
+#[repr(u8)]
+pub enum CompressionKind {
+    Zstd,
+    Gzip,
+}
+
+// the layout channels were written in before they carried a header, read as version 0
+#[derive(Archive, Deserialize, Serialize, Debug, PartialEq, CheckBytes)]
+pub struct LegacyCacheChannel {
+    pub name: String,
+    pub extension: Option<String>,
+    pub records: Vec<LegacyCacheRecord>,
+}
+
+#[derive(Archive, Deserialize, Serialize, Debug, PartialEq, CheckBytes, Clone)]
+pub struct LegacyCacheRecord {
+    pub tag: String,
+    pub filename: String,
+    pub size: usize,
+    pub sentence: String,
+}
+
+// written in front of the archived channel: the magic, the layout and the schema version, so
+// both can be told without decoding the channel
+const CHANNEL_MAGIC: &[u8; 4] = b"SBCH";
+const CHANNEL_HEADER_LEN: usize = 12;
+const CHANNEL_LAYOUT: u32 = 1;
+
+#[derive(Debug, Clone)]
+pub struct CacheStats {
+    pub channel: String,
+    pub entries: usize,
+    pub total_bytes: u64,
+    pub dirty: bool,
+    pub last_persist: Option<u64>,
+    pub memory_hits: u64,
+    pub memory_misses: u64,
+    pub memory_bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheTask {
+    Recovery,
+    Sweep,
+    Persist,
+}
+
+// reported by background tasks, which have no caller to return errors to
+#[derive(Debug, Clone)]
+pub struct CacheDiagnostic {
+    pub channel: String,
+    pub task: CacheTask,
+    pub error: String,
+    pub at: u64,
+}
+
+// how refreshing an entry over http ended
+#[derive(Debug, Clone)]
+pub enum CacheRefresh {
+    // the server answered 304, the entry is still current
+    NotModified(CacheRecord),
+    // the entry was missing or outdated and now holds the new body
+    Updated(CacheRecord),
+}
+
+impl CacheRefresh {
+    pub fn record(&self) -> &CacheRecord {
+        match self {
+            CacheRefresh::NotModified(record) | CacheRefresh::Updated(record) => record,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CacheRecordFilter {
+    pub tag_prefix: Option<String>,
+    pub min_size: Option<usize>,
+    pub max_size: Option<usize>,
+    pub older_than: Option<Duration>,
+    pub newer_than: Option<Duration>,
+}
+
+impl From<LegacyCacheRecord> for CacheRecord {
+    fn from(value: LegacyCacheRecord) -> Self {
+        Self {
+            tag: value.tag,
+            filename: value.filename,
+            size: value.size,
+            sentence: value.sentence,
+            expires_at: None,
+            last_access: 0,
+            cached_at: 0,
+            checksum: None,
+            compression: None,
+            encrypted: false,
+            metadata: Vec::new(),
+            pinned: false,
+        }
+    }
+}
+
+impl From<LegacyCacheChannel> for CacheChannel {
+    fn from(value: LegacyCacheChannel) -> Self {
+        Self {
+            name: value.name,
+            extension: value.extension,
+            version: 0,
+            records: value.records.into_iter().map(CacheRecord::from).collect(),
+        }
+    }
+}
+
+impl CacheChannel {
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let archived = rkyv::to_bytes::<rkyv::rancor::Error>(self)
+            .map_err(|e| format!("rkyv serialization failed: {}", e))?;
+        let mut bytes = Vec::with_capacity(CHANNEL_HEADER_LEN + archived.len());
+        bytes.extend_from_slice(CHANNEL_MAGIC);
+        bytes.extend_from_slice(&CHANNEL_LAYOUT.to_le_bytes());
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+        bytes.extend_from_slice(&archived);
+        Ok(bytes)
+    }
+
+    // the channels without a header are decoded in the legacy layout
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        match Self::header(bytes) {
+            Some((CHANNEL_LAYOUT, version)) => {
+                let body = aligned(&bytes[CHANNEL_HEADER_LEN..]);
+                let mut channel = rkyv::from_bytes::<CacheChannel, rkyv::rancor::Error>(&body)
+                    .map_err(|e| e.to_string())?;
+                channel.version = version;
+                Ok(channel)
+            }
+            Some((layout, _)) => Err(format!("unknown channel layout {}", layout)),
+            None => rkyv::from_bytes::<LegacyCacheChannel, rkyv::rancor::Error>(&aligned(bytes))
+                .map(CacheChannel::from)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    // the layout and the schema version, None for a channel written without a header
+    pub fn header(bytes: &[u8]) -> Option<(u32, u32)> {
+        if bytes.len() < CHANNEL_HEADER_LEN || &bytes[..4] != CHANNEL_MAGIC {
+            return None;
+        }
+        let layout = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        Some((layout, version))
+    }
+}
+
+// rkyv reads in place and needs the archive aligned
+fn aligned(bytes: &[u8]) -> rkyv::util::AlignedVec {
+    let mut aligned = rkyv::util::AlignedVec::with_capacity(bytes.len());
+    aligned.extend_from_slice(bytes);
+    aligned
+}
+
+impl CacheRecord {
+    pub fn metadata(&self, key: &str) -> Option<&String> {
+        self.metadata
+            .iter()
+            .find(|(entry_key, _)| entry_key == key)
+            .map(|(_, value)| value)
+    }
+
+    // existing keys are overwritten, new ones are appended
+    pub fn merge_metadata(&mut self, metadata: Vec<(String, String)>) {
+        for (key, value) in metadata {
+            match self.metadata.iter_mut().find(|(entry_key, _)| *entry_key == key) {
+                Some(entry) => entry.1 = value,
+                None => self.metadata.push((key, value)),
+            }
+        }
+    }
+
+    pub fn is_expired(&self, now_millis: u64) -> bool {
+        // pinned records outlive their ttl until unpinned
+        if self.pinned {
+            return false;
+        }
+        match self.expires_at {
+            Some(expires_at) => expires_at <= now_millis,
+            None => false,
+        }
+    }
+}
+
+impl CacheRecordFilter {
+    pub fn matches(&self, record: &CacheRecord, now_millis: u64) -> bool {
+        if let Some(tag_prefix) = &self.tag_prefix
+            && !record.tag.starts_with(tag_prefix)
+        {
+            return false;
+        }
+        if self.min_size.is_some_and(|min_size| record.size < min_size) {
+            return false;
+        }
+        if self.max_size.is_some_and(|max_size| record.size > max_size) {
+            return false;
+        }
+
+        let age = now_millis.saturating_sub(record.cached_at);
+        if self
+            .older_than
+            .is_some_and(|older_than| age < older_than.as_millis() as u64)
+        {
+            return false;
+        }
+        if self
+            .newer_than
+            .is_some_and(|newer_than| age > newer_than.as_millis() as u64)
+        {
+            return false;
+        }
+        true
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -31,6 +262,70 @@ pub enum CacheError {
     Serialization(String),
     #[error("Timeout: {0}")]
     Timeout(String),
+    #[error("Tag {0} has expired")]
+    Expired(String),
+    #[error("Tag {0} is corrupted")]
+    Corrupted(String),
+    #[error("Tag {0} is stored compressed or encrypted, its file cannot be read directly")]
+    Encoded(String),
+    #[error("Encryption Error: {0}")]
+    Encryption(String),
+    #[error("Tag {0} is larger than the channel can hold")]
+    TooLarge(String),
+    #[error("Channel {0} has an incompatible version")]
+    IncompatibleVersion(String),
+    #[error("{0} is not a valid channel name")]
+    InvalidName(String),
     #[error("Error Forwarding: {0}")]
     ErrorForward(String)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::file_cache_models::{
+        CacheChannel, CacheRecord, LegacyCacheChannel, LegacyCacheRecord,
+    };
+
+    #[test]
+    fn test_channel_round_trips_with_its_header() {
+        let channel = CacheChannel {
+            name: "channel".to_string(),
+            extension: Some("json".to_string()),
+            version: 3,
+            records: vec![CacheRecord::from(LegacyCacheRecord {
+                tag: "tag".to_string(),
+                filename: "file".to_string(),
+                size: 4,
+                sentence: "sentence".to_string(),
+            })],
+        };
+        let bytes = channel.to_bytes().unwrap();
+        assert_eq!(CacheChannel::header(&bytes), Some((1, 3)));
+        assert_eq!(CacheChannel::from_bytes(&bytes).unwrap(), channel);
+    }
+
+    #[test]
+    fn test_legacy_channel_is_read_as_version_zero() {
+        // the bytes a channel was stored as before the header
+        let legacy = LegacyCacheChannel {
+            name: "channel".to_string(),
+            extension: None,
+            records: vec![LegacyCacheRecord {
+                tag: "tag".to_string(),
+                filename: "file".to_string(),
+                size: 4,
+                sentence: "sentence".to_string(),
+            }],
+        };
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&legacy).unwrap();
+        assert_eq!(CacheChannel::header(&bytes), None);
+
+        let channel = CacheChannel::from_bytes(&bytes).unwrap();
+        assert_eq!(channel.version, 0);
+        assert_eq!(channel.name, "channel");
+        assert_eq!(channel.records.len(), 1);
+        assert_eq!(channel.records[0].tag, "tag");
+        assert_eq!(channel.records[0].filename, "file");
+        assert_eq!(channel.records[0].checksum, None);
+    }
+}
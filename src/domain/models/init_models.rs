@@ -0,0 +1,47 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubsystemStatus {
+    Started,
+    // running with less than it was configured with, such as http without its cookie store
+    Degraded(String),
+    Failed(String),
+    // not configured
+    Disabled,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubsystemReport {
+    pub name: String,
+    pub status: SubsystemStatus,
+}
+
+// how each subsystem came out of initialization, kept on the runtime
+#[derive(Debug, Clone, Default)]
+pub struct InitReport {
+    pub subsystems: Vec<SubsystemReport>,
+}
+
+impl InitReport {
+    pub fn record(&mut self, name: &str, status: SubsystemStatus) {
+        self.subsystems.push(SubsystemReport {
+            name: name.to_string(),
+            status,
+        });
+    }
+
+    pub fn status(&self, name: &str) -> Option<&SubsystemStatus> {
+        self.subsystems
+            .iter()
+            .find(|subsystem| subsystem.name == name)
+            .map(|subsystem| &subsystem.status)
+    }
+
+    // true when nothing failed or degraded
+    pub fn is_healthy(&self) -> bool {
+        self.subsystems.iter().all(|subsystem| {
+            matches!(
+                subsystem.status,
+                SubsystemStatus::Started | SubsystemStatus::Disabled
+            )
+        })
+    }
+}
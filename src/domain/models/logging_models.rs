@@ -0,0 +1,36 @@
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    // milliseconds since the unix epoch
+    pub timestamp: u64,
+    pub level: LogLevel,
+    pub target: String,
+    pub message: String,
+    pub fields: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogFileInfo {
+    pub path: String,
+    pub size: u64,
+    // milliseconds since the unix epoch
+    pub modified: u64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoggingError {
+    #[error("Another global tracing subscriber is already installed: {0}")]
+    SubscriberConflict(String),
+    #[error("Log file error: {0}")]
+    Storage(String),
+    #[error("Log bundle could not be created: {0}")]
+    Bundle(String),
+}
@@ -0,0 +1,51 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CounterSample {
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+    pub value: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GaugeSample {
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistogramSample {
+    pub name: String,
+    pub labels: Vec<(String, String)>,
+    pub count: u64,
+    pub sum: f64,
+    // both 0 while nothing was recorded
+    pub min: f64,
+    pub max: f64,
+    // counts[i] holds the values at or below bounds[i] and above the previous bound,
+    // the extra last count holds the values above every bound
+    pub bounds: Vec<f64>,
+    pub counts: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsSnapshot {
+    // milliseconds since the unix epoch
+    pub timestamp: u64,
+    pub counters: Vec<CounterSample>,
+    pub gauges: Vec<GaugeSample>,
+    pub histograms: Vec<HistogramSample>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MetricsError {
+    #[error("Metrics could not be serialized: {0}")]
+    Serialization(String),
+    #[error("Storage error: {0}")]
+    Storage(String),
+    #[error("HTTP error: {0}")]
+    Http(String),
+    #[error("Unexpected status {0}")]
+    Status(u16),
+}
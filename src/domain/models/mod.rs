@@ -6,3 +6,14 @@ pub mod audio_models;
 mod error_convert;
 pub mod monitor_models;
 pub mod coordinator_models;
+pub mod database_models;
+pub mod scheduler_models;
+pub mod download_models;
+
+pub mod upload_models;
+pub mod logging_models;
+pub mod metrics_models;
+pub mod crash_models;
+pub mod task_models;
+pub mod init_models;
+pub mod event_models;
\ No newline at end of file
@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub enum JobSchedule {
+    Interval(Duration),
+    // minute hour day-of-month month day-of-week, evaluated in UTC
+    Cron(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct JobInfo {
+    pub name: String,
+    pub schedule: JobSchedule,
+    pub paused: bool,
+    pub running: bool,
+    // unix millis, kept across restarts when the scheduler has a state path
+    pub last_run: Option<u64>,
+    pub next_run: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum JobError {
+    #[error("Job {0} is already registered")]
+    AlreadyExists(String),
+    #[error("Job {0} does not exist")]
+    NotExist(String),
+    #[error("Invalid schedule: {0}")]
+    InvalidSchedule(String),
+    #[error("Job failed: {0}")]
+    Failed(String),
+    #[error("Job state could not be persisted: {0}")]
+    Persistence(String),
+}
@@ -1,17 +1,87 @@
-use std::sync::Arc;
+use crate::domain::models::file_cache_models::CompressionKind;
 use std::time::Duration;
 
+#[derive(Clone)]
 pub struct ReadFile {
     pub path: String,
     pub timeout: Duration,
 }
 
+#[derive(Clone)]
 pub struct WriteFile<'a> {
     pub path: String,
     pub mode: WriteMode,
     pub timeout: Duration,
     pub ensure_mode: Option<EnsureMode>,
-    pub data: &'a Vec<u8>,
+    // stored behind a small header, reads decompress transparently
+    pub compression: Option<CompressionKind>,
+    pub data: &'a [u8],
+}
+
+#[derive(Clone)]
+pub struct DeleteFile {
+    pub path: String,
+    pub timeout: Duration,
+}
+
+// shared by rename, copy and move
+#[derive(Clone)]
+pub struct TransferFile {
+    pub from: String,
+    pub to: String,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    pub name: String,
+    pub size: u64,
+    // milliseconds since the unix epoch, 0 when the platform does not report it
+    pub modified: u64,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileMetadata {
+    pub size: u64,
+    // milliseconds since the unix epoch, 0 when the platform does not report it
+    pub modified: u64,
+    pub created: u64,
+    pub readonly: bool,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum HashAlgorithm {
+    Xxh3,
+    Sha256,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum FileHash {
+    Xxh3(u64),
+    Sha256([u8; 32]),
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskUsage {
+    pub total: u64,
+    pub free: u64,
+    // free bytes usable without elevated privileges
+    pub available: u64,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StorageEventKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageEvent {
+    pub path: String,
+    pub kind: StorageEventKind,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -26,21 +96,54 @@ pub enum StorageError {
     IOError(String),
     #[error("Timeout: {0}")]
     Timeout(String),
+    #[error("Checksum mismatch: {0}")]
+    ChecksumMismatch(String),
+    #[error("Encryption Error: {0}")]
+    Encryption(String),
+    #[error("Access denied: {0}")]
+    AccessDenied(String),
+    #[error("Transient IO Error: {0}")]
+    Transient(String),
+    #[error("{error} (gave up after {attempts} attempts)")]
+    RetriesExhausted { attempts: u32, error: String },
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum WriteMode {
+    // overwrites from the start in place, bytes past the new end are kept
     Cover,
+    // replaces the whole content
+    Truncate,
     Append,
+    // written to a temporary sibling and renamed over the target, always fully synced
+    Atomic,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum EnsureMode {
     Flush,
     SyncData,
     SyncAll
 }
 
+// exponential backoff for transient io errors, the first attempt counts
+#[derive(Debug, Clone)]
+pub struct StorageRetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for StorageRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
 impl ReadFile {
     pub fn path(path: String) -> Self {
         Self {
@@ -51,13 +154,65 @@ impl ReadFile {
 }
 
 impl<'a> WriteFile<'a> {
-    pub fn path(path: String, data: &'a Vec<u8>) -> Self {
+    pub fn path(path: String, data: &'a [u8]) -> Self {
         Self {
             path,
-            mode: WriteMode::Cover,
+            mode: WriteMode::Truncate,
             timeout: Duration::from_secs(60),
             ensure_mode: Some(EnsureMode::Flush),
+            compression: None,
             data,
         }
     }
 }
+
+impl DeleteFile {
+    pub fn path(path: String) -> Self {
+        Self {
+            path,
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl TransferFile {
+    pub fn paths(from: String, to: String) -> Self {
+        Self {
+            from,
+            to,
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl FileHash {
+    pub fn algorithm(&self) -> HashAlgorithm {
+        match self {
+            FileHash::Xxh3(_) => HashAlgorithm::Xxh3,
+            FileHash::Sha256(_) => HashAlgorithm::Sha256,
+        }
+    }
+
+    pub fn to_hex(&self) -> String {
+        match self {
+            FileHash::Xxh3(value) => format!("{:016x}", value),
+            FileHash::Sha256(bytes) => bytes.iter().map(|byte| format!("{:02x}", byte)).collect(),
+        }
+    }
+
+    pub fn from_hex(algorithm: HashAlgorithm, hex: &str) -> Option<FileHash> {
+        match algorithm {
+            HashAlgorithm::Xxh3 => u64::from_str_radix(hex, 16).ok().map(FileHash::Xxh3),
+            HashAlgorithm::Sha256 => {
+                if hex.len() != 64 || !hex.is_ascii() {
+                    return None;
+                }
+                let mut bytes = [0u8; 32];
+                for (index, byte) in bytes.iter_mut().enumerate() {
+                    *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).ok()?;
+                }
+                Some(FileHash::Sha256(bytes))
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+#[derive(Debug, Clone)]
+pub struct TaskInfo {
+    pub id: u64,
+    pub name: String,
+    // unix millis
+    pub started: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskOutcome {
+    Completed,
+    Cancelled,
+    Panicked,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TaskError {
+    #[error("Task {0} does not exist")]
+    NotExist(u64),
+    #[error("Task {0} was cancelled")]
+    Cancelled(u64),
+    #[error("Task {0} panicked: {1}")]
+    Panicked(u64, String),
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum UploadProtocol {
+    // a single multipart/form-data POST, a failed attempt starts over
+    Multipart {
+        field_name: String,
+        file_name: Option<String>,
+    },
+    // PUTs of "Content-Range: bytes a-b/total" chunks, the server answers unfinished
+    // uploads with 308 and an optional "Range: bytes=0-n" of what it has received
+    ChunkedRange {
+        chunk_size: u64,
+    },
+    // tus 1.0.0 creation, HEAD for the offset and PATCH for the data
+    Tus {
+        chunk_size: u64,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadRequest {
+    pub url: String,
+    pub source_path: String,
+    pub protocol: UploadProtocol,
+    // higher runs first, equal priorities run in the order they were enqueued
+    pub priority: i32,
+    pub headers: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum UploadState {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+// persisted with the queue so resumable uploads continue where the server left off
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadTask {
+    pub id: String,
+    pub url: String,
+    pub source_path: String,
+    pub protocol: UploadProtocol,
+    pub priority: i32,
+    pub headers: Vec<(String, String)>,
+    pub state: UploadState,
+    pub uploaded: u64,
+    pub total: Option<u64>,
+    pub attempts: u32,
+    pub error: Option<String>,
+    // the tus upload location returned by the creation request
+    pub upload_url: Option<String>,
+    pub response_status: Option<u16>,
+    pub response_body: Option<Vec<u8>>,
+    pub sequence: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadEvent {
+    pub id: String,
+    pub state: UploadState,
+    pub uploaded: u64,
+    pub total: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UploadError {
+    #[error("Upload {0} does not exist")]
+    NotExist(String),
+    #[error("Upload {id} cannot be {action} while {state:?}")]
+    InvalidState {
+        id: String,
+        action: String,
+        state: UploadState,
+    },
+    #[error("Network error: {0}")]
+    Network(String),
+    #[error("Unexpected status {0}")]
+    Status(u16),
+    #[error("HTTP error: {0}")]
+    Http(String),
+    #[error("Storage error: {0}")]
+    Storage(String),
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+    #[error("Upload queue could not be persisted: {0}")]
+    Persistence(String),
+}
+
+impl UploadRequest {
+    pub fn new(url: String, source_path: String, protocol: UploadProtocol) -> Self {
+        Self {
+            url,
+            source_path,
+            protocol,
+            priority: 0,
+            headers: Vec::new(),
+        }
+    }
+}
+
+impl From<&UploadTask> for UploadEvent {
+    fn from(value: &UploadTask) -> Self {
+        Self {
+            id: value.id.clone(),
+            state: value.state,
+            uploaded: value.uploaded,
+            total: value.total,
+            error: value.error.clone(),
+        }
+    }
+}
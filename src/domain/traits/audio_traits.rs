@@ -4,33 +4,33 @@ use async_trait::async_trait;
 use crate::domain::models::audio_models::{AudioEngineStatus, AudioError, RawAudioData};
 
 #[async_trait]
-trait AudioSource: Send + Sync + 'static {
+pub trait AudioSource: Send + Sync + 'static {
     async fn identifier(&self) -> String;
     async fn get_data(&self) -> Result<Arc<RawAudioData>, AudioError>;
 }
 
 #[async_trait]
-trait PlaylistManager: Send + Sync + 'static {
+pub trait PlaylistManager: Send + Sync + 'static {
     async fn has_sources(&self) -> bool;
     async fn set_sources(&self, sources: Vec<Arc<dyn AudioSource>>);
     async fn remove_sources(&self) -> Option<Vec<Arc<dyn AudioSource>>>;
     async fn get_sources(&self) -> Option<Vec<Arc<dyn AudioSource>>>;
     
-    async fn has_source(&self, identifier: &String) -> bool;
+    async fn has_source(&self, identifier: &str) -> bool;
     async fn add_source(&self, source: Arc<dyn AudioSource>);
-    async fn remove_source(&self, identifier: &String) -> Option<Arc<dyn AudioSource>>;
-    async fn get_source(&self, identifier: &String) -> Option<Arc<dyn AudioSource>>;
+    async fn remove_source(&self, identifier: &str) -> Option<Arc<dyn AudioSource>>;
+    async fn get_source(&self, identifier: &str) -> Option<Arc<dyn AudioSource>>;
     
     async fn get_identifiers(&self) -> Option<Vec<String>>;
 }
 
 #[async_trait]
-trait AudioSourceShuffler: Send + Sync + 'static {
-    async fn shuffle(&self, identifiers: &Vec<String>) -> Vec<String>;
+pub trait AudioSourceShuffler: Send + Sync + 'static {
+    async fn shuffle(&self, identifiers: &[String]) -> Vec<String>;
 }
 
 #[async_trait]
-trait AudioEngine: Send + Sync + 'static {
+pub trait AudioEngine: Send + Sync + 'static {
     async fn has_shuffler(&self) -> bool;
     async fn set_shuffler(&self, shuffler: Arc<dyn AudioSourceShuffler>);
     async fn remove_shuffler(&self) -> Option<Arc<dyn AudioSourceShuffler>>;
@@ -50,7 +50,7 @@ trait AudioEngine: Send + Sync + 'static {
     async fn next_source(&self) -> Result<Arc<dyn AudioSource>, AudioError>;
 
     async fn play(&self) -> Result<(), AudioError>;
-    async fn play_by_identifier(&self, identifier: &String) -> Result<(), AudioError>;
+    async fn play_by_identifier(&self, identifier: &str) -> Result<(), AudioError>;
     async fn play_by_index(&self, index: i32) -> Result<(), AudioError>;
     async fn play_previous(&self) -> Result<(), AudioError>;
     async fn play_next(&self) -> Result<(), AudioError>;
@@ -1,7 +1,11 @@
 use std::any::Any;
 use std::sync::Arc;
+use std::time::SystemTime;
 use async_trait::async_trait;
-use crate::domain::models::cookie_models::{Cookie, CookieError, CookieKey};
+use futures_util::stream::BoxStream;
+use crate::domain::models::cookie_models::{
+    Cookie, CookieChange, CookieError, CookieFormat, CookieKey, CookieStats,
+};
 
 impl dyn CookieStore {
     pub fn downcast_arc<T: CookieStore>(self: Arc<Self>) -> Option<Arc<T>> {
@@ -19,7 +23,7 @@ impl dyn CookieStore {
 pub trait CookieStore: Any + Send + Sync + 'static {
     async fn get(&self, key: &CookieKey) -> Option<Cookie>;
 
-    async fn set(&self, cookie: Cookie);
+    async fn set(&self, cookie: Cookie) -> Result<(), CookieError>;
 
     async fn remove(&self, key: &CookieKey);
 
@@ -27,9 +31,53 @@ pub trait CookieStore: Any + Send + Sync + 'static {
 
     async fn get_for_url(&self, url: &str) -> Vec<Cookie>;
 
+    async fn touch(&self, keys: &[CookieKey]);
+
+    // for callers that cannot await, such as the jar reqwest asks on every hop of a redirect,
+    // the cookies are read and changed right away and saving them is left to the async side
+    fn set_sync(&self, cookie: Cookie) -> Result<(), CookieError>;
+
+    fn remove_sync(&self, key: &CookieKey);
+
+    fn get_for_url_sync(&self, url: &str) -> Vec<Cookie>;
+
+    fn touch_sync(&self, keys: &[CookieKey]);
+
     async fn clear_all(&self);
 
+    async fn clear_session(&self) -> usize;
+
+    async fn promote_session_cookies(&self, keys: &[CookieKey], expires: SystemTime) -> usize;
+
     async fn persist(&self) -> Result<(), CookieError>;
 
     async fn load(&self) -> Result<(), CookieError>;
+
+    async fn export(&self, format: CookieFormat) -> Result<Vec<u8>, CookieError>;
+
+    async fn import(&self, format: CookieFormat, bytes: &[u8]) -> Result<usize, CookieError>;
+
+    async fn stats(&self) -> CookieStats;
+
+    fn subscribe(&self) -> BoxStream<'static, CookieChange>;
+}
+
+#[async_trait]
+pub trait CookieStoreFactory: Send + Sync + 'static {
+    async fn create_with_profile(&self, profile: &str)
+    -> Result<Arc<dyn CookieStore>, CookieError>;
+
+    fn get_with_profile(&self, profile: &str) -> Option<Arc<dyn CookieStore>>;
+
+    fn profiles(&self) -> Vec<String>;
+
+    async fn remove_profile(&self, profile: &str) -> Result<(), CookieError>;
+
+    // pauses the auto-save jobs and saves unsaved cookies, profiles opened while suspended
+    // start with a paused job until resumed
+    async fn suspend(&self) -> Result<(), CookieError>;
+
+    fn resume(&self);
+
+    async fn shutdown(&self) -> Result<(), CookieError>;
 }
@@ -35,7 +35,7 @@ pub trait RunnerDiscover: Send + Sync + 'static {
     fn cycle_once(&self, snapshot: &Arc<CycleSnapshot>) -> Result<(), DiscoverError>;
     fn discover_runner_by_category(
         &self,
-        category: &String,
+        category: &str,
         timeout: Duration,
     ) -> Result<Identifier, DiscoverError>;
 }
@@ -0,0 +1,10 @@
+use crate::domain::models::crash_models::{CrashError, CrashReport};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait CrashReporter: Send + Sync + 'static {
+    // called from the panic hook, it must neither block on the async runtime nor panic
+    fn record(&self, report: &CrashReport);
+    // reports recorded so far, including those of previous launches, are removed once returned
+    async fn take_pending(&self) -> Result<Vec<CrashReport>, CrashError>;
+}
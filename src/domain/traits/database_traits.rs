@@ -0,0 +1,25 @@
+use crate::domain::models::database_models::{
+    DatabaseError, DatabaseStatement, DatabaseValue, ExecuteResult, QueryResult,
+};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait DatabaseManager: Send + Sync + 'static {
+    async fn query(
+        &self,
+        sql: String,
+        params: Vec<DatabaseValue>,
+    ) -> Result<QueryResult, DatabaseError>;
+    async fn execute(
+        &self,
+        sql: String,
+        params: Vec<DatabaseValue>,
+    ) -> Result<ExecuteResult, DatabaseError>;
+    // several statements without parameters, e.g. a schema
+    async fn execute_batch(&self, sql: String) -> Result<(), DatabaseError>;
+    // all statements are committed together or rolled back on the first failure
+    async fn transaction(
+        &self,
+        statements: Vec<DatabaseStatement>,
+    ) -> Result<Vec<ExecuteResult>, DatabaseError>;
+}
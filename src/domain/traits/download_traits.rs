@@ -0,0 +1,24 @@
+use crate::domain::models::download_models::{
+    DownloadError, DownloadEvent, DownloadRequest, DownloadTask,
+};
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+#[async_trait]
+pub trait DownloadManager: Send + Sync + 'static {
+    // returns the id of the queued download
+    async fn enqueue(&self, request: DownloadRequest) -> Result<String, DownloadError>;
+    // keeps the partial file, resuming continues with a range request
+    async fn pause(&self, id: &str) -> Result<(), DownloadError>;
+    // also restarts failed downloads
+    async fn resume(&self, id: &str) -> Result<(), DownloadError>;
+    // stops the download and removes its partial file
+    async fn cancel(&self, id: &str) -> Result<(), DownloadError>;
+    // forgets a finished, failed or cancelled download, the target file is kept
+    async fn remove(&self, id: &str) -> Result<(), DownloadError>;
+    fn get(&self, id: &str) -> Option<DownloadTask>;
+    fn list(&self) -> Vec<DownloadTask>;
+    fn events(&self) -> broadcast::Receiver<DownloadEvent>;
+    // running downloads are interrupted and continue on the next start
+    async fn shutdown(&self) -> Result<(), DownloadError>;
+}
@@ -1,6 +1,37 @@
-use crate::domain::models::file_cache_models::{CacheChannel, CacheError, CacheRecord};
+use crate::domain::models::file_cache_models::{
+    CacheChannel, CacheDiagnostic, CacheError, CacheRecord, CacheRecordFilter, CacheStats,
+};
+use crate::service::config::FileCacheChannelConfig;
 use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::future::BoxFuture;
+use futures_util::stream::BoxStream;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncRead;
+use tokio::sync::broadcast;
+
+pub type CacheFetcher = Box<dyn FnOnce() -> BoxFuture<'static, Result<Vec<u8>, CacheError>> + Send>;
+
+// every event defaults to a no-op so observers only implement what they need
+pub trait FileCacheObserver: Send + Sync + 'static {
+    fn on_cache(&self, _channel: &str, _tag: &str, _size: usize) {}
+    fn on_evict(&self, _channel: &str, _tag: &str, _size: usize) {}
+    fn on_flush(&self, _channel: &str, _tag: &str, _size: usize) {}
+    fn on_persist(&self, _channel: &str, _entries: usize) {}
+}
+
+pub trait CacheMigration: Send + Sync + 'static {
+    // returns the migrated bytes, or None to drop the record
+    fn migrate(
+        &self,
+        from_version: u32,
+        to_version: u32,
+        record: &CacheRecord,
+        data: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, CacheError>;
+}
 
 #[async_trait]
 pub trait FileCacheManagerFactory: Send + Sync + 'static {
@@ -21,17 +52,99 @@ pub trait FileCacheManagerFactory: Send + Sync + 'static {
         channel: CacheChannel,
     ) -> Result<Arc<dyn FileCacheManager>, CacheError>;
     
-    async fn get_with_name(&self, name: &String) -> Result<Arc<dyn FileCacheManager>, CacheError>;
+    async fn create_with_options(
+        &self,
+        channel_config: FileCacheChannelConfig,
+    ) -> Result<Arc<dyn FileCacheManager>, CacheError>;
+
+    async fn get_with_name(&self, name: &str) -> Result<Arc<dyn FileCacheManager>, CacheError>;
+
+    async fn delete_channel(&self, name: &str) -> Result<(), CacheError>;
+
+    async fn stats_all(&self) -> Result<Vec<CacheStats>, CacheError>;
+
+    async fn clear_all(&self) -> Result<(), CacheError>;
+
+    async fn export_channel(&self, name: &str, path: &str) -> Result<(), CacheError>;
+
+    async fn import_channel(&self, path: &str) -> Result<String, CacheError>;
+
+    fn add_observer(&self, observer: Arc<dyn FileCacheObserver>);
+
+    fn subscribe_diagnostics(&self) -> broadcast::Receiver<CacheDiagnostic>;
+
+    async fn persist_all(&self) -> Result<(), CacheError>;
+
+    // applies to every channel, the auto-save jobs are registered again with the new interval
+    async fn set_auto_save_interval(&self, interval: Duration) -> Result<(), CacheError>;
+
+    async fn shutdown(&self) -> Result<(), CacheError>;
 }
 
 #[async_trait]
 pub trait FileCacheManager: Send + Sync + 'static {
-    async fn cache(&self, tag: String, sentence: String, bytes: &Vec<u8>) -> Result<(), CacheError>;
-    async fn should_update(&self, tag: &String, sentence: &String) -> Result<bool, CacheError>;
-    async fn fetch(&self, tag: &String) -> Result<Vec<u8>, CacheError>;
-    async fn flush(&self, tag: &String) -> Result<(), CacheError>;
+    async fn cache(&self, tag: String, sentence: String, bytes: &[u8]) -> Result<(), CacheError>;
+    async fn cache_with_ttl(
+        &self,
+        tag: String,
+        sentence: String,
+        bytes: &[u8],
+        ttl: Option<Duration>,
+    ) -> Result<(), CacheError>;
+    async fn cache_stream(
+        &self,
+        tag: String,
+        sentence: String,
+        reader: Pin<Box<dyn AsyncRead + Send>>,
+    ) -> Result<(), CacheError>;
+    async fn should_update(&self, tag: &str, sentence: &str) -> Result<bool, CacheError>;
+    async fn remove_if_stale(&self, tag: &str, sentence: &str) -> Result<bool, CacheError>;
+    async fn get_or_put(
+        &self,
+        tag: String,
+        sentence: String,
+        fetcher: CacheFetcher,
+    ) -> Result<Vec<u8>, CacheError>;
+    async fn fetch(&self, tag: &str) -> Result<Vec<u8>, CacheError>;
+    async fn fetch_stream(
+        &self,
+        tag: &str,
+    ) -> Result<BoxStream<'static, Result<Bytes, CacheError>>, CacheError>;
+    async fn flush(&self, tag: &str) -> Result<(), CacheError>;
     async fn persist(&self) -> Result<(), CacheError>;
+    async fn clear(&self) -> Result<(), CacheError>;
+    async fn destroy(&self) -> Result<(), CacheError>;
+    async fn migrate(&self) -> Result<(), CacheError>;
+    fn version(&self) -> u32;
+    fn observe(&self, observer: Arc<dyn FileCacheObserver>);
+    async fn export_archive(&self, path: &str) -> Result<(), CacheError>;
+    async fn import_records(
+        &self,
+        records: Vec<CacheRecord>,
+        extension: Option<String>,
+        source: &str,
+    ) -> Result<usize, CacheError>;
+    // removes leftover .tmp files and data files no record points to, only safe before the
+    // channel is in use
+    async fn recover_orphans(&self) -> Result<usize, CacheError>;
+    // sweeps the expired entries and persists the channel when it changed, every failure is
+    // also reported to the diagnostics
+    async fn auto_save(
+        &self,
+        diagnostics: &broadcast::Sender<CacheDiagnostic>,
+    ) -> Result<(), CacheError>;
+    async fn sweep_expired(&self) -> Result<usize, CacheError>;
+    async fn verify_all(&self, purge: bool) -> Result<Vec<String>, CacheError>;
 
-    async fn record(&self, tag: &String) -> Result<CacheRecord, CacheError>;
-    async fn path(&self, tag: &String) -> Result<String, CacheError>;
+    async fn record(&self, tag: &str) -> Result<CacheRecord, CacheError>;
+    async fn pin(&self, tag: &str) -> Result<(), CacheError>;
+    async fn unpin(&self, tag: &str) -> Result<(), CacheError>;
+    async fn update_metadata(
+        &self,
+        tag: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<(), CacheError>;
+    async fn stats(&self) -> Result<CacheStats, CacheError>;
+    async fn list(&self, filter: Option<CacheRecordFilter>) -> Result<Vec<CacheRecord>, CacheError>;
+    async fn path(&self, tag: &str) -> Result<String, CacheError>;
 }
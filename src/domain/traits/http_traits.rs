@@ -1,8 +1,7 @@
 use crate::domain::models::http_models::{
-    HttpClientError, HttpEndpoint, HttpResponse, HttpStreamResponse,
+    HttpClientError, HttpEndpoint, HttpLogRecord, HttpResponse, HttpStreamResponse,
 };
 use async_trait::async_trait;
-use futures_util::Stream;
 use std::sync::Arc;
 
 #[async_trait]
@@ -21,9 +20,28 @@ pub trait HttpClient: Send + Sync + 'static {
 }
 
 pub trait EncryptionProvider: Send + Sync + 'static {
-    fn encrypt(&self, bytes: &Vec<u8>) -> Result<Vec<u8>, HttpClientError>;
+    fn encrypt(&self, bytes: &[u8]) -> Result<Vec<u8>, HttpClientError>;
 }
 
 pub trait DecryptionProvider: Send + Sync + 'static {
-    fn decrypt(&self, bytes: &Vec<u8>) -> Result<Vec<u8>, HttpClientError>;
+    fn decrypt(&self, bytes: &[u8]) -> Result<Vec<u8>, HttpClientError>;
+}
+
+pub trait UserAgentProvider: Send + Sync + 'static {
+    fn user_agent(&self, endpoint: &HttpEndpoint) -> Option<String>;
+}
+
+pub trait HttpLogger: Send + Sync + 'static {
+    fn log(&self, record: &HttpLogRecord);
+}
+
+// supplies the credentials of an api client per request, so tokens may be refreshed meanwhile
+#[async_trait]
+pub trait AuthProvider: Send + Sync + 'static {
+    async fn headers(&self) -> Result<Vec<(String, String)>, HttpClientError>;
+}
+
+// a streamed response is validated before its body is read, the body it is given is empty
+pub trait ResponseValidator: Send + Sync + 'static {
+    fn validate(&self, response: &HttpResponse) -> Result<(), HttpClientError>;
 }
@@ -0,0 +1,25 @@
+use crate::domain::models::logging_models::{LogFileInfo, LogRecord, LoggingError};
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+#[async_trait]
+pub trait Logger: Send + Sync + 'static {
+    // receives every record that passes the level and target filters
+    fn records(&self) -> broadcast::Receiver<LogRecord>;
+    // stops collecting, tracing events are dropped afterwards
+    async fn shutdown(&self) -> Result<(), LoggingError>;
+}
+
+#[async_trait]
+pub trait LogManager: Send + Sync + 'static {
+    // queues a line for the current file, false when it was dropped because the writer lags
+    fn append(&self, line: String) -> bool;
+    // waits until the lines queued so far are written
+    async fn flush(&self) -> Result<(), LoggingError>;
+    // the current file first, then the rotated ones from newest to oldest
+    async fn files(&self) -> Result<Vec<LogFileInfo>, LoggingError>;
+    // packs the files modified at or after since into a gzip compressed tar in the temp root
+    // and returns its path, the caller removes it once it was sent
+    async fn collect(&self, since: Option<u64>) -> Result<String, LoggingError>;
+    async fn shutdown(&self) -> Result<(), LoggingError>;
+}
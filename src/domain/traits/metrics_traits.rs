@@ -0,0 +1,12 @@
+use crate::domain::models::metrics_models::MetricsSnapshot;
+
+// a metric is identified by its name and labels, the order of the labels does not matter
+pub trait MetricsRegistry: Send + Sync + 'static {
+    fn increment_counter(&self, name: &str, labels: &[(&str, &str)], value: u64);
+    fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64);
+    fn add_gauge(&self, name: &str, labels: &[(&str, &str)], delta: f64);
+    fn record_histogram(&self, name: &str, labels: &[(&str, &str)], value: f64);
+    // samples are sorted by name, then labels
+    fn snapshot(&self) -> MetricsSnapshot;
+    fn reset(&self);
+}
@@ -4,4 +4,11 @@ pub mod storage_traits;
 pub mod file_cache_traits;
 pub mod audio_traits;
 pub mod monitor_traits;
-pub mod coordinator_traits;
\ No newline at end of file
+pub mod coordinator_traits;
+pub mod database_traits;
+pub mod scheduler_traits;
+pub mod download_traits;
+pub mod upload_traits;
+pub mod logging_traits;
+pub mod metrics_traits;
+pub mod crash_traits;
\ No newline at end of file
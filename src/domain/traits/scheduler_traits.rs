@@ -0,0 +1,29 @@
+use crate::domain::models::scheduler_models::{JobError, JobInfo, JobSchedule};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait Job: Send + Sync + 'static {
+    async fn run(&self) -> Result<(), JobError>;
+}
+
+#[async_trait]
+pub trait JobScheduler: Send + Sync + 'static {
+    // a job never overlaps itself, a run that is due while it is still running waits
+    async fn register(
+        &self,
+        name: String,
+        schedule: JobSchedule,
+        job: Arc<dyn Job>,
+    ) -> Result<(), JobError>;
+    fn unregister(&self, name: &str) -> Result<(), JobError>;
+    fn pause(&self, name: &str) -> Result<(), JobError>;
+    fn resume(&self, name: &str) -> Result<(), JobError>;
+    // pausing all keeps the per-job flags, resuming all restores them
+    fn pause_all(&self);
+    fn resume_all(&self);
+    // runs the job as soon as a concurrency slot is free, even when it is paused
+    fn run_now(&self, name: &str) -> Result<(), JobError>;
+    fn jobs(&self) -> Vec<JobInfo>;
+    async fn shutdown(&self) -> Result<(), JobError>;
+}
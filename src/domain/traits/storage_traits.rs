@@ -1,8 +1,138 @@
 use async_trait::async_trait;
-use crate::domain::models::storage_models::{ReadFile, StorageError, WriteFile};
+use bytes::Bytes;
+use futures_util::StreamExt;
+use futures_util::stream::{self, BoxStream};
+use crate::domain::models::storage_models::{
+    DeleteFile, DirEntry, DiskUsage, FileHash, FileMetadata, HashAlgorithm, ReadFile, StorageError,
+    StorageEvent, TransferFile, WriteFile,
+};
+use std::ops::Range;
+use std::time::Duration;
+
+// called with (copied, total, delta) after every chunk
+pub type ProgressSink = Box<dyn Fn(u64, u64, u64) + Send + Sync>;
 
 #[async_trait]
 pub trait StorageManager: Send + Sync + 'static {
     async fn read(&self, request: ReadFile) -> Result<Vec<u8>, StorageError>;
     async fn write<'a>(&self, request: WriteFile<'a>) -> Result<(), StorageError>;
-}
\ No newline at end of file
+    // fails with StorageError::ChecksumMismatch when the content does not hash to expected
+    async fn read_verified(
+        &self,
+        request: ReadFile,
+        expected: FileHash,
+    ) -> Result<Vec<u8>, StorageError>;
+    async fn write_hashed<'a>(
+        &self,
+        request: WriteFile<'a>,
+        algorithm: HashAlgorithm,
+    ) -> Result<FileHash, StorageError>;
+    async fn delete(&self, request: DeleteFile) -> Result<(), StorageError>;
+    async fn rename(&self, request: TransferFile) -> Result<(), StorageError>;
+    async fn copy(&self, request: TransferFile) -> Result<(), StorageError>;
+    async fn copy_with_progress(
+        &self,
+        request: TransferFile,
+        progress_sink: ProgressSink,
+    ) -> Result<(), StorageError>;
+    // renames when possible, falls back to copy and delete across devices
+    async fn move_file(&self, request: TransferFile) -> Result<(), StorageError>;
+    async fn create_dir(&self, path: String, recursive: bool) -> Result<(), StorageError>;
+    async fn list_dir(&self, path: String) -> Result<Vec<DirEntry>, StorageError>;
+    async fn remove_dir(&self, path: String, recursive: bool) -> Result<(), StorageError>;
+    async fn read_range(
+        &self,
+        path: String,
+        offset: u64,
+        len: u64,
+    ) -> Result<Vec<u8>, StorageError>;
+    async fn read_stream(
+        &self,
+        path: String,
+        chunk_size: usize,
+    ) -> Result<BoxStream<'static, Result<Bytes, StorageError>>, StorageError>;
+    // replaces the file with the streamed chunks and returns the number of bytes written
+    async fn write_stream(
+        &self,
+        path: String,
+        stream: BoxStream<'static, Result<Bytes, StorageError>>,
+    ) -> Result<u64, StorageError>;
+    // appends the line and a trailing newline, a newline already ending the line is kept as is
+    async fn append_line(&self, path: String, line: Vec<u8>) -> Result<(), StorageError>;
+    // lines by zero based index without their line endings, ranges past the end are cut short
+    async fn read_lines(
+        &self,
+        path: String,
+        range: Range<usize>,
+    ) -> Result<Vec<Vec<u8>>, StorageError>;
+    async fn exists(&self, path: String) -> Result<bool, StorageError>;
+    // the path the other operations would use for `path`, refused when it leaves the allowed
+    // roots, for handing a path to code that opens it directly
+    async fn resolve_path(&self, path: String) -> Result<String, StorageError>;
+    // creates an empty file under the temp root and returns its path
+    async fn create_temp_file(
+        &self,
+        prefix: String,
+        extension: Option<String>,
+    ) -> Result<String, StorageError>;
+    // first free "base.ext", "base (1).ext", ... inside dir, nothing is created
+    async fn unique_path(
+        &self,
+        dir: String,
+        base: String,
+        extension: Option<String>,
+    ) -> Result<String, StorageError>;
+    // returns how many temp files were removed
+    async fn cleanup_temp_files(&self, older_than: Duration) -> Result<u64, StorageError>;
+    // figures for the volume containing the path
+    async fn disk_usage(&self, path: String) -> Result<DiskUsage, StorageError>;
+    // symbolic links are counted but not followed
+    async fn dir_size(&self, path: String) -> Result<u64, StorageError>;
+    // fails with StorageError::NotExist when nothing is at the path
+    async fn metadata(&self, path: String) -> Result<FileMetadata, StorageError>;
+
+    // directories are watched recursively, changes inside one debounce window are merged per path
+    async fn watch(
+        &self,
+        path: String,
+        debounce: Duration,
+    ) -> Result<BoxStream<'static, StorageEvent>, StorageError>;
+
+    // runs at most parallelism requests at once, results keep the order of the requests
+    async fn read_many(
+        &self,
+        requests: Vec<ReadFile>,
+        parallelism: usize,
+    ) -> Vec<Result<Vec<u8>, StorageError>> {
+        let reads: Vec<_> = requests.into_iter().map(|request| self.read(request)).collect();
+        stream::iter(reads)
+            .buffered(parallelism.max(1))
+            .collect()
+            .await
+    }
+
+    async fn write_many<'a>(
+        &self,
+        requests: Vec<WriteFile<'a>>,
+        parallelism: usize,
+    ) -> Vec<Result<(), StorageError>> {
+        let writes: Vec<_> = requests.into_iter().map(|request| self.write(request)).collect();
+        stream::iter(writes)
+            .buffered(parallelism.max(1))
+            .collect()
+            .await
+    }
+
+    async fn is_file(&self, path: String) -> Result<bool, StorageError> {
+        Ok(!self.metadata(path).await?.is_dir)
+    }
+
+    async fn is_dir(&self, path: String) -> Result<bool, StorageError> {
+        Ok(self.metadata(path).await?.is_dir)
+    }
+}
+
+// supplies the AES-256-GCM key used by the encrypted storage manager
+pub trait StorageKeyProvider: Send + Sync + 'static {
+    fn key(&self) -> Result<[u8; 32], StorageError>;
+}
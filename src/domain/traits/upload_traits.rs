@@ -0,0 +1,21 @@
+use crate::domain::models::upload_models::{UploadError, UploadEvent, UploadRequest, UploadTask};
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+#[async_trait]
+pub trait UploadManager: Send + Sync + 'static {
+    // returns the id of the queued upload
+    async fn enqueue(&self, request: UploadRequest) -> Result<String, UploadError>;
+    // resumable protocols continue from the server offset, multipart starts over
+    async fn pause(&self, id: &str) -> Result<(), UploadError>;
+    // also restarts failed uploads
+    async fn resume(&self, id: &str) -> Result<(), UploadError>;
+    async fn cancel(&self, id: &str) -> Result<(), UploadError>;
+    // forgets a finished, failed or cancelled upload, the source file is kept
+    async fn remove(&self, id: &str) -> Result<(), UploadError>;
+    fn get(&self, id: &str) -> Option<UploadTask>;
+    fn list(&self) -> Vec<UploadTask>;
+    fn events(&self) -> broadcast::Receiver<UploadEvent>;
+    // running uploads are interrupted and continue on the next start
+    async fn shutdown(&self) -> Result<(), UploadError>;
+}
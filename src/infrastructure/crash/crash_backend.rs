@@ -0,0 +1,175 @@
+use crate::domain::models::crash_models::{CrashError, CrashReport};
+use crate::domain::models::storage_models::{DeleteFile, ReadFile, StorageError};
+use crate::domain::traits::crash_traits::CrashReporter;
+use crate::domain::traits::storage_traits::StorageManager;
+use crate::service::config::CrashConfig;
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const REPORT_EXTENSION: &str = "json";
+
+fn storage_error(e: StorageError) -> CrashError {
+    CrashError::Storage(e.to_string())
+}
+
+// the timestamp leads the name so the names sort by age
+fn report_name(report: &CrashReport) -> String {
+    format!(
+        "{:020}-{}.{}",
+        report.timestamp, report.id, REPORT_EXTENSION
+    )
+}
+
+fn is_report(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .is_some_and(|extension| extension == REPORT_EXTENSION)
+}
+
+pub struct FileCrashReporter {
+    config: CrashConfig,
+    storage_manager: Arc<dyn StorageManager>,
+}
+
+impl FileCrashReporter {
+    pub fn new(config: CrashConfig, storage_manager: Arc<dyn StorageManager>) -> Self {
+        Self {
+            config,
+            storage_manager,
+        }
+    }
+
+    fn report_path(&self, name: &str) -> String {
+        PathBuf::from(&self.config.directory)
+            .join(name)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    // the panicking thread may be a runtime worker, so this uses std::fs instead of the
+    // storage manager
+    fn write_report(&self, report: &CrashReport) -> std::io::Result<()> {
+        let data = serde_json::to_vec_pretty(report)?;
+        std::fs::create_dir_all(&self.config.directory)?;
+        let path = self.report_path(&report_name(report));
+        let temp_path = format!("{}.tmp", path);
+        std::fs::write(&temp_path, data)?;
+        std::fs::rename(&temp_path, &path)?;
+
+        let mut names: Vec<String> = std::fs::read_dir(&self.config.directory)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| is_report(name))
+            .collect();
+        if names.len() > self.config.max_reports {
+            names.sort();
+            for name in &names[..names.len() - self.config.max_reports] {
+                let _ = std::fs::remove_file(self.report_path(name));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CrashReporter for FileCrashReporter {
+    fn record(&self, report: &CrashReport) {
+        // printed instead of logged, the logger may be what panicked
+        if let Err(e) = self.write_report(report) {
+            eprintln!("Failed to save the crash report: {}", e);
+        }
+    }
+
+    async fn take_pending(&self) -> Result<Vec<CrashReport>, CrashError> {
+        let entries = match self
+            .storage_manager
+            .list_dir(self.config.directory.clone())
+            .await
+        {
+            Ok(entries) => entries,
+            Err(StorageError::NotExist(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(storage_error(e)),
+        };
+        let mut names: Vec<String> = entries
+            .into_iter()
+            .filter(|entry| !entry.is_dir && is_report(&entry.name))
+            .map(|entry| entry.name)
+            .collect();
+        names.sort();
+
+        let mut reports = Vec::new();
+        for name in names {
+            let path = self.report_path(&name);
+            let data = self
+                .storage_manager
+                .read(ReadFile::path(path.clone()))
+                .await
+                .map_err(storage_error)?;
+            match serde_json::from_slice::<CrashReport>(&data) {
+                Ok(report) => reports.push(report),
+                // a report that cannot be read now never will be
+                Err(e) => tracing::warn!("Dropping the unreadable crash report {}: {}", path, e),
+            }
+            self.storage_manager
+                .delete(DeleteFile::path(path))
+                .await
+                .map_err(storage_error)?;
+        }
+        Ok(reports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::crash_models::CrashReport;
+    use crate::domain::traits::crash_traits::CrashReporter;
+    use crate::infrastructure::crash::crash_backend::FileCrashReporter;
+    use crate::infrastructure::storage::storage_backend::AsyncStorageManager;
+    use crate::service::config::CrashConfig;
+    use std::sync::Arc;
+
+    fn report(timestamp: u64) -> CrashReport {
+        CrashReport {
+            id: format!("report-{}", timestamp),
+            timestamp,
+            thread: Some("main".to_string()),
+            task: None,
+            message: format!("panic {}", timestamp),
+            location: Some("src/lib.rs:1:1".to_string()),
+            backtrace: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_record_and_take_pending() {
+        let directory = std::env::temp_dir()
+            .join(format!("strawberry_crash_reports_{}", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        let reporter = FileCrashReporter::new(
+            CrashConfig {
+                directory: directory.clone(),
+                max_reports: 2,
+            },
+            Arc::new(AsyncStorageManager::new()),
+        );
+
+        tokio_test::block_on(async {
+            assert!(reporter.take_pending().await.unwrap().is_empty());
+
+            // the oldest report is dropped once more than max_reports are kept
+            for timestamp in [3, 1, 2] {
+                reporter.record(&report(timestamp));
+            }
+            let reports = reporter.take_pending().await.unwrap();
+            let messages: Vec<&str> = reports
+                .iter()
+                .map(|report| report.message.as_str())
+                .collect();
+            assert_eq!(messages, vec!["panic 2", "panic 3"]);
+            assert!(reporter.take_pending().await.unwrap().is_empty());
+        });
+        let _ = std::fs::remove_dir_all(directory);
+    }
+}
@@ -0,0 +1 @@
+pub mod crash_backend;
\ No newline at end of file
@@ -0,0 +1,2 @@
+#[cfg(feature = "sqlite")]
+pub mod sqlite_database_backend;
@@ -0,0 +1,236 @@
+use crate::domain::models::database_models::{
+    DatabaseError, DatabaseStatement, DatabaseValue, ExecuteResult, QueryResult,
+};
+use crate::domain::traits::database_traits::DatabaseManager;
+use crate::service::config::DatabaseConfig;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use rusqlite::types::{ToSqlOutput, Value, ValueRef};
+use rusqlite::{Connection, ToSql, params_from_iter};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+impl ToSql for DatabaseValue {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            DatabaseValue::Null => ToSqlOutput::Owned(Value::Null),
+            DatabaseValue::Integer(value) => ToSqlOutput::Owned(Value::Integer(*value)),
+            DatabaseValue::Real(value) => ToSqlOutput::Owned(Value::Real(*value)),
+            DatabaseValue::Text(value) => ToSqlOutput::Borrowed(ValueRef::Text(value.as_bytes())),
+            DatabaseValue::Blob(value) => ToSqlOutput::Borrowed(ValueRef::Blob(value)),
+        })
+    }
+}
+
+fn value_from_ref(value: ValueRef) -> DatabaseValue {
+    match value {
+        ValueRef::Null => DatabaseValue::Null,
+        ValueRef::Integer(value) => DatabaseValue::Integer(value),
+        ValueRef::Real(value) => DatabaseValue::Real(value),
+        ValueRef::Text(value) => DatabaseValue::Text(String::from_utf8_lossy(value).to_string()),
+        ValueRef::Blob(value) => DatabaseValue::Blob(value.to_vec()),
+    }
+}
+
+fn sqlite_error(e: rusqlite::Error) -> DatabaseError {
+    DatabaseError::Sqlite(e.to_string())
+}
+
+fn execute_on(
+    connection: &Connection,
+    sql: &str,
+    params: &[DatabaseValue],
+) -> rusqlite::Result<ExecuteResult> {
+    let rows_affected = connection.execute(sql, params_from_iter(params.iter()))?;
+    Ok(ExecuteResult {
+        rows_affected: rows_affected as u64,
+        last_insert_id: connection.last_insert_rowid(),
+    })
+}
+
+// a fixed set of connections, each call borrows one on a blocking thread of the runtime
+pub struct SqliteDatabaseManager {
+    connections: Arc<Vec<Mutex<Connection>>>,
+    permits: Arc<Semaphore>,
+}
+
+impl SqliteDatabaseManager {
+    pub async fn new(config: DatabaseConfig) -> Result<Self, DatabaseError> {
+        // every in-memory connection would be a database of its own
+        let pool_size = match config.path {
+            Some(_) => config.pool_size.max(1),
+            None => 1,
+        };
+        let connections = tokio::task::spawn_blocking(move || {
+            let mut connections = Vec::with_capacity(pool_size);
+            for _ in 0..pool_size {
+                let connection = match &config.path {
+                    Some(path) => Connection::open(path)?,
+                    None => Connection::open_in_memory()?,
+                };
+                connection.busy_timeout(config.busy_timeout)?;
+                // readers on the other connections do not block the writer
+                connection.pragma_update(None, "journal_mode", "WAL")?;
+                connection.pragma_update(None, "foreign_keys", "ON")?;
+                connections.push(Mutex::new(connection));
+            }
+            Ok::<_, rusqlite::Error>(connections)
+        })
+        .await
+        .map_err(|e| DatabaseError::Pool(e.to_string()))?
+        .map_err(sqlite_error)?;
+
+        Ok(Self {
+            connections: Arc::new(connections),
+            permits: Arc::new(Semaphore::new(pool_size)),
+        })
+    }
+
+    async fn with_connection<R, F>(&self, f: F) -> Result<R, DatabaseError>
+    where
+        F: FnOnce(&mut Connection) -> rusqlite::Result<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        // a permit guarantees that one of the connections is free
+        let permit = self
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| DatabaseError::Pool(e.to_string()))?;
+        let connections = self.connections.clone();
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let mut connection = connections
+                .iter()
+                .find_map(|connection| connection.try_lock())
+                .ok_or_else(|| DatabaseError::Pool("no free connection".to_string()))?;
+            f(&mut connection).map_err(sqlite_error)
+        })
+        .await
+        .map_err(|e| DatabaseError::Pool(e.to_string()))?
+    }
+}
+
+#[async_trait]
+impl DatabaseManager for SqliteDatabaseManager {
+    async fn query(
+        &self,
+        sql: String,
+        params: Vec<DatabaseValue>,
+    ) -> Result<QueryResult, DatabaseError> {
+        self.with_connection(move |connection| {
+            let mut statement = connection.prepare_cached(&sql)?;
+            let columns: Vec<String> = statement
+                .column_names()
+                .into_iter()
+                .map(|column| column.to_string())
+                .collect();
+            let mut rows = statement.query(params_from_iter(params.iter()))?;
+            let mut result = Vec::new();
+            while let Some(row) = rows.next()? {
+                let mut values = Vec::with_capacity(columns.len());
+                for index in 0..columns.len() {
+                    values.push(value_from_ref(row.get_ref(index)?));
+                }
+                result.push(values);
+            }
+            Ok(QueryResult {
+                columns,
+                rows: result,
+            })
+        })
+        .await
+    }
+
+    async fn execute(
+        &self,
+        sql: String,
+        params: Vec<DatabaseValue>,
+    ) -> Result<ExecuteResult, DatabaseError> {
+        self.with_connection(move |connection| execute_on(connection, &sql, &params))
+            .await
+    }
+
+    async fn execute_batch(&self, sql: String) -> Result<(), DatabaseError> {
+        self.with_connection(move |connection| connection.execute_batch(&sql))
+            .await
+    }
+
+    async fn transaction(
+        &self,
+        statements: Vec<DatabaseStatement>,
+    ) -> Result<Vec<ExecuteResult>, DatabaseError> {
+        self.with_connection(move |connection| {
+            // dropped without commit on an error, which rolls back
+            let transaction = connection.transaction()?;
+            let mut results = Vec::with_capacity(statements.len());
+            for statement in statements.iter() {
+                results.push(execute_on(&transaction, &statement.sql, &statement.params)?);
+            }
+            transaction.commit()?;
+            Ok(results)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::database_models::{DatabaseStatement, DatabaseValue};
+    use crate::domain::traits::database_traits::DatabaseManager;
+    use crate::infrastructure::database::sqlite_database_backend::SqliteDatabaseManager;
+    use crate::service::config::DatabaseConfig;
+
+    macro_rules! await_test {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    #[test]
+    fn test_query_execute_and_rollback() {
+        await_test!(async {
+            let manager = SqliteDatabaseManager::new(DatabaseConfig::default())
+                .await
+                .unwrap();
+            manager
+                .execute_batch("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT)".to_string())
+                .await
+                .unwrap();
+            let inserted = manager
+                .execute(
+                    "INSERT INTO notes (body) VALUES (?1)".to_string(),
+                    vec![DatabaseValue::Text("first".to_string())],
+                )
+                .await
+                .unwrap();
+            assert_eq!(inserted.rows_affected, 1);
+            assert_eq!(inserted.last_insert_id, 1);
+
+            let failed = manager
+                .transaction(vec![
+                    DatabaseStatement::new(
+                        "INSERT INTO notes (body) VALUES (?1)".to_string(),
+                        vec![DatabaseValue::Text("second".to_string())],
+                    ),
+                    DatabaseStatement::new("INSERT INTO missing VALUES (1)".to_string(), vec![]),
+                ])
+                .await;
+            assert!(failed.is_err());
+
+            let result = manager
+                .query("SELECT id, body FROM notes".to_string(), vec![])
+                .await
+                .unwrap();
+            assert_eq!(result.columns, vec!["id".to_string(), "body".to_string()]);
+            assert_eq!(
+                result.rows,
+                vec![vec![
+                    DatabaseValue::Integer(1),
+                    DatabaseValue::Text("first".to_string())
+                ]]
+            );
+        });
+    }
+}
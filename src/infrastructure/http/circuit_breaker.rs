@@ -0,0 +1,249 @@
+use crate::domain::models::http_models::HttpClientError;
+use crate::service::config::CircuitBreakerConfig;
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use std::time::Instant;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CircuitState {
+    Closed,
+    Open { until: Instant },
+    HalfOpen { probes: usize },
+}
+
+struct HostCircuit {
+    state: CircuitState,
+    window_start: Instant,
+    requests: usize,
+    failures: usize,
+}
+
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    hosts: DashMap<String, Mutex<HostCircuit>>,
+}
+
+// the outcome of the admitted request is reported through it, a half open probe dropped
+// without an outcome, such as a cancelled request, frees its slot for the next probe
+pub struct CircuitPermit<'a> {
+    circuit_breaker: &'a CircuitBreaker,
+    host: String,
+    probe: bool,
+}
+
+impl HostCircuit {
+    fn new() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            window_start: Instant::now(),
+            requests: 0,
+            failures: 0,
+        }
+    }
+
+    fn reset_window(&mut self) {
+        self.window_start = Instant::now();
+        self.requests = 0;
+        self.failures = 0;
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            hosts: DashMap::new(),
+        }
+    }
+
+    pub fn state(&self, host: &str) -> CircuitState {
+        let circuit = self.hosts.get(host);
+        if circuit.is_none() {
+            return CircuitState::Closed;
+        }
+        circuit.unwrap().lock().state.clone()
+    }
+
+    pub fn acquire(&self, host: &str) -> Result<CircuitPermit<'_>, HttpClientError> {
+        let circuit = self
+            .hosts
+            .entry(host.to_string())
+            .or_insert_with(|| Mutex::new(HostCircuit::new()));
+        let mut circuit = circuit.lock();
+
+        let probe = match circuit.state {
+            CircuitState::Closed => false,
+            CircuitState::Open { until } => {
+                if Instant::now() < until {
+                    return Err(HttpClientError::CircuitOpen(host.to_string()));
+                }
+                circuit.state = CircuitState::HalfOpen { probes: 1 };
+                true
+            }
+            CircuitState::HalfOpen { probes } => {
+                if probes >= self.config.half_open_max_probes {
+                    return Err(HttpClientError::CircuitOpen(host.to_string()));
+                }
+                circuit.state = CircuitState::HalfOpen { probes: probes + 1 };
+                true
+            }
+        };
+        Ok(CircuitPermit {
+            circuit_breaker: self,
+            host: host.to_string(),
+            probe,
+        })
+    }
+
+    fn release_probe(&self, host: &str) {
+        let circuit = self.hosts.get(host);
+        if circuit.is_none() {
+            return;
+        }
+        let circuit = circuit.unwrap();
+        let mut circuit = circuit.lock();
+
+        if let CircuitState::HalfOpen { probes } = circuit.state {
+            circuit.state = CircuitState::HalfOpen {
+                probes: probes.saturating_sub(1),
+            };
+        }
+    }
+
+    fn record_success(&self, host: &str) {
+        let circuit = self.hosts.get(host);
+        if circuit.is_none() {
+            return;
+        }
+        let circuit = circuit.unwrap();
+        let mut circuit = circuit.lock();
+
+        if let CircuitState::HalfOpen { .. } = circuit.state {
+            circuit.state = CircuitState::Closed;
+            circuit.reset_window();
+            return;
+        }
+        self.roll_window(&mut circuit);
+        circuit.requests += 1;
+    }
+
+    fn record_failure(&self, host: &str) {
+        let circuit = self.hosts.get(host);
+        if circuit.is_none() {
+            return;
+        }
+        let circuit = circuit.unwrap();
+        let mut circuit = circuit.lock();
+
+        if let CircuitState::HalfOpen { .. } = circuit.state {
+            self.open(&mut circuit);
+            return;
+        }
+        self.roll_window(&mut circuit);
+        circuit.requests += 1;
+        circuit.failures += 1;
+
+        if circuit.requests < self.config.minimum_requests {
+            return;
+        }
+        let failure_rate = circuit.failures as f32 / circuit.requests as f32;
+        if failure_rate >= self.config.failure_rate_threshold {
+            self.open(&mut circuit);
+        }
+    }
+
+    fn roll_window(&self, circuit: &mut HostCircuit) {
+        if circuit.window_start.elapsed() >= self.config.window {
+            circuit.reset_window();
+        }
+    }
+
+    fn open(&self, circuit: &mut HostCircuit) {
+        circuit.state = CircuitState::Open {
+            until: Instant::now() + self.config.open_duration,
+        };
+        circuit.reset_window();
+    }
+}
+
+impl CircuitPermit<'_> {
+    pub fn record_success(mut self) {
+        self.probe = false;
+        self.circuit_breaker.record_success(&self.host);
+    }
+
+    pub fn record_failure(mut self) {
+        self.probe = false;
+        self.circuit_breaker.record_failure(&self.host);
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        if self.probe {
+            self.circuit_breaker.release_probe(&self.host);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CircuitBreaker, CircuitState};
+    use crate::service::config::CircuitBreakerConfig;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    #[test]
+    fn test_circuit_breaker() {
+        let circuit_breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            window: Duration::from_secs(60),
+            minimum_requests: 4,
+            failure_rate_threshold: 0.5,
+            open_duration: Duration::from_millis(100),
+            half_open_max_probes: 1,
+        });
+        let host = "example.com";
+
+        for _ in 0..3 {
+            circuit_breaker.acquire(host).unwrap().record_failure();
+        }
+        assert_eq!(circuit_breaker.state(host), CircuitState::Closed);
+
+        circuit_breaker.acquire(host).unwrap().record_failure();
+        assert!(circuit_breaker.acquire(host).is_err());
+
+        sleep(Duration::from_millis(150));
+        let probe = circuit_breaker.acquire(host).unwrap();
+        assert!(circuit_breaker.acquire(host).is_err());
+        probe.record_success();
+        assert_eq!(circuit_breaker.state(host), CircuitState::Closed);
+        assert!(circuit_breaker.acquire("another.com").is_ok());
+    }
+
+    #[test]
+    fn test_dropped_probe_frees_its_slot() {
+        let circuit_breaker = CircuitBreaker::new(CircuitBreakerConfig {
+            window: Duration::from_secs(60),
+            minimum_requests: 1,
+            failure_rate_threshold: 0.5,
+            open_duration: Duration::from_millis(50),
+            half_open_max_probes: 1,
+        });
+        let host = "example.com";
+
+        circuit_breaker.acquire(host).unwrap().record_failure();
+        sleep(Duration::from_millis(100));
+
+        // a cancelled request never reports an outcome
+        let probe = circuit_breaker.acquire(host).unwrap();
+        assert!(circuit_breaker.acquire(host).is_err());
+        drop(probe);
+        assert_eq!(
+            circuit_breaker.state(host),
+            CircuitState::HalfOpen { probes: 0 }
+        );
+
+        circuit_breaker.acquire(host).unwrap().record_failure();
+        assert!(circuit_breaker.acquire(host).is_err());
+    }
+}
@@ -1,22 +1,45 @@
-use crate::domain::models::cookie_models::{Cookie, CookieError, CookieKey};
-use crate::domain::traits::cookie_traits::CookieStore;
-use crate::service::config::CookieConfig;
+use crate::domain::models::cookie_models::{
+    Cookie, CookieChange, CookieError, CookieFormat, CookieKey, CookieLoadReport, CookieStats,
+};
+use crate::domain::models::storage_models::StorageError;
+use crate::domain::models::scheduler_models::{JobError, JobSchedule};
+use crate::domain::traits::cookie_traits::{CookieStore, CookieStoreFactory};
+use crate::domain::traits::scheduler_traits::{Job, JobScheduler};
+use crate::infrastructure::http::cookie_format::{
+    export_dart_json, export_netscape, import_dart_json, import_netscape,
+};
+use crate::service::config::{CookieBackend, CookieConfig, CookiePolicy, CookiePolicyRule};
+use crate::service::service_runtime::COOKIE_AUTO_SAVE_JOB;
+use crate::utils::broadcast_stream::broadcast_stream;
+use crate::utils::dirty_flag::DirtyGuard;
+use crate::infrastructure::http::memory_cookie_store::MemoryCookieStore;
+use crate::infrastructure::http::set_cookie::domain_matches;
+use crate::infrastructure::storage::storage_backend::write_atomically;
+#[cfg(feature = "sqlite")]
+use crate::infrastructure::http::sqlite_cookie_store::SqliteCookieStore;
+use crate::utils::public_suffix::PublicSuffixList;
 use crate::utils::url_component::extract_domain;
 use async_trait::async_trait;
+use dashmap::DashMap;
+use futures_util::stream::BoxStream;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
-use tokio::io;
-use tokio::io::AsyncWriteExt;
-use tokio::sync::RwLock as AsyncRwLock;
-use tokio::time::timeout;
+use tokio::sync::broadcast;
 
 pub struct FileBackedCookieStore {
-    inner: AsyncRwLock<InnerStore>,
+    // a plain lock so the cookies can be served without awaiting, see set_sync
+    inner: parking_lot::RwLock<InnerStore>,
+    // saves are written one at a time so an older one never wins
+    save_lock: tokio::sync::Mutex<()>,
     config: CookieConfig,
     storage_path: Option<String>,
     dirty: std::sync::atomic::AtomicBool,
+    public_suffix_list: PublicSuffixList,
+    changes: broadcast::Sender<CookieChange>,
+    // None until a file was loaded
+    load_report: parking_lot::Mutex<Option<CookieLoadReport>>,
 }
 
 struct InnerStore {
@@ -27,181 +50,1246 @@ struct InnerStore {
 #[async_trait]
 impl CookieStore for FileBackedCookieStore {
     async fn get(&self, key: &CookieKey) -> Option<Cookie> {
-        let store = self.inner.read().await;
+        let store = self.inner.read();
 
-        if let Some(cookie) = store.cookies.get(key) {
-            if !cookie.is_expired() {
-                return Some(cookie.clone());
-            }
+        if let Some(cookie) = store.cookies.get(key)
+            && !cookie.is_expired()
+        {
+            return Some(cookie.clone());
         }
 
         store.session_cookies.get(key).cloned()
     }
 
-    async fn set(&self, cookie: Cookie) {
-        let mut store = self.inner.write().await;
-
-        if cookie.persistent {
-            store.cookies.insert(cookie.key.clone(), cookie);
-        } else {
-            store.session_cookies.insert(cookie.key.clone(), cookie);
-        }
-
-        self.dirty.store(true, std::sync::atomic::Ordering::SeqCst);
+    async fn set(&self, cookie: Cookie) -> Result<(), CookieError> {
+        self.set_sync(cookie)
     }
 
     async fn remove(&self, key: &CookieKey) {
-        let mut store = self.inner.write().await;
-        store.cookies.remove(key);
-        store.session_cookies.remove(key);
-        self.dirty.store(true, std::sync::atomic::Ordering::SeqCst);
+        self.remove_sync(key)
     }
 
     async fn get_for_domain(&self, domain: &str) -> Vec<Cookie> {
-        let store = self.inner.read().await;
+        self.cookies_for_domain(domain)
+    }
 
-        let mut cookies = Vec::new();
-        let now = SystemTime::now();
+    async fn get_for_url(&self, url: &str) -> Vec<Cookie> {
+        self.get_for_url_sync(url)
+    }
 
-        for cookie in store.cookies.values() {
-            if cookie.key.domain == domain {
-                match cookie.expires {
-                    Some(expires) if expires < now => continue,
-                    _ => cookies.push(cookie.clone()),
-                }
-            }
+    async fn touch(&self, keys: &[CookieKey]) {
+        self.touch_sync(keys)
+    }
+
+    fn set_sync(&self, cookie: Cookie) -> Result<(), CookieError> {
+        if self.public_suffix_list.is_public_suffix(&cookie.key.domain) {
+            return Err(CookieError::Rejected(format!(
+                "{} is a public suffix",
+                cookie.key.domain
+            )));
+        }
+        if !allowed_by_policies(&self.config.policies, &cookie.key.domain) {
+            return Err(CookieError::Rejected(format!(
+                "{} is blocked by cookie policy",
+                cookie.key.domain
+            )));
         }
+        let cookie = limit_lifetime(&self.config, cookie);
 
-        for cookie in store.session_cookies.values() {
-            if cookie.key.domain == domain {
-                cookies.push(cookie.clone());
-            }
+        let mut store = self.inner.write();
+
+        let domain = cookie.key.domain.clone();
+        if cookie.persistent {
+            store.cookies.insert(cookie.key.clone(), cookie.clone());
+        } else {
+            store.session_cookies.insert(cookie.key.clone(), cookie.clone());
         }
+        let evicted = store.enforce_limits(
+            &domain,
+            self.config.max_cookies,
+            self.config.max_cookies_per_domain,
+        );
 
-        cookies
+        self.dirty.store(true, std::sync::atomic::Ordering::SeqCst);
+        self.notify(CookieChange::Set(cookie));
+        evicted
+            .into_iter()
+            .for_each(|key| self.notify(CookieChange::Removed(key)));
+        Ok(())
     }
 
-    async fn get_for_url(&self, url: &str) -> Vec<Cookie> {
+    fn remove_sync(&self, key: &CookieKey) {
+        let mut store = self.inner.write();
+        store.cookies.remove(key);
+        store.session_cookies.remove(key);
+        self.dirty.store(true, std::sync::atomic::Ordering::SeqCst);
+        self.notify(CookieChange::Removed(key.clone()));
+    }
+
+    fn get_for_url_sync(&self, url: &str) -> Vec<Cookie> {
         let domain = extract_domain(url);
         if domain.is_err() {
             return vec![];
         }
 
-        self.get_for_domain(&domain.unwrap()).await
+        self.cookies_for_domain(&domain.unwrap())
+    }
+
+    // only feeds LRU eviction, not worth a save on its own
+    fn touch_sync(&self, keys: &[CookieKey]) {
+        let mut store = self.inner.write();
+        let now = SystemTime::now();
+        for key in keys {
+            if let Some(cookie) = store.cookies.get_mut(key) {
+                cookie.last_access_time = now;
+            } else if let Some(cookie) = store.session_cookies.get_mut(key) {
+                cookie.last_access_time = now;
+            }
+        }
     }
 
     async fn clear_all(&self) {
-        let mut store = self.inner.write().await;
+        let mut store = self.inner.write();
         store.cookies.clear();
         store.session_cookies.clear();
         self.dirty.store(true, std::sync::atomic::Ordering::SeqCst);
+        self.notify(CookieChange::Cleared);
+    }
+
+    async fn clear_session(&self) -> usize {
+        let mut store = self.inner.write();
+        let keys: Vec<CookieKey> = store.session_cookies.drain().map(|(key, _)| key).collect();
+        let count = keys.len();
+        keys.into_iter()
+            .for_each(|key| self.notify(CookieChange::Removed(key)));
+        count
+    }
+
+    async fn promote_session_cookies(&self, keys: &[CookieKey], expires: SystemTime) -> usize {
+        if self.config.session_only {
+            return 0;
+        }
+        let expires = clamp_expires(&self.config, expires);
+        let mut store = self.inner.write();
+        let mut promoted = Vec::new();
+        for key in keys {
+            let cookie = store.session_cookies.remove(key);
+            if cookie.is_none() {
+                continue;
+            }
+            let mut cookie = cookie.unwrap();
+            cookie.persistent = true;
+            cookie.expires = Some(expires);
+            store.cookies.insert(key.clone(), cookie.clone());
+            promoted.push(cookie);
+        }
+        if !promoted.is_empty() {
+            self.dirty.store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+        let count = promoted.len();
+        promoted
+            .into_iter()
+            .for_each(|cookie| self.notify(CookieChange::Set(cookie)));
+        count
     }
 
     async fn persist(&self) -> Result<(), CookieError> {
+        // the initial and previously stored cookies are not written either
+        if self.config.session_only {
+            return Ok(());
+        }
         if let Some(path) = &self.storage_path {
-            let store = self.inner.read().await;
+            let _guard = self.save_lock.lock().await;
             let serializable = SerializableStore {
-                cookies: store.cookies.values().cloned().collect(),
+                version: STORE_VERSION,
+                cookies: self.inner.read().cookies.values().cloned().collect(),
                 saved_at: SystemTime::now(),
             };
 
             let json = serde_json::to_string_pretty(&serializable)
                 .map_err(|e| CookieError::Serialization(e.to_string()))?;
-            match timeout(
-                Duration::from_secs(60),
-                tokio::fs::write(path, json.into_bytes()),
-            )
-            .await
-            {
-                Ok(Ok(())) => Ok(()),
-                Ok(Err(e)) => Err(CookieError::IO(e.to_string())),
-                Err(e) => Err(CookieError::Timeout(e.to_string())),
-            }
+            // written aside and renamed over, a crash never leaves a truncated cookie file
+            write_atomically(path, json.as_bytes(), Duration::from_secs(60))
+                .await
+                .map_err(|e| match e {
+                    StorageError::Timeout(e) => CookieError::Timeout(e),
+                    e => CookieError::IO(e.to_string()),
+                })
         } else {
             Ok(())
         }
     }
 
     async fn load(&self) -> Result<(), CookieError> {
+        // the stored cookies belong to earlier sessions
+        if self.config.session_only {
+            return Ok(());
+        }
         if let Some(path) = &self.storage_path {
-            if !std::path::Path::new(path).exists() {
-                return Ok(());
+            let result = self.load_from(path).await;
+            *self.load_report.lock() = Some(match &result {
+                Ok(report) => report.clone(),
+                Err(e) => CookieLoadReport {
+                    error: Some(e.to_string()),
+                    ..CookieLoadReport::default()
+                },
+            });
+            result.map(|_| ())
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn export(&self, format: CookieFormat) -> Result<Vec<u8>, CookieError> {
+        let cookies: Vec<Cookie> = {
+            let store = self.inner.read();
+            store
+                .cookies
+                .values()
+                .chain(store.session_cookies.values())
+                .filter(|cookie| !cookie.is_expired())
+                .cloned()
+                .collect()
+        };
+
+        match format {
+            CookieFormat::Netscape => Ok(export_netscape(&cookies).into_bytes()),
+            CookieFormat::DartJson => Ok(export_dart_json(&cookies)?.into_bytes()),
+        }
+    }
+
+    async fn import(&self, format: CookieFormat, bytes: &[u8]) -> Result<usize, CookieError> {
+        let content =
+            std::str::from_utf8(bytes).map_err(|e| CookieError::Serialization(e.to_string()))?;
+        let cookies = match format {
+            CookieFormat::Netscape => import_netscape(content)?,
+            CookieFormat::DartJson => import_dart_json(content)?,
+        };
+
+        let mut imported = 0;
+        for cookie in cookies {
+            if cookie.is_expired() {
+                continue;
             }
+            if self.set(cookie).await.is_ok() {
+                imported += 1;
+            }
+        }
+        Ok(imported)
+    }
+
+    async fn stats(&self) -> CookieStats {
+        let mut stats = {
+            let store = self.inner.read();
+            let mut per_domain: HashMap<String, usize> = HashMap::new();
+            store
+                .cookies
+                .values()
+                .chain(store.session_cookies.values())
+                .for_each(|cookie| *per_domain.entry(cookie.key.domain.clone()).or_default() += 1);
 
-            let json = tokio::fs::read_to_string(path)
+            let mut per_domain: Vec<(String, usize)> = per_domain.into_iter().collect();
+            per_domain.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+            CookieStats {
+                total: store.len(),
+                persistent: store.cookies.len(),
+                session: store.session_cookies.len(),
+                expired: store.cookies.values().filter(|cookie| cookie.is_expired()).count(),
+                per_domain,
+                storage_size: None,
+            }
+        };
+
+        if let Some(path) = &self.storage_path {
+            stats.storage_size = tokio::fs::metadata(path)
                 .await
-                .map_err(|e| CookieError::IO(e.to_string()))?;
+                .ok()
+                .map(|metadata| metadata.len());
+        }
+        stats
+    }
 
-            let serializable: SerializableStore = serde_json::from_str(&json)
-                .map_err(|e| CookieError::Serialization(e.to_string()))?;
+    fn subscribe(&self) -> BoxStream<'static, CookieChange> {
+        broadcast_stream(self.changes.subscribe())
+    }
+}
 
-            let now = SystemTime::now();
-            let cookies: HashMap<_, _> = serializable
-                .cookies
-                .into_iter()
-                .filter(|cookie| match cookie.expires {
-                    Some(expires) => expires > now,
-                    None => true,
-                })
-                .map(|cookie| (cookie.key.clone(), cookie))
-                .collect();
+// shortens the expiry to max_cookie_lifetime, with session_only the cookie is kept for this
+// session only
+pub(crate) fn limit_lifetime(config: &CookieConfig, mut cookie: Cookie) -> Cookie {
+    if let Some(expires) = cookie.expires {
+        cookie.expires = Some(clamp_expires(config, expires));
+    }
+    if config.session_only {
+        cookie.persistent = false;
+    }
+    cookie
+}
 
-            let mut store = self.inner.write().await;
-            store.cookies = cookies;
+pub(crate) fn clamp_expires(config: &CookieConfig, expires: SystemTime) -> SystemTime {
+    match config.max_cookie_lifetime {
+        Some(max_cookie_lifetime) => expires.min(SystemTime::now() + max_cookie_lifetime),
+        None => expires,
+    }
+}
 
-            Ok(())
-        } else {
-            Ok(())
+pub(crate) fn allowed_by_policies(policies: &[CookiePolicy], domain: &str) -> bool {
+    let policy = policies
+        .iter()
+        .find(|policy| policy_matches(policy, domain));
+    if policy.is_none() {
+        return true;
+    }
+
+    match &policy.unwrap().rule {
+        CookiePolicyRule::AcceptAll => true,
+        CookiePolicyRule::RejectAll => false,
+        CookiePolicyRule::FirstPartyOnly {
+            first_party_domains,
+        } => first_party_domains
+            .iter()
+            .any(|first_party| domain_matches(domain, &first_party.to_lowercase())),
+    }
+}
+
+fn policy_matches(policy: &CookiePolicy, domain: &str) -> bool {
+    let pattern = policy.domain_pattern.to_lowercase();
+    if pattern == "*" {
+        return true;
+    }
+    if let Some(parent) = pattern.strip_prefix("*.") {
+        return domain_matches(domain, parent);
+    }
+    domain == pattern
+}
+
+impl InnerStore {
+    fn len(&self) -> usize {
+        self.cookies.len() + self.session_cookies.len()
+    }
+
+    fn domain_len(&self, domain: &str) -> usize {
+        self.cookies
+            .values()
+            .chain(self.session_cookies.values())
+            .filter(|cookie| cookie.key.domain == domain)
+            .count()
+    }
+
+    fn purge_expired(&mut self) -> Vec<CookieKey> {
+        let expired: Vec<CookieKey> = self
+            .cookies
+            .values()
+            .filter(|cookie| cookie.is_expired())
+            .map(|cookie| cookie.key.clone())
+            .collect();
+        expired.iter().for_each(|key| {
+            self.cookies.remove(key);
+        });
+        expired
+    }
+
+    fn least_recently_used(&self, domain: Option<&str>) -> Option<CookieKey> {
+        self.cookies
+            .values()
+            .chain(self.session_cookies.values())
+            .filter(|cookie| domain.is_none() || cookie.key.domain == domain.unwrap())
+            .min_by_key(|cookie| cookie.last_access_time)
+            .map(|cookie| cookie.key.clone())
+    }
+
+    fn remove(&mut self, key: &CookieKey) {
+        self.cookies.remove(key);
+        self.session_cookies.remove(key);
+    }
+
+    fn enforce_limits(
+        &mut self,
+        domain: &str,
+        max_cookies: Option<usize>,
+        max_cookies_per_domain: Option<usize>,
+    ) -> Vec<CookieKey> {
+        let mut evicted = Vec::new();
+        let over_domain_limit = |store: &InnerStore| {
+            max_cookies_per_domain.is_some()
+                && store.domain_len(domain) > max_cookies_per_domain.unwrap()
+        };
+        let over_total_limit =
+            |store: &InnerStore| max_cookies.is_some() && store.len() > max_cookies.unwrap();
+
+        if over_domain_limit(self) || over_total_limit(self) {
+            evicted.extend(self.purge_expired());
+        }
+        while over_domain_limit(self) {
+            let key = self.least_recently_used(Some(domain));
+            if key.is_none() {
+                break;
+            }
+            let key = key.unwrap();
+            self.remove(&key);
+            evicted.push(key);
+        }
+        while over_total_limit(self) {
+            let key = self.least_recently_used(None);
+            if key.is_none() {
+                break;
+            }
+            let key = key.unwrap();
+            self.remove(&key);
+            evicted.push(key);
         }
+        evicted
     }
 }
 
+// bumped whenever the stored shape changes, with a step in migrate_store bringing the older
+// files up to it, version 0 needs none since the serde default of host_only covers it
+const STORE_VERSION: u32 = 1;
+
 #[derive(Serialize, Deserialize)]
 struct SerializableStore {
+    // files written before versioning have none, they are version 0
+    #[serde(default)]
+    version: u32,
     cookies: Vec<Cookie>,
     saved_at: SystemTime,
 }
 
+// upgrades a stored file one version at a time, a cookie that still does not deserialize is
+// dropped on its own instead of failing the whole store, their number is returned with it
+fn migrate_store(mut value: serde_json::Value) -> Result<(SerializableStore, usize), CookieError> {
+    let version = value
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0);
+    if version > STORE_VERSION as u64 {
+        return Err(CookieError::Serialization(format!(
+            "cookie store version {} is newer than the supported {}",
+            version, STORE_VERSION
+        )));
+    }
+
+    let cookies = match value.get_mut("cookies").map(serde_json::Value::take) {
+        Some(serde_json::Value::Array(cookies)) => cookies,
+        _ => {
+            return Err(CookieError::Serialization(
+                "cookie store has no cookies".to_string(),
+            ));
+        }
+    };
+    let stored = cookies.len();
+    let cookies: Vec<Cookie> = cookies
+        .into_iter()
+        .filter_map(|cookie| match serde_json::from_value::<Cookie>(cookie) {
+            Ok(cookie) => Some(cookie),
+            Err(e) => {
+                tracing::warn!("Dropped a stored cookie that could not be read: {}", e);
+                None
+            }
+        })
+        .collect();
+    let malformed = stored - cookies.len();
+    let saved_at = value
+        .get_mut("saved_at")
+        .map(serde_json::Value::take)
+        .and_then(|saved_at| serde_json::from_value(saved_at).ok())
+        .unwrap_or_else(SystemTime::now);
+    Ok((
+        SerializableStore {
+            version: STORE_VERSION,
+            cookies,
+            saved_at,
+        },
+        malformed,
+    ))
+}
+
 impl FileBackedCookieStore {
     pub async fn new(config: CookieConfig) -> Result<Self, CookieError> {
         let mut initial_cookies: HashMap<CookieKey, Cookie> = HashMap::new();
         if let Some(initials) = config.initial_cookies.clone() {
             initials.into_iter().for_each(|cookie| {
+                let cookie = limit_lifetime(&config, cookie);
                 let key = cookie.key.clone();
                 initial_cookies.insert(key, cookie);
             });
         }
 
+        let public_suffix_list = if let Some(path) = &config.public_suffix_list_path {
+            let content = tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| CookieError::IO(e.to_string()))?;
+            PublicSuffixList::parse(&content)
+        } else {
+            PublicSuffixList::builtin()
+        };
+
         let store = Self {
-            inner: AsyncRwLock::new(InnerStore {
+            inner: parking_lot::RwLock::new(InnerStore {
                 cookies: initial_cookies,
                 session_cookies: HashMap::new(),
             }),
             storage_path: config.cookie_path.clone(),
+            save_lock: tokio::sync::Mutex::new(()),
             config,
             dirty: std::sync::atomic::AtomicBool::new(false),
+            public_suffix_list,
+            changes: broadcast::channel(64).0,
+            load_report: parking_lot::Mutex::new(None),
         };
 
-        store.load().await?;
+        // a corrupted file only costs its cookies, it is kept aside and the load report tells
+        // the app about it
+        match store.load().await {
+            Err(CookieError::Serialization(e)) => {
+                tracing::warn!("Started without the stored cookies: {}", e);
+                if let Some(path) = &store.storage_path {
+                    let _ = tokio::fs::rename(path, format!("{}.corrupted", path)).await;
+                }
+            }
+            result => result?,
+        }
         Ok(store)
     }
 
-    pub fn start_auto_save(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
-        if let Some(interval) = self.config.auto_save_interval {
-            let store = Arc::clone(&self);
-            tokio::spawn(async move {
-                let mut interval = tokio::time::interval(interval);
-                loop {
-                    interval.tick().await;
-                    if store.dirty.load(std::sync::atomic::Ordering::SeqCst) {
-                        if let Err(e) = store.persist().await {
-                            eprintln!("Failed to auto-save cookies: {}", e);
-                        }
-                    }
+    pub async fn purge_expired(&self) -> usize {
+        let mut store = self.inner.write();
+        let purged = store.purge_expired();
+        if !purged.is_empty() {
+            self.dirty.store(true, std::sync::atomic::Ordering::SeqCst);
+        }
+        let count = purged.len();
+        purged
+            .into_iter()
+            .for_each(|key| self.notify(CookieChange::Removed(key)));
+        count
+    }
+
+    fn cookies_for_domain(&self, domain: &str) -> Vec<Cookie> {
+        let store = self.inner.read();
+
+        let mut cookies = Vec::new();
+        let now = SystemTime::now();
+
+        let matches = |cookie: &Cookie| {
+            if cookie.host_only {
+                return cookie.key.domain == domain;
+            }
+            domain_matches(domain, &cookie.key.domain)
+        };
+
+        for cookie in store.cookies.values() {
+            if matches(cookie) {
+                match cookie.expires {
+                    Some(expires) if expires < now => continue,
+                    _ => cookies.push(cookie.clone()),
                 }
+            }
+        }
+
+        for cookie in store.session_cookies.values() {
+            if matches(cookie) {
+                cookies.push(cookie.clone());
+            }
+        }
+
+        cookies
+    }
+
+    fn notify(&self, change: CookieChange) {
+        // nobody listening is not an error
+        let _ = self.changes.send(change);
+    }
+
+    pub fn auto_save_interval(&self) -> Option<Duration> {
+        self.config.auto_save_interval
+    }
+
+    // one auto-save pass, purges expired cookies and persists when something changed
+    pub async fn auto_save(&self) -> Result<(), CookieError> {
+        self.purge_expired().await;
+        self.flush().await
+    }
+
+    pub async fn shutdown(&self) -> Result<(), CookieError> {
+        self.flush().await
+    }
+
+    async fn load_from(&self, path: &str) -> Result<CookieLoadReport, CookieError> {
+        if !std::path::Path::new(path).exists() {
+            return Ok(CookieLoadReport::default());
+        }
+
+        let json = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| CookieError::IO(e.to_string()))?;
+
+        let value: serde_json::Value =
+            serde_json::from_str(&json).map_err(|e| CookieError::Serialization(e.to_string()))?;
+        let (serializable, malformed) = migrate_store(value)?;
+
+        let now = SystemTime::now();
+        let (cookies, expired): (Vec<Cookie>, Vec<Cookie>) = serializable
+            .cookies
+            .into_iter()
+            .partition(|cookie| match cookie.expires {
+                Some(expires) => expires > now,
+                None => true,
+            });
+        let report = CookieLoadReport {
+            loaded: cookies.len(),
+            expired: expired.len(),
+            malformed,
+            error: None,
+        };
+        // a cookie stored before max_cookie_lifetime was lowered is held to it too
+        let cookies: HashMap<_, _> = cookies
+            .into_iter()
+            .map(|cookie| limit_lifetime(&self.config, cookie))
+            .map(|cookie| (cookie.key.clone(), cookie))
+            .collect();
+
+        let mut store = self.inner.write();
+        store.cookies = cookies;
+
+        Ok(report)
+    }
+
+    pub fn load_report(&self) -> Option<CookieLoadReport> {
+        self.load_report.lock().clone()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    // persists even without changes, the changes made while writing are left for the next save
+    pub async fn persist_now(&self) -> Result<(), CookieError> {
+        let dirty = DirtyGuard::take(&self.dirty);
+        self.persist().await?;
+        dirty.written();
+        Ok(())
+    }
+
+    // persists right away when something changed since the last save
+    pub async fn flush(&self) -> Result<(), CookieError> {
+        let dirty = DirtyGuard::take(&self.dirty);
+        if dirty.was_dirty() {
+            self.persist().await?;
+            dirty.written();
+        }
+        Ok(())
+    }
+}
+
+// every profile saves through its own job, named COOKIE_AUTO_SAVE_JOB:<profile>
+pub fn auto_save_job_name(profile: &str) -> String {
+    format!("{}:{}", COOKIE_AUTO_SAVE_JOB, profile)
+}
+
+struct AutoSaveJob {
+    store: Arc<FileBackedCookieStore>,
+}
+
+#[async_trait]
+impl Job for AutoSaveJob {
+    async fn run(&self) -> Result<(), JobError> {
+        self.store.auto_save().await.map_err(|e| {
+            tracing::warn!("Failed to auto-save cookies: {}", e);
+            JobError::Failed(e.to_string())
+        })
+    }
+}
+
+pub struct DefaultCookieStoreFactory {
+    config: CookieConfig,
+    stores: DashMap<String, Arc<dyn CookieStore>>,
+    create_lock: tokio::sync::Mutex<()>,
+    suspended: std::sync::atomic::AtomicBool,
+    job_scheduler: Arc<dyn JobScheduler>,
+}
+
+impl DefaultCookieStoreFactory {
+    pub fn new(config: CookieConfig, job_scheduler: Arc<dyn JobScheduler>) -> Self {
+        Self {
+            config,
+            stores: DashMap::new(),
+            create_lock: tokio::sync::Mutex::new(()),
+            suspended: std::sync::atomic::AtomicBool::new(false),
+            job_scheduler,
+        }
+    }
+
+    // memory and sqlite stores have no auto-save job and nothing unsaved
+    fn file_backed_stores(&self) -> Vec<(String, Arc<FileBackedCookieStore>)> {
+        self.stores
+            .iter()
+            .filter_map(|entry| {
+                let store = entry.value().clone().downcast_arc::<FileBackedCookieStore>()?;
+                Some((entry.key().clone(), store))
             })
-        } else {
-            tokio::spawn(async {})
+            .collect()
+    }
+
+    async fn schedule_auto_save(
+        &self,
+        profile: &str,
+        store: Arc<FileBackedCookieStore>,
+    ) -> Result<(), CookieError> {
+        let Some(interval) = store.auto_save_interval() else {
+            return Ok(());
+        };
+        let job_name = auto_save_job_name(profile);
+        self.job_scheduler
+            .register(
+                job_name.clone(),
+                JobSchedule::Interval(interval),
+                Arc::new(AutoSaveJob { store }),
+            )
+            .await
+            .map_err(|e| CookieError::IO(e.to_string()))?;
+        // a profile opened while suspended waits for resume like the others
+        if self.suspended.load(std::sync::atomic::Ordering::SeqCst) {
+            self.job_scheduler
+                .pause(&job_name)
+                .map_err(|e| CookieError::IO(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn profile_config(&self, profile: &str) -> CookieConfig {
+        let mut config = self.config.clone();
+        config.initial_cookies = None;
+        config.cookie_path = match config.backend {
+            CookieBackend::Memory => None,
+            _ => config
+                .cookie_path
+                .map(|path| profile_cookie_path(&path, profile)),
+        };
+        config
+    }
+
+    async fn open_store(
+        &self,
+        profile: &str,
+        config: CookieConfig,
+    ) -> Result<Arc<dyn CookieStore>, CookieError> {
+        match config.backend {
+            CookieBackend::File => {
+                let store = Arc::new(FileBackedCookieStore::new(config).await?);
+                self.schedule_auto_save(profile, store.clone()).await?;
+                Ok(store)
+            }
+            CookieBackend::Memory => Ok(Arc::new(MemoryCookieStore::new(config).await?)),
+            #[cfg(feature = "sqlite")]
+            CookieBackend::Sqlite => Ok(Arc::new(SqliteCookieStore::new(config).await?)),
+        }
+    }
+}
+
+// cookies.json -> cookies.<profile>.json
+fn profile_cookie_path(path: &str, profile: &str) -> String {
+    let path = std::path::Path::new(path);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{}.{}.{}", stem, profile, extension.to_string_lossy()),
+        None => format!("{}.{}", stem, profile),
+    };
+    path.with_file_name(file_name).to_string_lossy().to_string()
+}
+
+fn is_valid_profile(profile: &str) -> bool {
+    !profile.is_empty()
+        && profile
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[async_trait]
+impl CookieStoreFactory for DefaultCookieStoreFactory {
+    async fn create_with_profile(
+        &self,
+        profile: &str,
+    ) -> Result<Arc<dyn CookieStore>, CookieError> {
+        if let Some(store) = self.get_with_profile(profile) {
+            return Ok(store);
+        }
+        if !is_valid_profile(profile) {
+            return Err(CookieError::Rejected(format!(
+                "invalid cookie profile name: {}",
+                profile
+            )));
+        }
+
+        let _guard = self.create_lock.lock().await;
+        if let Some(store) = self.get_with_profile(profile) {
+            return Ok(store);
+        }
+
+        let store = self
+            .open_store(profile, self.profile_config(profile))
+            .await?;
+        self.stores.insert(profile.to_string(), store.clone());
+        Ok(store)
+    }
+
+    fn get_with_profile(&self, profile: &str) -> Option<Arc<dyn CookieStore>> {
+        self.stores
+            .get(profile)
+            .map(|entry| entry.value().clone())
+    }
+
+    fn profiles(&self) -> Vec<String> {
+        self.stores.iter().map(|entry| entry.key().clone()).collect()
+    }
+
+    async fn remove_profile(&self, profile: &str) -> Result<(), CookieError> {
+        let removed = self.stores.remove(profile);
+        if removed.is_none() {
+            return Ok(());
+        }
+        let (_, store) = removed.unwrap();
+        // only file backed profiles have a job
+        let _ = self.job_scheduler.unregister(&auto_save_job_name(profile));
+        store.clear_all().await;
+
+        if let Some(path) = &self.profile_config(profile).cookie_path
+            && std::path::Path::new(path).exists()
+        {
+            tokio::fs::remove_file(path)
+                .await
+                .map_err(|e| CookieError::IO(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    async fn suspend(&self) -> Result<(), CookieError> {
+        self.suspended
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        for (profile, store) in self.file_backed_stores() {
+            // a profile without an auto-save interval has no job to pause
+            let _ = self.job_scheduler.pause(&auto_save_job_name(&profile));
+            store.flush().await?;
         }
+        Ok(())
+    }
+
+    fn resume(&self) {
+        if !self
+            .suspended
+            .swap(false, std::sync::atomic::Ordering::SeqCst)
+        {
+            return;
+        }
+        for (profile, _) in self.file_backed_stores() {
+            let _ = self.job_scheduler.resume(&auto_save_job_name(&profile));
+        }
+    }
+
+    async fn shutdown(&self) -> Result<(), CookieError> {
+        for (profile, store) in self.file_backed_stores() {
+            let _ = self.job_scheduler.unregister(&auto_save_job_name(&profile));
+            store.shutdown().await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::cookie_models::Cookie;
+    use crate::domain::traits::cookie_traits::{CookieStore, CookieStoreFactory};
+    use crate::domain::traits::scheduler_traits::JobScheduler;
+    use crate::infrastructure::http::cookie_backend::{
+        FileBackedCookieStore, DefaultCookieStoreFactory, STORE_VERSION, auto_save_job_name,
+        migrate_store, profile_cookie_path,
+    };
+    use crate::infrastructure::storage::storage_backend::AsyncStorageManager;
+    use crate::service::config::{
+        CookieBackend, CookieConfig, CookiePolicy, CookiePolicyRule, SchedulerConfig,
+    };
+    use crate::superstructure::job_scheduler::DefaultJobScheduler;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    macro_rules! await_test {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    fn job_scheduler() -> Arc<dyn JobScheduler> {
+        let storage_manager = Arc::new(AsyncStorageManager::new());
+        let job_scheduler = await_test!(DefaultJobScheduler::new(
+            SchedulerConfig::default(),
+            storage_manager
+        ));
+        Arc::new(job_scheduler.unwrap())
+    }
+
+    fn cookie_config() -> CookieConfig {
+        CookieConfig {
+            backend: CookieBackend::File,
+            cookie_path: None,
+            debounce_delay: Duration::from_secs(10),
+            auto_save_interval: None,
+            initial_cookies: None,
+            public_suffix_list_path: None,
+            max_cookies: Some(3),
+            max_cookies_per_domain: Some(2),
+            policies: vec![],
+            max_cookie_lifetime: None,
+            session_only: false,
+        }
+    }
+
+    fn session_cookie(domain: &str, name: &str) -> Cookie {
+        Cookie::new_without_expires(
+            domain.to_string(),
+            "/".to_string(),
+            name.to_string(),
+            "value".to_string(),
+            false,
+            false,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_cookie_limits_evict_least_recently_used() {
+        let store = await_test!(FileBackedCookieStore::new(cookie_config())).unwrap();
+
+        await_test!(store.set(session_cookie("a.example.com", "first"))).unwrap();
+        await_test!(store.set(session_cookie("a.example.com", "second"))).unwrap();
+        await_test!(store.set(session_cookie("a.example.com", "third"))).unwrap();
+
+        let cookies = await_test!(store.get_for_domain("a.example.com"));
+        assert_eq!(cookies.len(), 2);
+        assert!(cookies.iter().all(|cookie| cookie.key.name != "first"));
+
+        await_test!(store.set(session_cookie("b.example.com", "fourth"))).unwrap();
+        await_test!(store.set(session_cookie("c.example.com", "fifth"))).unwrap();
+        assert_eq!(await_test!(store.get_for_domain("a.example.com")).len(), 1);
+    }
+
+    #[test]
+    fn test_cookie_rejected_for_public_suffix() {
+        let store = await_test!(FileBackedCookieStore::new(cookie_config())).unwrap();
+        assert!(await_test!(store.set(session_cookie("co.uk", "supercookie"))).is_err());
+    }
+
+    #[test]
+    fn test_cookie_policies() {
+        let mut config = cookie_config();
+        config.max_cookies = None;
+        config.max_cookies_per_domain = None;
+        config.policies = vec![
+            CookiePolicy {
+                domain_pattern: "*.tracker.com".to_string(),
+                rule: CookiePolicyRule::RejectAll,
+            },
+            CookiePolicy {
+                domain_pattern: "*".to_string(),
+                rule: CookiePolicyRule::FirstPartyOnly {
+                    first_party_domains: vec!["example.com".to_string()],
+                },
+            },
+        ];
+        let store = await_test!(FileBackedCookieStore::new(config)).unwrap();
+
+        assert!(await_test!(store.set(session_cookie("ads.tracker.com", "id"))).is_err());
+        assert!(await_test!(store.set(session_cookie("cdn.other.com", "id"))).is_err());
+        assert!(await_test!(store.set(session_cookie("api.example.com", "id"))).is_ok());
+    }
+
+    #[test]
+    fn test_cookie_profiles_are_isolated() {
+        assert_eq!(
+            profile_cookie_path("data/cookies.json", "alice"),
+            "data/cookies.alice.json"
+        );
+
+        let mut config = cookie_config();
+        config.max_cookies = None;
+        config.max_cookies_per_domain = None;
+        let factory = DefaultCookieStoreFactory::new(config, job_scheduler());
+
+        let alice = await_test!(factory.create_with_profile("alice")).unwrap();
+        let bob = await_test!(factory.create_with_profile("bob")).unwrap();
+        await_test!(alice.set(session_cookie("example.com", "session"))).unwrap();
+
+        assert_eq!(await_test!(alice.get_for_domain("example.com")).len(), 1);
+        assert!(await_test!(bob.get_for_domain("example.com")).is_empty());
+        assert!(await_test!(factory.create_with_profile("../escape")).is_err());
+
+        await_test!(factory.remove_profile("alice")).unwrap();
+        assert_eq!(factory.profiles(), vec!["bob".to_string()]);
+    }
+
+    #[test]
+    fn test_shutdown_flushes_dirty_cookies() {
+        let path = std::env::temp_dir().join(format!(
+            "strawberry_cookie_shutdown_{}.json",
+            std::process::id()
+        ));
+        let path = path.to_string_lossy().to_string();
+
+        let mut config = cookie_config();
+        config.cookie_path = Some(path.clone());
+        config.auto_save_interval = Some(Duration::from_secs(3600));
+        let mut cookie = session_cookie("example.com", "remember");
+        cookie.expires = Some(std::time::SystemTime::now() + Duration::from_secs(3600));
+        cookie.persistent = true;
+
+        await_test!(async {
+            let store = Arc::new(FileBackedCookieStore::new(config.clone()).await.unwrap());
+            store.set(cookie).await.unwrap();
+            store.shutdown().await.unwrap();
+
+            let reloaded = FileBackedCookieStore::new(config).await.unwrap();
+            assert_eq!(reloaded.get_for_domain("example.com").await.len(), 1);
+        });
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_auto_save_clears_the_dirty_mark() {
+        let path = std::env::temp_dir().join(format!(
+            "strawberry_cookie_auto_save_{}.json",
+            std::process::id()
+        ));
+        let path = path.to_string_lossy().to_string();
+
+        let mut config = cookie_config();
+        config.cookie_path = Some(path.clone());
+        let mut cookie = session_cookie("example.com", "remember");
+        cookie.expires = Some(std::time::SystemTime::now() + Duration::from_secs(3600));
+        cookie.persistent = true;
+
+        await_test!(async {
+            let store = FileBackedCookieStore::new(config.clone()).await.unwrap();
+            store.set(cookie).await.unwrap();
+            assert!(store.is_dirty());
+            store.auto_save().await.unwrap();
+            assert!(!store.is_dirty());
+
+            let reloaded = FileBackedCookieStore::new(config).await.unwrap();
+            assert_eq!(reloaded.get_for_domain("example.com").await.len(), 1);
+        });
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_suspend_flushes_profiles_and_resume_restarts_auto_save() {
+        let path = std::env::temp_dir().join(format!(
+            "strawberry_cookie_suspend_{}.json",
+            std::process::id()
+        ));
+        let path = path.to_string_lossy().to_string();
+        let profile_path = profile_cookie_path(&path, "alice");
+        let auto_save_paused = |job_scheduler: &Arc<dyn JobScheduler>| {
+            job_scheduler
+                .jobs()
+                .into_iter()
+                .find(|job| job.name == auto_save_job_name("alice"))
+                .map(|job| job.paused)
+        };
+
+        let mut config = cookie_config();
+        config.cookie_path = Some(path.clone());
+        config.auto_save_interval = Some(Duration::from_secs(3600));
+        let mut cookie = session_cookie("example.com", "remember");
+        cookie.expires = Some(std::time::SystemTime::now() + Duration::from_secs(3600));
+        cookie.persistent = true;
+
+        let job_scheduler = job_scheduler();
+        await_test!(async {
+            let factory = DefaultCookieStoreFactory::new(config.clone(), job_scheduler.clone());
+            let alice = factory
+                .create_with_profile("alice")
+                .await
+                .unwrap()
+                .downcast_arc::<FileBackedCookieStore>()
+                .unwrap();
+            alice.set(cookie).await.unwrap();
+
+            factory.suspend().await.unwrap();
+            assert_eq!(auto_save_paused(&job_scheduler), Some(true));
+            let mut profile_config = config.clone();
+            profile_config.cookie_path = Some(profile_path.clone());
+            let reloaded = FileBackedCookieStore::new(profile_config).await.unwrap();
+            assert_eq!(reloaded.get_for_domain("example.com").await.len(), 1);
+
+            factory.resume();
+            assert_eq!(auto_save_paused(&job_scheduler), Some(false));
+            factory.shutdown().await.unwrap();
+            assert_eq!(auto_save_paused(&job_scheduler), None);
+        });
+        let _ = std::fs::remove_file(profile_path);
+    }
+
+    #[test]
+    fn test_session_cookie_lifecycle() {
+        let mut config = cookie_config();
+        config.max_cookies = None;
+        config.max_cookies_per_domain = None;
+        let store = await_test!(FileBackedCookieStore::new(config)).unwrap();
+
+        let remember = session_cookie("example.com", "remember");
+        let key = remember.key.clone();
+        await_test!(store.set(remember)).unwrap();
+        await_test!(store.set(session_cookie("example.com", "session"))).unwrap();
+
+        let expires = std::time::SystemTime::now() + Duration::from_secs(3600);
+        assert_eq!(await_test!(store.promote_session_cookies(std::slice::from_ref(&key), expires)), 1);
+        assert_eq!(await_test!(store.clear_session()), 1);
+
+        let cookie = await_test!(store.get(&key)).unwrap();
+        assert!(cookie.persistent);
+        assert_eq!(cookie.expires, Some(expires));
+        assert_eq!(await_test!(store.get_for_domain("example.com")).len(), 1);
+    }
+
+    #[test]
+    fn test_touch_and_stats() {
+        let mut config = cookie_config();
+        config.max_cookies = None;
+        let store = await_test!(FileBackedCookieStore::new(config)).unwrap();
+
+        let first = session_cookie("example.com", "first");
+        let first_key = first.key.clone();
+        let created = first.last_access_time;
+        await_test!(store.set(first)).unwrap();
+        await_test!(store.set(session_cookie("example.com", "second"))).unwrap();
+        await_test!(store.set(session_cookie("other.com", "third"))).unwrap();
+
+        std::thread::sleep(Duration::from_millis(5));
+        await_test!(store.touch(std::slice::from_ref(&first_key)));
+        assert!(await_test!(store.get(&first_key)).unwrap().last_access_time > created);
+
+        let stats = await_test!(store.stats());
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.session, 3);
+        assert_eq!(stats.expired, 0);
+        assert_eq!(
+            stats.per_domain,
+            vec![("example.com".to_string(), 2), ("other.com".to_string(), 1)]
+        );
+        assert_eq!(stats.storage_size, None);
+    }
+
+    #[test]
+    fn test_legacy_store_is_migrated() {
+        let mut legacy = serde_json::to_value(session_cookie("example.com", "legacy")).unwrap();
+        legacy.as_object_mut().unwrap().remove("host_only");
+        let value = serde_json::json!({
+            "cookies": [legacy, { "name": "broken" }],
+            "saved_at": { "secs_since_epoch": 0, "nanos_since_epoch": 0 },
+        });
+        let (store, malformed) = migrate_store(value).unwrap();
+        assert_eq!(malformed, 1);
+        assert_eq!(store.version, STORE_VERSION);
+        assert_eq!(store.cookies.len(), 1);
+        assert_eq!(store.cookies[0].key.name, "legacy");
+        assert!(store.cookies[0].host_only);
+        assert_eq!(store.saved_at, std::time::UNIX_EPOCH);
+
+        let newer = serde_json::json!({ "version": STORE_VERSION + 1, "cookies": [] });
+        assert!(migrate_store(newer).is_err());
+    }
+
+    #[test]
+    fn test_load_report() {
+        let path = std::env::temp_dir().join(format!(
+            "strawberry_cookie_load_report_{}.json",
+            std::process::id()
+        ));
+        let path = path.to_string_lossy().to_string();
+        let mut config = cookie_config();
+        config.cookie_path = Some(path.clone());
+
+        let mut kept = session_cookie("example.com", "kept");
+        kept.expires = Some(std::time::SystemTime::now() + Duration::from_secs(3600));
+        let mut expired = session_cookie("example.com", "expired");
+        expired.expires = Some(std::time::UNIX_EPOCH);
+        let value = serde_json::json!({
+            "version": STORE_VERSION,
+            "cookies": [kept, expired, { "name": "broken" }],
+            "saved_at": { "secs_since_epoch": 0, "nanos_since_epoch": 0 },
+        });
+        std::fs::write(&path, value.to_string()).unwrap();
+        let store = await_test!(FileBackedCookieStore::new(config.clone())).unwrap();
+        let report = store.load_report().unwrap();
+        assert_eq!((report.loaded, report.expired, report.malformed), (1, 1, 1));
+        assert!(!report.is_clean());
+
+        // the store still starts, the corrupted file is kept aside
+        std::fs::write(&path, "{ not json").unwrap();
+        let store = await_test!(FileBackedCookieStore::new(config)).unwrap();
+        let report = store.load_report().unwrap();
+        assert_eq!(report.loaded, 0);
+        assert!(report.error.is_some());
+        let corrupted = format!("{}.corrupted", path);
+        assert!(std::path::Path::new(&corrupted).exists());
+        let _ = std::fs::remove_file(corrupted);
+    }
+
+    #[test]
+    fn test_lifetime_limits() {
+        let mut config = cookie_config();
+        config.max_cookie_lifetime = Some(Duration::from_secs(3600));
+        let store = await_test!(FileBackedCookieStore::new(config)).unwrap();
+        let mut cookie = session_cookie("example.com", "tracker");
+        cookie.expires = Some(std::time::SystemTime::now() + Duration::from_secs(86400 * 3650));
+        cookie.persistent = true;
+        let key = cookie.key.clone();
+        await_test!(store.set(cookie)).unwrap();
+        let expires = await_test!(store.get(&key)).unwrap().expires.unwrap();
+        assert!(expires <= std::time::SystemTime::now() + Duration::from_secs(3600));
+
+        let path = std::env::temp_dir().join(format!(
+            "strawberry_cookie_session_only_{}.json",
+            std::process::id()
+        ));
+        let mut config = cookie_config();
+        config.cookie_path = Some(path.to_string_lossy().to_string());
+        config.session_only = true;
+        let store = await_test!(FileBackedCookieStore::new(config)).unwrap();
+        let mut cookie = session_cookie("example.com", "remember");
+        cookie.expires = Some(std::time::SystemTime::now() + Duration::from_secs(3600));
+        cookie.persistent = true;
+        let key = cookie.key.clone();
+        await_test!(store.set(cookie)).unwrap();
+        assert!(!await_test!(store.get(&key)).unwrap().persistent);
+        let expires = std::time::SystemTime::now() + Duration::from_secs(3600);
+        let promoted = await_test!(store.promote_session_cookies(&[key], expires));
+        assert_eq!(promoted, 0);
+        await_test!(store.persist()).unwrap();
+        assert!(!path.exists());
+
+        // the cookies stored earlier are clamped on load, and not loaded at all for a session
+        let mut config = cookie_config();
+        config.cookie_path = Some(path.to_string_lossy().to_string());
+        let store = await_test!(FileBackedCookieStore::new(config.clone())).unwrap();
+        let mut cookie = session_cookie("example.com", "remember");
+        cookie.expires = Some(std::time::SystemTime::now() + Duration::from_secs(86400 * 3650));
+        cookie.persistent = true;
+        let key = cookie.key.clone();
+        await_test!(store.set(cookie)).unwrap();
+        await_test!(store.persist()).unwrap();
+
+        config.max_cookie_lifetime = Some(Duration::from_secs(3600));
+        let store = await_test!(FileBackedCookieStore::new(config.clone())).unwrap();
+        let expires = await_test!(store.get(&key)).unwrap().expires.unwrap();
+        assert!(expires <= std::time::SystemTime::now() + Duration::from_secs(3600));
+
+        config.session_only = true;
+        let store = await_test!(FileBackedCookieStore::new(config)).unwrap();
+        assert!(await_test!(store.get(&key)).is_none());
+        let _ = std::fs::remove_file(path);
     }
 }
@@ -0,0 +1,240 @@
+use crate::domain::models::cookie_models::{Cookie, CookieError, SameSite};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const NETSCAPE_HEADER: &str = "# Netscape HTTP Cookie File";
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DartCookie {
+    name: String,
+    value: String,
+    #[serde(default)]
+    expires: Option<u64>,
+    #[serde(default)]
+    max_age: Option<u64>,
+    #[serde(default)]
+    domain: Option<String>,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    http_only: bool,
+    #[serde(default)]
+    same_site: Option<String>,
+}
+
+fn to_epoch_seconds(time: &SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs()
+}
+
+fn to_epoch_millis(time: &SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_millis() as u64
+}
+
+fn flag(value: bool) -> &'static str {
+    if value { "TRUE" } else { "FALSE" }
+}
+
+pub fn export_netscape(cookies: &Vec<Cookie>) -> String {
+    let mut lines = vec![NETSCAPE_HEADER.to_string(), "".to_string()];
+    for cookie in cookies {
+        let domain = if cookie.http_only {
+            format!("{}{}", HTTP_ONLY_PREFIX, cookie.key.domain)
+        } else {
+            cookie.key.domain.clone()
+        };
+        let expires = cookie.expires.as_ref().map(to_epoch_seconds).unwrap_or(0);
+        lines.push(format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            domain,
+            flag(!cookie.host_only),
+            cookie.key.path,
+            flag(cookie.secure),
+            expires,
+            cookie.key.name,
+            cookie.value
+        ));
+    }
+    lines.join("\n")
+}
+
+pub fn import_netscape(content: &str) -> Result<Vec<Cookie>, CookieError> {
+    let mut cookies = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 7 {
+            return Err(CookieError::Serialization(format!(
+                "line {} has {} fields, expected 7",
+                index + 1,
+                fields.len()
+            )));
+        }
+        let expires = fields[4]
+            .parse::<u64>()
+            .map_err(|e| CookieError::Serialization(format!("line {}: {}", index + 1, e)))?;
+        let expires = if expires == 0 {
+            None
+        } else {
+            Some(UNIX_EPOCH + Duration::from_secs(expires))
+        };
+
+        let mut cookie = Cookie::new(
+            fields[0].trim_start_matches('.').to_string(),
+            fields[2].to_string(),
+            fields[5].to_string(),
+            fields[6].to_string(),
+            expires,
+            fields[3].eq_ignore_ascii_case("TRUE"),
+            http_only,
+            None,
+        );
+        cookie.host_only = !fields[1].eq_ignore_ascii_case("TRUE");
+        cookies.push(cookie);
+    }
+    Ok(cookies)
+}
+
+pub fn export_dart_json(cookies: &[Cookie]) -> Result<String, CookieError> {
+    let dart_cookies: Vec<DartCookie> = cookies
+        .iter()
+        .map(|cookie| DartCookie {
+            name: cookie.key.name.clone(),
+            value: cookie.value.clone(),
+            expires: cookie.expires.as_ref().map(to_epoch_millis),
+            max_age: None,
+            domain: Some(cookie.key.domain.clone()),
+            path: Some(cookie.key.path.clone()),
+            secure: cookie.secure,
+            http_only: cookie.http_only,
+            same_site: cookie.same_site.as_ref().map(|same_site| {
+                match same_site {
+                    SameSite::Strict => "Strict",
+                    SameSite::Lax => "Lax",
+                    SameSite::None => "None",
+                }
+                .to_string()
+            }),
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&dart_cookies)
+        .map_err(|e| CookieError::Serialization(e.to_string()))
+}
+
+pub fn import_dart_json(content: &str) -> Result<Vec<Cookie>, CookieError> {
+    let dart_cookies: Vec<DartCookie> =
+        serde_json::from_str(content).map_err(|e| CookieError::Serialization(e.to_string()))?;
+
+    let mut cookies = Vec::new();
+    for dart_cookie in dart_cookies {
+        if dart_cookie.domain.is_none() {
+            return Err(CookieError::Serialization(format!(
+                "cookie {} has no domain",
+                dart_cookie.name
+            )));
+        }
+        // Max-Age takes precedence over Expires, as it does for Set-Cookie
+        let expires = match (dart_cookie.max_age, dart_cookie.expires) {
+            (Some(max_age), _) => Some(SystemTime::now() + Duration::from_secs(max_age)),
+            (None, Some(expires)) => Some(UNIX_EPOCH + Duration::from_millis(expires)),
+            (None, None) => None,
+        };
+        let same_site = dart_cookie
+            .same_site
+            .map(|same_site| same_site.to_lowercase())
+            .and_then(|same_site| match same_site.as_str() {
+                "strict" => Some(SameSite::Strict),
+                "lax" => Some(SameSite::Lax),
+                "none" => Some(SameSite::None),
+                _ => None,
+            });
+
+        cookies.push(Cookie::new(
+            dart_cookie
+                .domain
+                .unwrap()
+                .trim_start_matches('.')
+                .to_string(),
+            dart_cookie.path.unwrap_or("/".to_string()),
+            dart_cookie.name,
+            dart_cookie.value,
+            expires,
+            dart_cookie.secure,
+            dart_cookie.http_only,
+            same_site,
+        ));
+    }
+    Ok(cookies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_dart_json, export_netscape, import_dart_json, import_netscape};
+    use crate::domain::models::cookie_models::{Cookie, SameSite};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn cookies() -> Vec<Cookie> {
+        vec![
+            Cookie::new(
+                "example.com".to_string(),
+                "/".to_string(),
+                "session".to_string(),
+                "abc".to_string(),
+                Some(UNIX_EPOCH + Duration::from_secs(4102444800)),
+                true,
+                true,
+                Some(SameSite::Lax),
+            ),
+            Cookie::new_without_expires(
+                "example.com".to_string(),
+                "/api".to_string(),
+                "theme".to_string(),
+                "dark".to_string(),
+                false,
+                false,
+                None,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_netscape_round_trip() {
+        let exported = export_netscape(&cookies());
+        let imported = import_netscape(&exported).unwrap();
+
+        assert_eq!(imported.len(), 2);
+        assert_eq!(imported[0].key, cookies()[0].key);
+        assert_eq!(imported[0].expires, cookies()[0].expires);
+        assert!(imported[0].http_only && imported[0].secure);
+        assert_eq!(imported[1].expires, None);
+        assert_eq!(imported[1].key.path, "/api");
+    }
+
+    #[test]
+    fn test_dart_json_round_trip() {
+        let exported = export_dart_json(&cookies()).unwrap();
+        let imported = import_dart_json(&exported).unwrap();
+
+        assert_eq!(imported.len(), 2);
+        assert_eq!(imported[0].key, cookies()[0].key);
+        assert_eq!(imported[0].same_site, Some(SameSite::Lax));
+        assert_eq!(imported[0].expires, cookies()[0].expires);
+        assert!(!imported[1].persistent);
+    }
+}
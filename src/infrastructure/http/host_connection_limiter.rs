@@ -0,0 +1,52 @@
+use dashmap::DashMap;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+// caps the requests in flight per host, a request keeps its permit until its body was read or
+// its stream dropped, the ones over the cap wait for a permit
+pub struct HostConnectionLimiter {
+    max_connections_per_host: usize,
+    hosts: DashMap<String, Arc<Semaphore>>,
+}
+
+impl HostConnectionLimiter {
+    pub fn new(max_connections_per_host: usize) -> Self {
+        Self {
+            max_connections_per_host,
+            hosts: DashMap::new(),
+        }
+    }
+
+    pub async fn acquire(&self, host: &str) -> OwnedSemaphorePermit {
+        let semaphore = self
+            .hosts
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_connections_per_host)))
+            .clone();
+        // the semaphores are never closed
+        semaphore.acquire_owned().await.unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::infrastructure::http::host_connection_limiter::HostConnectionLimiter;
+    use std::time::Duration;
+
+    #[test]
+    fn test_hosts_are_limited_separately() {
+        let limiter = HostConnectionLimiter::new(1);
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let first = limiter.acquire("a.com").await;
+            let _other = limiter.acquire("b.com").await;
+
+            let second = tokio::time::timeout(Duration::from_millis(20), limiter.acquire("a.com"));
+            assert!(second.await.is_err());
+
+            drop(first);
+            let second = tokio::time::timeout(Duration::from_millis(20), limiter.acquire("a.com"));
+            assert!(second.await.is_ok());
+        });
+    }
+}
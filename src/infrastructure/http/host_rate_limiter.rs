@@ -0,0 +1,77 @@
+use crate::service::config::RateLimitConfig;
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::time::Instant;
+
+// caps the requests started per host within a sliding period, the ones over the cap wait until
+// the oldest start leaves the period
+pub struct HostRateLimiter {
+    config: RateLimitConfig,
+    hosts: DashMap<String, Arc<Mutex<VecDeque<Instant>>>>,
+}
+
+impl HostRateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            hosts: DashMap::new(),
+        }
+    }
+
+    pub async fn acquire(&self, host: &str) {
+        let starts = self
+            .hosts
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(VecDeque::new())))
+            .clone();
+        loop {
+            let next_slot = {
+                let mut starts = starts.lock();
+                let now = Instant::now();
+                while starts
+                    .front()
+                    .is_some_and(|start| now.duration_since(*start) >= self.config.period)
+                {
+                    starts.pop_front();
+                }
+                if starts.len() < self.config.max_requests {
+                    starts.push_back(now);
+                    return;
+                }
+                // the queue holds max_requests starts, so it is not empty
+                *starts.front().unwrap() + self.config.period
+            };
+            tokio::time::sleep_until(next_slot).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::infrastructure::http::host_rate_limiter::HostRateLimiter;
+    use crate::service::config::RateLimitConfig;
+    use std::time::Duration;
+
+    #[test]
+    fn test_hosts_are_limited_separately() {
+        let limiter = HostRateLimiter::new(RateLimitConfig {
+            max_requests: 2,
+            period: Duration::from_millis(200),
+        });
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            limiter.acquire("a.com").await;
+            limiter.acquire("a.com").await;
+            limiter.acquire("b.com").await;
+
+            let third = tokio::time::timeout(Duration::from_millis(20), limiter.acquire("a.com"));
+            assert!(third.await.is_err());
+
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let third = tokio::time::timeout(Duration::from_millis(20), limiter.acquire("a.com"));
+            assert!(third.await.is_ok());
+        });
+    }
+}
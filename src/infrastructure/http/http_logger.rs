@@ -0,0 +1,174 @@
+use crate::domain::models::http_models::HttpLogRecord;
+use crate::domain::traits::http_traits::HttpLogger;
+use std::sync::Arc;
+
+const REDACTED: &str = "<redacted>";
+
+pub struct DefaultHttpLogger {
+    redacted_headers: Vec<String>,
+    redacted_query_parameters: Vec<String>,
+    log_headers: bool,
+}
+
+impl DefaultHttpLogger {
+    pub fn new(
+        redacted_headers: Vec<String>,
+        redacted_query_parameters: Vec<String>,
+        log_headers: bool,
+    ) -> Self {
+        Self {
+            redacted_headers,
+            redacted_query_parameters,
+            log_headers,
+        }
+    }
+
+    pub fn redact_url(&self, url: &str) -> String {
+        let split = url.split_once('?');
+        if split.is_none() {
+            return url.to_string();
+        }
+        let (path, query) = split.unwrap();
+
+        let query = query
+            .split('&')
+            .map(|pair| {
+                let key = pair.split('=').next().unwrap_or("");
+                let redacted = self
+                    .redacted_query_parameters
+                    .iter()
+                    .any(|parameter| parameter.eq_ignore_ascii_case(key));
+                if redacted {
+                    return format!("{}={}", key, REDACTED);
+                }
+                pair.to_string()
+            })
+            .collect::<Vec<String>>()
+            .join("&");
+
+        format!("{}?{}", path, query)
+    }
+
+    pub fn redact_headers(&self, headers: &[(String, String)]) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(key, value)| {
+                let redacted = self
+                    .redacted_headers
+                    .iter()
+                    .any(|header| header.eq_ignore_ascii_case(key));
+                if redacted {
+                    return (key.clone(), REDACTED.to_string());
+                }
+                (key.clone(), value.clone())
+            })
+            .collect()
+    }
+
+    fn format(&self, record: &HttpLogRecord) -> String {
+        let method = record.method.as_str();
+        let outcome = match (&record.status, &record.error) {
+            (Some(status), _) => status.to_string(),
+            (None, Some(error)) => format!("failed ({})", error),
+            (None, None) => "unknown".to_string(),
+        };
+        let response_size = record
+            .response_size
+            .map(|size| size.to_string())
+            .unwrap_or("?".to_string());
+
+        let mut line = format!(
+            "[http] {} {} -> {} in {}ms (request {} bytes, response {} bytes)",
+            method,
+            self.redact_url(&record.url),
+            outcome,
+            record.duration.as_millis(),
+            record.request_size,
+            response_size
+        );
+        if self.log_headers && !record.headers.is_empty() {
+            let headers = self
+                .redact_headers(&record.headers)
+                .iter()
+                .map(|(key, value)| format!("{}: {}", key, value))
+                .collect::<Vec<String>>()
+                .join(", ");
+            line = format!("{} [{}]", line, headers);
+        }
+        line
+    }
+}
+
+impl Default for DefaultHttpLogger {
+    fn default() -> Self {
+        Self::new(
+            vec![
+                "Authorization".to_string(),
+                "Proxy-Authorization".to_string(),
+                "Cookie".to_string(),
+                "Set-Cookie".to_string(),
+            ],
+            vec![
+                "token".to_string(),
+                "access_token".to_string(),
+                "password".to_string(),
+            ],
+            false,
+        )
+    }
+}
+
+impl HttpLogger for DefaultHttpLogger {
+    fn log(&self, record: &HttpLogRecord) {
+        tracing::info!("{}", self.format(record));
+    }
+}
+
+// hands every record to each logger in turn
+pub struct CompositeHttpLogger {
+    loggers: Vec<Arc<dyn HttpLogger>>,
+}
+
+impl CompositeHttpLogger {
+    pub fn new(loggers: Vec<Arc<dyn HttpLogger>>) -> Self {
+        Self { loggers }
+    }
+}
+
+impl HttpLogger for CompositeHttpLogger {
+    fn log(&self, record: &HttpLogRecord) {
+        for logger in &self.loggers {
+            logger.log(record);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DefaultHttpLogger;
+
+    #[test]
+    fn test_redact_url() {
+        let logger = DefaultHttpLogger::default();
+        assert_eq!(
+            logger.redact_url("https://example.com/a?token=abc&q=1"),
+            "https://example.com/a?token=<redacted>&q=1"
+        );
+        assert_eq!(
+            logger.redact_url("https://example.com/a"),
+            "https://example.com/a"
+        );
+    }
+
+    #[test]
+    fn test_redact_headers() {
+        let logger = DefaultHttpLogger::default();
+        let headers = vec![
+            ("authorization".to_string(), "Bearer abc".to_string()),
+            ("Accept".to_string(), "*/*".to_string()),
+        ];
+        let redacted = logger.redact_headers(&headers);
+        assert_eq!(redacted[0].1, "<redacted>");
+        assert_eq!(redacted[1].1, "*/*");
+    }
+}
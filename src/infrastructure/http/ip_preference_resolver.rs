@@ -0,0 +1,82 @@
+use crate::service::config::IpPreference;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::net::SocketAddr;
+
+// the connector races the families in the order the resolver lists them, so leaving out or
+// reordering addresses is enough to steer it
+pub struct IpPreferenceResolver {
+    ip_preference: IpPreference,
+}
+
+impl IpPreferenceResolver {
+    pub fn new(ip_preference: IpPreference) -> Self {
+        Self { ip_preference }
+    }
+}
+
+fn arrange(ip_preference: IpPreference, mut addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    match ip_preference {
+        IpPreference::Auto => {}
+        IpPreference::V4Only => addrs.retain(SocketAddr::is_ipv4),
+        IpPreference::V6Only => addrs.retain(SocketAddr::is_ipv6),
+        IpPreference::PreferV4 => addrs.sort_by_key(SocketAddr::is_ipv6),
+    }
+    addrs
+}
+
+impl Resolve for IpPreferenceResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let ip_preference = self.ip_preference;
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            // the port is filled in by the connector
+            let addrs = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            let addrs = arrange(ip_preference, addrs);
+            if addrs.is_empty() {
+                return Err(
+                    format!("{} has no address allowed by {:?}", host, ip_preference).into(),
+                );
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::infrastructure::http::ip_preference_resolver::{IpPreferenceResolver, arrange};
+    use crate::service::config::IpPreference;
+    use reqwest::dns::Resolve;
+    use std::net::SocketAddr;
+
+    #[test]
+    fn test_addresses_are_arranged() {
+        let addrs: Vec<SocketAddr> = vec![
+            "[::1]:0".parse().unwrap(),
+            "127.0.0.1:0".parse().unwrap(),
+            "[::2]:0".parse().unwrap(),
+            "127.0.0.2:0".parse().unwrap(),
+        ];
+        let arranged = |ip_preference| {
+            arrange(ip_preference, addrs.clone())
+                .iter()
+                .map(|addr| addr.ip().to_string())
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(
+            arranged(IpPreference::Auto),
+            ["::1", "127.0.0.1", "::2", "127.0.0.2"]
+        );
+        assert_eq!(arranged(IpPreference::V4Only), ["127.0.0.1", "127.0.0.2"]);
+        assert_eq!(arranged(IpPreference::V6Only), ["::1", "::2"]);
+        assert_eq!(
+            arranged(IpPreference::PreferV4),
+            ["127.0.0.1", "127.0.0.2", "::1", "::2"]
+        );
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let resolver = IpPreferenceResolver::new(IpPreference::V6Only);
+        let result = runtime.block_on(resolver.resolve("127.0.0.1".parse().unwrap()));
+        assert!(result.is_err());
+    }
+}
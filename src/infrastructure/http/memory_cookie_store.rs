@@ -0,0 +1,161 @@
+use crate::domain::models::cookie_models::{
+    Cookie, CookieChange, CookieError, CookieFormat, CookieKey, CookieStats,
+};
+use crate::domain::traits::cookie_traits::CookieStore;
+use crate::infrastructure::http::cookie_backend::FileBackedCookieStore;
+use crate::service::config::CookieConfig;
+use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+use std::time::SystemTime;
+
+// same policies and limits as the file backed store, but never touches disk
+pub struct MemoryCookieStore {
+    inner: FileBackedCookieStore,
+}
+
+impl MemoryCookieStore {
+    pub async fn new(mut config: CookieConfig) -> Result<Self, CookieError> {
+        config.cookie_path = None;
+        config.auto_save_interval = None;
+        let inner = FileBackedCookieStore::new(config).await?;
+        Ok(Self { inner })
+    }
+
+    pub async fn purge_expired(&self) -> usize {
+        self.inner.purge_expired().await
+    }
+}
+
+#[async_trait]
+impl CookieStore for MemoryCookieStore {
+    async fn get(&self, key: &CookieKey) -> Option<Cookie> {
+        self.inner.get(key).await
+    }
+
+    async fn set(&self, cookie: Cookie) -> Result<(), CookieError> {
+        self.inner.set(cookie).await
+    }
+
+    async fn remove(&self, key: &CookieKey) {
+        self.inner.remove(key).await
+    }
+
+    async fn get_for_domain(&self, domain: &str) -> Vec<Cookie> {
+        self.inner.get_for_domain(domain).await
+    }
+
+    async fn get_for_url(&self, url: &str) -> Vec<Cookie> {
+        self.inner.get_for_url(url).await
+    }
+
+    async fn touch(&self, keys: &[CookieKey]) {
+        self.inner.touch(keys).await
+    }
+
+    fn set_sync(&self, cookie: Cookie) -> Result<(), CookieError> {
+        self.inner.set_sync(cookie)
+    }
+
+    fn remove_sync(&self, key: &CookieKey) {
+        self.inner.remove_sync(key)
+    }
+
+    fn get_for_url_sync(&self, url: &str) -> Vec<Cookie> {
+        self.inner.get_for_url_sync(url)
+    }
+
+    fn touch_sync(&self, keys: &[CookieKey]) {
+        self.inner.touch_sync(keys)
+    }
+
+    async fn clear_all(&self) {
+        self.inner.clear_all().await
+    }
+
+    async fn clear_session(&self) -> usize {
+        self.inner.clear_session().await
+    }
+
+    async fn promote_session_cookies(&self, keys: &[CookieKey], expires: SystemTime) -> usize {
+        self.inner.promote_session_cookies(keys, expires).await
+    }
+
+    async fn persist(&self) -> Result<(), CookieError> {
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<(), CookieError> {
+        Ok(())
+    }
+
+    async fn export(&self, format: CookieFormat) -> Result<Vec<u8>, CookieError> {
+        self.inner.export(format).await
+    }
+
+    async fn import(&self, format: CookieFormat, bytes: &[u8]) -> Result<usize, CookieError> {
+        self.inner.import(format, bytes).await
+    }
+
+    async fn stats(&self) -> CookieStats {
+        self.inner.stats().await
+    }
+
+    fn subscribe(&self) -> BoxStream<'static, CookieChange> {
+        self.inner.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::cookie_models::Cookie;
+    use crate::domain::traits::cookie_traits::CookieStore;
+    use crate::infrastructure::http::memory_cookie_store::MemoryCookieStore;
+    use crate::service::config::{CookieBackend, CookieConfig};
+    use std::time::Duration;
+
+    macro_rules! await_test {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    #[test]
+    fn test_memory_store_never_touches_disk() {
+        let path = std::env::temp_dir().join(format!(
+            "strawberry_memory_cookie_{}.json",
+            std::process::id()
+        ));
+        let config = CookieConfig {
+            backend: CookieBackend::Memory,
+            cookie_path: Some(path.to_string_lossy().to_string()),
+            debounce_delay: Duration::from_secs(10),
+            auto_save_interval: Some(Duration::from_secs(1)),
+            initial_cookies: None,
+            public_suffix_list_path: None,
+            max_cookies: None,
+            max_cookies_per_domain: None,
+            policies: vec![],
+            max_cookie_lifetime: None,
+            session_only: false,
+        };
+        let store = await_test!(MemoryCookieStore::new(config)).unwrap();
+        let cookie = Cookie::new_without_expires(
+            "example.com".to_string(),
+            "/".to_string(),
+            "session".to_string(),
+            "value".to_string(),
+            false,
+            false,
+            None,
+        );
+
+        await_test!(store.set(cookie)).unwrap();
+        await_test!(store.persist()).unwrap();
+
+        assert_eq!(
+            await_test!(store.get_for_url("https://example.com/")).len(),
+            1
+        );
+        assert!(!path.exists());
+    }
+}
@@ -1,2 +1,16 @@
 pub mod reqwest_backend;
-pub mod cookie_backend;
\ No newline at end of file
+pub mod cookie_backend;
+pub mod memory_cookie_store;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_cookie_store;
+pub mod cookie_format;
+pub mod set_cookie;
+pub mod response_validator;
+pub mod user_agent_provider;
+pub mod circuit_breaker;
+pub mod http_logger;
+pub mod reloadable_http_client;
+pub mod ip_preference_resolver;
+pub mod host_connection_limiter;
+pub mod host_rate_limiter;
+pub mod reqwest_cookie_jar;
\ No newline at end of file
@@ -0,0 +1,244 @@
+use crate::domain::models::http_models::{
+    HttpClientError, HttpEndpoint, HttpResponse, HttpStreamResponse,
+};
+use crate::domain::traits::cookie_traits::{CookieStore, CookieStoreFactory};
+use crate::domain::traits::http_traits::{DecryptionProvider, EncryptionProvider, HttpClient};
+use crate::infrastructure::http::reqwest_backend::ReqwestBackend;
+use crate::service::config::{HttpConfig, HttpReconfiguration};
+use async_trait::async_trait;
+use parking_lot::{Mutex, RwLock};
+use std::sync::Arc;
+
+// hands every request to the current backend, reconfiguring builds a new one and swaps it in
+// while requests already running finish on the old one
+pub struct ReloadableHttpClient {
+    config: Mutex<HttpConfig>,
+    cookie_store: Option<Arc<dyn CookieStore>>,
+    cookie_store_factory: Option<Arc<dyn CookieStoreFactory>>,
+    backend: RwLock<Arc<dyn HttpClient>>,
+}
+
+pub struct PreparedBackend {
+    config: HttpConfig,
+    backend: Arc<dyn HttpClient>,
+}
+
+impl ReloadableHttpClient {
+    pub fn new(
+        config: HttpConfig,
+        cookie_store: Option<Arc<dyn CookieStore>>,
+        cookie_store_factory: Option<Arc<dyn CookieStoreFactory>>,
+    ) -> Result<Self, HttpClientError> {
+        let backend = ReqwestBackend::with_parameters(
+            config.clone(),
+            cookie_store.clone(),
+            cookie_store_factory.clone(),
+        )?;
+        Ok(Self {
+            config: Mutex::new(config),
+            cookie_store,
+            cookie_store_factory,
+            backend: RwLock::new(Arc::new(backend)),
+        })
+    }
+
+    // the current backend stays in place when the new one cannot be built, circuit breaker
+    // and rate limit state starts over with the new backend
+    pub fn reconfigure(&self, update: HttpReconfiguration) -> Result<(), HttpClientError> {
+        let prepared = self.prepare(update)?;
+        self.commit(prepared);
+        Ok(())
+    }
+
+    // builds the backend for an update without putting it in place, so a caller changing
+    // other settings along with it can still back out
+    pub fn prepare(&self, update: HttpReconfiguration) -> Result<PreparedBackend, HttpClientError> {
+        let mut updated = self.config.lock().clone();
+        if let Some(connect_timeout) = update.connect_timeout {
+            updated.connect_timeout = connect_timeout;
+        }
+        if let Some(request_timeout) = update.request_timeout {
+            updated.request_timeout = request_timeout;
+        }
+        if let Some(pool_idle_timeout) = update.pool_idle_timeout {
+            updated.pool_idle_timeout = pool_idle_timeout;
+        }
+        if let Some(all_proxy) = update.all_proxy {
+            updated.all_proxy = all_proxy;
+        }
+        if let Some(host_proxy) = update.host_proxy {
+            updated.host_proxy = host_proxy;
+        }
+        if let Some(circuit_breaker) = update.circuit_breaker {
+            updated.circuit_breaker = circuit_breaker;
+        }
+        if let Some(rate_limit) = update.rate_limit {
+            updated.rate_limit = rate_limit;
+        }
+        if let Some(encryption_provider) = update.encryption_provider {
+            updated.encryption_provider = encryption_provider;
+        }
+        if let Some(decryption_provider) = update.decryption_provider {
+            updated.decryption_provider = decryption_provider;
+        }
+        if let Some(response_validators) = update.response_validators {
+            updated.response_validators = response_validators;
+        }
+
+        let backend = ReqwestBackend::with_parameters(
+            updated.clone(),
+            self.cookie_store.clone(),
+            self.cookie_store_factory.clone(),
+        )?;
+        Ok(PreparedBackend {
+            config: updated,
+            backend: Arc::new(backend),
+        })
+    }
+
+    pub fn commit(&self, prepared: PreparedBackend) {
+        let mut config = self.config.lock();
+        *self.backend.write() = prepared.backend;
+        *config = prepared.config;
+    }
+
+    fn backend(&self) -> Arc<dyn HttpClient> {
+        self.backend.read().clone()
+    }
+
+    // requests still running may hold the backend, so a provider change rebuilds it
+    fn rebuild(&mut self) {
+        match ReqwestBackend::with_parameters(
+            self.config.get_mut().clone(),
+            self.cookie_store.clone(),
+            self.cookie_store_factory.clone(),
+        ) {
+            Ok(backend) => *self.backend.get_mut() = Arc::new(backend),
+            Err(e) => tracing::warn!("Failed to rebuild the http backend: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl HttpClient for ReloadableHttpClient {
+    fn set_encryption_provider(&mut self, encryption_provider: Arc<dyn EncryptionProvider>) {
+        self.config.get_mut().encryption_provider = Some(encryption_provider);
+        self.rebuild();
+    }
+
+    fn set_decryption_provider(&mut self, decryption_provider: Arc<dyn DecryptionProvider>) {
+        self.config.get_mut().decryption_provider = Some(decryption_provider);
+        self.rebuild();
+    }
+
+    fn remove_encryption_provider(&mut self) -> Option<Arc<dyn EncryptionProvider>> {
+        let removed = self.config.get_mut().encryption_provider.take();
+        self.rebuild();
+        removed
+    }
+
+    fn remove_decryption_provider(&mut self) -> Option<Arc<dyn DecryptionProvider>> {
+        let removed = self.config.get_mut().decryption_provider.take();
+        self.rebuild();
+        removed
+    }
+
+    async fn execute(&self, endpoint: HttpEndpoint) -> Result<HttpResponse, HttpClientError> {
+        self.backend().execute(endpoint).await
+    }
+
+    async fn execute_stream(
+        &self,
+        endpoint: HttpEndpoint,
+    ) -> Result<HttpStreamResponse, HttpClientError> {
+        self.backend().execute_stream(endpoint).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::infrastructure::http::reloadable_http_client::ReloadableHttpClient;
+    use crate::service::config::{HttpConfig, HttpReconfiguration, IpPreference, RateLimitConfig};
+    use std::time::Duration;
+
+    fn http_config() -> HttpConfig {
+        HttpConfig {
+            connect_timeout: Duration::from_secs(10),
+            request_timeout: Duration::from_secs(30),
+            pool_idle_timeout: Duration::from_secs(90),
+            pool_max_idle_per_host: 100,
+            max_connections_per_host: 100,
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            tcp_nodelay: true,
+            http2_keep_alive_interval: None,
+            encryption_provider: None,
+            decryption_provider: None,
+            cookie_config: None,
+            all_proxy: None,
+            host_proxy: None,
+            tls_danger_accept_invalid_certs: false,
+            tls_danger_accept_invalid_hostnames: false,
+            response_validators: None,
+            user_agent_provider: None,
+            circuit_breaker: None,
+            rate_limit: None,
+            logger: None,
+            ip_preference: IpPreference::Auto,
+            per_domain: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_reconfigure_keeps_the_backend_on_failure() {
+        let client = ReloadableHttpClient::new(http_config(), None, None).unwrap();
+        let backend = client.backend();
+
+        let result = client.reconfigure(HttpReconfiguration {
+            request_timeout: Some(Duration::from_secs(5)),
+            all_proxy: Some(Some("http://[::1".to_string())),
+            ..Default::default()
+        });
+        assert!(result.is_err());
+        assert!(std::sync::Arc::ptr_eq(&backend, &client.backend()));
+        assert_eq!(client.config.lock().request_timeout, Duration::from_secs(30));
+
+        client
+            .reconfigure(HttpReconfiguration {
+                request_timeout: Some(Duration::from_secs(5)),
+                all_proxy: Some(Some("http://127.0.0.1:8080".to_string())),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(!std::sync::Arc::ptr_eq(&backend, &client.backend()));
+        let config = client.config.lock();
+        assert_eq!(config.request_timeout, Duration::from_secs(5));
+        assert_eq!(config.connect_timeout, Duration::from_secs(10));
+        assert_eq!(config.all_proxy.as_deref(), Some("http://127.0.0.1:8080"));
+    }
+
+    #[test]
+    fn test_reconfigure_sets_and_removes_the_rate_limit() {
+        let client = ReloadableHttpClient::new(http_config(), None, None).unwrap();
+
+        client
+            .reconfigure(HttpReconfiguration {
+                rate_limit: Some(Some(RateLimitConfig {
+                    max_requests: 10,
+                    period: Duration::from_secs(1),
+                })),
+                ..Default::default()
+            })
+            .unwrap();
+        let rate_limit = client.config.lock().rate_limit.clone().unwrap();
+        assert_eq!(rate_limit.max_requests, 10);
+        assert_eq!(rate_limit.period, Duration::from_secs(1));
+
+        client
+            .reconfigure(HttpReconfiguration {
+                rate_limit: Some(None),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(client.config.lock().rate_limit.is_none());
+    }
+}
@@ -1,21 +1,36 @@
-use crate::domain::models::cookie_models::{Cookie, SameSite};
 use crate::domain::models::http_models::{
-    HttpClientError, HttpEndpoint, HttpMethod, HttpResponse, HttpStreamResponse,
+    DEFAULT_ENDPOINT_TIMEOUT, HttpClientError, HttpEndpoint, HttpLogRecord, HttpMethod,
+    HttpResponse, HttpRetryPolicy, HttpStreamResponse, ProxySelection,
 };
 use crate::domain::models::monitor_models::{EventStage, MonitorEvent, MonitorHttpData, Progress};
-use crate::domain::traits::cookie_traits::CookieStore;
-use crate::domain::traits::http_traits::{DecryptionProvider, EncryptionProvider, HttpClient};
+use crate::domain::traits::cookie_traits::{CookieStore, CookieStoreFactory};
+use crate::domain::traits::http_traits::{
+    DecryptionProvider, EncryptionProvider, HttpClient, HttpLogger, ResponseValidator,
+    UserAgentProvider,
+};
 use crate::domain::traits::monitor_traits::Monitor;
+use crate::infrastructure::http::circuit_breaker::CircuitBreaker;
+use crate::infrastructure::http::host_connection_limiter::HostConnectionLimiter;
+use crate::infrastructure::http::host_rate_limiter::HostRateLimiter;
+use crate::infrastructure::http::ip_preference_resolver::IpPreferenceResolver;
+use crate::infrastructure::http::reqwest_cookie_jar::ReqwestCookieJar;
+use crate::monitor::metrics_service::metrics;
 use crate::monitor::monitor_service::monitoring;
-use crate::service::config::HttpConfig;
-use crate::utils::progress_reader::{AsyncProgressReader, ProgressReader};
+use crate::service::config::{DomainOverride, HttpConfig, IpPreference, host_matches};
+use crate::utils::progress_reader::AsyncProgressReader;
 use crate::utils::stream_with_callback::StreamCallbackExt;
 use async_trait::async_trait;
-use futures_util::{Stream, StreamExt, TryStreamExt};
-use reqwest::{Client, Method, Proxy, Response, Url};
-use std::io::ErrorKind;
+use dashmap::DashMap;
+use futures_util::{StreamExt, TryStreamExt};
+use http_body_util::BodyStream;
+use parking_lot::Mutex;
+use reqwest::redirect::Policy;
+use reqwest::{Body, Client, ClientBuilder, Method, Proxy, Response, Url};
+use std::cell::RefCell;
+use std::future::Future;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::OwnedSemaphorePermit;
 use tokio_util::compat::FuturesAsyncReadCompatExt;
 
 fn send_monitor_event(
@@ -25,8 +40,7 @@ fn send_monitor_event(
     progress_values: Option<(u64, u64, u64)>,
 ) {
     let mut progress_option: Option<Progress> = None;
-    if progress_values.is_some() {
-        let values = progress_values.unwrap();
+    if let Some(values) = progress_values {
         progress_option = Some(Progress {
             value: values.0,
             total: values.1,
@@ -42,25 +56,141 @@ fn send_monitor_event(
     monitor.send(event);
 }
 
+tokio::task_local! {
+    // the urls that answered with a redirect, for the request sent from this task
+    static REDIRECTS: RefCell<Vec<String>>;
+}
+
+// follows redirects the way the default policy does, noting the chain on the way
+fn recording_redirect_policy() -> Policy {
+    let default_policy = Policy::default();
+    Policy::custom(move |attempt| {
+        let previous = attempt.previous().iter().map(Url::to_string).collect();
+        let _ = REDIRECTS.try_with(|redirects| *redirects.borrow_mut() = previous);
+        default_policy.redirect(attempt)
+    })
+}
+
 pub struct ReqwestBackend {
     encryption_provider: Option<Arc<dyn EncryptionProvider>>,
     decryption_provider: Option<Arc<dyn DecryptionProvider>>,
     cookie_store: Option<Arc<dyn CookieStore>>,
+    cookie_store_factory: Option<Arc<dyn CookieStoreFactory>>,
+    response_validators: Vec<(String, Arc<dyn ResponseValidator>)>,
+    user_agent_provider: Option<Arc<dyn UserAgentProvider>>,
+    circuit_breaker: Option<CircuitBreaker>,
+    logger: Option<Arc<dyn HttpLogger>>,
+    connection_limiter: Option<HostConnectionLimiter>,
+    rate_limiter: Option<HostRateLimiter>,
+    // each with the client its requests go through
+    per_domain: Vec<(DomainOverride, Client)>,
+    // the clients of the requests choosing a proxy of their own, built on first use, keyed by
+    // the domain of their override and the proxy
+    proxy_clients: DashMap<(Option<String>, ProxySelection), Client>,
+    // to build those, None for a backend created without one
+    config: Option<HttpConfig>,
     client: Client,
 }
 
+// the proxy of the override goes first as reqwest uses the first proxy that matches, a
+// proxy selection other than UseDefault replaces all of them
+fn build_client(
+    config: &HttpConfig,
+    domain_override: Option<&DomainOverride>,
+    proxy_selection: &ProxySelection,
+) -> Result<Client, HttpClientError> {
+    let connect_timeout = domain_override
+        .and_then(|domain_override| domain_override.connect_timeout)
+        .unwrap_or(config.connect_timeout);
+    let mut client = Client::builder()
+        .pool_idle_timeout(config.pool_idle_timeout)
+        .connect_timeout(connect_timeout)
+        .timeout(config.request_timeout)
+        .connection_verbose(true)
+        .redirect(recording_redirect_policy())
+        .cookie_provider(Arc::new(ReqwestCookieJar::per_request()))
+        .tls_danger_accept_invalid_hostnames(config.tls_danger_accept_invalid_hostnames)
+        .tls_danger_accept_invalid_certs(config.tls_danger_accept_invalid_certs)
+        .pool_max_idle_per_host(config.pool_max_idle_per_host)
+        .tcp_keepalive(config.tcp_keepalive)
+        .tcp_nodelay(config.tcp_nodelay)
+        .http2_keep_alive_interval(config.http2_keep_alive_interval);
+
+    // the default resolver already lists the families the way the system prefers them
+    if config.ip_preference != IpPreference::Auto {
+        client = client.dns_resolver(Arc::new(IpPreferenceResolver::new(config.ip_preference)));
+    }
+    match proxy_selection {
+        ProxySelection::UseDefault => {}
+        ProxySelection::Direct => return build(client.no_proxy()),
+        ProxySelection::Specific(proxy) => {
+            let proxy = Proxy::all(proxy.as_str())
+                .map_err(|e| HttpClientError::Configuration(e.to_string()))?;
+            return build(client.proxy(proxy));
+        }
+    }
+    if let Some(proxy) = domain_override.and_then(|domain_override| domain_override.proxy.as_ref())
+    {
+        let proxy = Proxy::all(proxy.as_str())
+            .map_err(|e| HttpClientError::Configuration(e.to_string()))?;
+        client = client.proxy(proxy);
+    }
+    if let Some(all_proxy) = &config.all_proxy {
+        let proxy = Proxy::all(all_proxy.as_str())
+            .map_err(|e| HttpClientError::Configuration(e.to_string()))?;
+        client = client.proxy(proxy);
+    }
+    if let Some(host_proxy) = config.host_proxy.clone() {
+        let proxy = Proxy::custom(move |url| {
+            let host_str = url.host_str()?;
+            for (host, proxy) in host_proxy.iter() {
+                if *host == host_str {
+                    let proxy_url = Url::parse(proxy);
+                    if proxy_url.is_err() {
+                        break;
+                    }
+                    let proxy_url = proxy_url.unwrap();
+                    return Some(proxy_url);
+                }
+            }
+
+            None::<Url>
+        });
+        client = client.proxy(proxy);
+    }
+
+    build(client)
+}
+
+fn build(client: ClientBuilder) -> Result<Client, HttpClientError> {
+    client
+        .build()
+        .map_err(|e| HttpClientError::Network(e.to_string()))
+}
+
 impl ReqwestBackend {
     pub fn new() -> Result<Self, HttpClientError> {
         let client = Client::builder()
             .pool_idle_timeout(Duration::from_secs(90))
             .tcp_keepalive(Duration::from_secs(60))
             .timeout(Duration::from_secs(30))
+            .redirect(recording_redirect_policy())
             .build()
             .map_err(|e| HttpClientError::Network(e.to_string()))?;
         Ok(Self {
             encryption_provider: None,
             decryption_provider: None,
             cookie_store: None,
+            cookie_store_factory: None,
+            response_validators: Vec::new(),
+            user_agent_provider: None,
+            circuit_breaker: None,
+            logger: None,
+            connection_limiter: None,
+            rate_limiter: None,
+            per_domain: Vec::new(),
+            proxy_clients: DashMap::new(),
+            config: None,
             client,
         })
     }
@@ -68,136 +198,219 @@ impl ReqwestBackend {
     pub fn with_parameters(
         config: HttpConfig,
         cookie_store: Option<Arc<dyn CookieStore>>,
+        cookie_store_factory: Option<Arc<dyn CookieStoreFactory>>,
     ) -> Result<Self, HttpClientError> {
-        let mut client = Client::builder()
-            .pool_idle_timeout(config.pool_idle_timeout)
-            .connect_timeout(config.connect_timeout)
-            .timeout(config.request_timeout)
-            .connection_verbose(true)
-            .tls_danger_accept_invalid_hostnames(config.tls_danger_accept_invalid_hostnames)
-            .tls_danger_accept_invalid_certs(config.tls_danger_accept_invalid_certs)
-            .pool_max_idle_per_host(config.max_connections_per_host);
-
-        if let Some(all_proxy) = config.all_proxy {
-            client = client.proxy(Proxy::all(all_proxy).unwrap());
-        }
-        if let Some(host_proxy) = config.host_proxy {
-            let proxy = Proxy::custom(move |url| {
-                let host_str = url.host_str()?;
-                for (host, proxy) in host_proxy.iter() {
-                    if host.to_string() == host_str.to_string() {
-                        let proxy_url = Url::parse(proxy);
-                        if proxy_url.is_err() {
-                            break;
-                        }
-                        let proxy_url = proxy_url.unwrap();
-                        return Some(proxy_url);
-                    }
-                }
-
-                return None::<Url>;
-            });
-            client = client.proxy(proxy);
+        let client = build_client(&config, None, &ProxySelection::UseDefault)?;
+        let mut per_domain = Vec::new();
+        for domain_override in config.per_domain.iter() {
+            // only these need connections of their own
+            let domain_client =
+                if domain_override.connect_timeout.is_some() || domain_override.proxy.is_some() {
+                    build_client(&config, Some(domain_override), &ProxySelection::UseDefault)?
+                } else {
+                    client.clone()
+                };
+            per_domain.push((domain_override.clone(), domain_client));
         }
 
-        let client = client
-            .build()
-            .map_err(|e| HttpClientError::Network(e.to_string()))?;
-
         Ok(Self {
+            config: Some(config.clone()),
             encryption_provider: config.encryption_provider,
             decryption_provider: config.decryption_provider,
             cookie_store,
+            cookie_store_factory,
+            response_validators: config.response_validators.unwrap_or_default(),
+            user_agent_provider: config.user_agent_provider,
+            circuit_breaker: config.circuit_breaker.map(CircuitBreaker::new),
+            logger: config.logger,
+            connection_limiter: Some(HostConnectionLimiter::new(config.max_connections_per_host)),
+            rate_limiter: config.rate_limit.map(HostRateLimiter::new),
+            per_domain,
+            proxy_clients: DashMap::new(),
             client,
         })
     }
 
+    fn validate_response(
+        &self,
+        host: Option<&String>,
+        response: &HttpResponse,
+    ) -> Result<(), HttpClientError> {
+        if host.is_none() {
+            return Ok(());
+        }
+        let host = host.unwrap();
+        for (domain, validator) in self.response_validators.iter() {
+            if host_matches(host, domain) {
+                validator.validate(response)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn begin_log_record(&self, endpoint: &HttpEndpoint) -> Option<(HttpLogRecord, Instant)> {
+        self.logger.as_ref()?;
+        let record = HttpLogRecord {
+            method: endpoint.method.clone(),
+            url: endpoint.build_url(),
+            headers: endpoint.headers.clone().unwrap_or_default(),
+            status: None,
+            duration: Duration::ZERO,
+            request_size: endpoint
+                .body
+                .as_ref()
+                .map(|body| body.len() as u64)
+                .unwrap_or(0),
+            response_size: None,
+            error: None,
+        };
+        Some((record, Instant::now()))
+    }
+
+    fn finish_log_record(
+        &self,
+        log_record: (HttpLogRecord, Instant),
+        outcome: Result<(u16, Option<u64>), &HttpClientError>,
+    ) {
+        let (mut record, started) = log_record;
+        record.duration = started.elapsed();
+        match outcome {
+            Ok((status, response_size)) => {
+                record.status = Some(status);
+                record.response_size = response_size;
+            }
+            Err(e) => record.error = Some(e.to_string()),
+        }
+        self.logger.as_ref().unwrap().log(&record);
+    }
+
+    fn record_metrics(method: &HttpMethod, started: Instant, status: Option<u16>) {
+        let status = status
+            .map(|status| status.to_string())
+            .unwrap_or_else(|| "error".to_string());
+        let elapsed = started.elapsed().as_secs_f64() * 1000.0;
+        metrics(|registry| {
+            registry.increment_counter(
+                "http.requests",
+                &[("method", method.as_str()), ("status", &status)],
+                1,
+            );
+            registry.record_histogram(
+                "http.request.duration_ms",
+                &[("method", method.as_str())],
+                elapsed,
+            );
+        });
+    }
+
+    fn domain_override(&self, host: Option<&String>) -> Option<&(DomainOverride, Client)> {
+        let host = host?;
+        self.per_domain
+            .iter()
+            .find(|(domain_override, _)| domain_override.matches(host))
+    }
+
+    fn proxy_client(
+        &self,
+        domain_override: Option<&DomainOverride>,
+        proxy_selection: &ProxySelection,
+    ) -> Result<Client, HttpClientError> {
+        let key = (
+            domain_override.map(|domain_override| domain_override.domain.clone()),
+            proxy_selection.clone(),
+        );
+        if let Some(client) = self.proxy_clients.get(&key) {
+            return Ok(client.clone());
+        }
+        let Some(config) = &self.config else {
+            return Err(HttpClientError::Configuration(
+                "proxy overrides require an http config".to_string(),
+            ));
+        };
+        let client = build_client(config, domain_override, proxy_selection)?;
+        Ok(self.proxy_clients.entry(key).or_insert(client).clone())
+    }
+
+    // only the domains with a retry policy are retried
+    async fn with_retries<T, F, Fut>(
+        &self,
+        endpoint: HttpEndpoint,
+        status: fn(&T) -> u16,
+        send: F,
+    ) -> Result<T, HttpClientError>
+    where
+        F: Fn(HttpEndpoint) -> Fut,
+        Fut: Future<Output = Result<T, HttpClientError>>,
+    {
+        let host = Url::parse(&endpoint.build_url())
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_string()));
+        let retry_policy = self
+            .domain_override(host.as_ref())
+            .and_then(|(domain_override, _)| domain_override.retry_policy.as_ref());
+        if retry_policy.is_none() {
+            return send(endpoint).await;
+        }
+        let retry_policy = retry_policy.unwrap();
+
+        let max_attempts = retry_policy.attempts_for(&endpoint);
+        let mut backoff = retry_policy.initial_backoff;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let result = send(endpoint.clone()).await;
+            let retryable = match &result {
+                Ok(response) => HttpRetryPolicy::is_retryable_status(status(response)),
+                Err(e) => HttpRetryPolicy::is_retryable_error(e),
+            };
+            if attempts >= max_attempts || !retryable {
+                return result;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = retry_policy.next_backoff(backoff);
+        }
+    }
+
+    async fn acquire_connection(&self, url: &str) -> Option<OwnedSemaphorePermit> {
+        let connection_limiter = self.connection_limiter.as_ref()?;
+        let host = Url::parse(url).ok()?.host_str()?.to_string();
+        Some(connection_limiter.acquire(&host).await)
+    }
+
     fn convert_method(method: &HttpMethod) -> Method {
         match method {
             HttpMethod::Get => Method::GET,
             HttpMethod::Post => Method::POST,
             HttpMethod::Put => Method::PUT,
             HttpMethod::Delete => Method::DELETE,
+            HttpMethod::Head => Method::HEAD,
+            HttpMethod::Patch => Method::PATCH,
         }
     }
 }
 
 impl ReqwestBackend {
-    async fn inject_cookies(
+    async fn resolve_cookie_store(
         &self,
-        url: &str,
-        request_builder: reqwest::RequestBuilder,
-    ) -> Result<reqwest::RequestBuilder, HttpClientError> {
-        let cookie_store = self.cookie_store.as_ref();
-        if cookie_store.is_none() {
-            return Err(HttpClientError::Configuration(
-                "Cookie Store is not configured".to_string(),
-            ));
+        cookie_profile: &Option<String>,
+    ) -> Result<Option<Arc<dyn CookieStore>>, HttpClientError> {
+        if cookie_profile.is_none() {
+            return Ok(self.cookie_store.clone());
         }
-        let cookie_store = cookie_store.unwrap();
-        let cookies = cookie_store.get_for_url(url).await;
-        if cookies.is_empty() {
-            return Ok(request_builder);
-        }
-
-        let cookie_header: String = cookies
-            .iter()
-            .map(|c| format!("{}={}", c.key.name, c.value))
-            .collect::<Vec<_>>()
-            .join("; ");
-
-        Ok(request_builder.header(
-            reqwest::header::COOKIE,
-            reqwest::header::HeaderValue::from_str(&cookie_header)
-                .map_err(|e| HttpClientError::InvalidHeader(e.to_string()))?,
-        ))
-    }
-
-    async fn extract_cookies(&self, response: &Response) -> Result<(), HttpClientError> {
-        if let Some(url) = response.url().host_str() {
-            let cookie_store = self.cookie_store.as_ref();
-            if cookie_store.is_none() {
-                return Err(HttpClientError::Configuration(
-                    "Cookie Store is not configured".to_string(),
-                ));
-            }
-            let cookie_store = cookie_store.unwrap();
-
-            for cookie in response.cookies() {
-                let name = cookie.name();
-                let value = cookie.value();
-
-                let first_same_site = match cookie.same_site_lax() {
-                    true => SameSite::Lax,
-                    false => SameSite::Strict,
-                };
-                let second_same_site = match cookie.same_site_strict() {
-                    true => SameSite::Strict,
-                    false => SameSite::Lax,
-                };
-
-                let same_site = if first_same_site != second_same_site {
-                    None
-                } else {
-                    Some(first_same_site)
-                };
+        let cookie_profile = cookie_profile.as_ref().unwrap();
 
-                let cookie = Cookie::new(
-                    url.to_string(),
-                    response.url().path().to_string(),
-                    name.to_string(),
-                    value.to_string(),
-                    cookie.expires(),
-                    cookie.secure(),
-                    cookie.http_only(),
-                    same_site,
-                );
-
-                cookie_store.set(cookie).await;
-            }
+        let cookie_store_factory = self.cookie_store_factory.as_ref();
+        if cookie_store_factory.is_none() {
+            return Err(HttpClientError::Configuration(
+                "Cookie Store Factory is not configured".to_string(),
+            ));
         }
-
-        Ok(())
+        let cookie_store = cookie_store_factory
+            .unwrap()
+            .create_with_profile(cookie_profile)
+            .await
+            .map_err(|e| HttpClientError::Configuration(e.to_string()))?;
+        Ok(Some(cookie_store))
     }
 
     async fn do_execute(&self, endpoint: HttpEndpoint) -> Result<Response, HttpClientError> {
@@ -220,27 +433,62 @@ impl ReqwestBackend {
 
         let method = Self::convert_method(&endpoint.method);
         let url = endpoint.build_url();
-        let mut request_builder = self.client.request(method, &url);
-
-        if endpoint.headers.is_some() {
-            let headers = endpoint.headers.unwrap();
-            for (key, value) in headers {
-                request_builder = request_builder.header(&key, value);
+        let host = Url::parse(&url)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| host.to_string()));
+        let domain_override = self.domain_override(host.as_ref());
+        let client = match endpoint.proxy_override.as_ref() {
+            None | Some(ProxySelection::UseDefault) => domain_override
+                .map(|(_, client)| client.clone())
+                .unwrap_or_else(|| self.client.clone()),
+            Some(proxy_selection) => self.proxy_client(
+                domain_override.map(|(domain_override, _)| domain_override),
+                proxy_selection,
+            )?,
+        };
+        let domain_override = domain_override.map(|(domain_override, _)| domain_override);
+        let user_agent = endpoint
+            .user_agent
+            .clone()
+            .or_else(|| {
+                domain_override.and_then(|domain_override| domain_override.user_agent.clone())
+            })
+            .or_else(|| {
+                self.user_agent_provider
+                    .as_ref()
+                    .and_then(|provider| provider.user_agent(&endpoint))
+            });
+        let timeout = endpoint
+            .timeout
+            .or_else(|| domain_override.and_then(|domain_override| domain_override.request_timeout))
+            .unwrap_or(DEFAULT_ENDPOINT_TIMEOUT);
+        let cookie_store = self.resolve_cookie_store(&endpoint.cookie_profile).await?;
+        let mut request_builder = client.request(method, &url);
+
+        let headers = endpoint.headers.unwrap_or_default();
+        if let Some(domain_override) = domain_override {
+            for (key, value) in domain_override.default_headers.iter() {
+                if !headers
+                    .iter()
+                    .any(|(name, _)| name.eq_ignore_ascii_case(key))
+                {
+                    request_builder = request_builder.header(key, value);
+                }
             }
         }
+        for (key, value) in headers {
+            request_builder = request_builder.header(&key, value);
+        }
 
-        if endpoint.user_agent.is_some() {
-            let user_agent = endpoint.user_agent.unwrap();
+        if let Some(user_agent) = user_agent {
             request_builder = request_builder.header(reqwest::header::USER_AGENT, user_agent);
         }
 
-        if endpoint.content_type.is_some() {
-            let content_type = endpoint.content_type.unwrap();
+        if let Some(content_type) = endpoint.content_type {
             request_builder = request_builder.header(reqwest::header::CONTENT_TYPE, content_type);
         }
 
-        if endpoint.body.is_some() {
-            let body = endpoint.body.unwrap();
+        if let Some(body) = endpoint.body {
             if endpoint.requires_encryption {
                 let body = self.encryption_provider.as_ref().unwrap().encrypt(&body)?;
                 request_builder = request_builder.body(body);
@@ -249,25 +497,40 @@ impl ReqwestBackend {
             }
         }
 
-        if self.cookie_store.as_ref().is_some() {
-            request_builder = self.inject_cookies(&url, request_builder).await?;
-        }
-
         let request = request_builder
-            .timeout(endpoint.timeout)
+            .timeout(timeout)
             .build()
             .map_err(|e| HttpClientError::Configuration(e.to_string()))?;
-        let response = self.client.execute(request).await.map_err(|e| {
-            if e.is_timeout() {
-                HttpClientError::Timeout(endpoint.timeout)
-            } else {
-                HttpClientError::Network(e.to_string())
-            }
-        })?;
 
-        if self.cookie_store.as_ref().is_some() {
-            let _ = self.extract_cookies(&response).await;
+        if let (Some(rate_limiter), Some(host)) = (&self.rate_limiter, &host) {
+            rate_limiter.acquire(host).await;
+        }
+        let circuit_permit = match (&self.circuit_breaker, &host) {
+            (Some(circuit_breaker), Some(host)) => Some(circuit_breaker.acquire(host)?),
+            _ => None,
+        };
+        // the jar of the client reads and writes the store on every hop of a redirect, execute
+        // builds the Cookie header of the first hop as soon as it is called so it is called in
+        // the scope too
+        let response =
+            ReqwestCookieJar::scope(cookie_store, async move { client.execute(request).await })
+                .await
+                .map_err(|e| {
+                    if e.is_timeout() {
+                        HttpClientError::Timeout(timeout)
+                    } else {
+                        HttpClientError::Network(e.to_string())
+                    }
+                });
+        if let Some(circuit_permit) = circuit_permit {
+            match &response {
+                Ok(response) if !response.status().is_server_error() => {
+                    circuit_permit.record_success()
+                }
+                _ => circuit_permit.record_failure(),
+            }
         }
+        let response = response?;
 
         Ok(response)
     }
@@ -292,6 +555,69 @@ impl HttpClient for ReqwestBackend {
     }
 
     async fn execute(&self, endpoint: HttpEndpoint) -> Result<HttpResponse, HttpClientError> {
+        let log_record = self.begin_log_record(&endpoint);
+        let method = endpoint.method.clone();
+        let started = Instant::now();
+        let result = self
+            .with_retries(
+                endpoint,
+                |response: &HttpResponse| response.status,
+                |endpoint| self.execute_response(endpoint),
+            )
+            .await;
+        Self::record_metrics(
+            &method,
+            started,
+            result.as_ref().ok().map(|response| response.status),
+        );
+        if let Some(log_record) = log_record {
+            let outcome = result
+                .as_ref()
+                .map(|response| (response.status, Some(response.body.len() as u64)));
+            self.finish_log_record(log_record, outcome);
+        }
+        result
+    }
+
+    async fn execute_stream(
+        &self,
+        endpoint: HttpEndpoint,
+    ) -> Result<HttpStreamResponse, HttpClientError> {
+        let log_record = self.begin_log_record(&endpoint);
+        let method = endpoint.method.clone();
+        let started = Instant::now();
+        let result = self
+            .with_retries(
+                endpoint,
+                |response: &HttpStreamResponse| response.status,
+                |endpoint| self.execute_stream_response(endpoint),
+            )
+            .await;
+        Self::record_metrics(
+            &method,
+            started,
+            result.as_ref().ok().map(|response| response.status),
+        );
+        if let Some(log_record) = log_record {
+            let outcome = result.as_ref().map(|response| {
+                let content_length = response
+                    .headers
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+                    .and_then(|(_, value)| value.parse::<u64>().ok());
+                (response.status, content_length)
+            });
+            self.finish_log_record(log_record, outcome);
+        }
+        result
+    }
+}
+
+impl ReqwestBackend {
+    async fn execute_response(
+        &self,
+        endpoint: HttpEndpoint,
+    ) -> Result<HttpResponse, HttpClientError> {
         let url = endpoint.build_url();
         let requires_decryption = endpoint.requires_decryption;
 
@@ -299,23 +625,51 @@ impl HttpClient for ReqwestBackend {
             send_monitor_event(monitor, &url, EventStage::Started, None);
         });
 
-        let response = self.do_execute(endpoint).await.inspect_err(|e| {
+        let _connection = self.acquire_connection(&url).await;
+        let (response, redirects) = REDIRECTS
+            .scope(RefCell::new(Vec::new()), async {
+                let response = self.do_execute(endpoint).await;
+                (response, REDIRECTS.with(|redirects| redirects.take()))
+            })
+            .await;
+        let response = response.inspect_err(|_| {
             monitoring(|monitor| send_monitor_event(monitor, &url, EventStage::Failed, None));
         })?;
         let status = response.status().as_u16();
+        let host = response.url().host_str().map(|host| host.to_string());
+        let final_url = response.url().to_string();
         let headers: Vec<(String, String)> = response
             .headers()
             .iter()
             .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
             .collect();
+        let content_length = response.content_length();
+
+        // read frame by frame, so the trailers following the body are kept
+        let trailers = Arc::new(Mutex::new(Vec::new()));
+        let body_trailers = trailers.clone();
+        let body_stream = BodyStream::new(Body::from(response)).try_filter_map(move |frame| {
+            let data = match frame.into_data() {
+                Ok(data) => Some(data),
+                Err(frame) => {
+                    if let Ok(frame_trailers) = frame.into_trailers() {
+                        body_trailers.lock().extend(
+                            frame_trailers.iter().map(|(k, v)| {
+                                (k.to_string(), v.to_str().unwrap_or("").to_string())
+                            }),
+                        );
+                    }
+                    None
+                }
+            };
+            futures_util::future::ready(Ok(data))
+        });
 
         let mut body: Vec<u8>;
-        let content_length = response.content_length();
-        if content_length.is_some() {
-            let stream = response.bytes_stream();
-            let stream = stream
-                .map_err(|e| std::io::Error::new(ErrorKind::Other, e.to_string()))
-                .inspect_err(|e| {
+        if let Some(content_length) = content_length {
+            let stream = body_stream
+                .map_err(|e| std::io::Error::other(e.to_string()))
+                .inspect_err(|_e| {
                     monitoring(|monitor| {
                         send_monitor_event(monitor, &url, EventStage::Failed, None)
                     });
@@ -326,7 +680,7 @@ impl HttpClient for ReqwestBackend {
             let cloned_url = url.clone();
             let mut reader = AsyncProgressReader::new(
                 tokio_async_read,
-                content_length.unwrap(),
+                content_length,
                 move |read, total, delta| {
                     monitoring(|monitor| {
                         send_monitor_event(
@@ -343,17 +697,18 @@ impl HttpClient for ReqwestBackend {
             tokio::io::copy(&mut reader, &mut body)
                 .await
                 .map_err(|e| HttpClientError::Network(e.to_string()))
-                .inspect_err(|e| {
+                .inspect_err(|_e| {
                     monitoring(|monitor| {
                         send_monitor_event(monitor, &url, EventStage::Failed, None)
                     });
                 })?;
         } else {
-            body = response
-                .bytes()
+            body = body_stream
+                .map_ok(|chunk| chunk.to_vec())
+                .try_concat()
                 .await
                 .map_err(|e| HttpClientError::Network(e.to_string()))
-                .inspect_err(|e| {
+                .inspect_err(|_e| {
                     monitoring(|monitor| {
                         send_monitor_event(monitor, &url, EventStage::Failed, None);
                     });
@@ -369,14 +724,20 @@ impl HttpClient for ReqwestBackend {
             body = self.decryption_provider.as_ref().unwrap().decrypt(&body)?;
         }
 
-        Ok(HttpResponse {
+        let response = HttpResponse {
             status,
             headers,
             body,
-        })
+            final_url: Some(final_url),
+            redirects,
+            trailers: std::mem::take(&mut *trailers.lock()),
+        };
+        self.validate_response(host.as_ref(), &response)?;
+
+        Ok(response)
     }
 
-    async fn execute_stream(
+    async fn execute_stream_response(
         &self,
         endpoint: HttpEndpoint,
     ) -> Result<HttpStreamResponse, HttpClientError> {
@@ -386,12 +747,21 @@ impl HttpClient for ReqwestBackend {
             send_monitor_event(monitor, &url, EventStage::Started, None);
         });
 
-        let response = self.do_execute(endpoint).await.inspect_err(|e| {
+        let connection = self.acquire_connection(&url).await;
+        let (response, redirects) = REDIRECTS
+            .scope(RefCell::new(Vec::new()), async {
+                let response = self.do_execute(endpoint).await;
+                (response, REDIRECTS.with(|redirects| redirects.take()))
+            })
+            .await;
+        let response = response.inspect_err(|_e| {
             monitoring(|monitor| {
                 send_monitor_event(monitor, &url, EventStage::Failed, None);
             });
         })?;
         let status = response.status().as_u16();
+        let host = response.url().host_str().map(|host| host.to_string());
+        let final_url = response.url().to_string();
         let headers: Vec<(String, String)> = response
             .headers()
             .iter()
@@ -399,17 +769,38 @@ impl HttpClient for ReqwestBackend {
             .collect();
         let content_length = response.content_length();
 
+        // the validators see the status and headers, the body is not read yet
+        let head = HttpResponse {
+            status,
+            headers: headers.clone(),
+            body: Vec::new(),
+            final_url: Some(final_url.clone()),
+            redirects: redirects.clone(),
+            trailers: Vec::new(),
+        };
+        self.validate_response(host.as_ref(), &head)
+            .inspect_err(|_| {
+                monitoring(|monitor| {
+                    send_monitor_event(monitor, &url, EventStage::Failed, None);
+                });
+            })?;
+
         let cloned_url = url.clone();
         let stream = response
             .bytes_stream()
             .map_err(|e| HttpClientError::Network(e.to_string()))
+            // the connection is released with the stream
+            .map(move |chunk| {
+                let _connection = &connection;
+                chunk
+            })
             .on_complete(move || {
                 monitoring(|monitor| {
                     send_monitor_event(monitor, &cloned_url, EventStage::Finished, None)
                 })
             });
 
-        if content_length.is_some() {
+        if let Some(content_length) = content_length {
             let stream = stream.inspect_ok(move |data| {
                 let length = data.len() as u64;
                 monitoring(|monitor| {
@@ -417,7 +808,7 @@ impl HttpClient for ReqwestBackend {
                         monitor,
                         &url,
                         EventStage::Running,
-                        Some((0u64, content_length.unwrap(), length)),
+                        Some((0u64, content_length, length)),
                     );
                 });
             });
@@ -425,6 +816,8 @@ impl HttpClient for ReqwestBackend {
             return Ok(HttpStreamResponse {
                 status,
                 headers,
+                final_url: Some(final_url),
+                redirects,
                 stream,
             });
         }
@@ -433,7 +826,147 @@ impl HttpClient for ReqwestBackend {
         Ok(HttpStreamResponse {
             status,
             headers,
+            final_url: Some(final_url),
+            redirects,
             stream,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::http_models::{HttpClientError, HttpEndpoint, HttpResponse};
+    use crate::domain::traits::http_traits::{HttpClient, ResponseValidator};
+    use crate::infrastructure::http::reqwest_backend::ReqwestBackend;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+
+    macro_rules! await_test {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    struct RejectDenied;
+
+    impl ResponseValidator for RejectDenied {
+        fn validate(&self, response: &HttpResponse) -> Result<(), HttpClientError> {
+            let denied = response
+                .headers
+                .iter()
+                .any(|(key, _)| key.eq_ignore_ascii_case("x-denied"));
+            if denied {
+                return Err(HttpClientError::Business {
+                    code: 403,
+                    message: "denied".to_string(),
+                });
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_streamed_responses_are_validated() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                let mut head = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !head.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    head.extend_from_slice(&buffer[..read]);
+                }
+                stream
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\nX-Denied: 1\r\nContent-Length: 5\r\n\
+                          Connection: close\r\n\r\nhello",
+                    )
+                    .unwrap();
+            }
+        });
+
+        let mut backend = ReqwestBackend::new().unwrap();
+        backend.response_validators = vec![("127.0.0.1".to_string(), Arc::new(RejectDenied))];
+        let endpoint = || HttpEndpoint::builder(format!("http://{}", address), "/").build();
+
+        await_test!(async {
+            let result = backend.execute(endpoint()).await;
+            assert!(matches!(
+                result,
+                Err(HttpClientError::Business { code: 403, .. })
+            ));
+            let result = backend.execute_stream(endpoint()).await;
+            assert!(matches!(
+                result,
+                Err(HttpClientError::Business { code: 403, .. })
+            ));
+        });
+    }
+
+    #[test]
+    fn test_streamed_responses_record_redirects() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(4) {
+                let mut stream = stream.unwrap();
+                let mut head = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !head.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    head.extend_from_slice(&buffer[..read]);
+                }
+                let response: &[u8] = if head.starts_with(b"GET /final ") {
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello"
+                } else {
+                    b"HTTP/1.1 302 Found\r\nLocation: /final\r\nContent-Length: 0\r\n\
+                      Connection: close\r\n\r\n"
+                };
+                stream.write_all(response).unwrap();
+            }
+        });
+
+        let backend = ReqwestBackend::new().unwrap();
+        let endpoint = || HttpEndpoint::builder(format!("http://{}", address), "/start").build();
+        let final_url = Some(format!("http://{}/final", address));
+        let redirects = vec![format!("http://{}/start", address)];
+
+        await_test!(async {
+            let response = backend.execute(endpoint()).await.unwrap();
+            assert_eq!(response.final_url, final_url);
+            assert_eq!(response.redirects, redirects);
+            let response = backend.execute_stream(endpoint()).await.unwrap();
+            assert_eq!(response.status, 200);
+            assert_eq!(response.final_url, final_url);
+            assert_eq!(response.redirects, redirects);
+        });
+    }
+
+    #[test]
+    fn test_validators_cover_subdomains() {
+        let mut backend = ReqwestBackend::new().unwrap();
+        backend.response_validators = vec![("example.com".to_string(), Arc::new(RejectDenied))];
+        let response = HttpResponse {
+            status: 200,
+            headers: vec![("X-Denied".to_string(), "1".to_string())],
+            body: Vec::new(),
+            final_url: None,
+            redirects: Vec::new(),
+            trailers: Vec::new(),
+        };
+        let validate = |host: &str| backend.validate_response(Some(&host.to_string()), &response);
+        assert!(validate("example.com").is_err());
+        assert!(validate("api.example.com").is_err());
+        assert!(validate("notexample.com").is_ok());
+        assert!(validate("example.org").is_ok());
+    }
+}
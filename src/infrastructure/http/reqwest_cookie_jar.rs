@@ -0,0 +1,214 @@
+use crate::domain::traits::cookie_traits::CookieStore;
+use crate::infrastructure::http::set_cookie::{parse_set_cookie, select_for_request};
+use reqwest::Url;
+use reqwest::header::HeaderValue;
+use std::future::Future;
+use std::sync::Arc;
+
+tokio::task_local! {
+    // the store of the request sent from this task, None for a request without cookies
+    static REQUEST_COOKIE_STORE: Option<Arc<dyn CookieStore>>;
+}
+
+// the value of the Cookie header for the url, the cookies sent are touched
+pub fn cookie_header(cookie_store: &Arc<dyn CookieStore>, url: &Url) -> Option<String> {
+    // the store only matches the domain
+    let secure = matches!(url.scheme(), "https" | "wss");
+    let cookies = select_for_request(
+        cookie_store.get_for_url_sync(url.as_str()),
+        secure,
+        url.path(),
+    );
+    if cookies.is_empty() {
+        return None;
+    }
+
+    let cookie_header = cookies
+        .iter()
+        .map(|cookie| format!("{}={}", cookie.key.name, cookie.value))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let keys: Vec<_> = cookies.into_iter().map(|cookie| cookie.key).collect();
+    cookie_store.touch_sync(&keys);
+    Some(cookie_header)
+}
+
+// the malformed and rejected Set-Cookie headers are skipped
+pub fn store_cookies(cookie_store: &Arc<dyn CookieStore>, set_cookies: &[String], url: &Url) {
+    let Some(host) = url.host_str() else {
+        return;
+    };
+    for set_cookie in set_cookies {
+        let Some(set_cookie) = parse_set_cookie(set_cookie) else {
+            continue;
+        };
+        let Ok(cookie) = set_cookie.into_cookie(host, url.path()) else {
+            continue;
+        };
+
+        // an already expired Set-Cookie is how servers delete cookies
+        if cookie.is_expired() {
+            cookie_store.remove_sync(&cookie.key);
+            continue;
+        }
+        let _ = cookie_store.set_sync(cookie);
+    }
+}
+
+// lets reqwest read and write the cookie stores of the crate, so the requests it sends on its
+// own while following redirects carry the cookies too, reqwest asks synchronously so the jar
+// goes through the sync methods of the store
+pub struct ReqwestCookieJar {
+    // None to use the store the request was sent with, see scope
+    cookie_store: Option<Arc<dyn CookieStore>>,
+}
+
+impl ReqwestCookieJar {
+    pub fn new(cookie_store: Arc<dyn CookieStore>) -> Self {
+        Self {
+            cookie_store: Some(cookie_store),
+        }
+    }
+
+    // for a client shared by requests with different cookie profiles
+    pub fn per_request() -> Self {
+        Self { cookie_store: None }
+    }
+
+    // the requests sent from the future use the store, a per request jar leaves the others
+    // without cookies
+    pub async fn scope<F: Future>(
+        cookie_store: Option<Arc<dyn CookieStore>>,
+        future: F,
+    ) -> F::Output {
+        REQUEST_COOKIE_STORE.scope(cookie_store, future).await
+    }
+
+    fn cookie_store(&self) -> Option<Arc<dyn CookieStore>> {
+        self.cookie_store.clone().or_else(|| {
+            REQUEST_COOKIE_STORE
+                .try_with(|cookie_store| cookie_store.clone())
+                .ok()
+                .flatten()
+        })
+    }
+}
+
+impl reqwest::cookie::CookieStore for ReqwestCookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let Some(cookie_store) = self.cookie_store() else {
+            return;
+        };
+        let set_cookies: Vec<String> = cookie_headers
+            .filter_map(|header| header.to_str().ok())
+            .map(|header| header.to_string())
+            .collect();
+        if set_cookies.is_empty() {
+            return;
+        }
+        store_cookies(&cookie_store, &set_cookies, url);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let cookie_store = self.cookie_store()?;
+        let cookie_header = cookie_header(&cookie_store, url)?;
+        HeaderValue::from_str(&cookie_header).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::traits::cookie_traits::CookieStore;
+    use crate::infrastructure::http::memory_cookie_store::MemoryCookieStore;
+    use crate::infrastructure::http::reqwest_cookie_jar::{ReqwestCookieJar, store_cookies};
+    use crate::service::config::{CookieBackend, CookieConfig};
+    use reqwest::{Client, Url};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    macro_rules! await_test {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    // answers every request with a Set-Cookie and hands back the request head
+    fn serve(listener: TcpListener, requests: usize) -> mpsc::Receiver<String> {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut head = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !head.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    head.extend_from_slice(&buffer[..read]);
+                }
+                stream
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\nSet-Cookie: seen=1; Path=/\r\n\
+                          Content-Length: 0\r\nConnection: close\r\n\r\n",
+                    )
+                    .unwrap();
+                sender
+                    .send(String::from_utf8_lossy(&head).to_lowercase())
+                    .unwrap();
+            }
+        });
+        receiver
+    }
+
+    // a current thread runtime, the jar must not depend on another worker
+    #[test]
+    fn test_jar_sends_the_store_on_the_first_hop() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let requests = serve(listener, 2);
+
+        await_test!(async {
+            let config = CookieConfig {
+                backend: CookieBackend::Memory,
+                cookie_path: None,
+                debounce_delay: Duration::from_secs(10),
+                auto_save_interval: None,
+                initial_cookies: None,
+                public_suffix_list_path: None,
+                max_cookies: None,
+                max_cookies_per_domain: None,
+                policies: vec![],
+                max_cookie_lifetime: None,
+                session_only: false,
+            };
+            let cookie_store: Arc<dyn CookieStore> =
+                Arc::new(MemoryCookieStore::new(config).await.unwrap());
+            store_cookies(&cookie_store, &["id=1; Path=/".to_string()], &url);
+            let client = Client::builder()
+                .no_proxy()
+                .cookie_provider(Arc::new(ReqwestCookieJar::per_request()))
+                .build()
+                .unwrap();
+
+            // without a store in scope the request goes out without cookies
+            client.get(url.clone()).send().await.unwrap();
+            let head = requests.recv().unwrap();
+            assert!(!head.contains("\r\ncookie:"));
+            assert_eq!(cookie_store.get_for_url(url.as_str()).await.len(), 1);
+
+            let request = client.get(url.clone()).build().unwrap();
+            ReqwestCookieJar::scope(Some(cookie_store.clone()), async move {
+                client.execute(request).await
+            })
+            .await
+            .unwrap();
+            let head = requests.recv().unwrap();
+            assert!(head.contains("\r\ncookie: id=1\r\n"));
+            assert_eq!(cookie_store.get_for_url(url.as_str()).await.len(), 2);
+        });
+    }
+}
@@ -0,0 +1,87 @@
+use crate::domain::models::http_models::{HttpClientError, HttpResponse};
+use crate::domain::traits::http_traits::ResponseValidator;
+
+pub struct JsonCodeResponseValidator {
+    code_field: String,
+    message_field: Option<String>,
+    success_codes: Vec<i64>,
+}
+
+impl JsonCodeResponseValidator {
+    pub fn new(code_field: String, message_field: Option<String>, success_codes: Vec<i64>) -> Self {
+        Self {
+            code_field,
+            message_field,
+            success_codes,
+        }
+    }
+}
+
+impl ResponseValidator for JsonCodeResponseValidator {
+    fn validate(&self, response: &HttpResponse) -> Result<(), HttpClientError> {
+        // bodies which are not json objects are not the business of this validator
+        let value = serde_json::from_slice::<serde_json::Value>(&response.body);
+        if value.is_err() {
+            return Ok(());
+        }
+        let value = value.unwrap();
+
+        let code = value.get(&self.code_field).and_then(|code| code.as_i64());
+        if code.is_none() {
+            return Ok(());
+        }
+        let code = code.unwrap();
+        if self.success_codes.contains(&code) {
+            return Ok(());
+        }
+
+        let message = self
+            .message_field
+            .as_ref()
+            .and_then(|field| value.get(field))
+            .and_then(|message| message.as_str())
+            .unwrap_or("")
+            .to_string();
+        Err(HttpClientError::Business { code, message })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonCodeResponseValidator;
+    use crate::domain::models::http_models::{HttpClientError, HttpResponse};
+    use crate::domain::traits::http_traits::ResponseValidator;
+
+    fn response(body: &str) -> HttpResponse {
+        HttpResponse {
+            status: 200,
+            headers: vec![],
+            body: body.as_bytes().to_vec(),
+            final_url: None,
+            redirects: Vec::new(),
+            trailers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_json_code_response_validator() {
+        let validator = JsonCodeResponseValidator::new(
+            "code".to_string(),
+            Some("message".to_string()),
+            vec![200],
+        );
+
+        assert!(validator.validate(&response(r#"{"code": 200}"#)).is_ok());
+        assert!(validator.validate(&response(r#"{"data": []}"#)).is_ok());
+        assert!(validator.validate(&response("<html></html>")).is_ok());
+
+        let result = validator.validate(&response(r#"{"code": 40001, "message": "expired"}"#));
+        match result {
+            Err(HttpClientError::Business { code, message }) => {
+                assert_eq!(code, 40001);
+                assert_eq!(message, "expired");
+            }
+            _ => panic!("expected a business error"),
+        }
+    }
+}
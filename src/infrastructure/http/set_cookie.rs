@@ -0,0 +1,380 @@
+use crate::domain::models::cookie_models::{Cookie, CookieError, SameSite};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetCookie {
+    pub name: String,
+    pub value: String,
+    pub expires: Option<SystemTime>,
+    pub max_age: Option<i64>,
+    pub domain: Option<String>,
+    pub path: Option<String>,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: Option<SameSite>,
+}
+
+pub fn parse_set_cookie(header: &str) -> Option<SetCookie> {
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut set_cookie = SetCookie {
+        name: name.to_string(),
+        value: value.trim().to_string(),
+        expires: None,
+        max_age: None,
+        domain: None,
+        path: None,
+        secure: false,
+        http_only: false,
+        same_site: None,
+    };
+
+    for attribute in parts {
+        let (key, value) = match attribute.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => (attribute.trim(), ""),
+        };
+        match key.to_lowercase().as_str() {
+            "expires" => set_cookie.expires = parse_cookie_date(value).or(set_cookie.expires),
+            // rfc 6265 5.2.2, anything but an optional "-" and digits is ignored
+            "max-age" => {
+                let digits = value.strip_prefix('-').unwrap_or(value);
+                if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+                    // too many digits still say which way the cookie goes
+                    let clamped = if value.starts_with('-') { i64::MIN } else { i64::MAX };
+                    set_cookie.max_age = Some(value.parse::<i64>().unwrap_or(clamped));
+                }
+            }
+            "domain" => {
+                let domain = value.trim_start_matches('.').to_lowercase();
+                if !domain.is_empty() {
+                    set_cookie.domain = Some(domain);
+                }
+            }
+            "path" if value.starts_with('/') => set_cookie.path = Some(value.to_string()),
+            "secure" => set_cookie.secure = true,
+            "httponly" => set_cookie.http_only = true,
+            "samesite" => {
+                set_cookie.same_site = match value.to_lowercase().as_str() {
+                    "strict" => Some(SameSite::Strict),
+                    "lax" => Some(SameSite::Lax),
+                    "none" => Some(SameSite::None),
+                    _ => None,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(set_cookie)
+}
+
+// the last second a four digit year reaches, 9999-12-31 23:59:59 UTC
+fn latest_expiry() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(253_402_300_799)
+}
+
+fn default_path(request_path: &str) -> String {
+    if !request_path.starts_with('/') {
+        return "/".to_string();
+    }
+    match request_path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => request_path[..index].to_string(),
+    }
+}
+
+pub fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+// rfc 6265 5.1.4, "/docs" matches "/docs", "/docs/" and "/docs/a" but not "/docsets"
+pub fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    match request_path.strip_prefix(cookie_path) {
+        Some(rest) => rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+// leaves out the cookies the request may not carry and orders the rest the way rfc 6265 5.4
+// suggests, longer paths first and older cookies first among equal paths
+pub fn select_for_request(mut cookies: Vec<Cookie>, secure: bool, path: &str) -> Vec<Cookie> {
+    let path = if path.starts_with('/') { path } else { "/" };
+    cookies.retain(|cookie| (secure || !cookie.secure) && path_matches(path, &cookie.key.path));
+    cookies.sort_by(|a, b| {
+        b.key
+            .path
+            .len()
+            .cmp(&a.key.path.len())
+            .then(a.creation_time.cmp(&b.creation_time))
+    });
+    cookies
+}
+
+impl SetCookie {
+    pub fn expiry(&self) -> Option<SystemTime> {
+        // Max-Age wins over Expires, a non-positive Max-Age expires the cookie at once
+        match self.max_age {
+            Some(max_age) if max_age <= 0 => Some(UNIX_EPOCH),
+            // past what SystemTime holds the cookie stays persistent instead of turning into a
+            // session cookie
+            Some(max_age) => Some(
+                SystemTime::now()
+                    .checked_add(Duration::from_secs(max_age as u64))
+                    .unwrap_or_else(latest_expiry),
+            ),
+            None => self.expires,
+        }
+    }
+
+    pub fn into_cookie(
+        self,
+        request_host: &str,
+        request_path: &str,
+    ) -> Result<Cookie, CookieError> {
+        let request_host = request_host.to_lowercase();
+        let expires = self.expiry();
+        let (domain, host_only) = match self.domain {
+            Some(domain) => {
+                if !domain_matches(&request_host, &domain) {
+                    return Err(CookieError::Rejected(format!(
+                        "domain {} does not match host {}",
+                        domain, request_host
+                    )));
+                }
+                (domain, false)
+            }
+            None => (request_host, true),
+        };
+        let path = self.path.unwrap_or_else(|| default_path(request_path));
+
+        let mut cookie = Cookie::new(
+            domain,
+            path,
+            self.name,
+            self.value,
+            expires,
+            self.secure,
+            self.http_only,
+            self.same_site,
+        );
+        cookie.host_only = host_only;
+        Ok(cookie)
+    }
+}
+
+// https://www.rfc-editor.org/rfc/rfc6265#section-5.1.1
+pub fn parse_cookie_date(value: &str) -> Option<SystemTime> {
+    let mut time: Option<(u64, u64, u64)> = None;
+    let mut day: Option<u64> = None;
+    let mut month: Option<u64> = None;
+    let mut year: Option<u64> = None;
+
+    let tokens = value
+        .split(|c: char| {
+            c == '\t'
+                || (' '..='/').contains(&c)
+                || (';'..='@').contains(&c)
+                || ('['..='`').contains(&c)
+                || ('{'..='~').contains(&c)
+        })
+        .filter(|token| !token.is_empty());
+
+    for token in tokens {
+        if time.is_none() {
+            let parts: Vec<&str> = token.splitn(3, ':').collect();
+            if parts.len() == 3 {
+                let parsed: Vec<Option<u64>> = parts
+                    .iter()
+                    .map(|part| {
+                        let digits: String =
+                            part.chars().take_while(|c| c.is_ascii_digit()).collect();
+                        if digits.is_empty() || digits.len() > 2 {
+                            return None;
+                        }
+                        digits.parse::<u64>().ok()
+                    })
+                    .collect();
+                if parsed.iter().all(|part| part.is_some()) {
+                    time = Some((parsed[0].unwrap(), parsed[1].unwrap(), parsed[2].unwrap()));
+                    continue;
+                }
+            }
+        }
+
+        let digits: String = token.chars().take_while(|c| c.is_ascii_digit()).collect();
+        if day.is_none() && (1..=2).contains(&digits.len()) {
+            day = digits.parse::<u64>().ok();
+            continue;
+        }
+        // the date comes from the server, a multibyte character must not split the prefix
+        if let (None, Some(prefix)) = (month, token.get(..3)) {
+            let prefix = prefix.to_lowercase();
+            let index = [
+                "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+            ]
+            .iter()
+            .position(|name| *name == prefix);
+            if let Some(index) = index {
+                month = Some(index as u64 + 1);
+                continue;
+            }
+        }
+        if year.is_none() && (2..=4).contains(&digits.len()) {
+            year = digits.parse::<u64>().ok();
+            continue;
+        }
+    }
+
+    let (hour, minute, second) = time?;
+    let day = day?;
+    let month = month?;
+    let mut year = year?;
+    if (70..=99).contains(&year) {
+        year += 1900;
+    } else if year <= 69 {
+        year += 2000;
+    }
+    if !(1..=31).contains(&day) || year < 1601 || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let days = days_from_civil(year as i64, month as i64, day as i64);
+    let seconds = days * 86400 + (hour * 3600 + minute * 60 + second) as i64;
+    if seconds < 0 {
+        return Some(UNIX_EPOCH);
+    }
+    Some(UNIX_EPOCH + Duration::from_secs(seconds as u64))
+}
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_cookie_date, parse_set_cookie, path_matches, select_for_request};
+    use crate::domain::models::cookie_models::SameSite;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn test_parse_cookie_date() {
+        let expected = Some(UNIX_EPOCH + Duration::from_secs(1445412480));
+        assert_eq!(parse_cookie_date("Wed, 21 Oct 2015 07:28:00 GMT"), expected);
+        assert_eq!(parse_cookie_date("Wed, 21-Oct-2015 07:28:00 GMT"), expected);
+        assert_eq!(
+            parse_cookie_date("Wednesday, 21-Oct-15 07:28:00 GMT"),
+            expected
+        );
+        assert_eq!(parse_cookie_date("Wed Oct 21 07:28:00 2015"), expected);
+        assert_eq!(parse_cookie_date("not a date"), None);
+        assert_eq!(parse_cookie_date("21 Oé 2015 07:28:00 GMT"), None);
+        assert_eq!(parse_cookie_date("21 é日 2015 07:28:00 GMT"), None);
+    }
+
+    #[test]
+    fn test_parse_set_cookie() {
+        let set_cookie = parse_set_cookie(
+            "id=a3fWa; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Max-Age=3600; Domain=.Example.com; \
+             Path=/docs; Secure; HttpOnly; SameSite=None",
+        )
+        .unwrap();
+        assert_eq!(set_cookie.name, "id");
+        assert_eq!(set_cookie.value, "a3fWa");
+        assert_eq!(set_cookie.max_age, Some(3600));
+        assert_eq!(set_cookie.domain, Some("example.com".to_string()));
+        assert_eq!(set_cookie.path, Some("/docs".to_string()));
+        assert!(set_cookie.secure && set_cookie.http_only);
+        assert_eq!(set_cookie.same_site, Some(SameSite::None));
+
+        let cookie = set_cookie
+            .clone()
+            .into_cookie("api.example.com", "/login")
+            .unwrap();
+        assert!(!cookie.host_only);
+        assert!(!cookie.is_expired());
+        assert!(set_cookie.into_cookie("example.org", "/").is_err());
+    }
+
+    #[test]
+    fn test_set_cookie_defaults_and_deletion() {
+        let cookie = parse_set_cookie("theme=dark")
+            .unwrap()
+            .into_cookie("Example.com", "/settings/display")
+            .unwrap();
+        assert!(cookie.host_only);
+        assert!(!cookie.persistent);
+        assert_eq!(cookie.key.domain, "example.com");
+        assert_eq!(cookie.key.path, "/settings");
+
+        let deleted = parse_set_cookie("theme=; Max-Age=0")
+            .unwrap()
+            .into_cookie("example.com", "/")
+            .unwrap();
+        assert!(deleted.is_expired());
+    }
+
+    #[test]
+    fn test_max_age_is_parsed_leniently() {
+        let set_cookie = parse_set_cookie("id=1; Max-Age=abc").unwrap();
+        assert_eq!(set_cookie.max_age, None);
+        assert_eq!(set_cookie.expiry(), None);
+        assert_eq!(parse_set_cookie("id=1; Max-Age=-").unwrap().max_age, None);
+        assert_eq!(parse_set_cookie("id=1; Max-Age=--5").unwrap().max_age, None);
+
+        let set_cookie = parse_set_cookie("id=1; Max-Age=-99999999999999999999").unwrap();
+        assert_eq!(set_cookie.max_age, Some(i64::MIN));
+        assert_eq!(set_cookie.expiry(), Some(UNIX_EPOCH));
+
+        let set_cookie = parse_set_cookie("id=1; Max-Age=99999999999999999999").unwrap();
+        assert_eq!(set_cookie.max_age, Some(i64::MAX));
+        let cookie = set_cookie.into_cookie("example.com", "/").unwrap();
+        assert!(cookie.persistent);
+        assert!(!cookie.is_expired());
+    }
+
+    #[test]
+    fn test_cookies_are_selected_for_request() {
+        assert!(path_matches("/docs", "/docs"));
+        assert!(path_matches("/docs/a", "/docs"));
+        assert!(path_matches("/docs/a", "/docs/"));
+        assert!(path_matches("/docs", "/"));
+        assert!(!path_matches("/docsets", "/docs"));
+        assert!(!path_matches("/", "/docs"));
+
+        let cookie = |header: &str, path: &str| {
+            parse_set_cookie(header)
+                .unwrap()
+                .into_cookie("example.com", path)
+                .unwrap()
+        };
+        let mut older = cookie("a=1; Path=/", "/");
+        older.creation_time = UNIX_EPOCH;
+        let cookies = vec![
+            cookie("b=2; Path=/", "/"),
+            cookie("c=3; Path=/docs", "/"),
+            cookie("d=4; Path=/docs; Secure", "/"),
+            cookie("e=5; Path=/other", "/"),
+            older,
+        ];
+        let names = |secure| {
+            select_for_request(cookies.clone(), secure, "/docs/intro")
+                .into_iter()
+                .map(|cookie| cookie.key.name)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(names(false), ["c", "a", "b"]);
+        assert_eq!(names(true), ["c", "d", "a", "b"]);
+    }
+}
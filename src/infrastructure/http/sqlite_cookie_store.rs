@@ -0,0 +1,799 @@
+use crate::domain::models::cookie_models::{
+    Cookie, CookieChange, CookieError, CookieFormat, CookieKey, CookieStats, SameSite,
+};
+use crate::domain::traits::cookie_traits::CookieStore;
+use crate::infrastructure::http::cookie_backend::{
+    allowed_by_policies, clamp_expires, limit_lifetime,
+};
+use crate::infrastructure::http::cookie_format::{
+    export_dart_json, export_netscape, import_dart_json, import_netscape,
+};
+use crate::service::config::CookieConfig;
+use crate::utils::broadcast_stream::broadcast_stream;
+use crate::utils::public_suffix::PublicSuffixList;
+use crate::utils::url_component::extract_domain;
+use async_trait::async_trait;
+use futures_util::stream::BoxStream;
+use parking_lot::Mutex;
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS cookies (
+    domain TEXT NOT NULL,
+    path TEXT NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL,
+    expires INTEGER,
+    creation_time INTEGER NOT NULL,
+    last_access_time INTEGER NOT NULL,
+    secure INTEGER NOT NULL,
+    http_only INTEGER NOT NULL,
+    same_site TEXT,
+    persistent INTEGER NOT NULL,
+    host_only INTEGER NOT NULL,
+    PRIMARY KEY (domain, path, name)
+);
+CREATE INDEX IF NOT EXISTS cookies_domain ON cookies (domain);
+CREATE INDEX IF NOT EXISTS cookies_last_access_time ON cookies (last_access_time);
+";
+
+const COLUMNS: &str = "domain, path, name, value, expires, creation_time, last_access_time, \
+    secure, http_only, same_site, persistent, host_only";
+
+pub struct SqliteCookieStore {
+    connection: Arc<Mutex<Connection>>,
+    config: CookieConfig,
+    public_suffix_list: PublicSuffixList,
+    changes: broadcast::Sender<CookieChange>,
+}
+
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn from_millis(millis: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis.max(0) as u64)
+}
+
+fn same_site_to_str(same_site: &Option<SameSite>) -> Option<&'static str> {
+    match same_site {
+        Some(SameSite::Strict) => Some("Strict"),
+        Some(SameSite::Lax) => Some("Lax"),
+        Some(SameSite::None) => Some("None"),
+        None => None,
+    }
+}
+
+fn same_site_from_str(same_site: Option<String>) -> Option<SameSite> {
+    match same_site.as_deref() {
+        Some("Strict") => Some(SameSite::Strict),
+        Some("Lax") => Some(SameSite::Lax),
+        Some("None") => Some(SameSite::None),
+        _ => None,
+    }
+}
+
+fn cookie_from_row(row: &Row) -> rusqlite::Result<Cookie> {
+    Ok(Cookie {
+        key: CookieKey {
+            domain: row.get(0)?,
+            path: row.get(1)?,
+            name: row.get(2)?,
+        },
+        value: row.get(3)?,
+        expires: row.get::<_, Option<i64>>(4)?.map(from_millis),
+        creation_time: from_millis(row.get(5)?),
+        last_access_time: from_millis(row.get(6)?),
+        secure: row.get(7)?,
+        http_only: row.get(8)?,
+        same_site: same_site_from_str(row.get(9)?),
+        persistent: row.get(10)?,
+        host_only: row.get(11)?,
+    })
+}
+
+fn key_from_row(row: &Row) -> rusqlite::Result<CookieKey> {
+    Ok(CookieKey {
+        domain: row.get(0)?,
+        path: row.get(1)?,
+        name: row.get(2)?,
+    })
+}
+
+fn upsert(connection: &Connection, cookie: &Cookie) -> rusqlite::Result<()> {
+    connection
+        .prepare_cached(&format!(
+            "INSERT OR REPLACE INTO cookies ({}) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            COLUMNS
+        ))?
+        .execute(params![
+            cookie.key.domain,
+            cookie.key.path,
+            cookie.key.name,
+            cookie.value,
+            cookie.expires.map(to_millis),
+            to_millis(cookie.creation_time),
+            to_millis(cookie.last_access_time),
+            cookie.secure,
+            cookie.http_only,
+            same_site_to_str(&cookie.same_site),
+            cookie.persistent,
+            cookie.host_only,
+        ])?;
+    Ok(())
+}
+
+fn delete_keys(connection: &Connection, keys: &Vec<CookieKey>) -> rusqlite::Result<()> {
+    let mut statement = connection
+        .prepare_cached("DELETE FROM cookies WHERE domain = ?1 AND path = ?2 AND name = ?3")?;
+    for key in keys {
+        statement.execute(params![key.domain, key.path, key.name])?;
+    }
+    Ok(())
+}
+
+// evicts least recently used cookies, domain limit first, then the global one
+fn enforce_limits(
+    connection: &Connection,
+    domain: &str,
+    max_cookies: Option<usize>,
+    max_cookies_per_domain: Option<usize>,
+) -> rusqlite::Result<Vec<CookieKey>> {
+    let mut evicted = Vec::new();
+
+    if let Some(max) = max_cookies_per_domain {
+        let keys = connection
+            .prepare_cached(
+                "SELECT domain, path, name FROM cookies WHERE domain = ?1 \
+                 ORDER BY last_access_time DESC LIMIT -1 OFFSET ?2",
+            )?
+            .query_map(params![domain, max as i64], key_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        delete_keys(connection, &keys)?;
+        evicted.extend(keys);
+    }
+
+    if let Some(max) = max_cookies {
+        let keys = connection
+            .prepare_cached(
+                "SELECT domain, path, name FROM cookies \
+                 ORDER BY last_access_time DESC LIMIT -1 OFFSET ?1",
+            )?
+            .query_map(params![max as i64], key_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        delete_keys(connection, &keys)?;
+        evicted.extend(keys);
+    }
+
+    Ok(evicted)
+}
+
+fn store_cookie(
+    connection: &mut Connection,
+    cookie: &Cookie,
+    max_cookies: Option<usize>,
+    max_cookies_per_domain: Option<usize>,
+) -> rusqlite::Result<Vec<CookieKey>> {
+    let transaction = connection.transaction()?;
+    upsert(&transaction, cookie)?;
+    let evicted = enforce_limits(
+        &transaction,
+        &cookie.key.domain,
+        max_cookies,
+        max_cookies_per_domain,
+    )?;
+    transaction.commit()?;
+    Ok(evicted)
+}
+
+fn select_for_domain(connection: &Connection, domain: &str) -> rusqlite::Result<Vec<Cookie>> {
+    let now = to_millis(SystemTime::now());
+    let mut statement = connection.prepare_cached(&format!(
+        "SELECT {} FROM cookies WHERE domain = ?1 AND (expires IS NULL OR expires > ?2)",
+        COLUMNS
+    ))?;
+
+    let mut cookies = Vec::new();
+    for candidate in candidate_domains(domain) {
+        let rows = statement
+            .query_map(params![candidate, now], cookie_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        cookies.extend(
+            rows.into_iter()
+                .filter(|cookie| !cookie.host_only || cookie.key.domain == domain),
+        );
+    }
+    Ok(cookies)
+}
+
+fn touch_keys(connection: &mut Connection, keys: &[CookieKey]) -> rusqlite::Result<()> {
+    let now = to_millis(SystemTime::now());
+    let transaction = connection.transaction()?;
+    {
+        let mut statement = transaction.prepare_cached(
+            "UPDATE cookies SET last_access_time = ?1 \
+             WHERE domain = ?2 AND path = ?3 AND name = ?4",
+        )?;
+        for key in keys {
+            statement.execute(params![now, key.domain, key.path, key.name])?;
+        }
+    }
+    transaction.commit()
+}
+
+// example.com itself plus every parent a domain cookie could be scoped to
+fn candidate_domains(domain: &str) -> Vec<String> {
+    let labels: Vec<&str> = domain.split('.').collect();
+    (0..labels.len())
+        .map(|start| labels[start..].join("."))
+        .collect()
+}
+
+impl SqliteCookieStore {
+    pub async fn new(config: CookieConfig) -> Result<Self, CookieError> {
+        let public_suffix_list = if let Some(path) = &config.public_suffix_list_path {
+            let content = tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| CookieError::IO(e.to_string()))?;
+            PublicSuffixList::parse(&content)
+        } else {
+            PublicSuffixList::builtin()
+        };
+
+        // with session_only nothing is read from or written to the database on disk
+        let path = config.cookie_path.clone().filter(|_| !config.session_only);
+        let initial_cookies: Vec<Cookie> = config
+            .initial_cookies
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|cookie| limit_lifetime(&config, cookie))
+            .collect();
+        let max_expires = config
+            .max_cookie_lifetime
+            .map(|max_cookie_lifetime| to_millis(SystemTime::now() + max_cookie_lifetime));
+        let connection = tokio::task::spawn_blocking(move || {
+            let connection = match path {
+                Some(path) => Connection::open(path)?,
+                None => Connection::open_in_memory()?,
+            };
+            connection.pragma_update(None, "journal_mode", "WAL")?;
+            connection.execute_batch(SCHEMA)?;
+
+            // session cookies do not survive a restart, same as a browser
+            connection.execute(
+                "DELETE FROM cookies WHERE persistent = 0 OR expires <= ?1",
+                params![to_millis(SystemTime::now())],
+            )?;
+            // a cookie stored before max_cookie_lifetime was lowered is held to it too
+            if let Some(max_expires) = max_expires {
+                connection.execute(
+                    "UPDATE cookies SET expires = ?1 WHERE expires > ?1",
+                    params![max_expires],
+                )?;
+            }
+            for cookie in initial_cookies.iter() {
+                upsert(&connection, cookie)?;
+            }
+            Ok::<_, rusqlite::Error>(connection)
+        })
+        .await
+        .map_err(|e| CookieError::Storage(e.to_string()))?
+        .map_err(|e| CookieError::Storage(e.to_string()))?;
+
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+            config,
+            public_suffix_list,
+            changes: broadcast::channel(64).0,
+        })
+    }
+
+    // the cookies are checked and their lifetime limited before they are stored
+    fn admit(&self, cookie: Cookie) -> Result<Cookie, CookieError> {
+        if self.public_suffix_list.is_public_suffix(&cookie.key.domain) {
+            return Err(CookieError::Rejected(format!(
+                "{} is a public suffix",
+                cookie.key.domain
+            )));
+        }
+        if !allowed_by_policies(&self.config.policies, &cookie.key.domain) {
+            return Err(CookieError::Rejected(format!(
+                "{} is blocked by cookie policy",
+                cookie.key.domain
+            )));
+        }
+        Ok(limit_lifetime(&self.config, cookie))
+    }
+
+    // runs on the calling thread, for the sync methods of the store
+    fn with_connection_now<R, F>(&self, f: F) -> Result<R, CookieError>
+    where
+        F: FnOnce(&mut Connection) -> rusqlite::Result<R>,
+    {
+        f(&mut self.connection.lock()).map_err(|e| CookieError::Storage(e.to_string()))
+    }
+
+    async fn with_connection<R, F>(&self, f: F) -> Result<R, CookieError>
+    where
+        F: FnOnce(&mut Connection) -> rusqlite::Result<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || f(&mut connection.lock()))
+            .await
+            .map_err(|e| CookieError::Storage(e.to_string()))?
+            .map_err(|e| CookieError::Storage(e.to_string()))
+    }
+
+    pub async fn purge_expired(&self) -> usize {
+        let now = to_millis(SystemTime::now());
+        let purged = self
+            .with_connection(move |connection| {
+                let keys = connection
+                    .prepare_cached("SELECT domain, path, name FROM cookies WHERE expires <= ?1")?
+                    .query_map(params![now], key_from_row)?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                delete_keys(connection, &keys)?;
+                Ok(keys)
+            })
+            .await
+            .unwrap_or_default();
+
+        let count = purged.len();
+        purged
+            .into_iter()
+            .for_each(|key| self.notify(CookieChange::Removed(key)));
+        count
+    }
+
+    async fn all_cookies(&self) -> Result<Vec<Cookie>, CookieError> {
+        let now = to_millis(SystemTime::now());
+        self.with_connection(move |connection| {
+            connection
+                .prepare_cached(&format!(
+                    "SELECT {} FROM cookies WHERE expires IS NULL OR expires > ?1",
+                    COLUMNS
+                ))?
+                .query_map(params![now], cookie_from_row)?
+                .collect()
+        })
+        .await
+    }
+
+    fn notify(&self, change: CookieChange) {
+        // nobody listening is not an error
+        let _ = self.changes.send(change);
+    }
+}
+
+#[async_trait]
+impl CookieStore for SqliteCookieStore {
+    async fn get(&self, key: &CookieKey) -> Option<Cookie> {
+        let key = key.clone();
+        let cookie = self
+            .with_connection(move |connection| {
+                connection
+                    .prepare_cached(&format!(
+                        "SELECT {} FROM cookies WHERE domain = ?1 AND path = ?2 AND name = ?3",
+                        COLUMNS
+                    ))?
+                    .query_row(params![key.domain, key.path, key.name], cookie_from_row)
+                    .optional()
+            })
+            .await
+            .ok()
+            .flatten();
+        cookie.filter(|cookie| !cookie.is_expired())
+    }
+
+    async fn set(&self, cookie: Cookie) -> Result<(), CookieError> {
+        let cookie = self.admit(cookie)?;
+
+        let max_cookies = self.config.max_cookies;
+        let max_cookies_per_domain = self.config.max_cookies_per_domain;
+        let stored = cookie.clone();
+        let evicted = self
+            .with_connection(move |connection| {
+                store_cookie(connection, &stored, max_cookies, max_cookies_per_domain)
+            })
+            .await?;
+
+        self.notify(CookieChange::Set(cookie));
+        evicted
+            .into_iter()
+            .for_each(|key| self.notify(CookieChange::Removed(key)));
+        Ok(())
+    }
+
+    async fn remove(&self, key: &CookieKey) {
+        let keys = vec![key.clone()];
+        let result = self
+            .with_connection(move |connection| delete_keys(connection, &keys))
+            .await;
+        if result.is_ok() {
+            self.notify(CookieChange::Removed(key.clone()));
+        }
+    }
+
+    async fn get_for_domain(&self, domain: &str) -> Vec<Cookie> {
+        let domain = domain.to_string();
+        self.with_connection(move |connection| select_for_domain(connection, &domain))
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn get_for_url(&self, url: &str) -> Vec<Cookie> {
+        let domain = extract_domain(url);
+        if domain.is_err() {
+            return vec![];
+        }
+
+        self.get_for_domain(&domain.unwrap()).await
+    }
+
+    async fn touch(&self, keys: &[CookieKey]) {
+        let keys = keys.to_vec();
+        let _ = self
+            .with_connection(move |connection| touch_keys(connection, &keys))
+            .await;
+    }
+
+    // the database is the store, so these query it on the calling thread
+    fn set_sync(&self, cookie: Cookie) -> Result<(), CookieError> {
+        let cookie = self.admit(cookie)?;
+        let evicted = self.with_connection_now(|connection| {
+            store_cookie(
+                connection,
+                &cookie,
+                self.config.max_cookies,
+                self.config.max_cookies_per_domain,
+            )
+        })?;
+
+        self.notify(CookieChange::Set(cookie));
+        evicted
+            .into_iter()
+            .for_each(|key| self.notify(CookieChange::Removed(key)));
+        Ok(())
+    }
+
+    fn remove_sync(&self, key: &CookieKey) {
+        let keys = vec![key.clone()];
+        if self
+            .with_connection_now(|connection| delete_keys(connection, &keys))
+            .is_ok()
+        {
+            self.notify(CookieChange::Removed(key.clone()));
+        }
+    }
+
+    fn get_for_url_sync(&self, url: &str) -> Vec<Cookie> {
+        let domain = extract_domain(url);
+        if domain.is_err() {
+            return vec![];
+        }
+
+        let domain = domain.unwrap();
+        self.with_connection_now(|connection| select_for_domain(connection, &domain))
+            .unwrap_or_default()
+    }
+
+    fn touch_sync(&self, keys: &[CookieKey]) {
+        let _ = self.with_connection_now(|connection| touch_keys(connection, keys));
+    }
+
+    async fn clear_all(&self) {
+        let result = self
+            .with_connection(|connection| connection.execute("DELETE FROM cookies", []))
+            .await;
+        if result.is_ok() {
+            self.notify(CookieChange::Cleared);
+        }
+    }
+
+    async fn clear_session(&self) -> usize {
+        let keys = self
+            .with_connection(|connection| {
+                let keys = connection
+                    .prepare_cached("SELECT domain, path, name FROM cookies WHERE persistent = 0")?
+                    .query_map([], key_from_row)?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                delete_keys(connection, &keys)?;
+                Ok(keys)
+            })
+            .await
+            .unwrap_or_default();
+
+        let count = keys.len();
+        keys.into_iter()
+            .for_each(|key| self.notify(CookieChange::Removed(key)));
+        count
+    }
+
+    async fn promote_session_cookies(&self, keys: &[CookieKey], expires: SystemTime) -> usize {
+        if self.config.session_only {
+            return 0;
+        }
+        let expires = clamp_expires(&self.config, expires);
+        let keys = keys.to_vec();
+        let promoted = self
+            .with_connection(move |connection| {
+                let transaction = connection.transaction()?;
+                let mut promoted = Vec::new();
+                {
+                    let mut update = transaction.prepare_cached(
+                        "UPDATE cookies SET persistent = 1, expires = ?1 \
+                         WHERE domain = ?2 AND path = ?3 AND name = ?4 AND persistent = 0",
+                    )?;
+                    let mut select = transaction.prepare_cached(&format!(
+                        "SELECT {} FROM cookies WHERE domain = ?1 AND path = ?2 AND name = ?3",
+                        COLUMNS
+                    ))?;
+                    for key in keys {
+                        let updated = update.execute(params![
+                            to_millis(expires),
+                            key.domain,
+                            key.path,
+                            key.name
+                        ])?;
+                        if updated == 0 {
+                            continue;
+                        }
+                        promoted.push(
+                            select.query_row(
+                                params![key.domain, key.path, key.name],
+                                cookie_from_row,
+                            )?,
+                        );
+                    }
+                }
+                transaction.commit()?;
+                Ok(promoted)
+            })
+            .await
+            .unwrap_or_default();
+
+        let count = promoted.len();
+        promoted
+            .into_iter()
+            .for_each(|cookie| self.notify(CookieChange::Set(cookie)));
+        count
+    }
+
+    // every write is already committed to the database
+    async fn persist(&self) -> Result<(), CookieError> {
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<(), CookieError> {
+        Ok(())
+    }
+
+    async fn export(&self, format: CookieFormat) -> Result<Vec<u8>, CookieError> {
+        let cookies = self.all_cookies().await?;
+        match format {
+            CookieFormat::Netscape => Ok(export_netscape(&cookies).into_bytes()),
+            CookieFormat::DartJson => Ok(export_dart_json(&cookies)?.into_bytes()),
+        }
+    }
+
+    async fn import(&self, format: CookieFormat, bytes: &[u8]) -> Result<usize, CookieError> {
+        let content =
+            std::str::from_utf8(bytes).map_err(|e| CookieError::Serialization(e.to_string()))?;
+        let cookies = match format {
+            CookieFormat::Netscape => import_netscape(content)?,
+            CookieFormat::DartJson => import_dart_json(content)?,
+        };
+
+        let mut imported = 0;
+        for cookie in cookies {
+            if cookie.is_expired() {
+                continue;
+            }
+            if self.set(cookie).await.is_ok() {
+                imported += 1;
+            }
+        }
+        Ok(imported)
+    }
+
+    async fn stats(&self) -> CookieStats {
+        let now = to_millis(SystemTime::now());
+        let mut stats = self
+            .with_connection(move |connection| {
+                let (total, persistent, expired) = connection.query_row(
+                    "SELECT COUNT(*), \
+                     COALESCE(SUM(persistent), 0), \
+                     COALESCE(SUM(CASE WHEN expires <= ?1 THEN 1 ELSE 0 END), 0) \
+                     FROM cookies",
+                    params![now],
+                    |row| {
+                        Ok((
+                            row.get::<_, i64>(0)?,
+                            row.get::<_, i64>(1)?,
+                            row.get::<_, i64>(2)?,
+                        ))
+                    },
+                )?;
+                let per_domain = connection
+                    .prepare_cached(
+                        "SELECT domain, COUNT(*) AS count FROM cookies \
+                         GROUP BY domain ORDER BY count DESC, domain ASC",
+                    )?
+                    .query_map([], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+
+                Ok(CookieStats {
+                    total: total as usize,
+                    persistent: persistent as usize,
+                    session: (total - persistent) as usize,
+                    expired: expired as usize,
+                    per_domain,
+                    storage_size: None,
+                })
+            })
+            .await
+            .unwrap_or_default();
+
+        if let Some(path) = &self.config.cookie_path {
+            stats.storage_size = tokio::fs::metadata(path)
+                .await
+                .ok()
+                .map(|metadata| metadata.len());
+        }
+        stats
+    }
+
+    fn subscribe(&self) -> BoxStream<'static, CookieChange> {
+        broadcast_stream(self.changes.subscribe())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::cookie_models::Cookie;
+    use crate::domain::traits::cookie_traits::CookieStore;
+    use crate::infrastructure::http::sqlite_cookie_store::SqliteCookieStore;
+    use crate::service::config::{CookieBackend, CookieConfig};
+    use std::time::{Duration, SystemTime};
+
+    macro_rules! await_test {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    fn cookie(domain: &str, name: &str, host_only: bool, age: u64) -> Cookie {
+        let mut cookie = Cookie::new(
+            domain.to_string(),
+            "/".to_string(),
+            name.to_string(),
+            "value".to_string(),
+            Some(SystemTime::now() + Duration::from_secs(3600)),
+            false,
+            false,
+            None,
+        );
+        cookie.host_only = host_only;
+        cookie.last_access_time = SystemTime::now() - Duration::from_secs(age);
+        cookie
+    }
+
+    #[test]
+    fn test_sqlite_store_persists_and_matches_domains() {
+        let path = std::env::temp_dir().join(format!(
+            "strawberry_sqlite_cookie_{}.db",
+            std::process::id()
+        ));
+        let config = CookieConfig {
+            backend: CookieBackend::Sqlite,
+            cookie_path: Some(path.to_string_lossy().to_string()),
+            debounce_delay: Duration::from_secs(10),
+            auto_save_interval: None,
+            initial_cookies: None,
+            public_suffix_list_path: None,
+            max_cookies: None,
+            max_cookies_per_domain: Some(2),
+            policies: vec![],
+            max_cookie_lifetime: None,
+            session_only: false,
+        };
+
+        await_test!(async {
+            let store = SqliteCookieStore::new(config.clone()).await.unwrap();
+            store
+                .set(cookie("example.com", "old", false, 100))
+                .await
+                .unwrap();
+            store
+                .set(cookie("example.com", "domain", false, 50))
+                .await
+                .unwrap();
+            store
+                .set(cookie("example.com", "host", true, 0))
+                .await
+                .unwrap();
+            let mut session = cookie("other.com", "session", true, 0);
+            session.expires = None;
+            session.persistent = false;
+            store.set(session).await.unwrap();
+            assert_eq!(store.get_for_domain("other.com").await.len(), 1);
+            drop(store);
+
+            let store = SqliteCookieStore::new(config).await.unwrap();
+            let cookies = store.get_for_domain("api.example.com").await;
+            assert_eq!(cookies.len(), 1);
+            assert_eq!(cookies[0].key.name, "domain");
+            assert_eq!(store.get_for_domain("example.com").await.len(), 2);
+            assert!(store.get_for_domain("other.com").await.is_empty());
+        });
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("db-wal"));
+        let _ = std::fs::remove_file(path.with_extension("db-shm"));
+    }
+
+    #[test]
+    fn test_sqlite_store_session_only_and_lifetime() {
+        let path = std::env::temp_dir().join(format!(
+            "strawberry_sqlite_cookie_session_{}.db",
+            std::process::id()
+        ));
+        let mut config = CookieConfig {
+            backend: CookieBackend::Sqlite,
+            cookie_path: Some(path.to_string_lossy().to_string()),
+            debounce_delay: Duration::from_secs(10),
+            auto_save_interval: None,
+            initial_cookies: None,
+            public_suffix_list_path: None,
+            max_cookies: None,
+            max_cookies_per_domain: None,
+            policies: vec![],
+            max_cookie_lifetime: None,
+            session_only: true,
+        };
+
+        await_test!(async {
+            // a session never opens the database on disk
+            let store = SqliteCookieStore::new(config.clone()).await.unwrap();
+            store
+                .set(cookie("example.com", "session", false, 0))
+                .await
+                .unwrap();
+            assert_eq!(store.get_for_domain("example.com").await.len(), 1);
+            drop(store);
+            assert!(!path.exists());
+
+            config.session_only = false;
+            let store = SqliteCookieStore::new(config.clone()).await.unwrap();
+            let mut remember = cookie("example.com", "remember", false, 0);
+            remember.expires = Some(SystemTime::now() + Duration::from_secs(86400 * 3650));
+            remember.persistent = true;
+            store.set(remember).await.unwrap();
+            drop(store);
+
+            // the stored cookies are held to a lowered max_cookie_lifetime once reopened
+            config.max_cookie_lifetime = Some(Duration::from_secs(60));
+            let store = SqliteCookieStore::new(config.clone()).await.unwrap();
+            let cookies = store.get_for_domain("example.com").await;
+            assert_eq!(cookies.len(), 1);
+            assert!(cookies[0].expires.unwrap() <= SystemTime::now() + Duration::from_secs(60));
+            drop(store);
+
+            config.session_only = true;
+            let store = SqliteCookieStore::new(config).await.unwrap();
+            assert!(store.get_for_domain("example.com").await.is_empty());
+        });
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("db-wal"));
+        let _ = std::fs::remove_file(path.with_extension("db-shm"));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::domain::models::http_models::HttpEndpoint;
+use crate::domain::traits::http_traits::UserAgentProvider;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub struct StaticUserAgentProvider {
+    user_agent: String,
+}
+
+pub struct RotatingUserAgentProvider {
+    user_agents: Vec<String>,
+    cursor: AtomicUsize,
+}
+
+pub struct PlatformUserAgentProvider {
+    user_agent: String,
+}
+
+impl StaticUserAgentProvider {
+    pub fn new(user_agent: String) -> Self {
+        Self { user_agent }
+    }
+}
+
+impl RotatingUserAgentProvider {
+    pub fn new(user_agents: Vec<String>) -> Self {
+        Self {
+            user_agents,
+            cursor: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl PlatformUserAgentProvider {
+    pub fn new(application: String, version: String) -> Self {
+        let user_agent = format!(
+            "{}/{} ({}; {})",
+            application,
+            version,
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        Self { user_agent }
+    }
+}
+
+impl UserAgentProvider for StaticUserAgentProvider {
+    fn user_agent(&self, _: &HttpEndpoint) -> Option<String> {
+        Some(self.user_agent.clone())
+    }
+}
+
+impl UserAgentProvider for RotatingUserAgentProvider {
+    fn user_agent(&self, _: &HttpEndpoint) -> Option<String> {
+        if self.user_agents.is_empty() {
+            return None;
+        }
+        let index = self.cursor.fetch_add(1, Ordering::Relaxed) % self.user_agents.len();
+        Some(self.user_agents[index].clone())
+    }
+}
+
+impl UserAgentProvider for PlatformUserAgentProvider {
+    fn user_agent(&self, _: &HttpEndpoint) -> Option<String> {
+        Some(self.user_agent.clone())
+    }
+}
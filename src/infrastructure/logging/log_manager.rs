@@ -0,0 +1,371 @@
+use crate::domain::models::file_cache_models::CompressionKind;
+use crate::domain::models::logging_models::{LogFileInfo, LoggingError};
+use crate::domain::models::storage_models::{
+    DeleteFile, ReadFile, StorageError, TransferFile, WriteFile, WriteMode,
+};
+use crate::domain::traits::logging_traits::LogManager;
+use crate::domain::traits::storage_traits::StorageManager;
+use crate::service::config::LogFileConfig;
+use crate::utils::compression::compress;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+// lines waiting for the writer, more are dropped and counted
+const QUEUE_SIZE: usize = 4096;
+// queued lines are combined into one write up to this size
+const WRITE_BATCH_SIZE: usize = 64 * 1024;
+
+enum LogCommand {
+    Line(String),
+    Flush(oneshot::Sender<()>),
+}
+
+fn storage_error(e: StorageError) -> LoggingError {
+    LoggingError::Storage(e.to_string())
+}
+
+fn rotated_path(config: &LogFileConfig, index: usize) -> String {
+    format!("{}.{}", config.path, index)
+}
+
+// app.log becomes app.log.1, app.log.1 becomes app.log.2 and so on, the oldest is dropped
+async fn rotate(
+    storage_manager: &dyn StorageManager,
+    config: &LogFileConfig,
+) -> Result<(), StorageError> {
+    if config.max_files == 0 {
+        return storage_manager
+            .delete(DeleteFile::path(config.path.clone()))
+            .await;
+    }
+    match storage_manager
+        .delete(DeleteFile::path(rotated_path(config, config.max_files)))
+        .await
+    {
+        Ok(()) | Err(StorageError::NotExist(_)) => {}
+        Err(e) => return Err(e),
+    }
+    for index in (1..config.max_files).rev() {
+        match storage_manager
+            .move_file(TransferFile::paths(
+                rotated_path(config, index),
+                rotated_path(config, index + 1),
+            ))
+            .await
+        {
+            Ok(()) | Err(StorageError::NotExist(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    storage_manager
+        .move_file(TransferFile::paths(
+            config.path.clone(),
+            rotated_path(config, 1),
+        ))
+        .await
+}
+
+// errors are printed instead of logged, logging them would feed back into this writer
+async fn write_lines(
+    mut receiver: mpsc::Receiver<LogCommand>,
+    config: LogFileConfig,
+    storage_manager: Arc<dyn StorageManager>,
+    mut size: u64,
+) {
+    // a command that ended the previous batch
+    let mut pending = None;
+    loop {
+        let command = match pending.take() {
+            Some(command) => command,
+            None => match receiver.recv().await {
+                Some(command) => command,
+                None => return,
+            },
+        };
+        let line = match command {
+            LogCommand::Line(line) => line,
+            LogCommand::Flush(done) => {
+                let _ = done.send(());
+                continue;
+            }
+        };
+        if size > 0 && size + line.len() as u64 + 1 > config.max_size {
+            if let Err(e) = rotate(storage_manager.as_ref(), &config).await {
+                eprintln!("Failed to rotate the log file: {}", e);
+            }
+            size = 0;
+        }
+
+        let mut data = line.into_bytes();
+        data.push(b'\n');
+        while data.len() < WRITE_BATCH_SIZE {
+            match receiver.try_recv() {
+                Ok(LogCommand::Line(line))
+                    if size + (data.len() + line.len() + 1) as u64 <= config.max_size =>
+                {
+                    data.extend_from_slice(line.as_bytes());
+                    data.push(b'\n');
+                }
+                Ok(command) => {
+                    pending = Some(command);
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+        let mut request = WriteFile::path(config.path.clone(), &data);
+        request.mode = WriteMode::Append;
+        match storage_manager.write(request).await {
+            Ok(_) => size += data.len() as u64,
+            Err(e) => eprintln!("Failed to write the log file: {}", e),
+        }
+    }
+}
+
+pub struct DefaultLogManager {
+    config: LogFileConfig,
+    storage_manager: Arc<dyn StorageManager>,
+    sender: Mutex<Option<mpsc::Sender<LogCommand>>>,
+    writer: Mutex<Option<JoinHandle<()>>>,
+    dropped: AtomicU64,
+}
+
+impl DefaultLogManager {
+    pub async fn new(
+        config: LogFileConfig,
+        storage_manager: Arc<dyn StorageManager>,
+    ) -> Result<Self, LoggingError> {
+        let size = match storage_manager.metadata(config.path.clone()).await {
+            Ok(metadata) => metadata.size,
+            Err(StorageError::NotExist(_)) => 0,
+            Err(e) => return Err(storage_error(e)),
+        };
+        let (sender, receiver) = mpsc::channel(QUEUE_SIZE);
+        let writer = tokio::spawn(write_lines(
+            receiver,
+            config.clone(),
+            storage_manager.clone(),
+            size,
+        ));
+        Ok(Self {
+            config,
+            storage_manager,
+            sender: Mutex::new(Some(sender)),
+            writer: Mutex::new(Some(writer)),
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    // lines that did not fit into the queue
+    pub fn dropped_lines(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn bundle(files: Vec<(LogFileInfo, Vec<u8>)>) -> std::io::Result<Vec<u8>> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (file, data) in files {
+            let name = Path::new(&file.path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or(file.path);
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mtime(file.modified / 1000);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, data.as_slice())?;
+        }
+        builder.into_inner()
+    }
+}
+
+#[async_trait]
+impl LogManager for DefaultLogManager {
+    fn append(&self, line: String) -> bool {
+        let sender = self.sender.lock();
+        let Some(sender) = sender.as_ref() else {
+            return false;
+        };
+        if sender.try_send(LogCommand::Line(line)).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+
+    async fn flush(&self) -> Result<(), LoggingError> {
+        let Some(sender) = self.sender.lock().clone() else {
+            return Ok(());
+        };
+        let (done, flushed) = oneshot::channel();
+        if sender.send(LogCommand::Flush(done)).await.is_err() {
+            return Ok(());
+        }
+        let _ = flushed.await;
+        Ok(())
+    }
+
+    async fn files(&self) -> Result<Vec<LogFileInfo>, LoggingError> {
+        let paths = std::iter::once(self.config.path.clone())
+            .chain((1..=self.config.max_files).map(|index| rotated_path(&self.config, index)));
+        let mut files = Vec::new();
+        for path in paths {
+            match self.storage_manager.metadata(path.clone()).await {
+                Ok(metadata) => files.push(LogFileInfo {
+                    path,
+                    size: metadata.size,
+                    modified: metadata.modified,
+                }),
+                Err(StorageError::NotExist(_)) => {}
+                Err(e) => return Err(storage_error(e)),
+            }
+        }
+        Ok(files)
+    }
+
+    async fn collect(&self, since: Option<u64>) -> Result<String, LoggingError> {
+        self.flush().await?;
+        let mut contents = Vec::new();
+        for file in self.files().await? {
+            if since.is_some_and(|since| file.modified < since) {
+                continue;
+            }
+            // a file rotated away meanwhile is skipped
+            match self
+                .storage_manager
+                .read(ReadFile::path(file.path.clone()))
+                .await
+            {
+                Ok(data) => contents.push((file, data)),
+                Err(StorageError::NotExist(_)) => {}
+                Err(e) => return Err(storage_error(e)),
+            }
+        }
+
+        let archive = tokio::task::spawn_blocking(move || Self::bundle(contents))
+            .await
+            .map_err(|e| LoggingError::Bundle(e.to_string()))?
+            .map_err(|e| LoggingError::Bundle(e.to_string()))?;
+        let compressed = compress(&CompressionKind::Gzip, &archive)
+            .await
+            .map_err(|e| LoggingError::Bundle(e.to_string()))?;
+        let path = self
+            .storage_manager
+            .create_temp_file("logs".to_string(), Some("tar.gz".to_string()))
+            .await
+            .map_err(storage_error)?;
+        self.storage_manager
+            .write(WriteFile::path(path.clone(), &compressed))
+            .await
+            .map_err(storage_error)?;
+        Ok(path)
+    }
+
+    async fn shutdown(&self) -> Result<(), LoggingError> {
+        // the writer ends once the queue is drained and its sender is gone
+        self.sender.lock().take();
+        let writer = self.writer.lock().take();
+        if let Some(writer) = writer {
+            writer
+                .await
+                .map_err(|e| LoggingError::Storage(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::file_cache_models::CompressionKind;
+    use crate::domain::traits::logging_traits::LogManager;
+    use crate::infrastructure::logging::log_manager::DefaultLogManager;
+    use crate::infrastructure::storage::storage_backend::AsyncStorageManager;
+    use crate::service::config::LogFileConfig;
+    use crate::utils::compression::decompress;
+    use std::io::Read;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_rotation_and_collect() {
+        let path = std::env::temp_dir()
+            .join(format!("strawberry_log_manager_{}.log", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        runtime.block_on(async {
+            let config = LogFileConfig {
+                path: path.clone(),
+                max_size: 16,
+                max_files: 2,
+            };
+            let manager = DefaultLogManager::new(config, Arc::new(AsyncStorageManager::new()))
+                .await
+                .unwrap();
+            // ten bytes per line with its newline, so every line starts a new file
+            for index in 0..4 {
+                assert!(manager.append(format!("line {:04}", index)));
+            }
+            manager.flush().await.unwrap();
+
+            let files = manager.files().await.unwrap();
+            let paths: Vec<String> = files.iter().map(|file| file.path.clone()).collect();
+            assert_eq!(
+                paths,
+                vec![path.clone(), format!("{}.1", path), format!("{}.2", path)]
+            );
+            assert_eq!(
+                tokio::fs::read_to_string(&path).await.unwrap(),
+                "line 0003\n"
+            );
+            assert_eq!(
+                tokio::fs::read_to_string(format!("{}.2", path))
+                    .await
+                    .unwrap(),
+                "line 0001\n"
+            );
+
+            let bundle_path = manager.collect(None).await.unwrap();
+            let bundle = tokio::fs::read(&bundle_path).await.unwrap();
+            let archive = decompress(&CompressionKind::Gzip, &bundle).await.unwrap();
+            let mut entries = Vec::new();
+            for entry in tar::Archive::new(archive.as_slice()).entries().unwrap() {
+                let mut entry = entry.unwrap();
+                let name = entry.path().unwrap().to_string_lossy().to_string();
+                let mut content = String::new();
+                entry.read_to_string(&mut content).unwrap();
+                entries.push((name, content));
+            }
+            assert_eq!(entries.len(), 3);
+            assert_eq!(entries[0].1, "line 0003\n");
+
+            // nothing was modified in the future
+            let bundle_path_since = manager.collect(Some(u64::MAX)).await.unwrap();
+            let bundle = tokio::fs::read(&bundle_path_since).await.unwrap();
+            let archive = decompress(&CompressionKind::Gzip, &bundle).await.unwrap();
+            assert_eq!(
+                tar::Archive::new(archive.as_slice())
+                    .entries()
+                    .unwrap()
+                    .count(),
+                0
+            );
+
+            manager.shutdown().await.unwrap();
+            for file in [
+                path.clone(),
+                format!("{}.1", path),
+                format!("{}.2", path),
+                bundle_path,
+                bundle_path_since,
+            ] {
+                let _ = tokio::fs::remove_file(file).await;
+            }
+        });
+    }
+}
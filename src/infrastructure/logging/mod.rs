@@ -0,0 +1,2 @@
+pub mod log_manager;
+pub mod tracing_backend;
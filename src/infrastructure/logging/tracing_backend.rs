@@ -0,0 +1,285 @@
+use crate::domain::models::logging_models::{LogLevel, LogRecord, LoggingError};
+use crate::domain::traits::logging_traits::{LogManager, Logger};
+use crate::service::config::LoggingConfig;
+use crate::utils::cron::civil_date;
+use crate::utils::time::now_millis;
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+use std::fmt::Debug;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::broadcast;
+use tracing::field::{Field, Visit};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+
+lazy_static! {
+    // the global subscriber is installed once, a new runtime replaces the pipeline it feeds
+    static ref ACTIVE_PIPELINE: RwLock<Option<Arc<LogPipeline>>> = RwLock::new(None);
+}
+
+static SUBSCRIBER: OnceLock<Result<(), String>> = OnceLock::new();
+
+fn level_filter(level: LogLevel) -> LevelFilter {
+    match level {
+        LogLevel::Trace => LevelFilter::TRACE,
+        LogLevel::Debug => LevelFilter::DEBUG,
+        LogLevel::Info => LevelFilter::INFO,
+        LogLevel::Warn => LevelFilter::WARN,
+        LogLevel::Error => LevelFilter::ERROR,
+    }
+}
+
+fn log_level(level: &Level) -> LogLevel {
+    match *level {
+        Level::TRACE => LogLevel::Trace,
+        Level::DEBUG => LogLevel::Debug,
+        Level::INFO => LogLevel::Info,
+        Level::WARN => LogLevel::Warn,
+        Level::ERROR => LogLevel::Error,
+    }
+}
+
+// 2026-01-31T23:59:59.999Z
+fn format_timestamp(millis: u64) -> String {
+    let seconds = millis / 1000;
+    let (year, month, day) = civil_date(seconds / 86400);
+    let seconds_of_day = seconds % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60,
+        millis % 1000
+    )
+}
+
+pub fn format_record(record: &LogRecord) -> String {
+    let level = match record.level {
+        LogLevel::Trace => "TRACE",
+        LogLevel::Debug => "DEBUG",
+        LogLevel::Info => "INFO",
+        LogLevel::Warn => "WARN",
+        LogLevel::Error => "ERROR",
+    };
+    let mut line = format!(
+        "{} {:>5} {}: {}",
+        format_timestamp(record.timestamp),
+        level,
+        record.target,
+        record.message
+    );
+    for (key, value) in &record.fields {
+        line.push_str(&format!(" {}={}", key, value));
+    }
+    line
+}
+
+#[derive(Default)]
+struct RecordVisitor {
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Visit for RecordVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields
+                .push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields
+                .push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+}
+
+struct LogPipeline {
+    filter: Targets,
+    stderr: bool,
+    records: broadcast::Sender<LogRecord>,
+    log_manager: Option<Arc<dyn LogManager>>,
+}
+
+impl LogPipeline {
+    fn dispatch(&self, record: LogRecord) {
+        let line = format_record(&record);
+        if self.stderr {
+            eprintln!("{}", line);
+        }
+        if let Some(log_manager) = &self.log_manager {
+            log_manager.append(line);
+        }
+        if self.records.receiver_count() > 0 {
+            let _ = self.records.send(record);
+        }
+    }
+}
+
+// forwards events to whichever pipeline is active, so it can stay installed for the process
+struct PipelineLayer;
+
+impl<S: Subscriber> Layer<S> for PipelineLayer {
+    fn register_callsite(&self, _: &'static Metadata<'static>) -> Interest {
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _: Context<'_, S>) -> bool {
+        ACTIVE_PIPELINE.read().as_ref().is_some_and(|pipeline| {
+            pipeline
+                .filter
+                .would_enable(metadata.target(), metadata.level())
+        })
+    }
+
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let Some(pipeline) = ACTIVE_PIPELINE.read().clone() else {
+            return;
+        };
+        let metadata = event.metadata();
+        let mut visitor = RecordVisitor::default();
+        event.record(&mut visitor);
+        pipeline.dispatch(LogRecord {
+            timestamp: now_millis(),
+            level: log_level(metadata.level()),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        });
+    }
+}
+
+pub struct TracingLogger {
+    pipeline: Arc<LogPipeline>,
+}
+
+impl TracingLogger {
+    // config.file is left to the log manager, lines go to it when one is given
+    pub fn new(
+        config: &LoggingConfig,
+        log_manager: Option<Arc<dyn LogManager>>,
+    ) -> Result<Self, LoggingError> {
+        SUBSCRIBER
+            .get_or_init(|| {
+                tracing_subscriber::registry()
+                    .with(PipelineLayer)
+                    .try_init()
+                    .map_err(|e| e.to_string())
+            })
+            .clone()
+            .map_err(LoggingError::SubscriberConflict)?;
+
+        let filter = config.targets.iter().fold(
+            Targets::new().with_default(level_filter(config.level)),
+            |filter, (target, level)| filter.with_target(target.clone(), level_filter(*level)),
+        );
+
+        let pipeline = Arc::new(LogPipeline {
+            filter,
+            stderr: config.stderr,
+            records: broadcast::channel(1024).0,
+            log_manager,
+        });
+        *ACTIVE_PIPELINE.write() = Some(pipeline.clone());
+        Ok(Self { pipeline })
+    }
+}
+
+#[async_trait]
+impl Logger for TracingLogger {
+    fn records(&self) -> broadcast::Receiver<LogRecord> {
+        self.pipeline.records.subscribe()
+    }
+
+    async fn shutdown(&self) -> Result<(), LoggingError> {
+        let mut active = ACTIVE_PIPELINE.write();
+        if active
+            .as_ref()
+            .is_some_and(|pipeline| Arc::ptr_eq(pipeline, &self.pipeline))
+        {
+            *active = None;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::logging_models::LogLevel;
+    use crate::domain::traits::logging_traits::{LogManager, Logger};
+    use crate::infrastructure::logging::log_manager::DefaultLogManager;
+    use crate::infrastructure::logging::tracing_backend::TracingLogger;
+    use crate::infrastructure::storage::storage_backend::AsyncStorageManager;
+    use crate::service::config::{LogFileConfig, LoggingConfig};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_filtered_records_reach_file_and_stream() {
+        let path = std::env::temp_dir()
+            .join(format!("strawberry_logging_{}.log", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        runtime.block_on(async {
+            let config = LoggingConfig {
+                level: LogLevel::Info,
+                targets: vec![("noisy".to_string(), LogLevel::Error)],
+                stderr: false,
+                file: None,
+            };
+            let log_manager = Arc::new(
+                DefaultLogManager::new(
+                    LogFileConfig {
+                        path: path.clone(),
+                        max_size: 1024 * 1024,
+                        max_files: 1,
+                    },
+                    Arc::new(AsyncStorageManager::new()),
+                )
+                .await
+                .unwrap(),
+            );
+            let logger = TracingLogger::new(&config, Some(log_manager.clone())).unwrap();
+            let mut records = logger.records();
+
+            tracing::info!(attempt = 3, "first line");
+            tracing::debug!("filtered by level");
+            tracing::warn!(target: "noisy", "filtered by target");
+            tracing::info!("second line");
+            logger.shutdown().await.unwrap();
+            tracing::info!("after shutdown");
+            log_manager.shutdown().await.unwrap();
+
+            let record = records.recv().await.unwrap();
+            assert_eq!(record.message, "first line");
+            assert_eq!(
+                record.fields,
+                vec![("attempt".to_string(), "3".to_string())]
+            );
+            assert_eq!(records.recv().await.unwrap().message, "second line");
+            assert!(records.try_recv().is_err());
+
+            let content = tokio::fs::read_to_string(&path).await.unwrap();
+            let lines: Vec<&str> = content.lines().collect();
+            assert_eq!(lines.len(), 2);
+            assert!(lines[0].ends_with("first line attempt=3"));
+            assert!(lines[1].contains(" INFO "));
+            assert!(lines[1].ends_with("second line"));
+            let _ = tokio::fs::remove_file(&path).await;
+        });
+    }
+}
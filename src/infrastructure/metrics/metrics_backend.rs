@@ -0,0 +1,202 @@
+use crate::domain::models::metrics_models::{
+    CounterSample, GaugeSample, HistogramSample, MetricsSnapshot,
+};
+use crate::domain::traits::metrics_traits::MetricsRegistry;
+use crate::utils::time::now_millis;
+use dashmap::DashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+type MetricKey = (String, Vec<(String, String)>);
+
+fn metric_key(name: &str, labels: &[(&str, &str)]) -> MetricKey {
+    let mut labels: Vec<(String, String)> = labels
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    labels.sort();
+    (name.to_string(), labels)
+}
+
+struct Histogram {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    counts: Vec<u64>,
+}
+
+impl Histogram {
+    fn new(buckets: usize) -> Self {
+        Self {
+            count: 0,
+            sum: 0.0,
+            min: 0.0,
+            max: 0.0,
+            counts: vec![0; buckets + 1],
+        }
+    }
+
+    fn record(&mut self, bounds: &[f64], value: f64) {
+        if self.count == 0 {
+            self.min = value;
+            self.max = value;
+        } else {
+            self.min = self.min.min(value);
+            self.max = self.max.max(value);
+        }
+        self.count += 1;
+        self.sum += value;
+        let bucket = bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(bounds.len());
+        self.counts[bucket] += 1;
+    }
+}
+
+pub struct DefaultMetricsRegistry {
+    bounds: Vec<f64>,
+    counters: DashMap<MetricKey, AtomicU64>,
+    gauges: DashMap<MetricKey, f64>,
+    histograms: DashMap<MetricKey, Histogram>,
+}
+
+impl DefaultMetricsRegistry {
+    pub fn new(mut histogram_buckets: Vec<f64>) -> Self {
+        histogram_buckets.retain(|bound| bound.is_finite());
+        histogram_buckets.sort_by(|a, b| a.total_cmp(b));
+        histogram_buckets.dedup();
+        Self {
+            bounds: histogram_buckets,
+            counters: DashMap::new(),
+            gauges: DashMap::new(),
+            histograms: DashMap::new(),
+        }
+    }
+}
+
+impl MetricsRegistry for DefaultMetricsRegistry {
+    fn increment_counter(&self, name: &str, labels: &[(&str, &str)], value: u64) {
+        let key = metric_key(name, labels);
+        if let Some(counter) = self.counters.get(&key) {
+            counter.fetch_add(value, Ordering::Relaxed);
+            return;
+        }
+        self.counters
+            .entry(key)
+            .or_insert_with(|| AtomicU64::new(0))
+            .fetch_add(value, Ordering::Relaxed);
+    }
+
+    fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.gauges.insert(metric_key(name, labels), value);
+    }
+
+    fn add_gauge(&self, name: &str, labels: &[(&str, &str)], delta: f64) {
+        *self.gauges.entry(metric_key(name, labels)).or_insert(0.0) += delta;
+    }
+
+    fn record_histogram(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.histograms
+            .entry(metric_key(name, labels))
+            .or_insert_with(|| Histogram::new(self.bounds.len()))
+            .record(&self.bounds, value);
+    }
+
+    fn snapshot(&self) -> MetricsSnapshot {
+        let mut counters: Vec<CounterSample> = self
+            .counters
+            .iter()
+            .map(|entry| CounterSample {
+                name: entry.key().0.clone(),
+                labels: entry.key().1.clone(),
+                value: entry.value().load(Ordering::Relaxed),
+            })
+            .collect();
+        counters.sort_by(|a, b| (&a.name, &a.labels).cmp(&(&b.name, &b.labels)));
+
+        let mut gauges: Vec<GaugeSample> = self
+            .gauges
+            .iter()
+            .map(|entry| GaugeSample {
+                name: entry.key().0.clone(),
+                labels: entry.key().1.clone(),
+                value: *entry.value(),
+            })
+            .collect();
+        gauges.sort_by(|a, b| (&a.name, &a.labels).cmp(&(&b.name, &b.labels)));
+
+        let mut histograms: Vec<HistogramSample> = self
+            .histograms
+            .iter()
+            .map(|entry| {
+                let histogram = entry.value();
+                HistogramSample {
+                    name: entry.key().0.clone(),
+                    labels: entry.key().1.clone(),
+                    count: histogram.count,
+                    sum: histogram.sum,
+                    min: histogram.min,
+                    max: histogram.max,
+                    bounds: self.bounds.clone(),
+                    counts: histogram.counts.clone(),
+                }
+            })
+            .collect();
+        histograms.sort_by(|a, b| (&a.name, &a.labels).cmp(&(&b.name, &b.labels)));
+
+        MetricsSnapshot {
+            timestamp: now_millis(),
+            counters,
+            gauges,
+            histograms,
+        }
+    }
+
+    fn reset(&self) {
+        self.counters.clear();
+        self.gauges.clear();
+        self.histograms.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::traits::metrics_traits::MetricsRegistry;
+    use crate::infrastructure::metrics::metrics_backend::DefaultMetricsRegistry;
+
+    #[test]
+    fn test_snapshot() {
+        let registry = DefaultMetricsRegistry::new(vec![100.0, 10.0]);
+        registry.increment_counter("requests", &[("status", "200"), ("method", "GET")], 1);
+        registry.increment_counter("requests", &[("method", "GET"), ("status", "200")], 2);
+        registry.increment_counter("requests", &[("method", "GET"), ("status", "500")], 1);
+        registry.set_gauge("open", &[], 4.0);
+        registry.add_gauge("open", &[], -1.5);
+        for value in [5.0, 10.0, 50.0, 500.0] {
+            registry.record_histogram("duration", &[], value);
+        }
+
+        let snapshot = registry.snapshot();
+        let counters: Vec<(String, u64)> = snapshot
+            .counters
+            .iter()
+            .map(|counter| (counter.labels[1].1.clone(), counter.value))
+            .collect();
+        assert_eq!(
+            counters,
+            vec![("200".to_string(), 3), ("500".to_string(), 1)]
+        );
+        assert_eq!(snapshot.gauges[0].value, 2.5);
+
+        let histogram = &snapshot.histograms[0];
+        assert_eq!(histogram.count, 4);
+        assert_eq!(histogram.sum, 565.0);
+        assert_eq!((histogram.min, histogram.max), (5.0, 500.0));
+        assert_eq!(histogram.bounds, vec![10.0, 100.0]);
+        assert_eq!(histogram.counts, vec![2, 1, 1]);
+
+        registry.reset();
+        assert!(registry.snapshot().counters.is_empty());
+    }
+}
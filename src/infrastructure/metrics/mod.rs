@@ -0,0 +1 @@
+pub mod metrics_backend;
\ No newline at end of file
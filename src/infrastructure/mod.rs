@@ -1,3 +1,7 @@
 pub mod http;
 pub mod storage;
-pub mod monitor;
\ No newline at end of file
+pub mod monitor;
+pub mod database;
+pub mod logging;
+pub mod metrics;
+pub mod crash;
\ No newline at end of file
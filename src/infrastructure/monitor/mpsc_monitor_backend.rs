@@ -8,9 +8,12 @@ use tokio::sync::mpsc::{Receiver, Sender};
 use uuid::Uuid;
 
 pub struct MpscMonitorBackend {
+    #[allow(dead_code)]
     tokio_runtime: Arc<Runtime>,
     self_weak: Mutex<Weak<MpscMonitorBackend>>,
+    #[allow(dead_code)]
     sender: Sender<MonitorEvent>,
+    #[allow(dead_code)]
     receiver: Receiver<MonitorEvent>,
     subscribers: DashMap<String, Arc<MpscMonitorSubscriber>>,
 }
@@ -0,0 +1,392 @@
+use crate::domain::models::storage_models::{
+    DeleteFile, DirEntry, DiskUsage, FileHash, FileMetadata, HashAlgorithm, ReadFile, StorageError,
+    StorageEvent, TransferFile, WriteFile, WriteMode,
+};
+use crate::domain::traits::storage_traits::{ProgressSink, StorageKeyProvider, StorageManager};
+use crate::infrastructure::storage::storage_backend::{
+    decode_compressed, encode_compressed, hash, split_lines,
+};
+use crate::utils::aead;
+use aes_gcm::Aes256Gcm;
+use aes_gcm::aead::KeyInit;
+use async_trait::async_trait;
+use bytes::Bytes;
+use dashmap::DashMap;
+use futures_util::stream::BoxStream;
+use futures_util::{StreamExt, TryStreamExt, stream};
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+pub struct StaticKeyProvider {
+    key: [u8; 32],
+}
+
+impl StaticKeyProvider {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+}
+
+impl StorageKeyProvider for StaticKeyProvider {
+    fn key(&self) -> Result<[u8; 32], StorageError> {
+        Ok(self.key)
+    }
+}
+
+// whole files are sealed, so ranges, streams and appends work on the decrypted content
+pub struct EncryptedStorageManager {
+    inner: Arc<dyn StorageManager>,
+    key_provider: Arc<dyn StorageKeyProvider>,
+    // appends and covers read the sealed file and write it back, writers to a path go one at a time
+    writing: DashMap<String, Arc<Mutex<()>>>,
+}
+
+impl EncryptedStorageManager {
+    pub fn new(inner: Arc<dyn StorageManager>, key_provider: Arc<dyn StorageKeyProvider>) -> Self {
+        Self {
+            inner,
+            key_provider,
+            writing: DashMap::new(),
+        }
+    }
+
+    fn cipher(&self) -> Result<Aes256Gcm, StorageError> {
+        let key = self.key_provider.key()?;
+        Aes256Gcm::new_from_slice(&key).map_err(|e| StorageError::Encryption(e.to_string()))
+    }
+
+    async fn read_plain(&self, request: ReadFile) -> Result<Vec<u8>, StorageError> {
+        let sealed = self.inner.read(request).await?;
+        let plain = aead::open(&self.cipher()?, &sealed).map_err(StorageError::Encryption)?;
+        decode_compressed(plain).await
+    }
+
+    async fn write_plain<'a>(&self, request: WriteFile<'a>) -> Result<(), StorageError> {
+        let path = request.path.clone();
+        let lock = self
+            .writing
+            .entry(path.clone())
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone();
+        let guard = lock.lock().await;
+        let result = self.write_plain_locked(request).await;
+        drop(guard);
+
+        self.writing.remove_if(&path, |_, writing| {
+            Arc::ptr_eq(writing, &lock) && Arc::strong_count(writing) == 2
+        });
+        result
+    }
+
+    async fn write_plain_locked<'a>(&self, request: WriteFile<'a>) -> Result<(), StorageError> {
+        let mut plain = match request.mode {
+            WriteMode::Append | WriteMode::Cover => {
+                match self.read_plain(ReadFile::path(request.path.clone())).await {
+                    Ok(existing) => existing,
+                    Err(StorageError::NotExist(_)) => Vec::new(),
+                    Err(e) => return Err(e),
+                }
+            }
+            WriteMode::Truncate | WriteMode::Atomic => Vec::new(),
+        };
+        match request.mode {
+            WriteMode::Append => plain.extend_from_slice(request.data),
+            WriteMode::Cover if plain.len() > request.data.len() => {
+                plain[..request.data.len()].copy_from_slice(request.data)
+            }
+            _ => plain = request.data.to_vec(),
+        }
+
+        // compressed before sealing, ciphertext does not compress
+        if let Some(compression) = &request.compression {
+            plain = encode_compressed(compression, &plain).await?;
+        }
+        let sealed = aead::seal(&self.cipher()?, &plain).map_err(StorageError::Encryption)?;
+        // a torn sealed file fails authentication and loses everything, never rewrite in place
+        self.inner
+            .write(WriteFile {
+                path: request.path,
+                mode: WriteMode::Atomic,
+                timeout: request.timeout,
+                ensure_mode: request.ensure_mode,
+                compression: None,
+                data: &sealed,
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl StorageManager for EncryptedStorageManager {
+    async fn read(&self, request: ReadFile) -> Result<Vec<u8>, StorageError> {
+        self.read_plain(request).await
+    }
+
+    async fn write<'a>(&self, request: WriteFile<'a>) -> Result<(), StorageError> {
+        self.write_plain(request).await
+    }
+
+    async fn read_verified(
+        &self,
+        request: ReadFile,
+        expected: FileHash,
+    ) -> Result<Vec<u8>, StorageError> {
+        let path = request.path.clone();
+        let data = self.read_plain(request).await?;
+        let actual = hash(expected.algorithm(), &data);
+        if actual != expected {
+            return Err(StorageError::ChecksumMismatch(format!(
+                "{} expected {} but was {}",
+                path,
+                expected.to_hex(),
+                actual.to_hex()
+            )));
+        }
+        Ok(data)
+    }
+
+    async fn write_hashed<'a>(
+        &self,
+        request: WriteFile<'a>,
+        algorithm: HashAlgorithm,
+    ) -> Result<FileHash, StorageError> {
+        let file_hash = hash(algorithm, request.data);
+        self.write_plain(request).await?;
+        Ok(file_hash)
+    }
+
+    async fn delete(&self, request: DeleteFile) -> Result<(), StorageError> {
+        self.inner.delete(request).await
+    }
+
+    async fn rename(&self, request: TransferFile) -> Result<(), StorageError> {
+        self.inner.rename(request).await
+    }
+
+    async fn copy(&self, request: TransferFile) -> Result<(), StorageError> {
+        self.inner.copy(request).await
+    }
+
+    // sealed files are copied as they are, so progress counts on-disk bytes
+    async fn copy_with_progress(
+        &self,
+        request: TransferFile,
+        progress_sink: ProgressSink,
+    ) -> Result<(), StorageError> {
+        self.inner.copy_with_progress(request, progress_sink).await
+    }
+
+    async fn move_file(&self, request: TransferFile) -> Result<(), StorageError> {
+        self.inner.move_file(request).await
+    }
+
+    async fn create_dir(&self, path: String, recursive: bool) -> Result<(), StorageError> {
+        self.inner.create_dir(path, recursive).await
+    }
+
+    async fn list_dir(&self, path: String) -> Result<Vec<DirEntry>, StorageError> {
+        self.inner.list_dir(path).await
+    }
+
+    async fn remove_dir(&self, path: String, recursive: bool) -> Result<(), StorageError> {
+        self.inner.remove_dir(path, recursive).await
+    }
+
+    async fn read_range(
+        &self,
+        path: String,
+        offset: u64,
+        len: u64,
+    ) -> Result<Vec<u8>, StorageError> {
+        let plain = self.read_plain(ReadFile::path(path)).await?;
+        let start = (offset as usize).min(plain.len());
+        let end = start.saturating_add(len as usize).min(plain.len());
+        Ok(plain[start..end].to_vec())
+    }
+
+    async fn read_stream(
+        &self,
+        path: String,
+        chunk_size: usize,
+    ) -> Result<BoxStream<'static, Result<Bytes, StorageError>>, StorageError> {
+        let plain = Bytes::from(self.read_plain(ReadFile::path(path)).await?);
+        let chunk_size = chunk_size.max(1);
+        let chunks = (0..plain.len())
+            .step_by(chunk_size)
+            .map(move |start| Ok(plain.slice(start..(start + chunk_size).min(plain.len()))))
+            .collect::<Vec<_>>();
+        Ok(stream::iter(chunks).boxed())
+    }
+
+    async fn write_stream(
+        &self,
+        path: String,
+        stream: BoxStream<'static, Result<Bytes, StorageError>>,
+    ) -> Result<u64, StorageError> {
+        let chunks: Vec<Bytes> = stream.try_collect().await?;
+        let plain = chunks.concat();
+        let mut request = WriteFile::path(path, &plain);
+        request.mode = WriteMode::Truncate;
+        self.write_plain(request).await?;
+        Ok(plain.len() as u64)
+    }
+
+    async fn append_line(&self, path: String, mut line: Vec<u8>) -> Result<(), StorageError> {
+        if !line.ends_with(b"\n") {
+            line.push(b'\n');
+        }
+        let mut request = WriteFile::path(path, &line);
+        request.mode = WriteMode::Append;
+        self.write_plain(request).await
+    }
+
+    async fn read_lines(
+        &self,
+        path: String,
+        range: Range<usize>,
+    ) -> Result<Vec<Vec<u8>>, StorageError> {
+        let plain = self.read_plain(ReadFile::path(path)).await?;
+        Ok(split_lines(&plain, range))
+    }
+
+    async fn exists(&self, path: String) -> Result<bool, StorageError> {
+        self.inner.exists(path).await
+    }
+
+    async fn resolve_path(&self, path: String) -> Result<String, StorageError> {
+        self.inner.resolve_path(path).await
+    }
+
+    // sizes are those of the sealed file on disk
+    async fn metadata(&self, path: String) -> Result<FileMetadata, StorageError> {
+        self.inner.metadata(path).await
+    }
+
+    async fn create_temp_file(
+        &self,
+        prefix: String,
+        extension: Option<String>,
+    ) -> Result<String, StorageError> {
+        self.inner.create_temp_file(prefix, extension).await
+    }
+
+    async fn unique_path(
+        &self,
+        dir: String,
+        base: String,
+        extension: Option<String>,
+    ) -> Result<String, StorageError> {
+        self.inner.unique_path(dir, base, extension).await
+    }
+
+    async fn cleanup_temp_files(&self, older_than: Duration) -> Result<u64, StorageError> {
+        self.inner.cleanup_temp_files(older_than).await
+    }
+
+    async fn disk_usage(&self, path: String) -> Result<DiskUsage, StorageError> {
+        self.inner.disk_usage(path).await
+    }
+
+    async fn dir_size(&self, path: String) -> Result<u64, StorageError> {
+        self.inner.dir_size(path).await
+    }
+
+    async fn watch(
+        &self,
+        path: String,
+        debounce: Duration,
+    ) -> Result<BoxStream<'static, StorageEvent>, StorageError> {
+        self.inner.watch(path, debounce).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::storage_models::{ReadFile, WriteFile, WriteMode};
+    use crate::domain::traits::storage_traits::StorageManager;
+    use crate::infrastructure::storage::encrypted_storage_backend::{
+        EncryptedStorageManager, StaticKeyProvider,
+    };
+    use crate::infrastructure::storage::storage_backend::AsyncStorageManager;
+    use std::sync::Arc;
+
+    macro_rules! await_test {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    #[test]
+    fn test_encrypted_round_trip_and_append() {
+        let path = std::env::temp_dir()
+            .join(format!("strawberry_encrypted_storage_{}", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        let inner = Arc::new(AsyncStorageManager::new());
+        let manager =
+            EncryptedStorageManager::new(inner.clone(), Arc::new(StaticKeyProvider::new([7; 32])));
+
+        await_test!(async {
+            let token = b"secret token".to_vec();
+            manager.write(WriteFile::path(path.clone(), &token)).await.unwrap();
+            let on_disk = inner.read(ReadFile::path(path.clone())).await.unwrap();
+            assert_ne!(on_disk, token);
+
+            let suffix = b"!".to_vec();
+            let mut append = WriteFile::path(path.clone(), &suffix);
+            append.mode = WriteMode::Append;
+            manager.write(append).await.unwrap();
+
+            let plain = manager.read(ReadFile::path(path.clone())).await.unwrap();
+            assert_eq!(plain, b"secret token!");
+            let range = manager.read_range(path.clone(), 7, 5).await.unwrap();
+            assert_eq!(range, b"token");
+            let _ = tokio::fs::remove_file(&path).await;
+        });
+    }
+
+    // why the download manager writes below this layer, it resumes from the size on disk
+    #[test]
+    fn test_metadata_reports_the_sealed_size() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("sealed.part");
+        let path = path.to_string_lossy().to_string();
+        let manager = EncryptedStorageManager::new(
+            Arc::new(AsyncStorageManager::new()),
+            Arc::new(StaticKeyProvider::new([3; 32])),
+        );
+
+        await_test!(async {
+            let data = b"first chunk".to_vec();
+            manager.write(WriteFile::path(path.clone(), &data)).await.unwrap();
+            let metadata = manager.metadata(path.clone()).await.unwrap();
+            assert_ne!(metadata.size, data.len() as u64);
+        });
+    }
+
+    #[test]
+    fn test_encrypted_concurrent_appends_keep_every_line() {
+        let path = std::env::temp_dir()
+            .join(format!("strawberry_encrypted_append_{}", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        let manager = EncryptedStorageManager::new(
+            Arc::new(AsyncStorageManager::new()),
+            Arc::new(StaticKeyProvider::new([9; 32])),
+        );
+
+        await_test!(async {
+            let appends = (0..16)
+                .map(|i| manager.append_line(path.clone(), format!("line-{}", i).into_bytes()));
+            for result in futures_util::future::join_all(appends).await {
+                result.unwrap();
+            }
+
+            let lines = manager.read_lines(path.clone(), 0..usize::MAX).await.unwrap();
+            assert_eq!(lines.len(), 16);
+            let _ = tokio::fs::remove_file(&path).await;
+        });
+    }
+}
@@ -1 +1,4 @@
-pub mod storage_backend;
\ No newline at end of file
+pub mod storage_backend;
+pub mod encrypted_storage_backend;
+pub mod retrying_storage_backend;
+pub mod storage_watcher;
\ No newline at end of file
@@ -0,0 +1,234 @@
+use crate::domain::models::storage_models::{
+    DeleteFile, DirEntry, DiskUsage, FileHash, FileMetadata, HashAlgorithm, ReadFile, StorageError,
+    StorageEvent, StorageRetryPolicy, TransferFile, WriteFile, WriteMode,
+};
+use crate::domain::traits::storage_traits::{ProgressSink, StorageManager};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::stream::BoxStream;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+// retries operations that failed with StorageError::Transient, everything else passes through
+pub struct RetryingStorageManager {
+    inner: Arc<dyn StorageManager>,
+    policy: StorageRetryPolicy,
+}
+
+impl RetryingStorageManager {
+    pub fn new(inner: Arc<dyn StorageManager>, policy: StorageRetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    async fn retry<T, F, Fut>(&self, mut operation: F) -> Result<T, StorageError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, StorageError>>,
+    {
+        let max_attempts = self.policy.max_attempts.max(1);
+        let mut backoff = self.policy.initial_backoff;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match operation().await {
+                Err(StorageError::Transient(error)) => {
+                    if attempts >= max_attempts {
+                        if attempts == 1 {
+                            return Err(StorageError::Transient(error));
+                        }
+                        return Err(StorageError::RetriesExhausted { attempts, error });
+                    }
+                    tokio::time::sleep(backoff).await;
+                    backoff = backoff.saturating_mul(2).min(self.policy.max_backoff);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StorageManager for RetryingStorageManager {
+    async fn read(&self, request: ReadFile) -> Result<Vec<u8>, StorageError> {
+        self.retry(|| self.inner.read(request.clone())).await
+    }
+
+    async fn write<'a>(&self, request: WriteFile<'a>) -> Result<(), StorageError> {
+        // a partially applied append would be duplicated by another attempt
+        if request.mode == WriteMode::Append {
+            return self.inner.write(request).await;
+        }
+        self.retry(|| self.inner.write(request.clone())).await
+    }
+
+    async fn read_verified(
+        &self,
+        request: ReadFile,
+        expected: FileHash,
+    ) -> Result<Vec<u8>, StorageError> {
+        self.retry(|| self.inner.read_verified(request.clone(), expected.clone()))
+            .await
+    }
+
+    async fn write_hashed<'a>(
+        &self,
+        request: WriteFile<'a>,
+        algorithm: HashAlgorithm,
+    ) -> Result<FileHash, StorageError> {
+        if request.mode == WriteMode::Append {
+            return self.inner.write_hashed(request, algorithm).await;
+        }
+        self.retry(|| self.inner.write_hashed(request.clone(), algorithm))
+            .await
+    }
+
+    async fn delete(&self, request: DeleteFile) -> Result<(), StorageError> {
+        self.retry(|| self.inner.delete(request.clone())).await
+    }
+
+    async fn rename(&self, request: TransferFile) -> Result<(), StorageError> {
+        self.retry(|| self.inner.rename(request.clone())).await
+    }
+
+    async fn copy(&self, request: TransferFile) -> Result<(), StorageError> {
+        self.retry(|| self.inner.copy(request.clone())).await
+    }
+
+    // the sink is shared across attempts, a retried copy reports from zero again
+    async fn copy_with_progress(
+        &self,
+        request: TransferFile,
+        progress_sink: ProgressSink,
+    ) -> Result<(), StorageError> {
+        let progress_sink = Arc::new(progress_sink);
+        self.retry(|| {
+            let progress_sink = progress_sink.clone();
+            self.inner.copy_with_progress(
+                request.clone(),
+                Box::new(move |value, total, delta| progress_sink(value, total, delta)),
+            )
+        })
+        .await
+    }
+
+    async fn move_file(&self, request: TransferFile) -> Result<(), StorageError> {
+        self.retry(|| self.inner.move_file(request.clone())).await
+    }
+
+    async fn create_dir(&self, path: String, recursive: bool) -> Result<(), StorageError> {
+        self.retry(|| self.inner.create_dir(path.clone(), recursive))
+            .await
+    }
+
+    async fn list_dir(&self, path: String) -> Result<Vec<DirEntry>, StorageError> {
+        self.retry(|| self.inner.list_dir(path.clone())).await
+    }
+
+    async fn remove_dir(&self, path: String, recursive: bool) -> Result<(), StorageError> {
+        self.retry(|| self.inner.remove_dir(path.clone(), recursive))
+            .await
+    }
+
+    async fn read_range(
+        &self,
+        path: String,
+        offset: u64,
+        len: u64,
+    ) -> Result<Vec<u8>, StorageError> {
+        self.retry(|| self.inner.read_range(path.clone(), offset, len))
+            .await
+    }
+
+    // only opening the file is retried, errors inside the stream are passed on
+    async fn read_stream(
+        &self,
+        path: String,
+        chunk_size: usize,
+    ) -> Result<BoxStream<'static, Result<Bytes, StorageError>>, StorageError> {
+        self.retry(|| self.inner.read_stream(path.clone(), chunk_size))
+            .await
+    }
+
+    // a consumed stream cannot be replayed
+    async fn write_stream(
+        &self,
+        path: String,
+        stream: BoxStream<'static, Result<Bytes, StorageError>>,
+    ) -> Result<u64, StorageError> {
+        self.inner.write_stream(path, stream).await
+    }
+
+    // not retried for the same reason as appending writes
+    async fn append_line(&self, path: String, line: Vec<u8>) -> Result<(), StorageError> {
+        self.inner.append_line(path, line).await
+    }
+
+    async fn read_lines(
+        &self,
+        path: String,
+        range: Range<usize>,
+    ) -> Result<Vec<Vec<u8>>, StorageError> {
+        self.retry(|| self.inner.read_lines(path.clone(), range.clone()))
+            .await
+    }
+
+    async fn exists(&self, path: String) -> Result<bool, StorageError> {
+        self.retry(|| self.inner.exists(path.clone())).await
+    }
+
+    async fn resolve_path(&self, path: String) -> Result<String, StorageError> {
+        self.retry(|| self.inner.resolve_path(path.clone())).await
+    }
+
+    async fn metadata(&self, path: String) -> Result<FileMetadata, StorageError> {
+        self.retry(|| self.inner.metadata(path.clone())).await
+    }
+
+    async fn create_temp_file(
+        &self,
+        prefix: String,
+        extension: Option<String>,
+    ) -> Result<String, StorageError> {
+        self.retry(|| {
+            self.inner
+                .create_temp_file(prefix.clone(), extension.clone())
+        })
+        .await
+    }
+
+    async fn unique_path(
+        &self,
+        dir: String,
+        base: String,
+        extension: Option<String>,
+    ) -> Result<String, StorageError> {
+        self.retry(|| {
+            self.inner
+                .unique_path(dir.clone(), base.clone(), extension.clone())
+        })
+        .await
+    }
+
+    async fn cleanup_temp_files(&self, older_than: Duration) -> Result<u64, StorageError> {
+        self.retry(|| self.inner.cleanup_temp_files(older_than))
+            .await
+    }
+
+    async fn disk_usage(&self, path: String) -> Result<DiskUsage, StorageError> {
+        self.retry(|| self.inner.disk_usage(path.clone())).await
+    }
+
+    async fn dir_size(&self, path: String) -> Result<u64, StorageError> {
+        self.retry(|| self.inner.dir_size(path.clone())).await
+    }
+
+    async fn watch(
+        &self,
+        path: String,
+        debounce: Duration,
+    ) -> Result<BoxStream<'static, StorageEvent>, StorageError> {
+        self.retry(|| self.inner.watch(path.clone(), debounce))
+            .await
+    }
+}
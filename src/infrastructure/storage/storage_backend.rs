@@ -1,27 +1,203 @@
 use std::sync::Arc;
+use crate::domain::models::file_cache_models::CompressionKind;
 use crate::domain::models::storage_models::{
-    EnsureMode, ReadFile, StorageError, WriteFile, WriteMode,
+    DeleteFile, DirEntry, DiskUsage, EnsureMode, FileHash, FileMetadata, HashAlgorithm, ReadFile,
+    StorageError, StorageEvent, TransferFile, WriteFile, WriteMode,
 };
-use crate::domain::traits::storage_traits::StorageManager;
+use crate::domain::traits::storage_traits::{ProgressSink, StorageManager};
+use crate::infrastructure::storage::storage_watcher::watch_path;
+use crate::utils::compression::{compress, decompress};
 use crate::utils::keyed_rw_lock::KeyedRwLock;
 use async_trait::async_trait;
-use tokio::fs::{OpenOptions, read, try_exists};
-use tokio::io::AsyncWriteExt;
+use bytes::Bytes;
+use futures_util::{StreamExt, TryStreamExt};
+use futures_util::stream::BoxStream;
+use sha2::{Digest, Sha256};
+use std::io::{ErrorKind, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::fs::{
+    File, OpenOptions, copy, create_dir, create_dir_all, metadata, read, read_dir, remove_dir,
+    remove_dir_all, remove_file, rename, symlink_metadata, try_exists,
+};
+use tokio::io::{
+    AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader, BufWriter,
+};
 use tokio::time::timeout;
-use crate::domain::models::monitor_models::{EventStage, MonitorEvent, MonitorHttpData, MonitorStorageData, Progress};
+use tokio_util::io::ReaderStream;
+use uuid::Uuid;
+use xxhash_rust::xxh3::xxh3_64;
+use crate::domain::models::monitor_models::{EventStage, MonitorEvent, MonitorStorageData, Progress};
 use crate::domain::traits::monitor_traits::Monitor;
+use crate::monitor::metrics_service::metrics;
 use crate::monitor::monitor_service::monitoring;
 
+// for the operations whose request carries no timeout, the same as the request defaults
+const OPERATION_TIMEOUT: Duration = Duration::from_secs(60);
+
 macro_rules! match_timeout {
     ( $x:expr, $y:expr ) => {{
         match timeout($x, $y).await {
             Ok(Ok(())) => Ok(()),
-            Ok(Err(e)) => Err(StorageError::IOError(e.to_string())),
+            Ok(Err(e)) => Err(io_error(e)),
             Err(timeout) => Err(StorageError::Timeout(timeout.to_string())),
         }
     }};
 }
 
+// errors worth retrying, a busy or briefly locked file rather than a real failure
+fn is_transient(e: &std::io::Error) -> bool {
+    if matches!(
+        e.kind(),
+        ErrorKind::ResourceBusy | ErrorKind::Interrupted | ErrorKind::WouldBlock
+    ) {
+        return true;
+    }
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION, raised while a scanner holds the file
+    #[cfg(windows)]
+    if matches!(e.raw_os_error(), Some(32) | Some(33)) {
+        return true;
+    }
+    // media scanners briefly revoke access to files they are indexing
+    #[cfg(target_os = "android")]
+    if e.kind() == ErrorKind::PermissionDenied {
+        return true;
+    }
+    false
+}
+
+pub(crate) fn io_error(e: std::io::Error) -> StorageError {
+    if is_transient(&e) {
+        return StorageError::Transient(e.to_string());
+    }
+    StorageError::IOError(e.to_string())
+}
+
+fn map_io_error(path: &str, e: std::io::Error) -> StorageError {
+    match e.kind() {
+        ErrorKind::NotFound => StorageError::NotExist(path.to_string()),
+        _ => io_error(e),
+    }
+}
+
+const COMPRESSION_MAGIC: &[u8; 4] = b"\x89SBZ";
+
+// magic followed by one byte naming the codec
+pub(crate) async fn encode_compressed(
+    compression: &CompressionKind,
+    data: &[u8],
+) -> Result<Vec<u8>, StorageError> {
+    let kind = match compression {
+        CompressionKind::Zstd => 0u8,
+        CompressionKind::Gzip => 1u8,
+    };
+    let compressed = compress(compression, data)
+        .await
+        .map_err(io_error)?;
+
+    let mut encoded = COMPRESSION_MAGIC.to_vec();
+    encoded.push(kind);
+    encoded.extend_from_slice(&compressed);
+    Ok(encoded)
+}
+
+// data without the header is returned untouched
+pub(crate) async fn decode_compressed(data: Vec<u8>) -> Result<Vec<u8>, StorageError> {
+    if data.len() <= COMPRESSION_MAGIC.len() || !data.starts_with(COMPRESSION_MAGIC) {
+        return Ok(data);
+    }
+    let compression = match data[COMPRESSION_MAGIC.len()] {
+        0 => CompressionKind::Zstd,
+        1 => CompressionKind::Gzip,
+        _ => return Ok(data),
+    };
+    decompress(&compression, &data[COMPRESSION_MAGIC.len() + 1..])
+        .await
+        .map_err(io_error)
+}
+
+pub(crate) fn hash(algorithm: HashAlgorithm, data: &[u8]) -> FileHash {
+    match algorithm {
+        HashAlgorithm::Xxh3 => FileHash::Xxh3(xxh3_64(data)),
+        HashAlgorithm::Sha256 => FileHash::Sha256(Sha256::digest(data).into()),
+    }
+}
+
+// mirrors read_lines, a final newline does not start another line
+pub(crate) fn split_lines(data: &[u8], range: Range<usize>) -> Vec<Vec<u8>> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+    let data = data.strip_suffix(b"\n").unwrap_or(data);
+    data.split(|byte| *byte == b'\n')
+        .skip(range.start)
+        .take(range.len())
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line).to_vec())
+        .collect()
+}
+
+fn epoch_millis(time: std::io::Result<SystemTime>) -> u64 {
+    time.ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+pub(crate) async fn write_atomically(
+    path: &String,
+    data: &[u8],
+    timeout_duration: Duration,
+) -> Result<(), StorageError> {
+    let target = Path::new(path);
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| StorageError::FileRequired(path.clone()))?
+        .to_string_lossy()
+        .to_string();
+    let temp = target.with_file_name(format!(".{}.{}.tmp", file_name, Uuid::new_v4()));
+
+    let result = async {
+        let mut file = File::create(&temp)
+            .await
+            .map_err(io_error)?;
+        match_timeout!(timeout_duration, file.write_all(data))?;
+        match_timeout!(timeout_duration, file.sync_all())?;
+        drop(file);
+
+        rename(&temp, target)
+            .await
+            .map_err(io_error)?;
+        sync_parent(target).await
+    }
+    .await;
+    if result.is_err() {
+        let _ = remove_file(&temp).await;
+    }
+    result
+}
+
+// makes the rename itself durable, directories cannot be opened for syncing on windows
+#[cfg(unix)]
+async fn sync_parent(target: &Path) -> Result<(), StorageError> {
+    let parent = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let directory = File::open(parent)
+        .await
+        .map_err(io_error)?;
+    directory
+        .sync_all()
+        .await
+        .map_err(io_error)
+}
+
+#[cfg(not(unix))]
+async fn sync_parent(_target: &Path) -> Result<(), StorageError> {
+    Ok(())
+}
+
 fn send_monitor_event(
     monitor: Arc<dyn Monitor>,
     path: &String,
@@ -29,8 +205,7 @@ fn send_monitor_event(
     progress_values: Option<(u64, u64, u64)>,
 ) {
     let mut progress_option: Option<Progress> = None;
-    if progress_values.is_some() {
-        let values = progress_values.unwrap();
+    if let Some(values) = progress_values {
         progress_option = Some(Progress {
             value: values.0,
             total: values.1,
@@ -48,23 +223,201 @@ fn send_monitor_event(
 
 pub struct AsyncStorageManager {
     keys: KeyedRwLock<()>,
+    temp_root: PathBuf,
+    // canonical roots every path must stay inside, unrestricted when empty
+    allowed_roots: Vec<PathBuf>,
+}
+
+// the same as resolve does for a path, a root that does not exist yet is compared by the
+// canonical form of its deepest existing ancestor
+fn canonical_root(root: &Path) -> PathBuf {
+    let mut existing = std::path::absolute(root).unwrap_or_else(|_| root.to_path_buf());
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        let Some(name) = existing.file_name() else {
+            break;
+        };
+        missing.push(name.to_os_string());
+        existing.pop();
+    }
+    let mut resolved = std::fs::canonicalize(&existing).unwrap_or(existing);
+    for name in missing.into_iter().rev() {
+        resolved.push(name);
+    }
+    resolved
+}
+
+impl Default for AsyncStorageManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AsyncStorageManager {
     pub fn new() -> Self {
+        Self::with_temp_root(std::env::temp_dir().join("strawberry_background"))
+    }
+
+    pub fn with_temp_root(temp_root: PathBuf) -> Self {
         Self {
             keys: KeyedRwLock::new(),
+            temp_root,
+            allowed_roots: Vec::new(),
+        }
+    }
+
+    // the temp root is always allowed so temp files stay usable
+    pub fn with_allowed_roots(mut self, allowed_roots: Vec<PathBuf>) -> Self {
+        if allowed_roots.is_empty() {
+            return self;
+        }
+        self.allowed_roots = allowed_roots.iter().map(|root| canonical_root(root)).collect();
+        self.allowed_roots.push(canonical_root(&self.temp_root));
+        self
+    }
+
+    // canonicalizes the deepest existing ancestor, so symbolic links and ".." cannot escape
+    async fn resolve(&self, path: &String) -> Result<String, StorageError> {
+        if self.allowed_roots.is_empty() {
+            return Ok(path.clone());
+        }
+        let denied = || StorageError::AccessDenied(path.clone());
+
+        let mut existing = PathBuf::from(path);
+        if existing.is_relative() {
+            let current = std::env::current_dir()
+                .map_err(io_error)?;
+            existing = current.join(existing);
+        }
+        let mut missing = Vec::new();
+        while !try_exists(&existing).await.unwrap_or(false) {
+            let name = existing.file_name().ok_or_else(denied)?.to_os_string();
+            missing.push(name);
+            if !existing.pop() {
+                return Err(denied());
+            }
         }
+
+        let mut resolved = tokio::fs::canonicalize(&existing)
+            .await
+            .map_err(|e| map_io_error(path, e))?;
+        for name in missing.into_iter().rev() {
+            resolved.push(name);
+        }
+        if !self.allowed_roots.iter().any(|root| resolved.starts_with(root)) {
+            return Err(denied());
+        }
+        Ok(resolved.to_string_lossy().to_string())
+    }
+
+    async fn ensure_exists(&self, path: &String) -> Result<(), StorageError> {
+        let exists = try_exists(path)
+            .await
+            .map_err(io_error)?;
+        if !exists {
+            return Err(StorageError::NotExist(path.clone()));
+        }
+        Ok(())
+    }
+
+    async fn ensure_dir(&self, path: &String) -> Result<(), StorageError> {
+        let metadata = metadata(path).await.map_err(|e| map_io_error(path, e))?;
+        if !metadata.is_dir() {
+            return Err(StorageError::DirectoryRequired(path.clone()));
+        }
+        Ok(())
+    }
+
+    // both paths are write-locked in a stable order so opposite transfers cannot deadlock
+    async fn write_pair<F, Fut>(
+        &self,
+        from: &String,
+        to: &String,
+        operation: F,
+    ) -> Result<(), StorageError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(), StorageError>>,
+    {
+        if from == to {
+            return self.keys.write(from, |_| operation()).await.await;
+        }
+        let (first, second) = if from < to { (from, to) } else { (to, from) };
+        self.keys
+            .write(first, |_| async {
+                self.keys.write(second, |_| operation()).await.await
+            })
+            .await
+            .await
+    }
+
+    async fn transfer(
+        &self,
+        request: TransferFile,
+        fallback_copy: bool,
+        keep_source: bool,
+    ) -> Result<(), StorageError> {
+        let from = self.resolve(&request.from).await?;
+        let to = self.resolve(&request.to).await?;
+
+        monitoring(|monitor| {
+            send_monitor_event(monitor, &from, EventStage::Started, None);
+        });
+
+        let result = match self.ensure_exists(&from).await {
+            Ok(()) => {
+                self.write_pair(&from, &to, || async {
+                    if from == to {
+                        return Ok(());
+                    }
+                    if !keep_source {
+                        match timeout(request.timeout, rename(&from, &to)).await {
+                            Ok(Ok(())) => return Ok(()),
+                            Ok(Err(e)) => {
+                                if !fallback_copy || e.kind() != ErrorKind::CrossesDevices {
+                                    return Err(io_error(e));
+                                }
+                            }
+                            Err(timeout) => {
+                                return Err(StorageError::Timeout(timeout.to_string()));
+                            }
+                        }
+                    }
+
+                    match timeout(request.timeout, copy(&from, &to)).await {
+                        Ok(Ok(_)) => {}
+                        Ok(Err(e)) => return Err(io_error(e)),
+                        Err(timeout) => return Err(StorageError::Timeout(timeout.to_string())),
+                    }
+                    if keep_source {
+                        return Ok(());
+                    }
+                    match_timeout!(request.timeout, remove_file(&from))
+                })
+                .await
+            }
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(()) => monitoring(|monitor| {
+                send_monitor_event(monitor, &from, EventStage::Finished, None);
+            }),
+            Err(_) => monitoring(|monitor| {
+                send_monitor_event(monitor, &from, EventStage::Failed, None);
+            }),
+        }
+        result
     }
 }
 
 #[async_trait]
 impl StorageManager for AsyncStorageManager {
     async fn read(&self, request: ReadFile) -> Result<Vec<u8>, StorageError> {
-        let path = request.path;
+        let path = self.resolve(&request.path).await?;
         let exists = try_exists(&path)
             .await
-            .map_err(|e| StorageError::IOError(e.to_string()))?;
+            .map_err(io_error)?;
         
         monitoring(|monitor| {
             send_monitor_event(monitor, &path, EventStage::Started, None);
@@ -80,27 +433,34 @@ impl StorageManager for AsyncStorageManager {
         self.keys
             .read(&path, |_| async {
                 match timeout(request.timeout, read(path.clone())).await {
-                    Ok(Ok(data)) => Ok(data),
-                    Ok(Err(e)) => Err(StorageError::IOError(e.to_string())),
+                    Ok(Ok(data)) => decode_compressed(data).await,
+                    Ok(Err(e)) => Err(io_error(e)),
                     Err(timeout) => Err(StorageError::Timeout(timeout.to_string())),
                 }
             })
             .await
             .await
-            .inspect(|_| {
+            .inspect(|data| {
                 monitoring(|monitor| {
                     send_monitor_event(monitor, &path, EventStage::Finished, None);
-                })
+                });
+                metrics(|registry| {
+                    registry.increment_counter("storage.bytes_read", &[], data.len() as u64)
+                });
             })
-            .inspect_err(|e| {
+            .inspect_err(|_e| {
                 monitoring(|monitor| {
                     send_monitor_event(monitor, &path, EventStage::Failed, None);
-                })
+                });
+                metrics(|registry| {
+                    registry.increment_counter("storage.errors", &[("op", "read")], 1)
+                });
             })
     }
 
     async fn write<'a>(&self, request: WriteFile<'a>) -> Result<(), StorageError> {
-        let path = request.path;
+        let path = self.resolve(&request.path).await?;
+        let size = request.data.len() as u64;
         
         monitoring(|monitor| {
             send_monitor_event(monitor, &path, EventStage::Started, None);
@@ -108,18 +468,38 @@ impl StorageManager for AsyncStorageManager {
         
         self.keys
             .write(&path.clone(), |_| async {
+                let encoded;
+                let data = match &request.compression {
+                    Some(_) if request.mode == WriteMode::Append => {
+                        return Err(StorageError::IOError(
+                            "compressed data cannot be appended".to_string(),
+                        ));
+                    }
+                    Some(compression) => {
+                        encoded = encode_compressed(compression, request.data).await?;
+                        &encoded
+                    }
+                    None => request.data,
+                };
+
+                if request.mode == WriteMode::Atomic {
+                    return write_atomically(&path, data, request.timeout).await;
+                }
+
                 let mut file = OpenOptions::new()
                     .create(true)
                     .append(request.mode == WriteMode::Append)
-                    .write(request.mode == WriteMode::Cover)
+                    .write(request.mode != WriteMode::Append)
+                    // a compressed frame covering a longer file would keep the old tail after it
+                    .truncate(request.mode == WriteMode::Truncate || request.compression.is_some())
                     .open(path.clone())
                     .await
-                    .map_err(|e| StorageError::IOError(e.to_string()))?;
+                    .map_err(io_error)?;
 
-                return match timeout(request.timeout, file.write_all(request.data)).await {
+                return match timeout(request.timeout, file.write_all(data)).await {
                     Ok(Ok(())) => {
-                        if request.ensure_mode.is_some() {
-                            return match request.ensure_mode.unwrap() {
+                        if let Some(ensure_mode) = request.ensure_mode {
+                            return match ensure_mode {
                                 EnsureMode::Flush => {
                                     match_timeout!(request.timeout, file.flush())
                                 }
@@ -133,9 +513,473 @@ impl StorageManager for AsyncStorageManager {
                         }
                         Ok(())
                     }
-                    Ok(Err(e)) => Err(StorageError::IOError(e.to_string())),
+                    Ok(Err(e)) => Err(io_error(e)),
+                    Err(timeout) => Err(StorageError::Timeout(timeout.to_string())),
+                };
+            })
+            .await
+            .await
+            .inspect(|_| {
+                monitoring(|monitor| {
+                    send_monitor_event(monitor, &path, EventStage::Finished, None);
+                });
+                metrics(|registry| registry.increment_counter("storage.bytes_written", &[], size));
+            })
+            .inspect_err(|_e| {
+                monitoring(|monitor| {
+                    send_monitor_event(monitor, &path, EventStage::Failed, None);
+                });
+                metrics(|registry| {
+                    registry.increment_counter("storage.errors", &[("op", "write")], 1)
+                });
+            })
+    }
+
+    async fn read_verified(
+        &self,
+        request: ReadFile,
+        expected: FileHash,
+    ) -> Result<Vec<u8>, StorageError> {
+        let path = request.path.clone();
+        let data = self.read(request).await?;
+        let actual = hash(expected.algorithm(), &data);
+        if actual != expected {
+            return Err(StorageError::ChecksumMismatch(format!(
+                "{} expected {} but was {}",
+                path,
+                expected.to_hex(),
+                actual.to_hex()
+            )));
+        }
+        Ok(data)
+    }
+
+    async fn write_hashed<'a>(
+        &self,
+        request: WriteFile<'a>,
+        algorithm: HashAlgorithm,
+    ) -> Result<FileHash, StorageError> {
+        let file_hash = hash(algorithm, request.data);
+        self.write(request).await?;
+        Ok(file_hash)
+    }
+
+    async fn delete(&self, request: DeleteFile) -> Result<(), StorageError> {
+        let path = self.resolve(&request.path).await?;
+
+        monitoring(|monitor| {
+            send_monitor_event(monitor, &path, EventStage::Started, None);
+        });
+
+        if let Err(e) = self.ensure_exists(&path).await {
+            monitoring(|monitor| {
+                send_monitor_event(monitor, &path, EventStage::Failed, None);
+            });
+            return Err(e);
+        }
+
+        self.keys
+            .write(&path.clone(), |_| async {
+                match_timeout!(request.timeout, remove_file(path.clone()))
+            })
+            .await
+            .await
+            .inspect(|_| {
+                monitoring(|monitor| {
+                    send_monitor_event(monitor, &path, EventStage::Finished, None);
+                })
+            })
+            .inspect_err(|_| {
+                monitoring(|monitor| {
+                    send_monitor_event(monitor, &path, EventStage::Failed, None);
+                })
+            })
+    }
+
+    async fn rename(&self, request: TransferFile) -> Result<(), StorageError> {
+        self.transfer(request, false, false).await
+    }
+
+    async fn copy(&self, request: TransferFile) -> Result<(), StorageError> {
+        self.transfer(request, false, true).await
+    }
+
+    async fn copy_with_progress(
+        &self,
+        request: TransferFile,
+        progress_sink: ProgressSink,
+    ) -> Result<(), StorageError> {
+        let from = self.resolve(&request.from).await?;
+        let to = self.resolve(&request.to).await?;
+        if from == to {
+            return self.ensure_exists(&from).await;
+        }
+
+        monitoring(|monitor| {
+            send_monitor_event(monitor, &from, EventStage::Started, None);
+        });
+
+        let result = self
+            .write_pair(&from, &to, || async {
+                let mut source = File::open(&from).await.map_err(|e| map_io_error(&from, e))?;
+                let total = source
+                    .metadata()
+                    .await
+                    .map_err(io_error)?
+                    .len();
+                let mut target = File::create(&to)
+                    .await
+                    .map_err(io_error)?;
+
+                let mut buffer = vec![0u8; 256 * 1024];
+                let mut copied = 0u64;
+                loop {
+                    let read = match timeout(request.timeout, source.read(&mut buffer)).await {
+                        Ok(Ok(0)) => break,
+                        Ok(Ok(read)) => read,
+                        Ok(Err(e)) => return Err(io_error(e)),
+                        Err(timeout) => return Err(StorageError::Timeout(timeout.to_string())),
+                    };
+                    match_timeout!(request.timeout, target.write_all(&buffer[..read]))?;
+
+                    let delta = read as u64;
+                    copied += delta;
+                    progress_sink(copied, total, delta);
+                    monitoring(|monitor| {
+                        send_monitor_event(
+                            monitor,
+                            &from,
+                            EventStage::Running,
+                            Some((copied, total, delta)),
+                        );
+                    });
+                }
+                match_timeout!(request.timeout, target.sync_all())
+            })
+            .await;
+
+        match result {
+            Ok(()) => monitoring(|monitor| {
+                send_monitor_event(monitor, &from, EventStage::Finished, None);
+            }),
+            Err(_) => {
+                let _ = remove_file(&to).await;
+                monitoring(|monitor| {
+                    send_monitor_event(monitor, &from, EventStage::Failed, None);
+                })
+            }
+        }
+        result
+    }
+
+    async fn move_file(&self, request: TransferFile) -> Result<(), StorageError> {
+        self.transfer(request, true, false).await
+    }
+
+    async fn create_dir(&self, path: String, recursive: bool) -> Result<(), StorageError> {
+        let path = self.resolve(&path).await?;
+        self.keys
+            .write(&path.clone(), |_| async {
+                if recursive {
+                    create_dir_all(&path).await
+                } else {
+                    create_dir(&path).await
+                }
+                .map_err(io_error)
+            })
+            .await
+            .await
+    }
+
+    async fn list_dir(&self, path: String) -> Result<Vec<DirEntry>, StorageError> {
+        let path = self.resolve(&path).await?;
+        self.ensure_dir(&path).await?;
+
+        self.keys
+            .read(&path.clone(), |_| async {
+                let mut reader = read_dir(&path)
+                    .await
+                    .map_err(io_error)?;
+                let mut entries = Vec::new();
+                while let Some(entry) = reader
+                    .next_entry()
+                    .await
+                    .map_err(io_error)?
+                {
+                    let metadata = entry
+                        .metadata()
+                        .await
+                        .map_err(io_error)?;
+                    entries.push(DirEntry {
+                        name: entry.file_name().to_string_lossy().to_string(),
+                        size: metadata.len(),
+                        modified: epoch_millis(metadata.modified()),
+                        is_dir: metadata.is_dir(),
+                    });
+                }
+                Ok(entries)
+            })
+            .await
+            .await
+    }
+
+    async fn remove_dir(&self, path: String, recursive: bool) -> Result<(), StorageError> {
+        let path = self.resolve(&path).await?;
+        self.ensure_dir(&path).await?;
+
+        self.keys
+            .write(&path.clone(), |_| async {
+                if recursive {
+                    remove_dir_all(&path).await
+                } else {
+                    remove_dir(&path).await
+                }
+                .map_err(io_error)
+            })
+            .await
+            .await
+    }
+
+    async fn exists(&self, path: String) -> Result<bool, StorageError> {
+        let path = self.resolve(&path).await?;
+        try_exists(&path)
+            .await
+            .map_err(io_error)
+    }
+
+    async fn resolve_path(&self, path: String) -> Result<String, StorageError> {
+        self.resolve(&path).await
+    }
+
+    async fn metadata(&self, path: String) -> Result<FileMetadata, StorageError> {
+        let path = self.resolve(&path).await?;
+        let metadata = metadata(&path).await.map_err(|e| map_io_error(&path, e))?;
+        Ok(FileMetadata {
+            size: metadata.len(),
+            modified: epoch_millis(metadata.modified()),
+            created: epoch_millis(metadata.created()),
+            readonly: metadata.permissions().readonly(),
+            is_dir: metadata.is_dir(),
+        })
+    }
+
+    async fn create_temp_file(
+        &self,
+        prefix: String,
+        extension: Option<String>,
+    ) -> Result<String, StorageError> {
+        create_dir_all(&self.temp_root)
+            .await
+            .map_err(io_error)?;
+
+        let file_name = match extension {
+            Some(extension) => format!("{}{}.{}", prefix, Uuid::new_v4(), extension),
+            None => format!("{}{}", prefix, Uuid::new_v4()),
+        };
+        let path = self.temp_root.join(file_name);
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .await
+            .map_err(io_error)?;
+        Ok(path.to_string_lossy().to_string())
+    }
+
+    async fn unique_path(
+        &self,
+        dir: String,
+        base: String,
+        extension: Option<String>,
+    ) -> Result<String, StorageError> {
+        let dir = self.resolve(&dir).await?;
+        self.ensure_dir(&dir).await?;
+
+        let file_name = |suffix: String| match &extension {
+            Some(extension) => format!("{}{}.{}", base, suffix, extension),
+            None => format!("{}{}", base, suffix),
+        };
+        let mut attempt = 0u64;
+        loop {
+            let suffix = if attempt == 0 {
+                String::new()
+            } else {
+                format!(" ({})", attempt)
+            };
+            let candidate = Path::new(&dir).join(file_name(suffix));
+            let exists = try_exists(&candidate)
+                .await
+                .map_err(io_error)?;
+            if !exists {
+                return Ok(candidate.to_string_lossy().to_string());
+            }
+            attempt += 1;
+        }
+    }
+
+    async fn cleanup_temp_files(&self, older_than: Duration) -> Result<u64, StorageError> {
+        let exists = try_exists(&self.temp_root)
+            .await
+            .map_err(io_error)?;
+        if !exists {
+            return Ok(0);
+        }
+
+        let threshold = SystemTime::now()
+            .checked_sub(older_than)
+            .unwrap_or(UNIX_EPOCH);
+        let mut reader = read_dir(&self.temp_root)
+            .await
+            .map_err(io_error)?;
+        let mut removed = 0u64;
+        while let Some(entry) = reader
+            .next_entry()
+            .await
+            .map_err(io_error)?
+        {
+            let Ok(metadata) = entry.metadata().await else {
+                continue;
+            };
+            let expired = metadata
+                .modified()
+                .map(|modified| modified < threshold)
+                .unwrap_or(false);
+            if metadata.is_file() && expired && remove_file(entry.path()).await.is_ok() {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn disk_usage(&self, path: String) -> Result<DiskUsage, StorageError> {
+        let path = self.resolve(&path).await?;
+        self.ensure_exists(&path).await?;
+        let stats = tokio::task::spawn_blocking(move || fs4::statvfs(&path))
+            .await
+            .map_err(|e| StorageError::IOError(e.to_string()))?
+            .map_err(io_error)?;
+        Ok(DiskUsage {
+            total: stats.total_space(),
+            free: stats.free_space(),
+            available: stats.available_space(),
+        })
+    }
+
+    async fn dir_size(&self, path: String) -> Result<u64, StorageError> {
+        let path = self.resolve(&path).await?;
+        self.ensure_dir(&path).await?;
+
+        let mut size = 0u64;
+        let mut directories = vec![path];
+        while let Some(directory) = directories.pop() {
+            let mut reader = read_dir(&directory)
+                .await
+                .map_err(|e| map_io_error(&directory, e))?;
+            while let Some(entry) = reader
+                .next_entry()
+                .await
+                .map_err(io_error)?
+            {
+                let metadata = symlink_metadata(entry.path())
+                    .await
+                    .map_err(io_error)?;
+                if metadata.is_dir() {
+                    directories.push(entry.path().to_string_lossy().to_string());
+                } else {
+                    size += metadata.len();
+                }
+            }
+        }
+        Ok(size)
+    }
+
+    async fn watch(
+        &self,
+        path: String,
+        debounce: Duration,
+    ) -> Result<BoxStream<'static, StorageEvent>, StorageError> {
+        let path = self.resolve(&path).await?;
+        watch_path(&path, debounce)
+    }
+
+    async fn read_range(
+        &self,
+        path: String,
+        offset: u64,
+        len: u64,
+    ) -> Result<Vec<u8>, StorageError> {
+        let path = self.resolve(&path).await?;
+        self.keys
+            .read(&path.clone(), |_| async {
+                let read_range = async {
+                    let mut file =
+                        File::open(&path).await.map_err(|e| map_io_error(&path, e))?;
+                    file.seek(SeekFrom::Start(offset))
+                        .await
+                        .map_err(io_error)?;
+                    let mut data = Vec::new();
+                    file.take(len)
+                        .read_to_end(&mut data)
+                        .await
+                        .map_err(io_error)?;
+                    Ok(data)
+                };
+                match timeout(OPERATION_TIMEOUT, read_range).await {
+                    Ok(result) => result,
                     Err(timeout) => Err(StorageError::Timeout(timeout.to_string())),
+                }
+            })
+            .await
+            .await
+    }
+
+    async fn read_stream(
+        &self,
+        path: String,
+        chunk_size: usize,
+    ) -> Result<BoxStream<'static, Result<Bytes, StorageError>>, StorageError> {
+        let path = self.resolve(&path).await?;
+        // held until the stream is dropped, so no write changes the file under the reader
+        let guard = self.keys.read_owned(&path).await;
+        let file = match timeout(OPERATION_TIMEOUT, File::open(&path)).await {
+            Ok(file) => file.map_err(|e| map_io_error(&path, e))?,
+            Err(timeout) => return Err(StorageError::Timeout(timeout.to_string())),
+        };
+
+        let stream = ReaderStream::with_capacity(file, chunk_size.max(1))
+            .map_err(io_error)
+            .map(move |chunk| {
+                let _guard = &guard;
+                chunk
+            });
+        Ok(stream.boxed())
+    }
+
+    async fn write_stream(
+        &self,
+        path: String,
+        mut stream: BoxStream<'static, Result<Bytes, StorageError>>,
+    ) -> Result<u64, StorageError> {
+        let path = self.resolve(&path).await?;
+        monitoring(|monitor| {
+            send_monitor_event(monitor, &path, EventStage::Started, None);
+        });
+
+        self.keys
+            .write(&path.clone(), |_| async {
+                // each file operation is timed, not the wait for the next chunk
+                let mut file = match timeout(OPERATION_TIMEOUT, File::create(&path)).await {
+                    Ok(file) => file.map_err(io_error)?,
+                    Err(timeout) => return Err(StorageError::Timeout(timeout.to_string())),
                 };
+                let mut written = 0u64;
+                while let Some(chunk) = stream.next().await {
+                    let chunk = chunk?;
+                    match_timeout!(OPERATION_TIMEOUT, file.write_all(&chunk))?;
+                    written += chunk.len() as u64;
+                }
+                match_timeout!(OPERATION_TIMEOUT, file.flush())?;
+                Ok(written)
             })
             .await
             .await
@@ -144,10 +988,280 @@ impl StorageManager for AsyncStorageManager {
                     send_monitor_event(monitor, &path, EventStage::Finished, None);
                 })
             })
-            .inspect_err(|e| {
+            .inspect_err(|_| {
                 monitoring(|monitor| {
                     send_monitor_event(monitor, &path, EventStage::Failed, None);
                 })
             })
     }
+
+    async fn append_line(&self, path: String, line: Vec<u8>) -> Result<(), StorageError> {
+        let path = self.resolve(&path).await?;
+        self.keys
+            .write(&path.clone(), |_| async {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .await
+                    .map_err(io_error)?;
+                // one write for the line and its newline instead of two
+                let mut writer = BufWriter::new(file);
+                writer.write_all(&line).await.map_err(io_error)?;
+                if !line.ends_with(b"\n") {
+                    writer.write_all(b"\n").await.map_err(io_error)?;
+                }
+                writer.flush().await.map_err(io_error)
+            })
+            .await
+            .await
+    }
+
+    async fn read_lines(
+        &self,
+        path: String,
+        range: Range<usize>,
+    ) -> Result<Vec<Vec<u8>>, StorageError> {
+        let path = self.resolve(&path).await?;
+        self.keys
+            .read(&path.clone(), |_| async {
+                let file = File::open(&path).await.map_err(|e| map_io_error(&path, e))?;
+                let mut segments = BufReader::new(file).split(b'\n');
+                let mut lines = Vec::new();
+                let mut index = 0;
+                // stops reading once the range is filled
+                while index < range.end {
+                    let Some(mut line) = segments.next_segment().await.map_err(io_error)? else {
+                        break;
+                    };
+                    if index >= range.start {
+                        if line.last() == Some(&b'\r') {
+                            line.pop();
+                        }
+                        lines.push(line);
+                    }
+                    index += 1;
+                }
+                Ok(lines)
+            })
+            .await
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::file_cache_models::CompressionKind;
+    use crate::domain::models::storage_models::{
+        FileHash, HashAlgorithm, ReadFile, StorageError, WriteFile, WriteMode,
+    };
+    use crate::domain::traits::storage_traits::StorageManager;
+    use crate::infrastructure::storage::storage_backend::AsyncStorageManager;
+    use bytes::Bytes;
+    use futures_util::TryStreamExt;
+    use std::time::Duration;
+
+    macro_rules! await_test {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("strawberry_storage_{}_{}", name, std::process::id()))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    async fn write(manager: &AsyncStorageManager, path: &str, data: &str, mode: WriteMode) {
+        let data = data.as_bytes().to_vec();
+        let mut request = WriteFile::path(path.to_string(), &data);
+        request.mode = mode;
+        manager.write(request).await.unwrap();
+    }
+
+    #[test]
+    fn test_append_creates_and_appends() {
+        let path = temp_path("append");
+        let manager = AsyncStorageManager::new();
+
+        await_test!(async {
+            let _ = tokio::fs::remove_file(&path).await;
+            write(&manager, &path, "hello", WriteMode::Append).await;
+            write(&manager, &path, " world", WriteMode::Append).await;
+
+            let data = manager.read(ReadFile::path(path.clone())).await.unwrap();
+            assert_eq!(data, b"hello world");
+            let _ = tokio::fs::remove_file(&path).await;
+        });
+    }
+
+    #[test]
+    fn test_truncate_and_cover_shorter_content() {
+        let path = temp_path("truncate");
+        let manager = AsyncStorageManager::new();
+
+        await_test!(async {
+            write(&manager, &path, "a longer content", WriteMode::Truncate).await;
+            write(&manager, &path, "short", WriteMode::Truncate).await;
+            let data = manager.read(ReadFile::path(path.clone())).await.unwrap();
+            assert_eq!(data, b"short");
+
+            write(&manager, &path, "SH", WriteMode::Cover).await;
+            let data = manager.read(ReadFile::path(path.clone())).await.unwrap();
+            assert_eq!(data, b"SHort");
+            let _ = tokio::fs::remove_file(&path).await;
+        });
+    }
+    #[test]
+    fn test_hashed_write_and_verified_read() {
+        let path = temp_path("hashed");
+        let manager = AsyncStorageManager::new();
+
+        await_test!(async {
+            let data = b"checksum me".to_vec();
+            let sha256 = manager
+                .write_hashed(WriteFile::path(path.clone(), &data), HashAlgorithm::Sha256)
+                .await
+                .unwrap();
+            let parsed = FileHash::from_hex(HashAlgorithm::Sha256, &sha256.to_hex()).unwrap();
+            assert_eq!(parsed, sha256);
+
+            let read = manager
+                .read_verified(ReadFile::path(path.clone()), sha256)
+                .await
+                .unwrap();
+            assert_eq!(read, data);
+
+            let result = manager
+                .read_verified(ReadFile::path(path.clone()), FileHash::Xxh3(0))
+                .await;
+            assert!(matches!(result, Err(StorageError::ChecksumMismatch(_))));
+            let _ = tokio::fs::remove_file(&path).await;
+        });
+    }
+    #[test]
+    fn test_compressed_write_reads_back_plain() {
+        let path = temp_path("compressed");
+        let manager = AsyncStorageManager::new();
+
+        await_test!(async {
+            let data = "a log line that repeats\n".repeat(1024).into_bytes();
+            let mut request = WriteFile::path(path.clone(), &data);
+            request.compression = Some(CompressionKind::Zstd);
+            manager.write(request).await.unwrap();
+
+            let stored = tokio::fs::read(&path).await.unwrap();
+            assert!(stored.len() < data.len());
+            let read = manager.read(ReadFile::path(path.clone())).await.unwrap();
+            assert_eq!(read, data);
+            let _ = tokio::fs::remove_file(&path).await;
+        });
+    }
+    #[test]
+    fn test_compressed_cover_over_longer_file() {
+        let path = temp_path("compressed_cover");
+        let manager = AsyncStorageManager::new();
+
+        await_test!(async {
+            let longer = "an uncompressible tail ".repeat(64);
+            write(&manager, &path, &longer, WriteMode::Truncate).await;
+
+            let data = b"short".to_vec();
+            let mut request = WriteFile::path(path.clone(), &data);
+            request.mode = WriteMode::Cover;
+            request.compression = Some(CompressionKind::Zstd);
+            manager.write(request).await.unwrap();
+
+            let read = manager.read(ReadFile::path(path.clone())).await.unwrap();
+            assert_eq!(read, data);
+            let _ = tokio::fs::remove_file(&path).await;
+        });
+    }
+    #[test]
+    fn test_paths_outside_allowed_roots_are_denied() {
+        let root = std::path::PathBuf::from(temp_path("sandbox"));
+        let manager = AsyncStorageManager::new().with_allowed_roots(vec![root.clone()]);
+
+        await_test!(async {
+            tokio::fs::create_dir_all(&root).await.unwrap();
+            let inside = root.join("nested/file.txt").to_string_lossy().to_string();
+            let nested = root.join("nested").to_string_lossy().to_string();
+            manager.create_dir(nested, false).await.unwrap();
+            write(&manager, &inside, "inside", WriteMode::Truncate).await;
+
+            let escaping = root.join("nested/../../escaped.txt");
+            let escaping = escaping.to_string_lossy().to_string();
+            let data = b"outside".to_vec();
+            let result = manager.write(WriteFile::path(escaping, &data)).await;
+            assert!(matches!(result, Err(StorageError::AccessDenied(_))));
+            let result = manager.exists("/".to_string()).await;
+            assert!(matches!(result, Err(StorageError::AccessDenied(_))));
+            let _ = tokio::fs::remove_dir_all(&root).await;
+        });
+    }
+
+    #[test]
+    fn test_read_stream_holds_the_lock() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("streamed.txt");
+        let path = path.to_string_lossy().to_string();
+        let manager = AsyncStorageManager::new();
+
+        await_test!(async {
+            write(&manager, &path, "streamed", WriteMode::Truncate).await;
+            let stream = manager.read_stream(path.clone(), 4).await.unwrap();
+            let data = b"changed".to_vec();
+            let write_during_stream = manager.write(WriteFile::path(path.clone(), &data));
+            let result = tokio::time::timeout(Duration::from_millis(100), write_during_stream);
+            assert!(result.await.is_err());
+
+            let chunks: Vec<Bytes> = stream.try_collect().await.unwrap();
+            assert_eq!(chunks.concat(), b"streamed");
+            write(&manager, &path, "changed", WriteMode::Truncate).await;
+        });
+    }
+
+    // the root is only created later and reached through a symbolic link
+    #[cfg(unix)]
+    #[test]
+    fn test_missing_allowed_root_is_canonicalized() {
+        let directory = tempfile::tempdir().unwrap();
+        let link = directory.path().join("link");
+        std::fs::create_dir(directory.path().join("target")).unwrap();
+        std::os::unix::fs::symlink(directory.path().join("target"), &link).unwrap();
+        let root = link.join("root");
+        let manager = AsyncStorageManager::new().with_allowed_roots(vec![root.clone()]);
+
+        await_test!(async {
+            let inside = root.join("file.txt").to_string_lossy().to_string();
+            let result = manager.exists(inside).await;
+            assert!(matches!(result, Ok(false)));
+            let outside = link.join("file.txt").to_string_lossy().to_string();
+            let result = manager.exists(outside).await;
+            assert!(matches!(result, Err(StorageError::AccessDenied(_))));
+        });
+    }
+
+    #[test]
+    fn test_append_line_and_read_lines() {
+        let path = temp_path("lines");
+        let manager = AsyncStorageManager::new();
+
+        await_test!(async {
+            let _ = tokio::fs::remove_file(&path).await;
+            for line in ["{\"a\":1}", "{\"a\":2}\n", "{\"a\":3}"] {
+                manager.append_line(path.clone(), line.as_bytes().to_vec()).await.unwrap();
+            }
+            let stored = tokio::fs::read_to_string(&path).await.unwrap();
+            assert_eq!(stored, "{\"a\":1}\n{\"a\":2}\n{\"a\":3}\n");
+
+            let lines = manager.read_lines(path.clone(), 1..10).await.unwrap();
+            assert_eq!(lines, vec![b"{\"a\":2}".to_vec(), b"{\"a\":3}".to_vec()]);
+            let lines = manager.read_lines(path.clone(), 5..10).await.unwrap();
+            assert!(lines.is_empty());
+            let _ = tokio::fs::remove_file(&path).await;
+        });
+    }
 }
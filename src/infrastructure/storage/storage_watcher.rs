@@ -0,0 +1,101 @@
+use crate::domain::models::storage_models::{StorageError, StorageEvent, StorageEventKind};
+use futures_util::StreamExt;
+use futures_util::stream::{self, BoxStream};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+
+struct WatchState {
+    // dropping the watcher stops the notifications, so it lives as long as the stream
+    _watcher: RecommendedWatcher,
+    receiver: mpsc::UnboundedReceiver<notify::Result<Event>>,
+    pending: VecDeque<StorageEvent>,
+}
+
+fn event_kind(kind: &EventKind) -> Option<StorageEventKind> {
+    match kind {
+        EventKind::Create(_) => Some(StorageEventKind::Created),
+        EventKind::Modify(_) => Some(StorageEventKind::Modified),
+        EventKind::Remove(_) => Some(StorageEventKind::Removed),
+        _ => None,
+    }
+}
+
+// several changes to one path inside a window collapse into a single event
+fn merge(batch: &mut Vec<StorageEvent>, event: notify::Result<Event>) {
+    let Ok(event) = event else {
+        return;
+    };
+    let Some(kind) = event_kind(&event.kind) else {
+        return;
+    };
+    for path in event.paths {
+        let path = path.to_string_lossy().to_string();
+        match batch.iter_mut().find(|pending| pending.path == path) {
+            Some(pending) => {
+                pending.kind = match (pending.kind, kind) {
+                    (StorageEventKind::Created, StorageEventKind::Modified) => {
+                        StorageEventKind::Created
+                    }
+                    (StorageEventKind::Removed, StorageEventKind::Created) => {
+                        StorageEventKind::Modified
+                    }
+                    (_, kind) => kind,
+                }
+            }
+            None => batch.push(StorageEvent { path, kind }),
+        }
+    }
+}
+
+pub fn watch_path(
+    path: &String,
+    debounce: Duration,
+) -> Result<BoxStream<'static, StorageEvent>, StorageError> {
+    if !Path::new(path).exists() {
+        return Err(StorageError::NotExist(path.clone()));
+    }
+
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = sender.send(event);
+    })
+    .map_err(|e| StorageError::IOError(e.to_string()))?;
+    watcher
+        .watch(Path::new(path), RecursiveMode::Recursive)
+        .map_err(|e| StorageError::IOError(e.to_string()))?;
+
+    let state = WatchState {
+        _watcher: watcher,
+        receiver,
+        pending: VecDeque::new(),
+    };
+    let stream = stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(event) = state.pending.pop_front() {
+                return Some((event, state));
+            }
+
+            let first = state.receiver.recv().await?;
+            let mut batch = Vec::new();
+            merge(&mut batch, first);
+
+            let window = sleep(debounce);
+            tokio::pin!(window);
+            loop {
+                tokio::select! {
+                    _ = &mut window => break,
+                    event = state.receiver.recv() => match event {
+                        Some(event) => merge(&mut batch, event),
+                        None => break,
+                    },
+                }
+            }
+            state.pending.extend(batch);
+        }
+    });
+    Ok(stream.boxed())
+}
@@ -0,0 +1,150 @@
+use crate::domain::models::crash_models::CrashReport;
+use crate::domain::traits::crash_traits::CrashReporter;
+use futures_util::FutureExt;
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+use std::backtrace::Backtrace;
+use std::panic::{AssertUnwindSafe, PanicHookInfo};
+use std::sync::{Arc, Once};
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+lazy_static! {
+    static ref CRASH_REPORTER: RwLock<Option<Arc<dyn CrashReporter>>> = RwLock::new(None);
+}
+
+static HOOK: Once = Once::new();
+
+tokio::task_local! {
+    static TASK_NAME: String;
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info.payload();
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message.to_string();
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+    "Box<dyn Any>".to_string()
+}
+
+fn capture(info: &PanicHookInfo<'_>) {
+    let Some(reporter) = CRASH_REPORTER.read().clone() else {
+        return;
+    };
+    let report = CrashReport {
+        id: Uuid::new_v4().to_string(),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0),
+        thread: std::thread::current().name().map(|name| name.to_string()),
+        task: TASK_NAME.try_with(|name| name.clone()).ok(),
+        message: panic_message(info),
+        location: info.location().map(|location| location.to_string()),
+        backtrace: Backtrace::force_capture().to_string(),
+    };
+    reporter.record(&report);
+}
+
+// the hook is installed once and kept for the process, a later runtime replaces the reporter
+pub fn initialize_crash_reporter(reporter: Arc<dyn CrashReporter>) {
+    *CRASH_REPORTER.write() = Some(reporter);
+    HOOK.call_once(|| {
+        // the previous hook still runs, so panics are printed as before
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            capture(info);
+            previous(info);
+        }));
+    });
+}
+
+// panics are no longer recorded unless another runtime installed its reporter meanwhile
+pub fn release_crash_reporter(reporter: &Arc<dyn CrashReporter>) {
+    let mut active = CRASH_REPORTER.write();
+    if active
+        .as_ref()
+        .is_some_and(|active| Arc::ptr_eq(active, reporter))
+    {
+        *active = None;
+    }
+}
+
+// names the task in the crash reports of its panics, the panic is passed on afterwards so
+// awaiting the join handle still reports it
+pub fn guard_task<F>(name: impl Into<String>, future: F) -> impl Future<Output = F::Output>
+where
+    F: Future,
+{
+    let name = name.into();
+    TASK_NAME.scope(name.clone(), async move {
+        match AssertUnwindSafe(future).catch_unwind().await {
+            Ok(output) => output,
+            Err(payload) => {
+                tracing::error!(task = name, "Task panicked");
+                std::panic::resume_unwind(payload)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::crash_models::{CrashError, CrashReport};
+    use crate::domain::traits::crash_traits::CrashReporter;
+    use crate::monitor::crash_service::{
+        guard_task, initialize_crash_reporter, release_crash_reporter,
+    };
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct MemoryCrashReporter {
+        reports: Mutex<Vec<CrashReport>>,
+    }
+
+    #[async_trait]
+    impl CrashReporter for MemoryCrashReporter {
+        fn record(&self, report: &CrashReport) {
+            self.reports.lock().push(report.clone());
+        }
+
+        async fn take_pending(&self) -> Result<Vec<CrashReport>, CrashError> {
+            Ok(std::mem::take(&mut *self.reports.lock()))
+        }
+    }
+
+    #[test]
+    fn test_guarded_task_panic_is_recorded() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let memory = Arc::new(MemoryCrashReporter::default());
+        let reporter: Arc<dyn CrashReporter> = memory.clone();
+        initialize_crash_reporter(reporter.clone());
+
+        let result = runtime.block_on(runtime.spawn(guard_task("crash_test", async {
+            panic!("crash test panic");
+        })));
+        assert!(result.unwrap_err().is_panic());
+        release_crash_reporter(&reporter);
+
+        // other tests may panic at the same time
+        let reports = runtime.block_on(memory.take_pending()).unwrap();
+        let report = reports
+            .iter()
+            .find(|report| report.task.as_deref() == Some("crash_test"))
+            .unwrap();
+        assert_eq!(report.message, "crash test panic");
+        assert!(
+            report
+                .location
+                .as_ref()
+                .unwrap()
+                .contains("crash_service.rs")
+        );
+        assert!(!report.backtrace.is_empty());
+    }
+}
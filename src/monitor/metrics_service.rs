@@ -0,0 +1,24 @@
+use crate::domain::traits::metrics_traits::MetricsRegistry;
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+use std::sync::Arc;
+
+lazy_static! {
+    static ref METRICS_REGISTRY: RwLock<Option<Arc<dyn MetricsRegistry>>> = RwLock::new(None);
+}
+
+// a later runtime replaces the registry of an earlier one
+pub fn initialize_metrics(registry: Arc<dyn MetricsRegistry>) {
+    *METRICS_REGISTRY.write() = Some(registry);
+}
+
+// does nothing while metrics are not configured
+pub fn metrics<F>(func: F)
+where
+    F: FnOnce(&dyn MetricsRegistry),
+{
+    let registry = METRICS_REGISTRY.read().clone();
+    if let Some(registry) = registry {
+        func(registry.as_ref());
+    }
+}
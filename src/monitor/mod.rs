@@ -1 +1,3 @@
-pub mod monitor_service;
\ No newline at end of file
+pub mod monitor_service;
+pub mod metrics_service;
+pub mod crash_service;
\ No newline at end of file
@@ -1,7 +1,6 @@
 use crate::domain::models::monitor_models::{MonitorError, MonitorEvent};
 use crate::domain::traits::monitor_traits::{Monitor, MonitorSubscriber};
 use crate::infrastructure::monitor::mpsc_monitor_backend::MpscMonitorBackend;
-use std::cell::OnceCell;
 use std::sync::{Arc, RwLock};
 use lazy_static::lazy_static;
 use tokio::runtime::Runtime;
@@ -1,6 +1,6 @@
 use crate::domain::models::file_cache_models::CacheChannel;
 use rkv::backend::{SafeMode, SafeModeDatabase, SafeModeEnvironment};
-use rkv::{Manager, Rkv, SingleStore, StoreOptions, Value};
+use rkv::{Manager, Rkv, SingleStore, StoreError, StoreOptions, Value};
 use std::error::Error;
 use std::fs;
 use std::path::Path;
@@ -62,8 +62,7 @@ impl RkvService {
         key: &str,
         data: &CacheChannel,
     ) -> Result<(), Box<dyn Error>> {
-        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(data)
-            .map_err(|e| format!("rkyv serialization failed: {}", e))?;
+        let bytes = data.to_bytes()?;
 
         let env = self.env.as_ref().unwrap().read().unwrap();
         let mut writer = env.write()?;
@@ -73,6 +72,23 @@ impl RkvService {
         Ok(())
     }
 
+    pub fn delete_rkyv_cache_channel_data(
+        &self,
+        store: &SingleStore<SafeModeDatabase>,
+        key: &str,
+    ) -> Result<(), Box<dyn Error>> {
+        let env = self.env.as_ref().unwrap().read().unwrap();
+        let mut writer = env.write()?;
+        match store.delete(&mut writer, key) {
+            // a channel that was never persisted has nothing to delete
+            Ok(()) | Err(StoreError::KeyValuePairNotFound) => {}
+            Err(e) => return Err(e.into()),
+        }
+        writer.commit()?;
+
+        Ok(())
+    }
+
     pub fn read_rkyv_cache_channel_data(
         &self,
         store: &SingleStore<SafeModeDatabase>,
@@ -82,11 +98,7 @@ impl RkvService {
         let reader = env.read()?;
         match store.get(&reader, key)? {
             None => Ok(None),
-            Some(Value::Blob(bytes)) => {
-                let archived =
-                    rkyv::from_bytes::<CacheChannel, bytecheck::rancor::Error>(&bytes.to_vec())?;
-                Ok(Some(archived))
-            }
+            Some(Value::Blob(bytes)) => Ok(Some(CacheChannel::from_bytes(bytes)?)),
             Some(_) => Err("unknown type".into()),
         }
     }
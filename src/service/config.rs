@@ -1,55 +1,413 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use crate::domain::models::cookie_models::Cookie;
-use crate::domain::traits::http_traits::{DecryptionProvider, EncryptionProvider};
+use crate::domain::models::file_cache_models::CompressionKind;
+use crate::domain::models::http_models::HttpRetryPolicy;
+use crate::domain::models::logging_models::LogLevel;
+use crate::domain::models::storage_models::StorageRetryPolicy;
+use crate::domain::traits::file_cache_traits::CacheMigration;
+use crate::domain::traits::http_traits::{
+    DecryptionProvider, EncryptionProvider, HttpLogger, ResponseValidator, UserAgentProvider,
+};
+use crate::domain::traits::storage_traits::StorageKeyProvider;
 
+pub type ResponseValidators = Vec<(String, Arc<dyn ResponseValidator>)>;
+
+#[derive(Default)]
 pub struct RuntimeConfig {
     pub http: Option<HttpConfig>,
     pub cookie: Option<CookieConfig>,
-    pub file_cache_config: Option<FileCacheConfig>
+    pub file_cache_config: Option<FileCacheConfig>,
+    pub storage: Option<StorageConfig>,
+    pub database: Option<DatabaseConfig>,
+    // the scheduler always runs, this only changes its defaults
+    pub scheduler: Option<SchedulerConfig>,
+    // requires the http client
+    pub download: Option<DownloadConfig>,
+    pub upload: Option<UploadConfig>,
+    // no subscriber is installed when None, events then reach the host's subscriber if any
+    pub logging: Option<LoggingConfig>,
+    // metrics are not recorded when None
+    pub metrics: Option<MetricsConfig>,
+    // panics are not recorded when None
+    pub crash: Option<CrashConfig>,
+    // requires the file cache, requests are not audited when None
+    pub http_audit: Option<HttpAuditConfig>,
+    // a cookie store or file cache that fails to start fails initialization instead of
+    // being left out, see ServiceRuntime::init_report
+    pub strict_init: bool,
+}
+
+pub struct StorageConfig {
+    // files written through the runtime are encrypted at rest when set
+    pub key_provider: Option<Arc<dyn StorageKeyProvider>>,
+    // defaults to a strawberry_background folder inside the system temp directory
+    pub temp_root: Option<PathBuf>,
+    // temp files older than this are removed on start and then hourly, kept forever when None
+    pub temp_max_age: Option<Duration>,
+    // paths from callers must resolve inside one of these, unrestricted when empty
+    pub allowed_roots: Vec<PathBuf>,
+    // transient io errors such as sharing violations are retried with backoff when set
+    pub retry_policy: Option<StorageRetryPolicy>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    // an in-memory database when None, which always uses a single connection
+    pub path: Option<String>,
+    pub pool_size: usize,
+    // how long a statement waits for a lock held by another connection
+    pub busy_timeout: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    // last-run times are kept in this file when set, so schedules carry over restarts
+    pub state_path: Option<String>,
+    pub max_concurrent_jobs: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadConfig {
+    // the queue is kept in this file when set, unfinished downloads continue on the next start
+    pub state_path: Option<String>,
+    pub max_concurrent_downloads: usize,
+    // network errors, 5xx and 429 responses are retried with a doubling backoff
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    // covers the whole transfer of one request, not just the connection
+    pub request_timeout: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoggingConfig {
+    pub level: LogLevel,
+    // overrides the level for targets starting with a prefix such as "strawberry_background::superstructure"
+    pub targets: Vec<(String, LogLevel)>,
+    pub stderr: bool,
+    // lines are written by the log manager, which rotates the files by size, when set
+    pub file: Option<LogFileConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogFileConfig {
+    pub path: String,
+    pub max_size: u64,
+    // rotated files kept next to the current one as "path.1", "path.2" and so on
+    pub max_files: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricsConfig {
+    // upper bounds shared by all histograms, larger values land in an extra overflow bucket
+    pub histogram_buckets: Vec<f64>,
+    pub export: Option<MetricsExportConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricsExportConfig {
+    pub interval: Duration,
+    pub target: MetricsExportTarget,
 }
 
+#[derive(Debug, Clone)]
+pub enum MetricsExportTarget {
+    // the latest snapshot replaces the file
+    File(String),
+    // the snapshot is posted as JSON, requires the http client
+    Http {
+        url: String,
+        headers: Vec<(String, String)>,
+    },
+}
+
+// applied to a running runtime by reconfigure, fields left None keep their current value
+#[derive(Clone, Default)]
+pub struct RuntimeReconfiguration {
+    // rebuilds the http backend, requires the http client
+    pub http: Option<HttpReconfiguration>,
+    // restarts the auto-save loop of every file cache channel
+    pub file_cache_auto_save_interval: Option<Duration>,
+}
+
+#[derive(Clone, Default)]
+pub struct HttpReconfiguration {
+    pub connect_timeout: Option<Duration>,
+    pub request_timeout: Option<Duration>,
+    pub pool_idle_timeout: Option<Duration>,
+    // Some(None) removes the proxy
+    pub all_proxy: Option<Option<String>>,
+    pub host_proxy: Option<Option<Vec<(String, String)>>>,
+    pub circuit_breaker: Option<Option<CircuitBreakerConfig>>,
+    pub rate_limit: Option<Option<RateLimitConfig>>,
+    pub encryption_provider: Option<Option<Arc<dyn EncryptionProvider>>>,
+    pub decryption_provider: Option<Option<Arc<dyn DecryptionProvider>>>,
+    // replaces every validator, Some(None) removes them
+    pub response_validators: Option<Option<ResponseValidators>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CrashConfig {
+    // one json file per report, kept until taken on a later launch
+    pub directory: String,
+    // the oldest reports are removed beyond this
+    pub max_reports: usize,
+}
+
+// every request is appended as a json line to a segment, the segments are the entries of a
+// cache channel of their own
+#[derive(Debug, Clone)]
+pub struct HttpAuditConfig {
+    pub channel: String,
+    // a new segment is started once the current one holds this many records
+    pub records_per_segment: usize,
+    // the oldest segments are removed beyond this
+    pub max_segments: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadConfig {
+    // the queue is kept in this file when set, unfinished uploads continue on the next start
+    pub state_path: Option<String>,
+    pub max_concurrent_uploads: usize,
+    // network errors, 5xx and 429 responses are retried with a doubling backoff
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    // applies to each request, a chunk for the resumable protocols
+    pub request_timeout: Duration,
+}
+
+#[derive(Clone)]
 pub struct HttpConfig {
     pub connect_timeout: Duration,
     pub request_timeout: Duration,
     pub pool_idle_timeout: Duration,
+    // idle connections kept open per host for reuse
+    pub pool_max_idle_per_host: usize,
+    // requests in flight per host, the others wait for one of them to finish
     pub max_connections_per_host: usize,
+    // None turns the probes off
+    pub tcp_keepalive: Option<Duration>,
+    pub tcp_nodelay: bool,
+    // pings idle http2 connections, None turns them off
+    pub http2_keep_alive_interval: Option<Duration>,
     pub cookie_config: Option<CookieConfig>,
     pub encryption_provider: Option<Arc<dyn EncryptionProvider>>,
     pub decryption_provider: Option<Arc<dyn DecryptionProvider>>,
     pub all_proxy: Option<String>,
     pub host_proxy: Option<Vec<(String, String)>>,
     pub tls_danger_accept_invalid_hostnames: bool,
-    pub tls_danger_accept_invalid_certs: bool
+    pub tls_danger_accept_invalid_certs: bool,
+    pub response_validators: Option<ResponseValidators>,
+    pub user_agent_provider: Option<Arc<dyn UserAgentProvider>>,
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    // applies to each host separately
+    pub rate_limit: Option<RateLimitConfig>,
+    pub logger: Option<Arc<dyn HttpLogger>>,
+    pub ip_preference: IpPreference,
+    // the first override whose domain matches the host of a request applies to it
+    pub per_domain: Vec<DomainOverride>,
+}
+
+// settings for the requests to one domain and its subdomains, anything unset falls back to
+// the rest of HttpConfig
+#[derive(Debug, Clone, Default)]
+pub struct DomainOverride {
+    pub domain: String,
+    pub connect_timeout: Option<Duration>,
+    // used for the endpoints without a timeout of their own
+    pub request_timeout: Option<Duration>,
+    // the requests are not retried without one
+    pub retry_policy: Option<HttpRetryPolicy>,
+    // takes precedence over all_proxy and host_proxy
+    pub proxy: Option<String>,
+    // sent unless the endpoint sets a header of the same name
+    pub default_headers: Vec<(String, String)>,
+    // takes precedence over user_agent_provider, not over the user agent of the endpoint
+    pub user_agent: Option<String>,
+}
+
+impl DomainOverride {
+    pub fn matches(&self, host: &str) -> bool {
+        host_matches(host, &self.domain)
+    }
+}
+
+// whether the host is the domain or one of its subdomains
+pub fn host_matches(host: &str, domain: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|subdomain| subdomain.ends_with('.'))
+}
+
+// the address families connections are made over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IpPreference {
+    // whichever family the resolver lists first, the other one is raced after a short delay
+    #[default]
+    Auto,
+    V4Only,
+    V6Only,
+    // ipv4 first, ipv6 is still raced after a short delay
+    PreferV4,
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    pub window: Duration,
+    pub minimum_requests: usize,
+    pub failure_rate_threshold: f32,
+    pub open_duration: Duration,
+    pub half_open_max_probes: usize,
+}
+
+// requests over the limit wait until the period has room for them, retries count as requests
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    pub max_requests: usize,
+    pub period: Duration,
 }
 
 #[derive(Debug, Clone)]
 pub struct CookieConfig {
+    pub backend: CookieBackend,
     pub cookie_path: Option<String>,
     pub debounce_delay: Duration,
     pub auto_save_interval: Option<Duration>,
-    pub initial_cookies: Option<Vec<Cookie>>
+    pub initial_cookies: Option<Vec<Cookie>>,
+    // a public_suffix_list.dat used instead of the bundled copy
+    pub public_suffix_list_path: Option<String>,
+    pub max_cookies: Option<usize>,
+    pub max_cookies_per_domain: Option<usize>,
+    pub policies: Vec<CookiePolicy>,
+    // longer expiries are shortened to it when a cookie is set
+    pub max_cookie_lifetime: Option<Duration>,
+    // every cookie is kept for the session only and nothing is written to disk
+    pub session_only: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CookieBackend {
+    File,
+    Memory,
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+}
+
+#[derive(Debug, Clone)]
+pub struct CookiePolicy {
+    pub domain_pattern: String,
+    pub rule: CookiePolicyRule,
 }
 
 #[derive(Debug, Clone)]
+pub enum CookiePolicyRule {
+    AcceptAll,
+    RejectAll,
+    FirstPartyOnly { first_party_domains: Vec<String> },
+}
+
+#[derive(Clone)]
 pub struct FileCacheConfig {
     pub base_path: String,
     pub auto_save_interval: Duration,
-    pub channels: Option<Vec<FileCacheChannelConfig>>
+    pub channels: Option<Vec<FileCacheChannelConfig>>,
+    // channels that were not declared are created with default options on first use
+    pub auto_create_channels: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct FileCacheChannelConfig {
     pub name: String,
     pub extension: Option<String>,
+    pub default_ttl: Option<Duration>,
+    pub max_bytes: Option<u64>,
+    pub max_entries: Option<usize>,
+    pub compression: Option<CompressionKind>,
+    // AES-256-GCM key, records are stored encrypted when set
+    pub encryption_key: Option<[u8; 32]>,
+    // byte budget of the in-memory tier in front of the disk, disabled when None
+    pub memory_budget: Option<u64>,
+    // a stored channel with another version is migrated, or wiped when there is no migration
+    pub schema_version: Option<u32>,
+    pub migration: Option<Arc<dyn CacheMigration>>,
+}
+
+impl Default for HttpAuditConfig {
+    fn default() -> Self {
+        Self {
+            channel: "http_audit".to_string(),
+            records_per_segment: 500,
+            max_segments: 10,
+        }
+    }
+}
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            pool_size: 4,
+            busy_timeout: Duration::from_secs(5),
+        }
+    }
+}
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            state_path: None,
+            max_concurrent_jobs: 4,
+        }
+    }
+}
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            state_path: None,
+            max_concurrent_downloads: 3,
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            request_timeout: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::Info,
+            targets: Vec::new(),
+            stderr: true,
+            file: None,
+        }
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            // milliseconds, which is what the built-in duration histograms record
+            histogram_buckets: vec![
+                1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0,
+            ],
+            export: None,
+        }
+    }
 }
 
-impl Default for RuntimeConfig {
+impl Default for UploadConfig {
     fn default() -> Self {
         Self {
-            http: None,
-            cookie: None,
-            file_cache_config: None
+            state_path: None,
+            max_concurrent_uploads: 2,
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            request_timeout: Duration::from_secs(60 * 60),
         }
     }
 }
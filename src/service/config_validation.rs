@@ -0,0 +1,432 @@
+use crate::service::config::{
+    CookieBackend, CookieConfig, FileCacheConfig, HttpConfig, MetricsExportTarget, RuntimeConfig,
+};
+use reqwest::Proxy;
+use std::time::Duration;
+use url::Url;
+
+// collects every problem instead of stopping at the first one
+#[derive(Default)]
+struct Problems(Vec<String>);
+
+impl Problems {
+    fn check(&mut self, valid: bool, problem: impl FnOnce() -> String) {
+        if !valid {
+            self.0.push(problem());
+        }
+    }
+
+    fn positive_duration(&mut self, field: &str, value: Duration) {
+        self.check(!value.is_zero(), || {
+            format!("{} must be greater than zero", field)
+        });
+    }
+
+    fn positive_count(&mut self, field: &str, value: usize) {
+        self.check(value > 0, || format!("{} must be greater than zero", field));
+    }
+
+    fn not_empty(&mut self, field: &str, value: &str) {
+        self.check(!value.trim().is_empty(), || {
+            format!("{} must not be empty", field)
+        });
+    }
+
+    fn into_result(self) -> Result<(), Vec<String>> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(self.0)
+        }
+    }
+}
+
+impl RuntimeConfig {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Problems::default();
+        if let Some(http) = &self.http {
+            check_http(&mut problems, "http", http);
+        }
+        if let Some(cookie) = &self.cookie {
+            check_cookie(&mut problems, "cookie", cookie);
+        }
+        if let Some(file_cache) = &self.file_cache_config {
+            check_file_cache(&mut problems, "file_cache_config", file_cache);
+        }
+        if let Some(database) = &self.database {
+            problems.positive_count("database.pool_size", database.pool_size);
+            if let Some(path) = &database.path {
+                problems.not_empty("database.path", path);
+            }
+        }
+        if let Some(scheduler) = &self.scheduler {
+            problems.positive_count(
+                "scheduler.max_concurrent_jobs",
+                scheduler.max_concurrent_jobs,
+            );
+        }
+        if let Some(download) = &self.download {
+            problems.check(self.http.is_some(), || {
+                "download requires the http client".to_string()
+            });
+            problems.positive_count(
+                "download.max_concurrent_downloads",
+                download.max_concurrent_downloads,
+            );
+            problems.positive_duration("download.request_timeout", download.request_timeout);
+            problems.check(download.initial_backoff <= download.max_backoff, || {
+                "download.initial_backoff must not exceed download.max_backoff".to_string()
+            });
+        }
+        if let Some(upload) = &self.upload {
+            problems.check(self.http.is_some(), || {
+                "upload requires the http client".to_string()
+            });
+            problems.positive_count(
+                "upload.max_concurrent_uploads",
+                upload.max_concurrent_uploads,
+            );
+            problems.positive_duration("upload.request_timeout", upload.request_timeout);
+            problems.check(upload.initial_backoff <= upload.max_backoff, || {
+                "upload.initial_backoff must not exceed upload.max_backoff".to_string()
+            });
+        }
+        if let Some(file) = self
+            .logging
+            .as_ref()
+            .and_then(|logging| logging.file.as_ref())
+        {
+            problems.not_empty("logging.file.path", &file.path);
+            problems.check(file.max_size > 0, || {
+                "logging.file.max_size must be greater than zero".to_string()
+            });
+        }
+        if let Some(export) = self
+            .metrics
+            .as_ref()
+            .and_then(|metrics| metrics.export.as_ref())
+        {
+            problems.positive_duration("metrics.export.interval", export.interval);
+            match &export.target {
+                MetricsExportTarget::File(path) => {
+                    problems.not_empty("metrics.export.target", path)
+                }
+                MetricsExportTarget::Http { url, .. } => {
+                    problems.check(self.http.is_some(), || {
+                        "metrics.export to http requires the http client".to_string()
+                    });
+                    problems.check(Url::parse(url).is_ok(), || {
+                        format!("metrics.export.target url {} cannot be parsed", url)
+                    });
+                }
+            }
+        }
+        if let Some(crash) = &self.crash {
+            problems.not_empty("crash.directory", &crash.directory);
+        }
+        if let Some(http_audit) = &self.http_audit {
+            problems.check(self.file_cache_config.is_some(), || {
+                "http_audit requires the file cache".to_string()
+            });
+            problems.not_empty("http_audit.channel", &http_audit.channel);
+            problems.positive_count(
+                "http_audit.records_per_segment",
+                http_audit.records_per_segment,
+            );
+            problems.positive_count("http_audit.max_segments", http_audit.max_segments);
+        }
+        problems.into_result()
+    }
+}
+
+impl HttpConfig {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Problems::default();
+        check_http(&mut problems, "http", self);
+        problems.into_result()
+    }
+}
+
+impl CookieConfig {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Problems::default();
+        check_cookie(&mut problems, "cookie", self);
+        problems.into_result()
+    }
+}
+
+impl FileCacheConfig {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Problems::default();
+        check_file_cache(&mut problems, "file_cache_config", self);
+        problems.into_result()
+    }
+}
+
+fn check_http(problems: &mut Problems, prefix: &str, config: &HttpConfig) {
+    problems.positive_duration(
+        &format!("{}.connect_timeout", prefix),
+        config.connect_timeout,
+    );
+    problems.positive_duration(
+        &format!("{}.request_timeout", prefix),
+        config.request_timeout,
+    );
+    problems.positive_count(
+        &format!("{}.max_connections_per_host", prefix),
+        config.max_connections_per_host,
+    );
+    if let Some(tcp_keepalive) = config.tcp_keepalive {
+        problems.positive_duration(&format!("{}.tcp_keepalive", prefix), tcp_keepalive);
+    }
+    if let Some(http2_keep_alive_interval) = config.http2_keep_alive_interval {
+        problems.positive_duration(
+            &format!("{}.http2_keep_alive_interval", prefix),
+            http2_keep_alive_interval,
+        );
+    }
+    if let Some(all_proxy) = &config.all_proxy {
+        problems.check(Proxy::all(all_proxy.as_str()).is_ok(), || {
+            format!("{}.all_proxy {} cannot be parsed", prefix, all_proxy)
+        });
+    }
+    for (host, proxy) in config.host_proxy.iter().flatten() {
+        problems.check(Url::parse(proxy).is_ok(), || {
+            format!(
+                "{}.host_proxy {} for {} cannot be parsed",
+                prefix, proxy, host
+            )
+        });
+    }
+    if let Some(circuit_breaker) = &config.circuit_breaker {
+        problems.positive_duration(
+            &format!("{}.circuit_breaker.window", prefix),
+            circuit_breaker.window,
+        );
+        problems.check(
+            circuit_breaker.failure_rate_threshold > 0.0
+                && circuit_breaker.failure_rate_threshold <= 1.0,
+            || {
+                format!(
+                    "{}.circuit_breaker.failure_rate_threshold must be within (0, 1]",
+                    prefix
+                )
+            },
+        );
+    }
+    if let Some(rate_limit) = &config.rate_limit {
+        problems.positive_count(
+            &format!("{}.rate_limit.max_requests", prefix),
+            rate_limit.max_requests,
+        );
+        problems.positive_duration(&format!("{}.rate_limit.period", prefix), rate_limit.period);
+    }
+    for domain_override in config.per_domain.iter() {
+        let domain_prefix = format!("{}.per_domain {}", prefix, domain_override.domain);
+        problems.not_empty(
+            &format!("{}.per_domain.domain", prefix),
+            &domain_override.domain,
+        );
+        if let Some(connect_timeout) = domain_override.connect_timeout {
+            problems.positive_duration(
+                &format!("{}.connect_timeout", domain_prefix),
+                connect_timeout,
+            );
+        }
+        if let Some(request_timeout) = domain_override.request_timeout {
+            problems.positive_duration(
+                &format!("{}.request_timeout", domain_prefix),
+                request_timeout,
+            );
+        }
+        if let Some(proxy) = &domain_override.proxy {
+            problems.check(Proxy::all(proxy.as_str()).is_ok(), || {
+                format!("{}.proxy {} cannot be parsed", domain_prefix, proxy)
+            });
+        }
+        if let Some(retry_policy) = &domain_override.retry_policy {
+            problems.check(retry_policy.max_attempts > 0, || {
+                format!(
+                    "{}.retry_policy.max_attempts must be greater than zero",
+                    domain_prefix
+                )
+            });
+        }
+    }
+    if let Some(cookie_config) = &config.cookie_config {
+        check_cookie(
+            problems,
+            &format!("{}.cookie_config", prefix),
+            cookie_config,
+        );
+    }
+}
+
+fn check_cookie(problems: &mut Problems, prefix: &str, config: &CookieConfig) {
+    // the file and sqlite backends keep cookies in memory only when there is no path
+    if config.backend != CookieBackend::Memory
+        && let Some(cookie_path) = &config.cookie_path
+    {
+        problems.not_empty(&format!("{}.cookie_path", prefix), cookie_path);
+    }
+    if let Some(auto_save_interval) = config.auto_save_interval {
+        problems.positive_duration(
+            &format!("{}.auto_save_interval", prefix),
+            auto_save_interval,
+        );
+    }
+    if let Some(max_cookies) = config.max_cookies {
+        problems.positive_count(&format!("{}.max_cookies", prefix), max_cookies);
+    }
+    if let Some(max_cookies_per_domain) = config.max_cookies_per_domain {
+        problems.positive_count(
+            &format!("{}.max_cookies_per_domain", prefix),
+            max_cookies_per_domain,
+        );
+    }
+    if let Some(max_cookie_lifetime) = config.max_cookie_lifetime {
+        problems.positive_duration(
+            &format!("{}.max_cookie_lifetime", prefix),
+            max_cookie_lifetime,
+        );
+    }
+}
+
+fn check_file_cache(problems: &mut Problems, prefix: &str, config: &FileCacheConfig) {
+    problems.not_empty(&format!("{}.base_path", prefix), &config.base_path);
+    problems.positive_duration(
+        &format!("{}.auto_save_interval", prefix),
+        config.auto_save_interval,
+    );
+    let mut names = Vec::new();
+    for channel in config.channels.iter().flatten() {
+        problems.not_empty(&format!("{}.channels.name", prefix), &channel.name);
+        problems.check(!names.contains(&&channel.name), || {
+            format!("{}.channels {} is declared twice", prefix, channel.name)
+        });
+        names.push(&channel.name);
+        if channel.migration.is_some() {
+            problems.check(channel.schema_version.is_some(), || {
+                format!(
+                    "{}.channels {} has a migration but no schema_version",
+                    prefix, channel.name
+                )
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::service::config::{
+        CookieBackend, CookieConfig, DomainOverride, FileCacheConfig, HttpAuditConfig, HttpConfig,
+        IpPreference, RuntimeConfig, UploadConfig,
+    };
+    use std::time::Duration;
+
+    fn http_config() -> HttpConfig {
+        HttpConfig {
+            connect_timeout: Duration::from_secs(10),
+            request_timeout: Duration::from_secs(30),
+            pool_idle_timeout: Duration::from_secs(90),
+            pool_max_idle_per_host: 100,
+            max_connections_per_host: 100,
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            tcp_nodelay: true,
+            http2_keep_alive_interval: None,
+            cookie_config: None,
+            encryption_provider: None,
+            decryption_provider: None,
+            all_proxy: None,
+            host_proxy: None,
+            tls_danger_accept_invalid_hostnames: false,
+            tls_danger_accept_invalid_certs: false,
+            response_validators: None,
+            user_agent_provider: None,
+            circuit_breaker: None,
+            rate_limit: None,
+            logger: None,
+            ip_preference: IpPreference::Auto,
+            per_domain: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_every_problem_is_reported() {
+        assert!(RuntimeConfig::default().validate().is_ok());
+
+        let mut http = http_config();
+        http.connect_timeout = Duration::ZERO;
+        http.all_proxy = Some("http://[::1".to_string());
+        http.host_proxy = Some(vec![("example.com".to_string(), "not a url".to_string())]);
+        http.per_domain = vec![DomainOverride {
+            domain: "example.com".to_string(),
+            proxy: Some("http://[::1".to_string()),
+            ..DomainOverride::default()
+        }];
+        assert!(http.per_domain[0].matches("api.example.com"));
+        assert!(!http.per_domain[0].matches("badexample.com"));
+        let config = RuntimeConfig {
+            http: Some(http),
+            cookie: Some(CookieConfig {
+                backend: CookieBackend::File,
+                cookie_path: Some("".to_string()),
+                debounce_delay: Duration::from_secs(1),
+                auto_save_interval: None,
+                initial_cookies: None,
+                public_suffix_list_path: None,
+                max_cookies: None,
+                max_cookies_per_domain: None,
+                policies: Vec::new(),
+                max_cookie_lifetime: Some(Duration::ZERO),
+                session_only: false,
+            }),
+            file_cache_config: Some(FileCacheConfig {
+                base_path: "".to_string(),
+                auto_save_interval: Duration::from_secs(60),
+                channels: None,
+                auto_create_channels: true,
+            }),
+            ..RuntimeConfig::default()
+        };
+        let problems = config.validate().unwrap_err();
+        assert_eq!(problems.len(), 7, "{:?}", problems);
+        assert!(problems[0].starts_with("http.connect_timeout"));
+        assert!(problems[1].starts_with("http.all_proxy"));
+        assert!(problems[2].starts_with("http.host_proxy"));
+        assert!(problems[3].starts_with("http.per_domain example.com.proxy"));
+        assert!(problems[4].starts_with("cookie.cookie_path"));
+        assert!(problems[5].starts_with("cookie.max_cookie_lifetime"));
+        assert!(problems[6].starts_with("file_cache_config.base_path"));
+
+        let config = RuntimeConfig {
+            upload: Some(UploadConfig {
+                max_concurrent_uploads: 0,
+                ..UploadConfig::default()
+            }),
+            ..RuntimeConfig::default()
+        };
+        assert_eq!(
+            config.validate().unwrap_err(),
+            vec![
+                "upload requires the http client".to_string(),
+                "upload.max_concurrent_uploads must be greater than zero".to_string(),
+            ]
+        );
+
+        let config = RuntimeConfig {
+            http_audit: Some(HttpAuditConfig {
+                max_segments: 0,
+                ..HttpAuditConfig::default()
+            }),
+            ..RuntimeConfig::default()
+        };
+        assert_eq!(
+            config.validate().unwrap_err(),
+            vec![
+                "http_audit requires the file cache".to_string(),
+                "http_audit.max_segments must be greater than zero".to_string(),
+            ]
+        );
+    }
+}
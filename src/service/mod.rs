@@ -1,3 +1,4 @@
 pub mod config;
 pub mod service_runtime;
-pub mod service_exporter;
\ No newline at end of file
+pub mod service_exporter;
+pub mod config_validation;
\ No newline at end of file
@@ -1,4 +1,3 @@
-use crate::domain::traits::monitor_traits::Monitor;
 use crate::service::config::RuntimeConfig;
 use crate::service::service_runtime::{InitError, ServiceRuntime};
 use std::sync::Arc;
@@ -28,28 +27,43 @@ pub fn create_service_exporter_with_tokio_runtime(
 
 #[cfg(test)]
 mod tests {
+    use crate::domain::models::cookie_models::Cookie;
     use crate::domain::models::coordinator_models::{
-        CategorizerError, CoordinatorConfiguration, Identifier, Priority, Request,
+        CategorizerError, CoordinatorConfiguration, Identifier, Request,
         RunnerConfiguration, RunnerError, RunnerSnapshot, RunnerStatus,
     };
-    use crate::domain::models::http_models::{HttpEndpoint, HttpMethod};
-    use crate::domain::models::storage_models::{EnsureMode, ReadFile, WriteFile, WriteMode};
+    use crate::domain::models::http_models::{
+        HttpClientError, HttpEndpoint, HttpResponse, HttpStreamResponse,
+    };
+    use crate::domain::models::file_cache_models::{CacheRecord, CacheRefresh};
+    use crate::domain::models::init_models::SubsystemStatus;
+    use crate::domain::models::scheduler_models::JobSchedule;
+    use crate::domain::models::storage_models::{
+        EnsureMode, ReadFile, StorageError, WriteFile, WriteMode,
+    };
     use crate::domain::traits::coordinator_traits::{
         Categorizer, Coordinator, Runner, RunnerWatcher,
     };
+    use crate::domain::traits::http_traits::{DecryptionProvider, EncryptionProvider, HttpClient};
     use crate::rkv::rkv_impl::initialize_rkv;
     use crate::service::config::{
-        CookieConfig, FileCacheChannelConfig, FileCacheConfig, HttpConfig, RuntimeConfig,
+        CookieBackend, CookieConfig, FileCacheChannelConfig, FileCacheConfig, HttpConfig, IpPreference,
+        HttpReconfiguration, RuntimeConfig, RuntimeReconfiguration, StorageConfig,
     };
     use crate::service::service_exporter::create_service_exporter_with_tokio_runtime;
-    use crate::service::service_runtime::ServiceRuntime;
+    use crate::service::service_runtime::{InitError, RuntimeError, ServiceRuntime};
+    use async_trait::async_trait;
     use crate::superstructure::coordinator::coordinator::DefaultCoordinator;
     use crate::superstructure::coordinator::registry::RunnerRegistry;
+    use crate::superstructure::file_cache_backend::auto_save_job_name;
     use parking_lot::Mutex;
-    use std::ops::Deref;
-    use std::sync::Arc;
-    use std::thread::sleep;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, LazyLock};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{JoinHandle, sleep};
     use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
     use tokio::runtime::Runtime;
     use tokio_test::{assert_err, assert_ok};
     use tokio_util::sync::CancellationToken;
@@ -60,9 +74,44 @@ mod tests {
         };
     }
 
-    fn initialize_runtime() -> Arc<ServiceRuntime> {
-        initialize_rkv("databases".into());
+    fn http_config() -> HttpConfig {
+        HttpConfig {
+            connect_timeout: Duration::from_secs(10),
+            request_timeout: Duration::from_secs(30),
+            pool_idle_timeout: Duration::from_secs(90),
+            pool_max_idle_per_host: 100,
+            max_connections_per_host: 100,
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            tcp_nodelay: true,
+            http2_keep_alive_interval: None,
+            encryption_provider: None,
+            decryption_provider: None,
+            cookie_config: None,
+            all_proxy: None,
+            host_proxy: None,
+            tls_danger_accept_invalid_certs: false,
+            tls_danger_accept_invalid_hostnames: false,
+            response_validators: None,
+            user_agent_provider: None,
+            circuit_breaker: None,
+            rate_limit: None,
+            logger: None,
+            ip_preference: IpPreference::Auto,
+            per_domain: Vec::new(),
+        }
+    }
+
+    // rkv is initialized once per process, so every test shares this directory
+    fn initialize_test_rkv() {
+        static DATABASES: LazyLock<TempDir> = LazyLock::new(|| tempfile::tempdir().unwrap());
+        initialize_rkv(DATABASES.path().to_string_lossy().to_string());
+    }
+
+    // the cookies and the file cache live in `directory`
+    fn initialize_runtime(directory: &TempDir) -> Arc<ServiceRuntime> {
+        initialize_test_rkv();
         let runtime = Runtime::new().unwrap();
+        let path = |name: &str| directory.path().join(name).to_string_lossy().to_string();
 
         let service_exporter = create_service_exporter_with_tokio_runtime(
             RuntimeConfig {
@@ -71,66 +120,80 @@ mod tests {
                 //     thread_stack_size: None,
                 //     thread_name_prefix: Some("strawberry-background-worker".to_string()),
                 // },
-                http: Some(HttpConfig {
-                    connect_timeout: Duration::from_secs(10),
-                    request_timeout: Duration::from_secs(30),
-                    pool_idle_timeout: Duration::from_secs(90),
-                    max_connections_per_host: 100,
-                    encryption_provider: None,
-                    decryption_provider: None,
-                    cookie_config: None,
-                    all_proxy: None,
-                    host_proxy: None,
-                    tls_danger_accept_invalid_certs: false,
-                    tls_danger_accept_invalid_hostnames: false,
-                }),
+                http: Some(http_config()),
                 cookie: Some(CookieConfig {
-                    cookie_path: Some("test_cookie.json".to_string()),
+                    backend: CookieBackend::File,
+                    cookie_path: Some(path("test_cookie.json")),
                     debounce_delay: Duration::from_secs(10),
                     auto_save_interval: Some(Duration::from_secs(60)),
                     initial_cookies: None,
+                    public_suffix_list_path: None,
+                    max_cookies: Some(3000),
+                    max_cookies_per_domain: Some(180),
+                    policies: vec![],
+                    max_cookie_lifetime: None,
+                    session_only: false,
                 }),
                 file_cache_config: Some(FileCacheConfig {
-                    base_path: "file_cache_test".to_string(),
+                    base_path: path("file_cache_test"),
                     auto_save_interval: Duration::from_secs(10),
+                    auto_create_channels: false,
                     channels: Some(vec![
                         FileCacheChannelConfig {
                             name: "test-channel-1".to_string(),
                             extension: None,
+                            default_ttl: None,
+                            max_bytes: None,
+                            max_entries: None,
+                            compression: None,
+                            encryption_key: None,
+                            memory_budget: None,
+                            schema_version: None,
+                            migration: None,
                         },
                         FileCacheChannelConfig {
                             name: "test-channel-2".to_string(),
                             extension: Some("extension".to_string()),
+                            default_ttl: None,
+                            max_bytes: None,
+                            max_entries: None,
+                            compression: None,
+                            encryption_key: None,
+                            memory_budget: None,
+                            schema_version: None,
+                            migration: None,
                         },
                     ]),
                 }),
+                storage: None,
+                database: None,
+                scheduler: None,
+                download: None,
+                upload: None,
+                logging: None,
+                metrics: None,
+                crash: None,
+                http_audit: None,
+                strict_init: false,
             },
             Arc::new(runtime),
         )
         .unwrap();
-        let runtime = service_exporter.runtime;
-        runtime
+        service_exporter.runtime
     }
 
     #[test]
     fn test_http() {
-        let runtime = initialize_runtime();
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = initialize_runtime(&directory);
         let response = await_test!(
             runtime
-                .execute_http(HttpEndpoint {
-                    path: "/search".to_string(),
-                    domain: "https://cn.bing.com".to_string(),
-                    body: None,
-                    timeout: Duration::from_secs(60),
-                    headers: None,
-                    path_params: None,
-                    query_params: Some(vec![("q".to_string(), "netease".to_string())]),
-                    method: HttpMethod::Get,
-                    requires_encryption: false,
-                    requires_decryption: false,
-                    user_agent: None,
-                    content_type: None,
-                })
+                .execute_http(
+                    HttpEndpoint::builder("https://cn.bing.com", "/search")
+                        .timeout(Duration::from_secs(60))
+                        .query_param("q", "netease")
+                        .build()
+                )
                 .unwrap()
         )
         .unwrap()
@@ -142,36 +205,624 @@ mod tests {
         // await_test!(async { loop {} });
     }
 
+    #[test]
+    fn test_enable_after_initialization() {
+        let runtime = ServiceRuntime::with_tokio_runtime(
+            RuntimeConfig::default(),
+            Arc::new(Runtime::new().unwrap()),
+        )
+        .unwrap();
+        assert!(runtime.http_client.read().is_none());
+        assert!(runtime.cookie_store.read().is_none());
+
+        let cookie_config = CookieConfig {
+            backend: CookieBackend::Memory,
+            cookie_path: None,
+            debounce_delay: Duration::from_secs(10),
+            auto_save_interval: None,
+            initial_cookies: None,
+            public_suffix_list_path: None,
+            max_cookies: None,
+            max_cookies_per_domain: None,
+            policies: vec![],
+            max_cookie_lifetime: None,
+            session_only: false,
+        };
+        let enable_cookie_store = |runtime: Arc<ServiceRuntime>| {
+            let cookie_config = cookie_config.clone();
+            runtime.clone().execute_block(async move {
+                runtime.enable_cookie_store(cookie_config).await
+            })
+        };
+        assert_ok!(enable_cookie_store(runtime.clone()));
+        assert!(runtime.cookie_store.read().is_some());
+        assert_err!(enable_cookie_store(runtime.clone()));
+
+        assert_ok!(runtime.enable_http(http_config()));
+        assert!(runtime.http_client.read().is_some());
+        assert_err!(runtime.enable_http(http_config()));
+    }
+
+    #[test]
+    fn test_concurrent_file_cache_enables() {
+        initialize_test_rkv();
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = ServiceRuntime::with_tokio_runtime(
+            RuntimeConfig::default(),
+            Arc::new(Runtime::new().unwrap()),
+        )
+        .unwrap();
+        let config = FileCacheConfig {
+            base_path: directory.path().join("file_cache").to_string_lossy().to_string(),
+            auto_save_interval: Duration::from_secs(60),
+            auto_create_channels: false,
+            channels: Some(vec![FileCacheChannelConfig {
+                name: "enable_race".to_string(),
+                extension: None,
+                default_ttl: None,
+                max_bytes: None,
+                max_entries: None,
+                compression: None,
+                encryption_key: None,
+                memory_budget: None,
+                schema_version: None,
+                migration: None,
+            }]),
+        };
+
+        let enable_runtime = runtime.clone();
+        let results = runtime.execute_block(async move {
+            let runtime = enable_runtime;
+            let (first, second) = tokio::join!(
+                runtime.enable_file_cache(config.clone()),
+                runtime.enable_file_cache(config)
+            );
+            [first, second]
+        });
+        // the later call is turned away before it built a factory of its own
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+        assert!(
+            results
+                .iter()
+                .any(|result| matches!(result, Err(InitError::Configuration(_))))
+        );
+        let job_name = auto_save_job_name("enable_race");
+        let jobs = runtime.job_scheduler.jobs();
+        assert_eq!(jobs.iter().filter(|job| job.name == job_name).count(), 1);
+    }
+
+    #[test]
+    fn test_cookie_maintenance() {
+        let path = std::env::temp_dir().join(format!(
+            "strawberry_runtime_cookie_{}.json",
+            std::process::id()
+        ));
+        let path = path.to_string_lossy().to_string();
+        let config = RuntimeConfig {
+            cookie: Some(CookieConfig {
+                backend: CookieBackend::File,
+                cookie_path: Some(path.clone()),
+                debounce_delay: Duration::from_secs(10),
+                auto_save_interval: None,
+                initial_cookies: None,
+                public_suffix_list_path: None,
+                max_cookies: None,
+                max_cookies_per_domain: None,
+                policies: vec![],
+                max_cookie_lifetime: None,
+                session_only: false,
+            }),
+            ..RuntimeConfig::default()
+        };
+        let runtime =
+            ServiceRuntime::with_tokio_runtime(config, Arc::new(Runtime::new().unwrap())).unwrap();
+        let mut cookie = Cookie::new_without_expires(
+            "example.com".to_string(),
+            "/".to_string(),
+            "remember".to_string(),
+            "value".to_string(),
+            false,
+            false,
+            None,
+        );
+        cookie.expires = Some(std::time::SystemTime::now() + Duration::from_secs(3600));
+        cookie.persistent = true;
+
+        let report = runtime.cookie_load_report().unwrap().unwrap();
+        assert!(report.is_clean());
+        let cookie_store = runtime.cookie_store.read().clone().unwrap();
+        let maintenance_runtime = runtime.clone();
+        runtime.execute_block(async move {
+            let runtime = maintenance_runtime;
+            cookie_store.set(cookie).await.unwrap();
+            assert!(runtime.cookie_has_unsaved_changes().unwrap());
+            runtime.cookie_persist_now().await.unwrap();
+            assert!(!runtime.cookie_has_unsaved_changes().unwrap());
+            assert!(std::path::Path::new(&path).exists());
+
+            let cookies = runtime.cookie_get_for_domain("example.com").await.unwrap();
+            assert_eq!(cookies.len(), 1);
+            runtime.cookie_clear_all().await.unwrap();
+            let cookies = runtime.cookie_get_for_domain("example.com").await.unwrap();
+            assert!(cookies.is_empty());
+            assert!(runtime.cookie_has_unsaved_changes().unwrap());
+            let _ = std::fs::remove_file(path);
+        });
+
+        let runtime = ServiceRuntime::with_tokio_runtime(
+            RuntimeConfig::default(),
+            Arc::new(Runtime::new().unwrap()),
+        )
+        .unwrap();
+        assert!(matches!(
+            runtime.cookie_has_unsaved_changes(),
+            Err(RuntimeError::NotConfigured(_))
+        ));
+    }
+
+    // echoes the path after a short delay, /fail cannot be reached
+    struct SlowServer {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl HttpClient for SlowServer {
+        fn set_encryption_provider(&mut self, _: Arc<dyn EncryptionProvider>) {}
+        fn set_decryption_provider(&mut self, _: Arc<dyn DecryptionProvider>) {}
+        fn remove_encryption_provider(&mut self) -> Option<Arc<dyn EncryptionProvider>> {
+            None
+        }
+        fn remove_decryption_provider(&mut self) -> Option<Arc<dyn DecryptionProvider>> {
+            None
+        }
+
+        async fn execute(&self, endpoint: HttpEndpoint) -> Result<HttpResponse, HttpClientError> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            if endpoint.path == "/fail" {
+                return Err(HttpClientError::Network("unreachable".to_string()));
+            }
+            Ok(HttpResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: endpoint.path.into_bytes(),
+                final_url: None,
+                redirects: Vec::new(),
+                trailers: Vec::new(),
+            })
+        }
+
+        async fn execute_stream(
+            &self,
+            _: HttpEndpoint,
+        ) -> Result<HttpStreamResponse, HttpClientError> {
+            Err(HttpClientError::Configuration("not supported".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_http_batch() {
+        let runtime = ServiceRuntime::with_tokio_runtime(
+            RuntimeConfig::default(),
+            Arc::new(Runtime::new().unwrap()),
+        )
+        .unwrap();
+        let server = Arc::new(SlowServer {
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+        });
+        *runtime.http_client.write() = Some(server.clone());
+
+        let endpoints = ["/1", "/fail", "/3", "/4", "/5"]
+            .iter()
+            .map(|path| HttpEndpoint::builder("https://example.com", *path).build())
+            .collect();
+        let handle = runtime.execute_http_batch(endpoints, 2).unwrap();
+        let results = runtime.execute_block(handle).unwrap();
+
+        let bodies: Vec<Option<String>> = results
+            .into_iter()
+            .map(|result| result.ok().map(|response| response.text()))
+            .collect();
+        assert_eq!(
+            bodies,
+            vec![
+                Some("/1".to_string()),
+                None,
+                Some("/3".to_string()),
+                Some("/4".to_string()),
+                Some("/5".to_string()),
+            ]
+        );
+        assert_eq!(server.max_in_flight.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_init_report() {
+        // the public suffix list cannot be read, so the cookie store fails to start
+        let config = |strict_init: bool| RuntimeConfig {
+            http: Some(http_config()),
+            cookie: Some(CookieConfig {
+                backend: CookieBackend::Memory,
+                cookie_path: None,
+                debounce_delay: Duration::from_secs(10),
+                auto_save_interval: None,
+                initial_cookies: None,
+                public_suffix_list_path: Some("missing_public_suffix_list.dat".to_string()),
+                max_cookies: None,
+                max_cookies_per_domain: None,
+                policies: vec![],
+                max_cookie_lifetime: None,
+                session_only: false,
+            }),
+            strict_init,
+            ..RuntimeConfig::default()
+        };
+
+        let runtime =
+            ServiceRuntime::with_tokio_runtime(config(false), Arc::new(Runtime::new().unwrap()))
+                .unwrap();
+        let report = runtime.init_report();
+        assert!(!report.is_healthy());
+        assert!(matches!(
+            report.status("cookie_store"),
+            Some(SubsystemStatus::Failed(_))
+        ));
+        assert!(matches!(
+            report.status("http_client"),
+            Some(SubsystemStatus::Degraded(_))
+        ));
+        assert_eq!(report.status("file_cache"), Some(&SubsystemStatus::Disabled));
+        assert!(runtime.http_client.read().is_some());
+
+        assert!(
+            ServiceRuntime::with_tokio_runtime(config(true), Arc::new(Runtime::new().unwrap()))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_reconfigure() {
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = initialize_runtime(&directory);
+        let reconfiguration = || RuntimeReconfiguration {
+            http: Some(HttpReconfiguration {
+                request_timeout: Some(Duration::from_secs(5)),
+                ..Default::default()
+            }),
+            file_cache_auto_save_interval: Some(Duration::from_secs(30)),
+        };
+
+        // awaited on the runtime's own threads, like every call coming through the bridge
+        let reconfigure_runtime = runtime.clone();
+        runtime.execute_block(async move {
+            let runtime = reconfigure_runtime;
+            let factory = runtime.file_cache_manager_factory.read().clone().unwrap();
+            factory.get_with_name("test-channel-1").await.unwrap();
+            runtime.reconfigure(reconfiguration()).await.unwrap();
+
+            let job_name = auto_save_job_name("test-channel-1");
+            let job = runtime
+                .job_scheduler
+                .jobs()
+                .into_iter()
+                .find(|job| job.name == job_name)
+                .unwrap();
+            assert!(matches!(
+                job.schedule,
+                JobSchedule::Interval(interval) if interval == Duration::from_secs(30)
+            ));
+        });
+
+        // nothing is changed when one of the settings has no service to apply to
+        let runtime = ServiceRuntime::with_tokio_runtime(
+            RuntimeConfig::default(),
+            Arc::new(Runtime::new().unwrap()),
+        )
+        .unwrap();
+        runtime.enable_http(http_config()).unwrap();
+        let reconfigure_runtime = runtime.clone();
+        let result = runtime.execute_block(async move {
+            reconfigure_runtime.reconfigure(reconfiguration()).await
+        });
+        assert!(matches!(result, Err(RuntimeError::NotConfigured(_))));
+    }
+
+    #[test]
+    fn test_file_cache_archives_stay_in_allowed_roots() {
+        initialize_test_rkv();
+        let directory = tempfile::tempdir().unwrap();
+        let path = |name: &str| directory.path().join(name).to_string_lossy().to_string();
+        let config = RuntimeConfig {
+            storage: Some(StorageConfig {
+                key_provider: None,
+                temp_root: None,
+                temp_max_age: None,
+                allowed_roots: vec![directory.path().join("allowed")],
+                retry_policy: None,
+            }),
+            file_cache_config: Some(FileCacheConfig {
+                base_path: path("file_cache"),
+                auto_save_interval: Duration::from_secs(60),
+                auto_create_channels: true,
+                channels: None,
+            }),
+            ..RuntimeConfig::default()
+        };
+        let runtime =
+            ServiceRuntime::with_tokio_runtime(config, Arc::new(Runtime::new().unwrap())).unwrap();
+        let allowed = path("allowed/archive.tar");
+        let outside = path("archive.tar");
+
+        let archive_runtime = runtime.clone();
+        runtime.execute_block(async move {
+            let runtime = archive_runtime;
+            let factory = runtime.file_cache_manager_factory.read().clone().unwrap();
+            factory.get_with_name("archive_roots").await.unwrap();
+
+            let exported = runtime
+                .file_cache_export_channel("archive_roots", &outside)
+                .await;
+            assert!(matches!(
+                exported,
+                Err(RuntimeError::Storage(StorageError::AccessDenied(_)))
+            ));
+            assert!(!std::path::Path::new(&outside).exists());
+            let imported = runtime.file_cache_import_channel(&outside).await;
+            assert!(matches!(
+                imported,
+                Err(RuntimeError::Storage(StorageError::AccessDenied(_)))
+            ));
+
+            std::fs::create_dir_all(directory.path().join("allowed")).unwrap();
+            runtime
+                .file_cache_export_channel("archive_roots", &allowed)
+                .await
+                .unwrap();
+            assert!(std::path::Path::new(&allowed).exists());
+        });
+    }
+
+    // answers the requests in turn with the given responses and hands back their heads, in
+    // lowercase, once all of them were answered
+    fn scripted_server(responses: Vec<&'static str>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut heads = Vec::new();
+            for (response, stream) in responses.into_iter().zip(listener.incoming()) {
+                let mut stream = stream.unwrap();
+                let mut head = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !head.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    head.extend_from_slice(&buffer[..read]);
+                }
+                stream.write_all(response.as_bytes()).unwrap();
+                heads.push(String::from_utf8_lossy(&head).to_ascii_lowercase());
+            }
+            heads
+        });
+        (address, server)
+    }
+
+    // http and a file cache creating its channels on first use, kept in `directory`
+    fn http_cache_runtime(directory: &TempDir) -> Arc<ServiceRuntime> {
+        initialize_test_rkv();
+        let config = RuntimeConfig {
+            http: Some(http_config()),
+            file_cache_config: Some(FileCacheConfig {
+                base_path: directory.path().join("file_cache").to_string_lossy().to_string(),
+                auto_save_interval: Duration::from_secs(60),
+                auto_create_channels: true,
+                channels: None,
+            }),
+            ..RuntimeConfig::default()
+        };
+        ServiceRuntime::with_tokio_runtime(config, Arc::new(Runtime::new().unwrap())).unwrap()
+    }
+
+    #[test]
+    fn test_http_cached() {
+        let (address, server) = scripted_server(vec![
+            // stored with its validator, then revalidated
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello",
+            "HTTP/1.1 304 Not Modified\r\nETag: \"v2\"\r\nConnection: close\r\n\r\n",
+            // served from the cache while its max-age lasts
+            "HTTP/1.1 200 OK\r\nCache-Control: public, max-age=3600\r\nContent-Length: 5\r\n\
+             Connection: close\r\n\r\nfresh",
+            // nothing to revalidate with, fetched again
+            "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nfirst",
+            "HTTP/1.1 200 OK\r\nContent-Length: 6\r\nConnection: close\r\n\r\nsecond",
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = http_cache_runtime(&directory);
+
+        let cached_runtime = runtime.clone();
+        runtime.execute_block(async move {
+            let runtime = cached_runtime;
+            let cached = |path: &str| {
+                let endpoint = HttpEndpoint::builder(address.clone(), path).build();
+                runtime.http_cached(endpoint, "http_cached", path.to_string())
+            };
+
+            let response = cached("/validated").await.unwrap();
+            assert_eq!(response.text(), "hello");
+            let response = cached("/validated").await.unwrap();
+            assert_eq!(response.status, 200);
+            assert_eq!(response.text(), "hello");
+            assert_eq!(response.header("etag"), Some("\"v2\""));
+
+            for _ in 0..2 {
+                let response = cached("/fresh").await.unwrap();
+                assert_eq!(response.text(), "fresh");
+            }
+
+            assert_eq!(cached("/unvalidated").await.unwrap().text(), "first");
+            assert_eq!(cached("/unvalidated").await.unwrap().text(), "second");
+
+            let response = cached("/missing").await.unwrap();
+            assert_eq!(response.status, 404);
+            let factory = runtime.file_cache_manager_factory.read().clone().unwrap();
+            let channel = factory.get_with_name("http_cached").await.unwrap();
+            assert!(channel.record("/missing").await.is_err());
+        });
+
+        let heads = server.join().unwrap();
+        assert_eq!(heads.len(), 6);
+        assert!(!heads[0].contains("if-none-match"));
+        assert!(heads[1].contains("if-none-match: \"v1\""));
+        assert!(heads[4].starts_with("get /unvalidated "));
+        assert!(!heads[4].contains("if-none-match"));
+    }
+
+    #[test]
+    fn test_http_to_cache() {
+        let (address, server) = scripted_server(vec![
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Type: text/plain\r\n\
+             Content-Length: 8\r\nConnection: close\r\n\r\nstreamed",
+            "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 6\r\n\
+             Connection: close\r\n\r\nbroken",
+        ]);
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = http_cache_runtime(&directory);
+
+        let cache_runtime = runtime.clone();
+        runtime.execute_block(async move {
+            let runtime = cache_runtime;
+            let to_cache = || {
+                let endpoint = HttpEndpoint::builder(address.clone(), "/entry").build();
+                runtime.http_to_cache(
+                    endpoint,
+                    "http_to_cache",
+                    "entry".to_string(),
+                    "sentence".to_string(),
+                )
+            };
+
+            let record = to_cache().await.unwrap();
+            assert_eq!(
+                record.metadata,
+                vec![
+                    ("etag".to_string(), "\"v1\"".to_string()),
+                    ("content-type".to_string(), "text/plain".to_string()),
+                ]
+            );
+            // anything but a 2xx leaves the entry as it was
+            let result = to_cache().await;
+            assert!(matches!(
+                result,
+                Err(RuntimeError::Http(HttpClientError::Status(500)))
+            ));
+            let body = runtime.file_cache_fetch("http_to_cache", "entry").await.unwrap();
+            assert_eq!(body, b"streamed");
+        });
+        assert_eq!(server.join().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_http_refresh_cache() {
+        let (address, server) = scripted_server(vec![
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 3\r\nConnection: close\r\n\r\none",
+            "HTTP/1.1 304 Not Modified\r\nETag: \"v2\"\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nETag: \"v3\"\r\nContent-Length: 3\r\nConnection: close\r\n\r\ntwo",
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = http_cache_runtime(&directory);
+
+        let refresh_runtime = runtime.clone();
+        runtime.execute_block(async move {
+            let runtime = refresh_runtime;
+            let refresh = || {
+                let endpoint = HttpEndpoint::builder(address.clone(), "/entry").build();
+                runtime.http_refresh_cache(
+                    endpoint,
+                    "http_refresh_cache",
+                    "entry".to_string(),
+                    "sentence".to_string(),
+                )
+            };
+            let etag = |record: &CacheRecord| {
+                record
+                    .metadata
+                    .iter()
+                    .find(|(key, _)| key == "etag")
+                    .map(|(_, value)| value.clone())
+            };
+            let body = || runtime.file_cache_fetch("http_refresh_cache", "entry");
+
+            assert!(matches!(
+                refresh().await.unwrap(),
+                CacheRefresh::Updated(record) if etag(&record).as_deref() == Some("\"v1\"")
+            ));
+            assert!(matches!(
+                refresh().await.unwrap(),
+                CacheRefresh::NotModified(record) if etag(&record).as_deref() == Some("\"v2\"")
+            ));
+            assert_eq!(body().await.unwrap(), b"one");
+            assert!(matches!(
+                refresh().await.unwrap(),
+                CacheRefresh::Updated(record) if etag(&record).as_deref() == Some("\"v3\"")
+            ));
+            assert_eq!(body().await.unwrap(), b"two");
+
+            // a failed refresh keeps the entry
+            let result = refresh().await;
+            assert!(matches!(
+                result,
+                Err(RuntimeError::Http(HttpClientError::Status(503)))
+            ));
+            assert_eq!(body().await.unwrap(), b"two");
+        });
+
+        let heads = server.join().unwrap();
+        assert!(!heads[0].contains("if-none-match"));
+        assert!(heads[1].contains("if-none-match: \"v1\""));
+        assert!(heads[2].contains("if-none-match: \"v2\""));
+        assert!(heads[3].contains("if-none-match: \"v3\""));
+    }
+
     #[test]
     fn test_storage() {
-        let runtime = initialize_runtime();
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = initialize_runtime(&directory);
 
         let mut write_costs: Vec<f32> = Vec::new();
         let mut read_costs: Vec<f32> = Vec::new();
         for _ in 0..1000 {
-            let path = "storage_test.txt".to_string();
+            let path = directory
+                .path()
+                .join("storage_test.txt")
+                .to_string_lossy()
+                .to_string();
             let data = "http world, this is the storage test\n"
                 .repeat(10086 ^ 2)
                 .to_string()
                 .into_bytes();
 
             let current_time = SystemTime::now();
-            let _ = await_test!(runtime.write_file(WriteFile {
+            await_test!(runtime.write_file(WriteFile {
                 path: path.clone(),
                 data: &data,
                 mode: WriteMode::Cover,
                 timeout: Duration::from_secs(60),
-                ensure_mode: Some(EnsureMode::SyncAll)
+                ensure_mode: Some(EnsureMode::SyncAll),
+                compression: None,
             }))
-            .unwrap()
             .unwrap();
 
             write_costs.push(current_time.elapsed().unwrap().as_millis() as f32);
 
             let current_time = SystemTime::now();
-            let read_data = await_test!(runtime.read_file(ReadFile::path(path)))
-                .unwrap()
-                .unwrap();
+            let read_data = await_test!(runtime.read_file(ReadFile::path(path))).unwrap();
 
             read_costs.push(current_time.elapsed().unwrap().as_millis() as f32);
 
@@ -189,84 +840,102 @@ mod tests {
 
     #[test]
     fn test_file_cache_cache_fetch() {
-        let runtime = initialize_runtime();
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = initialize_runtime(&directory);
 
         let data = "http world, this is the file cache test\n"
             .repeat(10086 ^ 2)
             .to_string()
             .into_bytes();
 
-        let factory = runtime.file_cache_manager_factory.clone().unwrap();
-        let channel1 = await_test!(factory.get_with_name(&"test-channel-1".to_string())).unwrap();
+        let factory = runtime.file_cache_manager_factory.read().clone().unwrap();
+        let channel1 = await_test!(factory.get_with_name("test-channel-1")).unwrap();
 
-        let _ =
-            await_test!(channel1.cache("test-tag".to_string(), "test-sentence".to_string(), &data))
-                .unwrap();
-        let fetched = await_test!(channel1.fetch(&"test-tag".to_string())).unwrap();
+        await_test!(channel1.cache("test-tag".to_string(), "test-sentence".to_string(), &data))
+            .unwrap();
+        let fetched = await_test!(channel1.fetch("test-tag")).unwrap();
 
         assert_eq!(data, fetched);
     }
 
     #[test]
     fn test_file_cache_fetch() {
-        let runtime = initialize_runtime();
+        let directory = tempfile::tempdir().unwrap();
+        // a channel of its own, the other tests persist the index of the configured ones
+        let name = "test-channel-fetch".to_string();
 
         let data = "http world, this is the file cache test\n"
             .repeat(10086 ^ 2)
             .to_string()
             .into_bytes();
 
-        let factory = runtime.file_cache_manager_factory.clone().unwrap();
-        let channel1 = await_test!(factory.get_with_name(&"test-channel-1".to_string())).unwrap();
+        {
+            let runtime = initialize_runtime(&directory);
+            let factory = runtime.file_cache_manager_factory.read().clone().unwrap();
+            let channel = await_test!(factory.create_with_name(name.clone(), None)).unwrap();
+            for i in 0..10 {
+                await_test!(channel.cache(
+                    format!("test-tag-{}", i),
+                    format!("test-sentence-{}", i),
+                    &data
+                ))
+                .unwrap();
+            }
+            await_test!(channel.persist()).unwrap();
+        }
+
+        let runtime = initialize_runtime(&directory);
+        let factory = runtime.file_cache_manager_factory.read().clone().unwrap();
+        let channel = await_test!(factory.create_with_name(name, None)).unwrap();
 
         for i in 0..10 {
-            let fetched = await_test!(channel1.fetch(&format!("test-tag-{}", i))).unwrap();
+            let fetched = await_test!(channel.fetch(&format!("test-tag-{}", i))).unwrap();
             assert_eq!(data, fetched);
         }
     }
 
     #[test]
     fn test_file_cache_cache_fetch_with_extension() {
-        let runtime = initialize_runtime();
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = initialize_runtime(&directory);
 
         let data = "http world, this is the file cache test\n"
             .repeat(10086 ^ 2)
             .to_string()
             .into_bytes();
 
-        let factory = runtime.file_cache_manager_factory.clone().unwrap();
-        let channel2 = await_test!(factory.get_with_name(&"test-channel-2".to_string())).unwrap();
+        let factory = runtime.file_cache_manager_factory.read().clone().unwrap();
+        let channel2 = await_test!(factory.get_with_name("test-channel-2")).unwrap();
 
-        let _ =
-            await_test!(channel2.cache("test-tag".to_string(), "test-sentence".to_string(), &data))
-                .unwrap();
-        let fetched = await_test!(channel2.fetch(&"test-tag".to_string())).unwrap();
+        await_test!(channel2.cache("test-tag".to_string(), "test-sentence".to_string(), &data))
+            .unwrap();
+        let fetched = await_test!(channel2.fetch("test-tag")).unwrap();
 
         assert_eq!(data, fetched);
     }
 
     #[test]
     fn test_file_cache_cache_flush() {
-        let runtime = initialize_runtime();
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = initialize_runtime(&directory);
 
         let data = "http world, this is the file cache test\n"
             .repeat(10086 ^ 2)
             .to_string()
             .into_bytes();
 
-        let factory = runtime.file_cache_manager_factory.clone().unwrap();
-        let channel1 = await_test!(factory.get_with_name(&"test-channel-1".to_string())).unwrap();
+        let factory = runtime.file_cache_manager_factory.read().clone().unwrap();
+        let channel1 = await_test!(factory.get_with_name("test-channel-1")).unwrap();
 
-        let _ =
-            await_test!(channel1.cache("test-tag".to_string(), "test-sentence".to_string(), &data))
-                .unwrap();
+        await_test!(channel1.cache("test-tag".to_string(), "test-sentence".to_string(), &data))
+            .unwrap();
 
-        let fetched = await_test!(channel1.fetch(&"test-tag".to_string())).unwrap();
+        let fetched = await_test!(channel1.fetch("test-tag")).unwrap();
         assert_eq!(data, fetched);
 
-        let _ = await_test!(channel1.flush(&"test-tag".to_string())).unwrap();
+        await_test!(channel1.flush("test-tag")).unwrap();
 
-        let fetched = await_test!(channel1.fetch(&"test-tag".to_string()));
+        let fetched = await_test!(channel1.fetch("test-tag"));
         assert_err!(fetched);
     }
 
@@ -277,12 +946,13 @@ mod tests {
             .to_string()
             .into_bytes();
 
-        let runtime = initialize_runtime();
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = initialize_runtime(&directory);
         for i in 0..10 {
             {
-                let factory = runtime.file_cache_manager_factory.clone().unwrap();
+                let factory = runtime.file_cache_manager_factory.read().clone().unwrap();
                 let channel1 =
-                    await_test!(factory.get_with_name(&"test-channel-1".to_string())).unwrap();
+                    await_test!(factory.get_with_name("test-channel-1")).unwrap();
 
                 await_test!(channel1.cache(
                     format!("test-tag-{}", i),
@@ -298,11 +968,11 @@ mod tests {
                 assert_ok!(persist);
             }
             // {
-            //     let runtime = initialize_runtime();
+            //     let runtime = initialize_runtime(&directory);
             //
-            //     let factory = runtime.file_cache_manager_factory.clone().unwrap();
+            //     let factory = runtime.file_cache_manager_factory.read().clone().unwrap();
             //     let channel1 =
-            //         await_test!(factory.get_with_name(&"test-channel-1".to_string())).unwrap();
+            //         await_test!(factory.get_with_name("test-channel-1")).unwrap();
             //
             //     let fetched = await_test!(channel1.fetch(&format!("test-tag-{}", i)));
             //     assert_ok!(&fetched);
@@ -320,14 +990,15 @@ mod tests {
             .to_string()
             .into_bytes();
 
-        let runtime = initialize_runtime();
+        let directory = tempfile::tempdir().unwrap();
+        let runtime = initialize_runtime(&directory);
 
         for _ in 0..2 {
-            let factory = runtime.file_cache_manager_factory.clone().unwrap();
+            let factory = runtime.file_cache_manager_factory.read().clone().unwrap();
             let channel1 =
-                await_test!(factory.get_with_name(&"test-channel-1".to_string())).unwrap();
+                await_test!(factory.get_with_name("test-channel-1")).unwrap();
 
-            let _ = await_test!(channel1.cache(
+            await_test!(channel1.cache(
                 format!("test-tag-auto-save-{}", 0),
                 format!("test-sentence-auto-save-{}", 0),
                 &data
@@ -340,14 +1011,13 @@ mod tests {
 
             sleep(Duration::from_secs(10));
         }
-
-        await_test!(async { loop {} });
     }
 
     #[test]
     fn test_download_coordinator() {
-        let service_runtime = initialize_runtime();
-        let tokio_runtime = service_runtime.tokio_runtime.clone();
+        let directory = tempfile::tempdir().unwrap();
+        let service_runtime = initialize_runtime(&directory);
+        let _tokio_runtime = service_runtime.tokio_runtime.clone();
 
         {
             let runner_configuration_1 = RunnerConfiguration {
@@ -485,7 +1155,7 @@ mod tests {
     impl Categorizer for TestCategorizer {
         fn categorize(&self, request: &Request) -> Result<String, CategorizerError> {
             let identifier = &request.identifier;
-            if identifier.id == "second-request".to_string() {
+            if identifier.id == "second-request" {
                 return Ok("second-request-requires-specific-runner".to_string());
             }
             Ok("omnipotence".to_string())
@@ -507,9 +1177,9 @@ mod tests {
             let mut status = { self.status.lock().clone() };
             if status == RunnerStatus::Busy {
                 let mut current = self.test_cycle_count.lock();
-                *current = current.clone() + 1;
+                *current += 1;
 
-                if current.clone() >= self.test_cycle_threshold {
+                if *current >= self.test_cycle_threshold {
                     println!("Runner {}: change status to idle", self.identifier);
                     *self.status.lock() = RunnerStatus::Idle;
                     status = RunnerStatus::Idle;
@@ -523,7 +1193,7 @@ mod tests {
             })
         }
 
-        fn submit(&self, request: Request, watcher: Arc<dyn RunnerWatcher>) -> Result<(), RunnerError> {
+        fn submit(&self, request: Request, _watcher: Arc<dyn RunnerWatcher>) -> Result<(), RunnerError> {
             println!(
                 "Runner {}: working on {}",
                 self.identifier, request.identifier
@@ -1,25 +1,94 @@
-use crate::domain::models::file_cache_models::CacheError;
+use crate::domain::models::cookie_models::{Cookie, CookieError, CookieLoadReport};
+use crate::domain::models::database_models::{
+    DatabaseError, DatabaseStatement, DatabaseValue, ExecuteResult, QueryResult,
+};
+use crate::domain::models::download_models::{
+    DownloadError, DownloadEvent, DownloadRequest, DownloadTask,
+};
+use crate::domain::models::file_cache_models::{
+    CacheDiagnostic, CacheError, CacheRecord, CacheRecordFilter, CacheRefresh, CacheStats,
+};
 use crate::domain::models::http_models::{
-    HttpClientError, HttpEndpoint, HttpResponse, HttpStreamResponse,
+    HttpAuditRecord, HttpClientError, HttpEndpoint, HttpResponse, HttpStreamResponse,
+    LongPollOptions,
+};
+use crate::domain::models::crash_models::{CrashError, CrashReport};
+use crate::domain::models::logging_models::{LogRecord, LoggingError};
+use crate::domain::models::event_models::RuntimeEvent;
+use crate::domain::models::task_models::{TaskError, TaskInfo};
+use crate::domain::models::init_models::{InitReport, SubsystemStatus};
+use crate::domain::models::metrics_models::MetricsSnapshot;
+use crate::domain::models::scheduler_models::{JobError, JobInfo, JobSchedule};
+use crate::domain::models::upload_models::{UploadError, UploadEvent, UploadRequest, UploadTask};
+use crate::domain::models::storage_models::{
+    DeleteFile, DirEntry, DiskUsage, FileHash, FileMetadata, HashAlgorithm, ReadFile, StorageError,
+    StorageEvent, TransferFile, WriteFile,
 };
-use crate::domain::models::storage_models::{ReadFile, StorageError, WriteFile};
-use crate::domain::traits::cookie_traits::CookieStore;
-use crate::domain::traits::file_cache_traits::FileCacheManagerFactory;
-use crate::domain::traits::http_traits::HttpClient;
-use crate::domain::traits::storage_traits::StorageManager;
-use crate::infrastructure::http::cookie_backend::FileBackedCookieStore;
-use crate::infrastructure::http::reqwest_backend::ReqwestBackend;
+use crate::domain::traits::cookie_traits::{CookieStore, CookieStoreFactory};
+use crate::domain::traits::database_traits::DatabaseManager;
+use crate::domain::traits::download_traits::DownloadManager;
+use crate::domain::traits::file_cache_traits::{
+    CacheFetcher, FileCacheManager, FileCacheManagerFactory, FileCacheObserver,
+};
+use crate::domain::traits::http_traits::{HttpClient, HttpLogger};
+use crate::domain::traits::crash_traits::CrashReporter;
+use crate::domain::traits::logging_traits::{LogManager, Logger};
+use crate::domain::traits::metrics_traits::MetricsRegistry;
+use crate::domain::traits::scheduler_traits::{Job, JobScheduler};
+use crate::domain::traits::storage_traits::{ProgressSink, StorageManager};
+use crate::domain::traits::upload_traits::UploadManager;
+#[cfg(feature = "sqlite")]
+use crate::infrastructure::database::sqlite_database_backend::SqliteDatabaseManager;
+use crate::infrastructure::http::cookie_backend::{
+    FileBackedCookieStore, DefaultCookieStoreFactory,
+};
+use crate::infrastructure::http::memory_cookie_store::MemoryCookieStore;
+use crate::infrastructure::http::http_logger::CompositeHttpLogger;
+use crate::infrastructure::http::reloadable_http_client::ReloadableHttpClient;
+use crate::infrastructure::crash::crash_backend::FileCrashReporter;
+use crate::infrastructure::logging::log_manager::DefaultLogManager;
+use crate::infrastructure::logging::tracing_backend::TracingLogger;
+use crate::infrastructure::metrics::metrics_backend::DefaultMetricsRegistry;
+#[cfg(feature = "sqlite")]
+use crate::infrastructure::http::sqlite_cookie_store::SqliteCookieStore;
+use crate::infrastructure::storage::encrypted_storage_backend::EncryptedStorageManager;
+use crate::infrastructure::storage::retrying_storage_backend::RetryingStorageManager;
 use crate::infrastructure::storage::storage_backend::AsyncStorageManager;
+use crate::monitor::crash_service::{initialize_crash_reporter, release_crash_reporter};
+use crate::monitor::metrics_service::initialize_metrics;
 use crate::service::config::{
-    CookieConfig, FileCacheConfig, HttpConfig, RuntimeConfig,
+    CookieBackend, CookieConfig, CrashConfig, DatabaseConfig, DownloadConfig,
+    FileCacheChannelConfig, FileCacheConfig, HttpAuditConfig, HttpConfig, LogFileConfig,
+    LoggingConfig,
+    MetricsConfig, RuntimeConfig, RuntimeReconfiguration, SchedulerConfig, StorageConfig,
+    UploadConfig,
 };
+use crate::superstructure::download_manager::DefaultDownloadManager;
 use crate::superstructure::file_cache_backend::{
-    DefaultFileCacheManager, SingletonFileCacheManagerFactory,
+    DefaultFileCacheManager, SingletonFileCacheManagerFactory, check_channel_name,
 };
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex};
+use crate::superstructure::job_scheduler::{DefaultJobScheduler, FnJob};
+use crate::superstructure::metrics_exporter::MetricsExportJob;
+use crate::superstructure::api_client::ApiClient;
+use crate::superstructure::event_bus::EventBus;
+use crate::superstructure::http_audit::HttpAuditTrail;
+use crate::superstructure::long_poll::long_poll;
+use crate::superstructure::task_registry::{TaskHandle, TaskRegistry};
+use crate::superstructure::upload_manager::DefaultUploadManager;
+use parking_lot::RwLock;
+use bytes::Bytes;
+use futures_util::{StreamExt, TryStreamExt};
+use futures_util::stream::BoxStream;
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncRead;
 use tokio::runtime::Runtime;
+use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
+use tokio_util::io::StreamReader;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, thiserror::Error)]
 pub enum InitError {
@@ -31,20 +100,176 @@ pub enum InitError {
     Configuration(String),
     #[error("File Cache initialization failed: {0}")]
     FileCacheInit(String),
+    #[error("Database initialization failed: {0}")]
+    DatabaseInit(String),
+    #[error("Job Scheduler initialization failed: {0}")]
+    SchedulerInit(String),
+    #[error("Download Manager initialization failed: {0}")]
+    DownloadInit(String),
+    #[error("Upload Manager initialization failed: {0}")]
+    UploadInit(String),
+    #[error("Logging initialization failed: {0}")]
+    LoggingInit(String),
+    #[error("Invalid configuration: {}", .0.join("; "))]
+    Validation(Vec<String>),
 }
 
+// a single error for every service call, so callers match once instead of unwrapping twice
 #[derive(Debug, thiserror::Error)]
-pub enum ServiceError {
+pub enum RuntimeError {
     #[error("{0} service is not configured")]
     NotConfigured(String),
+    #[error(transparent)]
+    Http(#[from] HttpClientError),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+    #[error(transparent)]
+    Cache(#[from] CacheError),
+    #[error(transparent)]
+    Cookie(#[from] CookieError),
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+    #[error(transparent)]
+    Download(#[from] DownloadError),
+    #[error(transparent)]
+    Upload(#[from] UploadError),
+    #[error(transparent)]
+    Logging(#[from] LoggingError),
+    #[error(transparent)]
+    Crash(#[from] CrashError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ShutdownError {
+    #[error(transparent)]
+    Cookie(#[from] CookieError),
+    #[error(transparent)]
+    Cache(#[from] CacheError),
+    #[error(transparent)]
+    Job(#[from] JobError),
+    #[error(transparent)]
+    Download(#[from] DownloadError),
+    #[error(transparent)]
+    Upload(#[from] UploadError),
+    #[error(transparent)]
+    Logging(#[from] LoggingError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LifecycleError {
+    #[error(transparent)]
+    Cookie(#[from] CookieError),
+    #[error(transparent)]
+    Cache(#[from] CacheError),
+    #[error(transparent)]
+    Logging(#[from] LoggingError),
+}
+
+// names of the jobs the runtime registers itself
+pub const COOKIE_AUTO_SAVE_JOB: &str = "cookie_auto_save";
+pub const TEMP_CLEANUP_JOB: &str = "storage_temp_cleanup";
+pub const METRICS_EXPORT_JOB: &str = "metrics_export";
+
+// response headers kept in the metadata of a cache entry, the first two act as validators
+const CACHED_HEADERS: [&str; 3] = ["etag", "last-modified", "content-type"];
+
+fn kept_headers(headers: &[(String, String)]) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter(|(key, _)| CACHED_HEADERS.contains(&key.to_ascii_lowercase().as_str()))
+        .map(|(key, value)| (key.to_ascii_lowercase(), value.clone()))
+        .collect()
+}
+
+// asks the server to answer 304 while the kept validators still match
+fn conditional_headers(kept: &[(String, String)]) -> Vec<(String, String)> {
+    kept.iter()
+        .filter_map(|(key, value)| {
+            let conditional_key = match key.as_str() {
+                "etag" => "If-None-Match",
+                "last-modified" => "If-Modified-Since",
+                _ => return None,
+            };
+            Some((conditional_key.to_string(), value.clone()))
+        })
+        .collect()
+}
+
+// the kept headers go to the metadata of the entry
+async fn store_response(
+    cache_manager: &Arc<dyn FileCacheManager>,
+    response: HttpStreamResponse,
+    tag: String,
+    sentence: String,
+) -> Result<CacheRecord, RuntimeError> {
+    let metadata = kept_headers(&response.headers);
+    let reader = StreamReader::new(
+        response
+            .stream
+            .map_err(|e| std::io::Error::other(e.to_string())),
+    );
+    cache_manager
+        .cache_stream(tag.clone(), sentence, Box::pin(reader))
+        .await?;
+    if !metadata.is_empty() {
+        cache_manager.update_metadata(&tag, metadata).await?;
+    }
+    Ok(cache_manager.record(&tag).await?)
+}
+
+// how long a response may be served without asking the server, from Cache-Control: max-age
+fn max_age(headers: &[(String, String)]) -> Option<Duration> {
+    headers
+        .iter()
+        .filter(|(key, _)| key.eq_ignore_ascii_case("cache-control"))
+        .flat_map(|(_, value)| value.split(','))
+        .find_map(|directive| {
+            let (name, seconds) = directive.trim().split_once('=')?;
+            if !name.trim().eq_ignore_ascii_case("max-age") {
+                return None;
+            }
+            seconds.trim().parse().ok().map(Duration::from_secs)
+        })
+}
+
+// the stored record and body of a cached response
+type CachedResponse = (CacheRecord, Vec<u8>);
+
+// "GET https://example.com/search", the query is left out as it may carry secrets
+fn http_task_name(endpoint: &HttpEndpoint) -> String {
+    format!(
+        "{} {}{}",
+        endpoint.method.as_str(),
+        endpoint.domain,
+        endpoint.path
+    )
 }
 
 pub struct ServiceRuntime {
     pub tokio_runtime: Arc<Runtime>,
-    pub http_client: Option<Arc<dyn HttpClient>>,
-    pub cookie_auto_save_handle: Option<Arc<Mutex<JoinHandle<()>>>>,
+    // these can be enabled after initialization, so they sit behind a lock
+    pub http_client: RwLock<Option<Arc<dyn HttpClient>>>,
+    // the same client as http_client, kept to reconfigure it
+    pub reloadable_http_client: RwLock<Option<Arc<ReloadableHttpClient>>>,
+    // also audits the clients enabled later
+    pub http_audit: Option<Arc<HttpAuditTrail>>,
+    pub cookie_store: RwLock<Option<Arc<dyn CookieStore>>>,
+    pub cookie_store_factory: RwLock<Option<Arc<dyn CookieStoreFactory>>>,
     pub storage_manager: Option<Arc<dyn StorageManager>>,
-    pub file_cache_manager_factory: Option<Arc<dyn FileCacheManagerFactory>>,
+    pub file_cache_manager_factory: RwLock<Option<Arc<dyn FileCacheManagerFactory>>>,
+    pub database_manager: Option<Arc<dyn DatabaseManager>>,
+    pub job_scheduler: Arc<dyn JobScheduler>,
+    pub download_manager: Option<Arc<dyn DownloadManager>>,
+    pub upload_manager: Option<Arc<dyn UploadManager>>,
+    pub logger: Option<Arc<dyn Logger>>,
+    pub log_manager: Option<Arc<dyn LogManager>>,
+    pub metrics_registry: Option<Arc<dyn MetricsRegistry>>,
+    pub crash_reporter: Option<Arc<dyn CrashReporter>>,
+    pub task_registry: Arc<TaskRegistry>,
+    pub event_bus: Arc<EventBus>,
+    pub init_report: InitReport,
+    reconfigure_lock: tokio::sync::Mutex<()>,
+    enable_file_cache_lock: tokio::sync::Mutex<()>,
 }
 
 impl ServiceRuntime {
@@ -52,264 +277,1794 @@ impl ServiceRuntime {
         config: RuntimeConfig,
         tokio_runtime: Arc<Runtime>,
     ) -> Result<Arc<Self>, InitError> {
-        let cookie_store_initialization =
-            Self::initialize_cookie_store(&tokio_runtime, config.cookie);
-        let optional_cookie_store_initialization: Option<(
-            Arc<dyn CookieStore>,
-            Arc<Mutex<JoinHandle<()>>>,
-        )>;
-        if cookie_store_initialization.is_ok() {
-            optional_cookie_store_initialization = Some(cookie_store_initialization?);
+        // every problem is reported at once, before anything touches the disk or network
+        config.validate().map_err(InitError::Validation)?;
+        let event_bus = Arc::new(EventBus::new());
+        // installed first so the other subsystems can log while they start
+        let log_manager = Self::initialize_log_manager(
+            &tokio_runtime,
+            config.logging.as_ref().and_then(|logging| logging.file.clone()),
+            Self::create_storage_manager(None)?,
+        )?;
+        let logger = Self::initialize_logging(config.logging, log_manager.clone())?;
+        let crash_reporter =
+            Self::initialize_crash_reporter(config.crash, Self::create_storage_manager(None)?);
+        // before the backends are created so their first operations are counted
+        let metrics_registry = Self::initialize_metrics(config.metrics.as_ref());
+        // the scheduler keeps its state outside the roots callers are restricted to
+        let job_scheduler = Self::initialize_job_scheduler(
+            &tokio_runtime,
+            config.scheduler.unwrap_or_default(),
+            Self::create_storage_manager(None)?,
+        )?;
+        // the profile stores save through jobs of the scheduler
+        let cookie_store_factory: Option<Arc<dyn CookieStoreFactory>> =
+            config.cookie.clone().map(|cookie_config| {
+                Arc::new(DefaultCookieStoreFactory::new(
+                    cookie_config,
+                    job_scheduler.clone(),
+                )) as Arc<dyn CookieStoreFactory>
+            });
+        let (cookie_store, cookie_store_status) =
+            Self::settle(config.strict_init, config.cookie.is_some(), || {
+                Self::initialize_cookie_store(&tokio_runtime, config.cookie)
+            })?;
+        Self::schedule_cookie_auto_save(
+            &tokio_runtime,
+            &job_scheduler,
+            cookie_store.as_ref(),
+            &event_bus,
+        )?;
+        if let Some(cookie_store) = &cookie_store {
+            event_bus.forward_cookie_changes(&tokio_runtime, cookie_store);
+        }
+
+        // the file cache has its own encryption, opens its files directly and lives outside
+        // the roots callers are restricted to
+        let (file_cache_manager_factory, file_cache_status) = Self::settle(
+            config.strict_init,
+            config.file_cache_config.is_some(),
+            || {
+                Self::initialize_file_cache(
+                    &tokio_runtime,
+                    config.file_cache_config,
+                    Self::create_storage_manager(None)?,
+                    job_scheduler.clone(),
+                )
+            },
+        )?;
+        if let Some(file_cache_manager_factory) = &file_cache_manager_factory {
+            Self::watch_file_cache(&tokio_runtime, &event_bus, file_cache_manager_factory);
+        }
+        // before the http client, which hands its requests to the audit trail
+        let http_audit = Self::initialize_http_audit(
+            &tokio_runtime,
+            config.http_audit,
+            file_cache_manager_factory.as_ref(),
+        )?;
+
+        let reloadable_http_client = if let Some(http_config) = config.http {
+            let http_client = Self::create_http_client(
+                http_config,
+                cookie_store.clone(),
+                cookie_store_factory.clone(),
+                http_audit.as_ref(),
+            )?;
+            Some(http_client)
         } else {
-            optional_cookie_store_initialization = None;
+            None
+        };
+        let http_client = reloadable_http_client
+            .clone()
+            .map(|http_client| http_client as Arc<dyn HttpClient>);
+
+        let storage_manager = Self::create_storage_manager(config.storage.as_ref())?;
+        Self::schedule_temp_cleanup(
+            &tokio_runtime,
+            &job_scheduler,
+            storage_manager.clone(),
+            config.storage.as_ref(),
+        )?;
+        Self::schedule_metrics_export(
+            &tokio_runtime,
+            &job_scheduler,
+            metrics_registry.as_ref(),
+            config.metrics,
+            http_client.clone(),
+            Self::create_storage_manager(None)?,
+        )?;
+        // the transfers are retried like every other storage user, but skip the encryption layer:
+        // a download resumes from the size metadata reports, which is that of the sealed file,
+        // and every appended chunk would re-seal the whole file
+        let storage_manager = Self::retrying_storage_manager(storage_manager, config.storage.as_ref());
+        let download_manager = Self::initialize_download_manager(
+            &tokio_runtime,
+            config.download,
+            http_client.clone(),
+            storage_manager.clone(),
+        )?;
+        let upload_manager = Self::initialize_upload_manager(
+            &tokio_runtime,
+            config.upload,
+            http_client.clone(),
+            storage_manager.clone(),
+        )?;
+        let storage_manager = Self::encrypting_storage_manager(storage_manager, config.storage);
+        let database_manager = Self::initialize_database(&tokio_runtime, config.database)?;
+
+        let http_status = match (&http_client, &cookie_store_status) {
+            (None, _) => SubsystemStatus::Disabled,
+            (Some(_), SubsystemStatus::Failed(e)) => {
+                SubsystemStatus::Degraded(format!("running without the cookie store: {}", e))
+            }
+            (Some(_), _) => SubsystemStatus::Started,
+        };
+        let mut init_report = InitReport::default();
+        init_report.record("logging", Self::started_if(logger.is_some()));
+        init_report.record("crash_reporter", Self::started_if(crash_reporter.is_some()));
+        init_report.record("metrics", Self::started_if(metrics_registry.is_some()));
+        init_report.record("cookie_store", cookie_store_status);
+        init_report.record("job_scheduler", SubsystemStatus::Started);
+        init_report.record("http_client", http_status);
+        init_report.record("file_cache", file_cache_status);
+        init_report.record("http_audit", Self::started_if(http_audit.is_some()));
+        init_report.record("storage", SubsystemStatus::Started);
+        init_report.record("database", Self::started_if(database_manager.is_some()));
+        init_report.record("download_manager", Self::started_if(download_manager.is_some()));
+        init_report.record("upload_manager", Self::started_if(upload_manager.is_some()));
+
+        Ok(Arc::new(Self {
+            tokio_runtime,
+            http_client: RwLock::new(http_client),
+            reloadable_http_client: RwLock::new(reloadable_http_client),
+            http_audit,
+            cookie_store: RwLock::new(cookie_store),
+            cookie_store_factory: RwLock::new(cookie_store_factory),
+            storage_manager: Some(storage_manager),
+            file_cache_manager_factory: RwLock::new(file_cache_manager_factory),
+            database_manager,
+            job_scheduler,
+            download_manager,
+            upload_manager,
+            logger,
+            log_manager,
+            metrics_registry,
+            crash_reporter,
+            task_registry: Arc::new(TaskRegistry::with_event_bus(event_bus.clone())),
+            event_bus,
+            init_report,
+            reconfigure_lock: tokio::sync::Mutex::new(()),
+            enable_file_cache_lock: tokio::sync::Mutex::new(()),
+        }))
+    }
+
+    pub fn init_report(&self) -> &InitReport {
+        &self.init_report
+    }
+
+    pub fn available_runtime(&self) -> Arc<Runtime> {
+        self.tokio_runtime.clone()
+    }
+
+    pub fn execute_block<F, R>(&self, future: F) -> R
+    where
+        F: Future<Output = R> + Send + 'static,
+        R: Send + 'static,
+    {
+        self.available_runtime().block_on(future)
+    }
+
+    pub fn execute_async_blocking<F, R>(&self, func: F) -> JoinHandle<R>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        self.available_runtime().spawn_blocking(func)
+    }
+
+    // the task is listed by list_tasks until it ends and can be stopped through cancel_task
+    pub fn execute_async<F>(&self, name: impl Into<String>, future: F) -> TaskHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.task_registry
+            .spawn(&self.tokio_runtime, name.into(), future)
+    }
+
+    // the id of a task started later by one of the *_as methods, so a caller that only gets
+    // the result can still cancel it, also while it has not started yet
+    pub fn reserve_task_id(&self) -> u64 {
+        self.task_registry.reserve()
+    }
+
+    pub fn execute_async_as<F>(
+        &self,
+        task_id: u64,
+        name: impl Into<String>,
+        future: F,
+    ) -> TaskHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.task_registry
+            .spawn_reserved(&self.tokio_runtime, task_id, name.into(), future)
+    }
+
+    fn reserved_http_client(&self, task_id: u64) -> Result<Arc<dyn HttpClient>, RuntimeError> {
+        let Some(client) = self.http_client.read().clone() else {
+            // nothing is spawned under the id
+            self.task_registry.release(task_id);
+            return Err(RuntimeError::NotConfigured("Http Client".to_string()));
+        };
+        Ok(client)
+    }
+
+    // cookie changes, cache evictions, auto-save failures, connectivity changes and finished
+    // tasks, including those of subsystems enabled later
+    pub fn subscribe_events(&self) -> broadcast::Receiver<RuntimeEvent> {
+        self.event_bus.subscribe()
+    }
+
+    pub fn list_tasks(&self) -> Vec<TaskInfo> {
+        self.task_registry.list()
+    }
+
+    pub fn cancel_task(&self, id: u64) -> Result<(), TaskError> {
+        self.task_registry.cancel(id)
+    }
+    
+    // the cookie store has to come first, an http client enabled before it runs without cookies
+    pub async fn enable_cookie_store(&self, config: CookieConfig) -> Result<(), InitError> {
+        if self.cookie_store.read().is_some() {
+            return Err(InitError::Configuration(
+                "the cookie store is already enabled".to_string(),
+            ));
+        }
+        config.validate().map_err(InitError::Validation)?;
+        let cookie_store_factory: Arc<dyn CookieStoreFactory> =
+            Arc::new(DefaultCookieStoreFactory::new(
+                config.clone(),
+                self.job_scheduler.clone(),
+            ));
+        let cookie_store = Self::create_cookie_store(config).await?;
+
+        {
+            // same order as enable_http
+            let http_client = self.http_client.read();
+            let mut current = self.cookie_store.write();
+            if http_client.is_some() {
+                return Err(InitError::Configuration(
+                    "the cookie store must be enabled before the http client".to_string(),
+                ));
+            }
+            if current.is_some() {
+                return Err(InitError::Configuration(
+                    "the cookie store is already enabled".to_string(),
+                ));
+            }
+            *current = Some(cookie_store.clone());
+            *self.cookie_store_factory.write() = Some(cookie_store_factory);
+        }
+        self.event_bus
+            .forward_cookie_changes(&self.tokio_runtime, &cookie_store);
+
+        let Some((interval, job)) =
+            Self::cookie_auto_save_job(Some(&cookie_store), &self.event_bus)
+        else {
+            return Ok(());
+        };
+        self.job_scheduler
+            .register(
+                COOKIE_AUTO_SAVE_JOB.to_string(),
+                JobSchedule::Interval(interval),
+                job,
+            )
+            .await
+            .map_err(|e| InitError::SchedulerInit(e.to_string()))
+    }
+
+    // uses the cookie store when one is enabled
+    pub fn enable_http(&self, config: HttpConfig) -> Result<(), InitError> {
+        config.validate().map_err(InitError::Validation)?;
+        let mut http_client = self.http_client.write();
+        if http_client.is_some() {
+            return Err(InitError::Configuration(
+                "the http client is already enabled".to_string(),
+            ));
+        }
+        let reloadable_http_client = Self::create_http_client(
+            config,
+            self.cookie_store.read().clone(),
+            self.cookie_store_factory.read().clone(),
+            self.http_audit.as_ref(),
+        )?;
+        *self.reloadable_http_client.write() = Some(reloadable_http_client.clone());
+        *http_client = Some(reloadable_http_client);
+        Ok(())
+    }
+
+    pub async fn enable_file_cache(&self, config: FileCacheConfig) -> Result<(), InitError> {
+        // held until the factory is stored, a second factory would register the auto-save
+        // jobs of its channels next to the ones of the first
+        let _guard = self.enable_file_cache_lock.lock().await;
+        if self.file_cache_manager_factory.read().is_some() {
+            return Err(InitError::Configuration(
+                "the file cache is already enabled".to_string(),
+            ));
+        }
+        config.validate().map_err(InitError::Validation)?;
+        let file_cache_manager_factory = Self::create_file_cache_factory(
+            config,
+            Self::create_storage_manager(None)?,
+            self.job_scheduler.clone(),
+        )
+        .await?;
+
+        let mut current = self.file_cache_manager_factory.write();
+        Self::watch_file_cache(
+            &self.tokio_runtime,
+            &self.event_bus,
+            &file_cache_manager_factory,
+        );
+        *current = Some(file_cache_manager_factory);
+        Ok(())
+    }
+
+    // changes the given settings in place, the tokio runtime and every other service keep
+    // running, nothing is changed when the http backend cannot be rebuilt or the file cache
+    // refuses the new interval
+    pub async fn reconfigure(&self, config: RuntimeReconfiguration) -> Result<(), RuntimeError> {
+        // one at a time, a prepared backend is based on the configuration it replaces
+        let _guard = self.reconfigure_lock.lock().await;
+        let reloadable_http_client = self.reloadable_http_client.read().clone();
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone();
+        if config.http.is_some() && reloadable_http_client.is_none() {
+            return Err(RuntimeError::NotConfigured("Http Client".to_string()));
+        }
+        if config.file_cache_auto_save_interval.is_some() && file_cache_manager_factory.is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let prepared = config
+            .http
+            .map(|http| reloadable_http_client.as_ref().unwrap().prepare(http))
+            .transpose()?;
+        if let Some(interval) = config.file_cache_auto_save_interval {
+            let file_cache_manager_factory = file_cache_manager_factory.unwrap();
+            file_cache_manager_factory
+                .set_auto_save_interval(interval)
+                .await?;
+        }
+        if let Some(prepared) = prepared {
+            reloadable_http_client.unwrap().commit(prepared);
+        }
+        Ok(())
+    }
+
+    // for the app moving to the background, the periodic loops stop and unsaved state is
+    // written right away since the process may be killed without further notice, downloads
+    // and uploads keep running as they were started by the user
+    pub async fn on_background(&self) -> Result<(), LifecycleError> {
+        self.job_scheduler.pause_all();
+        let cookie_store = self.cookie_store.read().clone();
+        if let Some(cookie_store) = cookie_store {
+            let file_backend_cookie_store = cookie_store
+                .clone()
+                .downcast_arc::<FileBackedCookieStore>();
+            if let Some(file_backend_cookie_store) = file_backend_cookie_store {
+                file_backend_cookie_store.flush().await?;
+            }
+        }
+        let cookie_store_factory = self.cookie_store_factory.read().clone();
+        if let Some(cookie_store_factory) = cookie_store_factory {
+            cookie_store_factory.suspend().await?;
+        }
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone();
+        if let Some(file_cache_manager_factory) = file_cache_manager_factory {
+            file_cache_manager_factory.persist_all().await?;
+        }
+        if let Some(log_manager) = &self.log_manager {
+            log_manager.flush().await?;
+        }
+        Ok(())
+    }
+
+    // jobs paused one by one before going to the background stay paused
+    pub fn on_foreground(&self) {
+        let cookie_store_factory = self.cookie_store_factory.read().clone();
+        if let Some(cookie_store_factory) = cookie_store_factory {
+            cookie_store_factory.resume();
+        }
+        self.job_scheduler.resume_all();
+    }
+
+    pub async fn shutdown(&self) -> Result<(), ShutdownError> {
+        // stopped first so no job or task touches a subsystem that is shutting down
+        self.job_scheduler.shutdown().await?;
+        self.task_registry.cancel_all();
+        if let Some(download_manager) = &self.download_manager {
+            download_manager.shutdown().await?;
+        }
+        if let Some(upload_manager) = &self.upload_manager {
+            upload_manager.shutdown().await?;
+        }
+        let cookie_store = self.cookie_store.read().clone();
+        if let Some(cookie_store) = cookie_store {
+            let file_backend_cookie_store = cookie_store
+                .clone()
+                .downcast_arc::<FileBackedCookieStore>();
+            if let Some(file_backend_cookie_store) = file_backend_cookie_store {
+                file_backend_cookie_store.shutdown().await?;
+            }
+        }
+        let cookie_store_factory = self.cookie_store_factory.read().clone();
+        if let Some(cookie_store_factory) = cookie_store_factory {
+            cookie_store_factory.shutdown().await?;
+        }
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone();
+        if let Some(file_cache_manager_factory) = file_cache_manager_factory {
+            file_cache_manager_factory.shutdown().await?;
+        }
+        if let Some(crash_reporter) = &self.crash_reporter {
+            release_crash_reporter(crash_reporter);
+        }
+        // last so whatever the other subsystems logged while stopping reaches the file
+        if let Some(logger) = &self.logger {
+            logger.shutdown().await?;
+        }
+        if let Some(log_manager) = &self.log_manager {
+            log_manager.shutdown().await?;
+        }
+        Ok(())
+    }
+
+    fn default_cookie_store(&self) -> Result<Arc<dyn CookieStore>, RuntimeError> {
+        self.cookie_store
+            .read()
+            .clone()
+            .ok_or_else(|| RuntimeError::NotConfigured("Cookie Store".to_string()))
+    }
+
+    // for saving before the os may terminate the app, the profiles are saved by on_background
+    pub async fn cookie_persist_now(&self) -> Result<(), RuntimeError> {
+        let cookie_store = self.default_cookie_store()?;
+        match cookie_store.clone().downcast_arc::<FileBackedCookieStore>() {
+            Some(file_backend_cookie_store) => file_backend_cookie_store.persist_now().await?,
+            None => cookie_store.persist().await?,
+        }
+        Ok(())
+    }
+
+    // only a file backed store holds changes back, the others save them as they are made
+    pub fn cookie_has_unsaved_changes(&self) -> Result<bool, RuntimeError> {
+        let cookie_store = self.default_cookie_store()?;
+        Ok(cookie_store
+            .downcast_arc::<FileBackedCookieStore>()
+            .is_some_and(|file_backend_cookie_store| file_backend_cookie_store.is_dirty()))
+    }
+
+    // None for a store that has no file to load, a report that is not clean means stored
+    // cookies were lost and the user is likely signed out
+    pub fn cookie_load_report(&self) -> Result<Option<CookieLoadReport>, RuntimeError> {
+        let cookie_store = self.default_cookie_store()?;
+        Ok(cookie_store
+            .downcast_arc::<FileBackedCookieStore>()
+            .and_then(|file_backend_cookie_store| file_backend_cookie_store.load_report()))
+    }
+
+    // the cookies of the profiles are left alone
+    pub async fn cookie_clear_all(&self) -> Result<(), RuntimeError> {
+        let cookie_store = self.default_cookie_store()?;
+        cookie_store.clear_all().await;
+        Ok(())
+    }
+
+    pub async fn cookie_get_for_domain(&self, domain: &str) -> Result<Vec<Cookie>, RuntimeError> {
+        let cookie_store = self.default_cookie_store()?;
+        Ok(cookie_store.get_for_domain(domain).await)
+    }
+
+    pub fn log_records(&self) -> Result<broadcast::Receiver<LogRecord>, RuntimeError> {
+        if self.logger.is_none() {
+            return Err(RuntimeError::NotConfigured("Logging".to_string()));
+        }
+
+        let logger = self.logger.as_ref().unwrap();
+        Ok(logger.records())
+    }
+
+    pub async fn http_audit_records(&self) -> Result<Vec<HttpAuditRecord>, RuntimeError> {
+        if self.http_audit.is_none() {
+            return Err(RuntimeError::NotConfigured("Http Audit".to_string()));
+        }
+
+        let http_audit = self.http_audit.as_ref().unwrap();
+        Ok(http_audit.records().await?)
+    }
+
+    pub async fn take_pending_crash_reports(&self) -> Result<Vec<CrashReport>, RuntimeError> {
+        if self.crash_reporter.is_none() {
+            return Err(RuntimeError::NotConfigured("Crash Reporter".to_string()));
+        }
+
+        let crash_reporter = self.crash_reporter.as_ref().unwrap();
+        Ok(crash_reporter.take_pending().await?)
+    }
+
+    pub fn metrics_snapshot(&self) -> Result<MetricsSnapshot, RuntimeError> {
+        if self.metrics_registry.is_none() {
+            return Err(RuntimeError::NotConfigured("Metrics".to_string()));
+        }
+
+        let metrics_registry = self.metrics_registry.as_ref().unwrap();
+        Ok(metrics_registry.snapshot())
+    }
+
+    pub async fn collect_logs(&self, since: Option<u64>) -> Result<String, RuntimeError> {
+        if self.log_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Log Manager".to_string()));
+        }
+
+        let log_manager = self.log_manager.as_ref().unwrap();
+        Ok(log_manager.collect(since).await?)
+    }
+
+    pub fn file_cache_add_observer(
+        &self,
+        observer: Arc<dyn FileCacheObserver>,
+    ) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        file_cache_manager_factory.add_observer(observer);
+        Ok(())
+    }
+
+    pub fn file_cache_diagnostics(
+        &self,
+    ) -> Result<broadcast::Receiver<CacheDiagnostic>, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        Ok(file_cache_manager_factory.subscribe_diagnostics())
+    }
+
+    // sends through the runtime's http client, so cookies, proxies and providers apply
+    pub fn api_client(&self, domain: impl Into<String>) -> Result<ApiClient, RuntimeError> {
+        let Some(client) = self.http_client.read().clone() else {
+            return Err(RuntimeError::NotConfigured("Http Client".to_string()));
+        };
+        Ok(ApiClient::new(client, domain))
+    }
+
+    pub fn execute_http(
+        &self,
+        endpoint: HttpEndpoint,
+    ) -> Result<TaskHandle<Result<HttpResponse, HttpClientError>>, RuntimeError> {
+        self.execute_http_as(self.reserve_task_id(), endpoint)
+    }
+
+    pub fn execute_http_as(
+        &self,
+        task_id: u64,
+        endpoint: HttpEndpoint,
+    ) -> Result<TaskHandle<Result<HttpResponse, HttpClientError>>, RuntimeError> {
+        let client = self.reserved_http_client(task_id)?;
+        let name = http_task_name(&endpoint);
+        let event_bus = self.event_bus.clone();
+        Ok(self.execute_async_as(task_id, name, async move {
+            let result = client.execute(endpoint).await;
+            event_bus.observe_http(&result);
+            result
+        }))
+    }
+
+    // at most max_concurrency requests are in flight, the results come in the order of the
+    // endpoints and a failed request does not stop the others
+    pub fn execute_http_batch(
+        &self,
+        endpoints: Vec<HttpEndpoint>,
+        max_concurrency: usize,
+    ) -> Result<TaskHandle<Vec<Result<HttpResponse, HttpClientError>>>, RuntimeError> {
+        self.execute_http_batch_as(self.reserve_task_id(), endpoints, max_concurrency)
+    }
+
+    pub fn execute_http_batch_as(
+        &self,
+        task_id: u64,
+        endpoints: Vec<HttpEndpoint>,
+        max_concurrency: usize,
+    ) -> Result<TaskHandle<Vec<Result<HttpResponse, HttpClientError>>>, RuntimeError> {
+        let client = self.reserved_http_client(task_id)?;
+        let name = format!("http batch of {}", endpoints.len());
+        let event_bus = self.event_bus.clone();
+        Ok(self.execute_async_as(task_id, name, async move {
+            futures_util::stream::iter(endpoints)
+                .map(|endpoint| {
+                    let client = client.clone();
+                    let event_bus = event_bus.clone();
+                    async move {
+                        let result = client.execute(endpoint).await;
+                        event_bus.observe_http(&result);
+                        result
+                    }
+                })
+                .buffered(max_concurrency.max(1))
+                .collect()
+                .await
+        }))
+    }
+
+    // see long_poll, the stream keeps polling until the token is cancelled or it is dropped
+    pub fn execute_long_poll(
+        &self,
+        endpoint: HttpEndpoint,
+        options: LongPollOptions,
+        token: CancellationToken,
+    ) -> Result<BoxStream<'static, Result<HttpResponse, HttpClientError>>, RuntimeError> {
+        if self.http_client.read().is_none() {
+            return Err(RuntimeError::NotConfigured("Http Client".to_string()));
+        }
+        let client = self.http_client.read().clone().unwrap();
+        let event_bus = self.event_bus.clone();
+        let stream = long_poll(client, endpoint, options, token)
+            .inspect(move |result| event_bus.observe_http(result));
+        Ok(Box::pin(stream))
+    }
+
+    pub fn execute_stream_http(
+        &self,
+        endpoint: HttpEndpoint,
+    ) -> Result<TaskHandle<Result<HttpStreamResponse, HttpClientError>>, RuntimeError> {
+        self.execute_stream_http_as(self.reserve_task_id(), endpoint)
+    }
+
+    pub fn execute_stream_http_as(
+        &self,
+        task_id: u64,
+        endpoint: HttpEndpoint,
+    ) -> Result<TaskHandle<Result<HttpStreamResponse, HttpClientError>>, RuntimeError> {
+        let client = self.reserved_http_client(task_id)?;
+        let name = http_task_name(&endpoint);
+        let event_bus = self.event_bus.clone();
+        Ok(self.execute_async_as(task_id, name, async move {
+            let result = client.execute_stream(endpoint).await;
+            event_bus.observe_http(&result);
+            result
+        }))
+    }
+
+    // a path the caller hands in for code that opens it without the storage manager
+    async fn resolve_caller_path(&self, path: &str) -> Result<String, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.resolve_path(path.to_string()).await?)
+    }
+
+    pub async fn read_file(&self, read_file: ReadFile) -> Result<Vec<u8>, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.read(read_file).await?)
+    }
+
+    pub async fn write_file<'a>(&self, write_file: WriteFile<'a>) -> Result<(), RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.write(write_file).await?)
+    }
+
+    pub async fn read_files(
+        &self,
+        read_files: Vec<ReadFile>,
+        parallelism: usize,
+    ) -> Result<Vec<Result<Vec<u8>, StorageError>>, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.read_many(read_files, parallelism).await)
+    }
+
+    pub async fn write_files<'a>(
+        &self,
+        write_files: Vec<WriteFile<'a>>,
+        parallelism: usize,
+    ) -> Result<Vec<Result<(), StorageError>>, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.write_many(write_files, parallelism).await)
+    }
+
+    pub async fn read_file_verified(
+        &self,
+        read_file: ReadFile,
+        expected: FileHash,
+    ) -> Result<Vec<u8>, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.read_verified(read_file, expected).await?)
+    }
+
+    pub async fn write_file_hashed<'a>(
+        &self,
+        write_file: WriteFile<'a>,
+        algorithm: HashAlgorithm,
+    ) -> Result<FileHash, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.write_hashed(write_file, algorithm).await?)
+    }
+
+    pub async fn delete_file(&self, delete_file: DeleteFile) -> Result<(), RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.delete(delete_file).await?)
+    }
+
+    pub async fn rename_file(&self, transfer_file: TransferFile) -> Result<(), RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.rename(transfer_file).await?)
+    }
+
+    pub async fn copy_file(&self, transfer_file: TransferFile) -> Result<(), RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.copy(transfer_file).await?)
+    }
+
+    pub async fn move_file(&self, transfer_file: TransferFile) -> Result<(), RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.move_file(transfer_file).await?)
+    }
+
+    pub async fn copy_file_with_progress(
+        &self,
+        transfer_file: TransferFile,
+        progress_sink: ProgressSink,
+    ) -> Result<(), RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.copy_with_progress(transfer_file, progress_sink).await?)
+    }
+
+    pub async fn create_dir(&self, path: String, recursive: bool) -> Result<(), RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.create_dir(path, recursive).await?)
+    }
+
+    pub async fn list_dir(&self, path: String) -> Result<Vec<DirEntry>, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.list_dir(path).await?)
+    }
+
+    pub async fn remove_dir(&self, path: String, recursive: bool) -> Result<(), RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.remove_dir(path, recursive).await?)
+    }
+
+    pub async fn read_file_range(
+        &self,
+        path: String,
+        offset: u64,
+        len: u64,
+    ) -> Result<Vec<u8>, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.read_range(path, offset, len).await?)
+    }
+
+    pub async fn read_file_stream(
+        &self,
+        path: String,
+        chunk_size: usize,
+    ) -> Result<BoxStream<'static, Result<Bytes, StorageError>>, RuntimeError>
+    {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.read_stream(path, chunk_size).await?)
+    }
+
+    pub async fn write_file_stream(
+        &self,
+        path: String,
+        stream: BoxStream<'static, Result<Bytes, StorageError>>,
+    ) -> Result<u64, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.write_stream(path, stream).await?)
+    }
+
+    pub async fn append_line(&self, path: String, line: Vec<u8>) -> Result<(), RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.append_line(path, line).await?)
+    }
+
+    pub async fn read_lines(
+        &self,
+        path: String,
+        range: Range<usize>,
+    ) -> Result<Vec<Vec<u8>>, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.read_lines(path, range).await?)
+    }
+
+    pub async fn register_job(
+        &self,
+        name: String,
+        schedule: JobSchedule,
+        job: Arc<dyn Job>,
+    ) -> Result<(), JobError> {
+        self.job_scheduler.register(name, schedule, job).await
+    }
+
+    pub fn unregister_job(&self, name: &str) -> Result<(), JobError> {
+        self.job_scheduler.unregister(name)
+    }
+
+    pub fn pause_job(&self, name: &str) -> Result<(), JobError> {
+        self.job_scheduler.pause(name)
+    }
+
+    pub fn resume_job(&self, name: &str) -> Result<(), JobError> {
+        self.job_scheduler.resume(name)
+    }
+
+    pub fn pause_jobs(&self) {
+        self.job_scheduler.pause_all()
+    }
+
+    pub fn resume_jobs(&self) {
+        self.job_scheduler.resume_all()
+    }
+
+    pub fn run_job_now(&self, name: &str) -> Result<(), JobError> {
+        self.job_scheduler.run_now(name)
+    }
+
+    pub fn jobs(&self) -> Vec<JobInfo> {
+        self.job_scheduler.jobs()
+    }
+
+    pub async fn enqueue_download(&self, request: DownloadRequest) -> Result<String, RuntimeError> {
+        if self.download_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Download Manager".to_string()));
+        }
+
+        let download_manager = self.download_manager.as_ref().unwrap();
+        Ok(download_manager.enqueue(request).await?)
+    }
+
+    pub async fn pause_download(&self, id: &str) -> Result<(), RuntimeError> {
+        if self.download_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Download Manager".to_string()));
+        }
+
+        let download_manager = self.download_manager.as_ref().unwrap();
+        Ok(download_manager.pause(id).await?)
+    }
+
+    pub async fn resume_download(&self, id: &str) -> Result<(), RuntimeError> {
+        if self.download_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Download Manager".to_string()));
+        }
+
+        let download_manager = self.download_manager.as_ref().unwrap();
+        Ok(download_manager.resume(id).await?)
+    }
+
+    pub async fn cancel_download(&self, id: &str) -> Result<(), RuntimeError> {
+        if self.download_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Download Manager".to_string()));
+        }
+
+        let download_manager = self.download_manager.as_ref().unwrap();
+        Ok(download_manager.cancel(id).await?)
+    }
+
+    pub async fn remove_download(&self, id: &str) -> Result<(), RuntimeError> {
+        if self.download_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Download Manager".to_string()));
+        }
+
+        let download_manager = self.download_manager.as_ref().unwrap();
+        Ok(download_manager.remove(id).await?)
+    }
+
+    pub fn downloads(&self) -> Result<Vec<DownloadTask>, RuntimeError> {
+        if self.download_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Download Manager".to_string()));
+        }
+
+        let download_manager = self.download_manager.as_ref().unwrap();
+        Ok(download_manager.list())
+    }
+
+    pub fn download_events(&self) -> Result<broadcast::Receiver<DownloadEvent>, RuntimeError> {
+        if self.download_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Download Manager".to_string()));
+        }
+
+        let download_manager = self.download_manager.as_ref().unwrap();
+        Ok(download_manager.events())
+    }
+
+    pub async fn enqueue_upload(&self, request: UploadRequest) -> Result<String, RuntimeError> {
+        if self.upload_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Upload Manager".to_string()));
+        }
+
+        let upload_manager = self.upload_manager.as_ref().unwrap();
+        Ok(upload_manager.enqueue(request).await?)
+    }
+
+    pub async fn pause_upload(&self, id: &str) -> Result<(), RuntimeError> {
+        if self.upload_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Upload Manager".to_string()));
+        }
+
+        let upload_manager = self.upload_manager.as_ref().unwrap();
+        Ok(upload_manager.pause(id).await?)
+    }
+
+    pub async fn resume_upload(&self, id: &str) -> Result<(), RuntimeError> {
+        if self.upload_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Upload Manager".to_string()));
+        }
+
+        let upload_manager = self.upload_manager.as_ref().unwrap();
+        Ok(upload_manager.resume(id).await?)
+    }
+
+    pub async fn cancel_upload(&self, id: &str) -> Result<(), RuntimeError> {
+        if self.upload_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Upload Manager".to_string()));
+        }
+
+        let upload_manager = self.upload_manager.as_ref().unwrap();
+        Ok(upload_manager.cancel(id).await?)
+    }
+
+    pub async fn remove_upload(&self, id: &str) -> Result<(), RuntimeError> {
+        if self.upload_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Upload Manager".to_string()));
+        }
+
+        let upload_manager = self.upload_manager.as_ref().unwrap();
+        Ok(upload_manager.remove(id).await?)
+    }
+
+    pub fn uploads(&self) -> Result<Vec<UploadTask>, RuntimeError> {
+        if self.upload_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Upload Manager".to_string()));
+        }
+
+        let upload_manager = self.upload_manager.as_ref().unwrap();
+        Ok(upload_manager.list())
+    }
+
+    pub fn upload_events(&self) -> Result<broadcast::Receiver<UploadEvent>, RuntimeError> {
+        if self.upload_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Upload Manager".to_string()));
+        }
+
+        let upload_manager = self.upload_manager.as_ref().unwrap();
+        Ok(upload_manager.events())
+    }
+
+    pub async fn database_query(
+        &self,
+        sql: String,
+        params: Vec<DatabaseValue>,
+    ) -> Result<QueryResult, RuntimeError> {
+        if self.database_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Database".to_string()));
+        }
+
+        let database_manager = self.database_manager.as_ref().unwrap();
+        Ok(database_manager.query(sql, params).await?)
+    }
+
+    pub async fn database_execute(
+        &self,
+        sql: String,
+        params: Vec<DatabaseValue>,
+    ) -> Result<ExecuteResult, RuntimeError> {
+        if self.database_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Database".to_string()));
+        }
+
+        let database_manager = self.database_manager.as_ref().unwrap();
+        Ok(database_manager.execute(sql, params).await?)
+    }
+
+    pub async fn database_execute_batch(&self, sql: String) -> Result<(), RuntimeError> {
+        if self.database_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Database".to_string()));
+        }
+
+        let database_manager = self.database_manager.as_ref().unwrap();
+        Ok(database_manager.execute_batch(sql).await?)
+    }
+
+    pub async fn database_transaction(
+        &self,
+        statements: Vec<DatabaseStatement>,
+    ) -> Result<Vec<ExecuteResult>, RuntimeError> {
+        if self.database_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Database".to_string()));
+        }
+
+        let database_manager = self.database_manager.as_ref().unwrap();
+        Ok(database_manager.transaction(statements).await?)
+    }
+
+    pub async fn watch_path(
+        &self,
+        path: String,
+        debounce: Duration,
+    ) -> Result<BoxStream<'static, StorageEvent>, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.watch(path, debounce).await?)
+    }
+
+    pub async fn disk_usage(&self, path: String) -> Result<DiskUsage, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.disk_usage(path).await?)
+    }
+
+    pub async fn dir_size(&self, path: String) -> Result<u64, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.dir_size(path).await?)
+    }
+
+    pub async fn create_temp_file(
+        &self,
+        prefix: String,
+        extension: Option<String>,
+    ) -> Result<String, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.create_temp_file(prefix, extension).await?)
+    }
+
+    pub async fn unique_path(
+        &self,
+        dir: String,
+        base: String,
+        extension: Option<String>,
+    ) -> Result<String, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.unique_path(dir, base, extension).await?)
+    }
+
+    pub async fn file_exists(&self, path: String) -> Result<bool, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.exists(path).await?)
+    }
+
+    pub async fn file_metadata(&self, path: String) -> Result<FileMetadata, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.metadata(path).await?)
+    }
+
+    pub async fn is_file(&self, path: String) -> Result<bool, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.is_file(path).await?)
+    }
+
+    pub async fn is_dir(&self, path: String) -> Result<bool, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
+        }
+
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.is_dir(path).await?)
+    }
+
+    // returns how many temp files were removed, on top of the scheduled cleanup
+    pub async fn cleanup_temp_files(&self, older_than: Duration) -> Result<u64, RuntimeError> {
+        if self.storage_manager.is_none() {
+            return Err(RuntimeError::NotConfigured("Storage Manager".to_string()));
         }
 
-        let mut cookie_store: Option<Arc<dyn CookieStore>> = None;
-        let mut cookie_auto_save_handle: Option<Arc<Mutex<JoinHandle<()>>>> = None;
+        let storage_manager = self.storage_manager.as_ref().unwrap();
+        Ok(storage_manager.cleanup_temp_files(older_than).await?)
+    }
 
-        if optional_cookie_store_initialization.is_some() {
-            let cookie_store_initialize = optional_cookie_store_initialization.unwrap();
-            cookie_store = Some(cookie_store_initialize.0);
-            cookie_auto_save_handle = Some(cookie_store_initialize.1);
+    pub async fn file_cache_cache(
+        &self,
+        channel: &str,
+        tag: String,
+        sentence: String,
+        bytes: &[u8],
+    ) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
 
-        let http_client = if let Some(http_config) = config.http {
-            let http_client = Self::create_http_client(http_config, cookie_store)?;
-            Some(http_client)
-        } else {
-            None
-        };
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.cache(tag, sentence, bytes).await?)
+    }
 
-        let storage_manager = Self::create_storage_manager()?;
-        let file_cache_manager_factory = Self::initialize_file_cache(
-            &tokio_runtime,
-            config.file_cache_config,
-            storage_manager.clone(),
-        );
-        let optional_file_cache_manager_factory: Option<Arc<dyn FileCacheManagerFactory>>;
-        if file_cache_manager_factory.is_ok() {
-            optional_file_cache_manager_factory = Some(file_cache_manager_factory?);
-        } else {
-            println!("{}", file_cache_manager_factory.err().unwrap());
-            optional_file_cache_manager_factory = None;
+    pub async fn file_cache_cache_with_ttl(
+        &self,
+        channel: &str,
+        tag: String,
+        sentence: String,
+        bytes: &[u8],
+        ttl: Option<Duration>,
+    ) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
 
-        Ok(Arc::new(Self {
-            tokio_runtime,
-            http_client,
-            cookie_auto_save_handle,
-            storage_manager: Some(storage_manager),
-            file_cache_manager_factory: optional_file_cache_manager_factory,
-        }))
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.cache_with_ttl(tag, sentence, bytes, ttl).await?)
     }
 
-    pub fn available_runtime(&self) -> Arc<Runtime> {
-        self.tokio_runtime.clone()
+    pub async fn file_cache_sweep_expired(&self, channel: &str) -> Result<usize, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.sweep_expired().await?)
     }
 
-    pub fn execute_block<F, R>(&self, future: F) -> R
-    where
-        F: Future<Output = R> + Send + 'static,
-        R: Send + 'static,
-    {
-        self.available_runtime().block_on(future)
+    pub async fn file_cache_cache_stream(
+        &self,
+        channel: &str,
+        tag: String,
+        sentence: String,
+        reader: Pin<Box<dyn AsyncRead + Send>>,
+    ) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.cache_stream(tag, sentence, reader).await?)
     }
 
-    pub fn execute_async_blocking<F, R>(&self, func: F) -> JoinHandle<R>
-    where
-        F: FnOnce() -> R + Send + 'static,
-        R: Send + 'static,
+    pub async fn file_cache_fetch_stream(
+        &self,
+        channel: &str,
+        tag: &str,
+    ) -> Result<BoxStream<'static, Result<Bytes, CacheError>>, RuntimeError>
     {
-        self.available_runtime().spawn_blocking(func)
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.fetch_stream(tag).await?)
     }
 
-    pub fn execute_async<F>(&self, future: F) -> JoinHandle<F::Output>
-    where
-        F: Future + Send + 'static,
-        F::Output: Send + 'static,
-    {
-        self.available_runtime().spawn(future)
+    pub async fn file_cache_should_update(
+        &self,
+        channel: &str,
+        tag: &str,
+        sentence: &str,
+    ) -> Result<bool, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.should_update(tag, sentence).await?)
     }
-    
-    pub fn execute_http(
+
+    pub async fn file_cache_remove_if_stale(
         &self,
-        endpoint: HttpEndpoint,
-    ) -> Result<JoinHandle<Result<HttpResponse, HttpClientError>>, ServiceError> {
-        if self.http_client.is_none() {
-            return Err(ServiceError::NotConfigured("Http Client".to_string()));
+        channel: &str,
+        tag: &str,
+        sentence: &str,
+    ) -> Result<bool, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
-        let client = self.http_client.as_ref().unwrap().clone();
-        Ok(self.execute_async(async move { client.execute(endpoint).await }))
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.remove_if_stale(tag, sentence).await?)
     }
 
-    pub fn execute_stream_http(
+    pub async fn http_cached(
         &self,
         endpoint: HttpEndpoint,
-    ) -> Result<JoinHandle<Result<HttpStreamResponse, HttpClientError>>, ServiceError> {
-        if self.http_client.is_none() {
-            return Err(ServiceError::NotConfigured("Http Client".to_string()));
+        channel: &str,
+        tag: String,
+    ) -> Result<HttpResponse, RuntimeError> {
+        if self.http_client.read().is_none() {
+            return Err(RuntimeError::NotConfigured("Http Client".to_string()));
         }
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let client = self.http_client.read().clone().unwrap();
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+
+        // an expired, missing or unreadable entry is treated as a miss
+        let mut cached: Option<CachedResponse> = None;
+        if let Ok(record) = cache_manager.record(&tag).await
+            && let Ok(body) = cache_manager.fetch(&tag).await
+        {
+            cached = Some((record, body));
+        }
+
+        let sentence = http_task_name(&endpoint);
+        let mut endpoint = endpoint;
+        if let Some((record, body)) = &cached {
+            let conditional_headers = conditional_headers(&record.metadata);
+            if !conditional_headers.is_empty() {
+                endpoint
+                    .headers
+                    .get_or_insert_with(Vec::new)
+                    .extend(conditional_headers);
+            } else if record.expires_at.is_some() {
+                // nothing to revalidate with, the entry stays valid until its ttl runs out, one
+                // without a ttl is fetched again
+                return Ok(HttpResponse {
+                    status: 200,
+                    headers: record.metadata.clone(),
+                    body: body.clone(),
+                    final_url: None,
+                    redirects: Vec::new(),
+                    trailers: Vec::new(),
+                });
+            }
+        }
+
+        let response = client.execute(endpoint).await;
+        self.event_bus.observe_http(&response);
+        let response = response?;
 
-        let client = self.http_client.as_ref().unwrap().clone();
-        Ok(self.execute_async(async move { client.execute_stream(endpoint).await }))
+        if response.status == 304
+            && let Some((_, body)) = cached
+        {
+            // a 304 may carry fresher validators than the ones stored
+            let validators = kept_headers(&response.headers);
+            if !validators.is_empty() {
+                cache_manager.update_metadata(&tag, validators).await?;
+            }
+            let record = cache_manager.record(&tag).await?;
+            return Ok(HttpResponse {
+                status: 200,
+                headers: record.metadata,
+                body,
+                final_url: response.final_url,
+                redirects: response.redirects,
+                trailers: response.trailers,
+            });
+        }
+
+        if (200..300).contains(&response.status) {
+            let metadata = kept_headers(&response.headers);
+            // a response without validators is only served again within its max-age, one
+            // with them is revalidated instead
+            let ttl = if conditional_headers(&metadata).is_empty() {
+                max_age(&response.headers)
+            } else {
+                None
+            };
+            cache_manager
+                .cache_with_ttl(tag.clone(), sentence, &response.body, ttl)
+                .await?;
+            if !metadata.is_empty() {
+                cache_manager.update_metadata(&tag, metadata).await?;
+            }
+        }
+        Ok(response)
     }
 
-    pub async fn read_file(
+    // the body goes to the cache file as it arrives, the validators and Content-Type are kept
+    // in the metadata of the entry, anything but a 2xx leaves the cache untouched
+    pub async fn http_to_cache(
         &self,
-        read_file: ReadFile,
-    ) -> Result<Result<Vec<u8>, StorageError>, ServiceError> {
-        if self.storage_manager.is_none() {
-            return Err(ServiceError::NotConfigured("Storage Manager".to_string()));
+        endpoint: HttpEndpoint,
+        channel: &str,
+        tag: String,
+        sentence: String,
+    ) -> Result<CacheRecord, RuntimeError> {
+        if self.http_client.read().is_none() {
+            return Err(RuntimeError::NotConfigured("Http Client".to_string()));
+        }
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
 
-        let storage_manager = self.storage_manager.as_ref().unwrap();
-        Ok(storage_manager.read(read_file).await)
+        let client = self.http_client.read().clone().unwrap();
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+
+        let response = client.execute_stream(endpoint).await;
+        self.event_bus.observe_http(&response);
+        let response = response?;
+        if !(200..300).contains(&response.status) {
+            return Err(HttpClientError::Status(response.status).into());
+        }
+        store_response(&cache_manager, response, tag, sentence).await
     }
 
-    pub async fn write_file<'a>(
+    // like http_to_cache, but an existing entry is sent along as If-None-Match and
+    // If-Modified-Since from its metadata, a 304 keeps the entry and only refreshes them
+    pub async fn http_refresh_cache(
         &self,
-        write_file: WriteFile<'a>,
-    ) -> Result<Result<(), StorageError>, ServiceError> {
-        if self.storage_manager.is_none() {
-            return Err(ServiceError::NotConfigured("Storage Manager".to_string()));
+        endpoint: HttpEndpoint,
+        channel: &str,
+        tag: String,
+        sentence: String,
+    ) -> Result<CacheRefresh, RuntimeError> {
+        if self.http_client.read().is_none() {
+            return Err(RuntimeError::NotConfigured("Http Client".to_string()));
+        }
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
 
-        let storage_manager = self.storage_manager.as_ref().unwrap();
-        Ok(storage_manager.write(write_file).await)
+        let client = self.http_client.read().clone().unwrap();
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+
+        // an expired or missing entry is fetched unconditionally
+        let cached = cache_manager.record(&tag).await.ok();
+        let mut endpoint = endpoint;
+        if let Some(record) = &cached {
+            endpoint
+                .headers
+                .get_or_insert_with(Vec::new)
+                .extend(conditional_headers(&record.metadata));
+        }
+
+        let response = client.execute_stream(endpoint).await;
+        self.event_bus.observe_http(&response);
+        let response = response?;
+        if response.status == 304 && cached.is_some() {
+            let validators = kept_headers(&response.headers);
+            if !validators.is_empty() {
+                cache_manager.update_metadata(&tag, validators).await?;
+            }
+            return Ok(CacheRefresh::NotModified(cache_manager.record(&tag).await?));
+        }
+        if !(200..300).contains(&response.status) {
+            return Err(HttpClientError::Status(response.status).into());
+        }
+        let record = store_response(&cache_manager, response, tag, sentence).await?;
+        Ok(CacheRefresh::Updated(record))
     }
 
-    pub async fn file_cache_cache(
+    pub async fn file_cache_get_or_put(
         &self,
-        channel: &String,
+        channel: &str,
         tag: String,
         sentence: String,
-        bytes: &Vec<u8>,
-    ) -> Result<Result<(), CacheError>, ServiceError> {
-        if self.file_cache_manager_factory.is_none() {
-            return Err(ServiceError::NotConfigured("File Cache".to_string()));
+        fetcher: CacheFetcher,
+    ) -> Result<Vec<u8>, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
 
-        let file_cache_manager_factory = self.file_cache_manager_factory.as_ref().unwrap();
-        let cache_manager = file_cache_manager_factory.get_with_name(channel).await;
-        if cache_manager.is_err() {
-            return Ok(cache_manager.map(|_| ()));
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.get_or_put(tag, sentence, fetcher).await?)
+    }
+
+    pub async fn file_cache_fetch(
+        &self,
+        channel: &str,
+        tag: &str,
+    ) -> Result<Vec<u8>, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
-        let cache_manager = cache_manager.unwrap();
-        Ok(cache_manager.cache(tag, sentence, bytes).await)
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.fetch(tag).await?)
     }
 
-    pub async fn file_cache_should_update(
+    pub async fn file_cache_flush(
         &self,
-        channel: &String,
-        tag: &String,
-        sentence: &String,
-    ) -> Result<Result<bool, CacheError>, ServiceError> {
-        if self.file_cache_manager_factory.is_none() {
-            return Err(ServiceError::NotConfigured("File Cache".to_string()));
+        channel: &str,
+        tag: &str,
+    ) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
 
-        let file_cache_manager_factory = self.file_cache_manager_factory.as_ref().unwrap();
-        let cache_manager = file_cache_manager_factory.get_with_name(channel).await;
-        if cache_manager.is_err() {
-            return Ok(cache_manager.map(|_| false));
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.flush(tag).await?)
+    }
+
+    pub async fn file_cache_persist(&self, channel: &str) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
-        let cache_manager = cache_manager.unwrap();
-        Ok(cache_manager.should_update(tag, sentence).await)
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.persist().await?)
     }
 
-    pub async fn file_cache_fetch(
+    pub async fn file_cache_clear(&self, channel: &str) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.clear().await?)
+    }
+
+    pub async fn file_cache_create_channel(
         &self,
-        channel: &String,
-        tag: &String,
-    ) -> Result<Result<Vec<u8>, CacheError>, ServiceError> {
-        if self.file_cache_manager_factory.is_none() {
-            return Err(ServiceError::NotConfigured("File Cache".to_string()));
+        channel_config: FileCacheChannelConfig,
+    ) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
+        check_channel_name(&channel_config.name)?;
 
-        let file_cache_manager_factory = self.file_cache_manager_factory.as_ref().unwrap();
-        let cache_manager = file_cache_manager_factory.get_with_name(channel).await;
-        if cache_manager.is_err() {
-            return Ok(cache_manager.map(|_| vec![]));
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        file_cache_manager_factory
+            .create_with_options(channel_config)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn file_cache_delete_channel(&self, channel: &str) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
-        let cache_manager = cache_manager.unwrap();
-        Ok(cache_manager.fetch(tag).await)
+        check_channel_name(channel)?;
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        Ok(file_cache_manager_factory.delete_channel(channel).await?)
     }
 
-    pub async fn file_cache_flush(
+    pub async fn file_cache_export_channel(
+        &self,
+        channel: &str,
+        path: &str,
+    ) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        // the archive is written directly, so the path is checked against the allowed roots
+        // like any other path a caller hands in
+        let path = self.resolve_caller_path(path).await?;
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        Ok(file_cache_manager_factory.export_channel(channel, &path).await?)
+    }
+
+    pub async fn file_cache_import_channel(&self, path: &str) -> Result<String, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let path = self.resolve_caller_path(path).await?;
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        Ok(file_cache_manager_factory.import_channel(&path).await?)
+    }
+
+    pub async fn file_cache_clear_all(&self) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        Ok(file_cache_manager_factory.clear_all().await?)
+    }
+
+    pub async fn file_cache_verify_all(
+        &self,
+        channel: &str,
+        purge: bool,
+    ) -> Result<Vec<String>, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.verify_all(purge).await?)
+    }
+
+    pub async fn file_cache_record(
         &self,
-        channel: &String,
-        tag: &String,
-    ) -> Result<Result<(), CacheError>, ServiceError> {
-        if self.file_cache_manager_factory.is_none() {
-            return Err(ServiceError::NotConfigured("File Cache".to_string()));
+        channel: &str,
+        tag: &str,
+    ) -> Result<CacheRecord, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.record(tag).await?)
+    }
+
+    pub async fn file_cache_pin(&self, channel: &str, tag: &str) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
 
-        let file_cache_manager_factory = self.file_cache_manager_factory.as_ref().unwrap();
-        let cache_manager = file_cache_manager_factory.get_with_name(channel).await;
-        if cache_manager.is_err() {
-            return Ok(cache_manager.map(|_| ()));
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.pin(tag).await?)
+    }
+
+    pub async fn file_cache_unpin(
+        &self,
+        channel: &str,
+        tag: &str,
+    ) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
-        let cache_manager = cache_manager.unwrap();
-        Ok(cache_manager.flush(tag).await)
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.unpin(tag).await?)
     }
 
-    pub async fn file_cache_persist(
+    pub async fn file_cache_update_metadata(
         &self,
-        channel: &String,
-    ) -> Result<Result<(), CacheError>, ServiceError> {
-        if self.file_cache_manager_factory.is_none() {
-            return Err(ServiceError::NotConfigured("File Cache".to_string()));
+        channel: &str,
+        tag: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<(), RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
 
-        let file_cache_manager_factory = self.file_cache_manager_factory.as_ref().unwrap();
-        let cache_manager = file_cache_manager_factory.get_with_name(channel).await;
-        if cache_manager.is_err() {
-            return Ok(cache_manager.map(|_| ()));
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.update_metadata(tag, metadata).await?)
+    }
+
+    pub async fn file_cache_stats(&self, channel: &str) -> Result<CacheStats, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
-        let cache_manager = cache_manager.unwrap();
-        Ok(cache_manager.persist().await)
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.stats().await?)
     }
 
-    pub async fn file_cache_path(
+    pub async fn file_cache_stats_all(&self) -> Result<Vec<CacheStats>, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
+        }
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        Ok(file_cache_manager_factory.stats_all().await?)
+    }
+
+    pub async fn file_cache_list(
         &self,
-        channel: &String,
-        tag: &String,
-    ) -> Result<Result<String, CacheError>, ServiceError> {
-        if self.file_cache_manager_factory.is_none() {
-            return Err(ServiceError::NotConfigured("File Cache".to_string()));
+        channel: &str,
+        filter: Option<CacheRecordFilter>,
+    ) -> Result<Vec<CacheRecord>, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
 
-        let file_cache_manager_factory = self.file_cache_manager_factory.as_ref().unwrap();
-        let cache_manager = file_cache_manager_factory.get_with_name(channel).await;
-        if cache_manager.is_err() {
-            return Ok(cache_manager.map(|_| "".to_string()));
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.list(filter).await?)
+    }
+
+    // the path of the cached file, for callers that read it themselves such as a native player
+    pub async fn file_cache_path(
+        &self,
+        channel: &str,
+        tag: &str,
+    ) -> Result<String, RuntimeError> {
+        if self.file_cache_manager_factory.read().is_none() {
+            return Err(RuntimeError::NotConfigured("File Cache".to_string()));
         }
-        let cache_manager = cache_manager.unwrap();
-        Ok(cache_manager.path(tag).await)
+
+        let file_cache_manager_factory = self.file_cache_manager_factory.read().clone().unwrap();
+        let cache_manager = file_cache_manager_factory.get_with_name(channel).await?;
+        Ok(cache_manager.path(tag).await?)
     }
 
     pub fn spawn_handle(&self) -> tokio::runtime::Handle {
         self.available_runtime().handle().clone()
     }
 
+    // evictions and failed auto-saves of the file cache go to the event bus
+    fn watch_file_cache(
+        tokio_runtime: &Runtime,
+        event_bus: &Arc<EventBus>,
+        file_cache_manager_factory: &Arc<dyn FileCacheManagerFactory>,
+    ) {
+        file_cache_manager_factory.add_observer(event_bus.clone());
+        event_bus.forward_cache_diagnostics(
+            tokio_runtime,
+            file_cache_manager_factory.subscribe_diagnostics(),
+        );
+    }
+
     fn initialize_file_cache(
         tokio_runtime: &Runtime,
         config: Option<FileCacheConfig>,
         storage_manager: Arc<dyn StorageManager>,
+        job_scheduler: Arc<dyn JobScheduler>,
     ) -> Result<Arc<dyn FileCacheManagerFactory>, InitError> {
         if config.is_none() {
             return Err(InitError::Configuration("config is null".to_string()));
         }
         let config = config.unwrap();
-        let factory = tokio_runtime
-            .block_on(async { Self::create_file_cache_factory(config, storage_manager).await })?;
+        let factory = tokio_runtime.block_on(Self::create_file_cache_factory(
+            config,
+            storage_manager,
+            job_scheduler,
+        ))?;
         Ok(factory)
     }
 
+    fn initialize_http_audit(
+        tokio_runtime: &Runtime,
+        config: Option<HttpAuditConfig>,
+        file_cache_manager_factory: Option<&Arc<dyn FileCacheManagerFactory>>,
+    ) -> Result<Option<Arc<HttpAuditTrail>>, InitError> {
+        // left out like everything else relying on a file cache that failed to start
+        let (Some(config), Some(file_cache_manager_factory)) = (config, file_cache_manager_factory)
+        else {
+            return Ok(None);
+        };
+        let cache_manager = tokio_runtime
+            .block_on(file_cache_manager_factory.create_with_name(config.channel.clone(), None))
+            .map_err(|e| InitError::FileCacheInit(e.to_string()))?;
+        Ok(Some(Arc::new(HttpAuditTrail::start(
+            tokio_runtime,
+            cache_manager,
+            config,
+        ))))
+    }
+
+    fn initialize_database(
+        tokio_runtime: &Runtime,
+        config: Option<DatabaseConfig>,
+    ) -> Result<Option<Arc<dyn DatabaseManager>>, InitError> {
+        let Some(config) = config else {
+            return Ok(None);
+        };
+        #[cfg(feature = "sqlite")]
+        {
+            let database_manager = tokio_runtime
+                .block_on(SqliteDatabaseManager::new(config))
+                .map_err(|e| InitError::DatabaseInit(e.to_string()))?;
+            Ok(Some(Arc::new(database_manager)))
+        }
+        #[cfg(not(feature = "sqlite"))]
+        {
+            let _ = (tokio_runtime, config);
+            Err(InitError::Configuration(
+                "the database requires the sqlite feature".to_string(),
+            ))
+        }
+    }
+
     fn initialize_cookie_store(
         tokio_runtime: &Runtime,
         config: Option<CookieConfig>,
-    ) -> Result<(Arc<dyn CookieStore>, Arc<Mutex<JoinHandle<()>>>), InitError> {
+    ) -> Result<Arc<dyn CookieStore>, InitError> {
         let cookie_store_option = if let Some(cookie_config) = config {
             Some(tokio_runtime.block_on(async {
                 let cookie_store = Self::create_cookie_store(cookie_config).await?;
@@ -319,39 +2074,226 @@ impl ServiceRuntime {
             return Err(InitError::Configuration("config is null".to_string()));
         };
 
-        let cookie_store = if let Some(cookie_store) = cookie_store_option {
-            if cookie_store.is_err() {
-                return Err(cookie_store.err().unwrap());
-            } else {
-                Some(cookie_store?)
+        match cookie_store_option {
+            Some(cookie_store) => cookie_store,
+            None => Err(InitError::Configuration("cookie store is null".to_string())),
+        }
+    }
+
+    fn initialize_download_manager(
+        tokio_runtime: &Runtime,
+        config: Option<DownloadConfig>,
+        http_client: Option<Arc<dyn HttpClient>>,
+        storage_manager: Arc<dyn StorageManager>,
+    ) -> Result<Option<Arc<dyn DownloadManager>>, InitError> {
+        let Some(config) = config else {
+            return Ok(None);
+        };
+        let Some(http_client) = http_client else {
+            return Err(InitError::Configuration(
+                "the download manager requires the http client".to_string(),
+            ));
+        };
+        let download_manager = tokio_runtime
+            .block_on(DefaultDownloadManager::new(config, http_client, storage_manager))
+            .map_err(|e| InitError::DownloadInit(e.to_string()))?;
+        Ok(Some(Arc::new(download_manager)))
+    }
+
+    // a failure stops initialization in strict mode, otherwise the runtime starts without
+    // the subsystem and the failure ends up in the init report
+    fn settle<T>(
+        strict_init: bool,
+        configured: bool,
+        initialize: impl FnOnce() -> Result<T, InitError>,
+    ) -> Result<(Option<T>, SubsystemStatus), InitError> {
+        if !configured {
+            return Ok((None, SubsystemStatus::Disabled));
+        }
+        match initialize() {
+            Ok(value) => Ok((Some(value), SubsystemStatus::Started)),
+            Err(e) if strict_init => Err(e),
+            Err(e) => {
+                tracing::warn!("{}", e);
+                Ok((None, SubsystemStatus::Failed(e.to_string())))
             }
+        }
+    }
+
+    fn started_if(enabled: bool) -> SubsystemStatus {
+        if enabled {
+            SubsystemStatus::Started
         } else {
-            return Err(InitError::Configuration("cookie store is null".to_string()));
+            SubsystemStatus::Disabled
+        }
+    }
+
+    fn initialize_crash_reporter(
+        config: Option<CrashConfig>,
+        storage_manager: Arc<dyn StorageManager>,
+    ) -> Option<Arc<dyn CrashReporter>> {
+        let config = config?;
+        let crash_reporter: Arc<dyn CrashReporter> =
+            Arc::new(FileCrashReporter::new(config, storage_manager));
+        initialize_crash_reporter(crash_reporter.clone());
+        Some(crash_reporter)
+    }
+
+    fn initialize_metrics(config: Option<&MetricsConfig>) -> Option<Arc<dyn MetricsRegistry>> {
+        let config = config?;
+        let registry: Arc<dyn MetricsRegistry> =
+            Arc::new(DefaultMetricsRegistry::new(config.histogram_buckets.clone()));
+        initialize_metrics(registry.clone());
+        Some(registry)
+    }
+
+    fn schedule_metrics_export(
+        tokio_runtime: &Runtime,
+        job_scheduler: &Arc<dyn JobScheduler>,
+        metrics_registry: Option<&Arc<dyn MetricsRegistry>>,
+        config: Option<MetricsConfig>,
+        http_client: Option<Arc<dyn HttpClient>>,
+        storage_manager: Arc<dyn StorageManager>,
+    ) -> Result<(), InitError> {
+        let (Some(metrics_registry), Some(export)) =
+            (metrics_registry, config.and_then(|config| config.export))
+        else {
+            return Ok(());
         };
+        let job = MetricsExportJob::new(
+            metrics_registry.clone(),
+            export.target,
+            storage_manager,
+            http_client,
+        );
+        tokio_runtime
+            .block_on(job_scheduler.register(
+                METRICS_EXPORT_JOB.to_string(),
+                JobSchedule::Interval(export.interval),
+                Arc::new(job),
+            ))
+            .map_err(|e| InitError::SchedulerInit(e.to_string()))
+    }
 
-        let cookie_auto_save_handle = if let Some(cookie_store) = &cookie_store {
-            let unwrapped = cookie_store.clone();
-            let file_backend_cookie_store = unwrapped.downcast_arc::<FileBackedCookieStore>();
-            if let Some(file_backend_cookie_store) = file_backend_cookie_store {
-                let handle =
-                    tokio_runtime.block_on(async { file_backend_cookie_store.start_auto_save() });
+    fn initialize_log_manager(
+        tokio_runtime: &Runtime,
+        config: Option<LogFileConfig>,
+        storage_manager: Arc<dyn StorageManager>,
+    ) -> Result<Option<Arc<dyn LogManager>>, InitError> {
+        let Some(config) = config else {
+            return Ok(None);
+        };
+        let log_manager = tokio_runtime
+            .block_on(DefaultLogManager::new(config, storage_manager))
+            .map_err(|e| InitError::LoggingInit(e.to_string()))?;
+        Ok(Some(Arc::new(log_manager)))
+    }
 
-                Some(Arc::new(Mutex::new(handle)))
-            } else {
-                return Err(InitError::Configuration(
-                    "file cookie store is null".to_string(),
-                ));
-            }
-        } else {
-            return Err(InitError::Configuration("cookie store is null".to_string()));
+    fn initialize_logging(
+        config: Option<LoggingConfig>,
+        log_manager: Option<Arc<dyn LogManager>>,
+    ) -> Result<Option<Arc<dyn Logger>>, InitError> {
+        let Some(config) = config else {
+            return Ok(None);
+        };
+        let logger = TracingLogger::new(&config, log_manager)
+            .map_err(|e| InitError::LoggingInit(e.to_string()))?;
+        Ok(Some(Arc::new(logger)))
+    }
+
+    fn initialize_upload_manager(
+        tokio_runtime: &Runtime,
+        config: Option<UploadConfig>,
+        http_client: Option<Arc<dyn HttpClient>>,
+        storage_manager: Arc<dyn StorageManager>,
+    ) -> Result<Option<Arc<dyn UploadManager>>, InitError> {
+        let Some(config) = config else {
+            return Ok(None);
+        };
+        let Some(http_client) = http_client else {
+            return Err(InitError::Configuration(
+                "the upload manager requires the http client".to_string(),
+            ));
+        };
+        let upload_manager = tokio_runtime
+            .block_on(DefaultUploadManager::new(config, http_client, storage_manager))
+            .map_err(|e| InitError::UploadInit(e.to_string()))?;
+        Ok(Some(Arc::new(upload_manager)))
+    }
+
+    fn initialize_job_scheduler(
+        tokio_runtime: &Runtime,
+        config: SchedulerConfig,
+        storage_manager: Arc<dyn StorageManager>,
+    ) -> Result<Arc<dyn JobScheduler>, InitError> {
+        let job_scheduler = tokio_runtime
+            .block_on(DefaultJobScheduler::new(config, storage_manager))
+            .map_err(|e| InitError::SchedulerInit(e.to_string()))?;
+        Ok(Arc::new(job_scheduler))
+    }
+
+    fn schedule_cookie_auto_save(
+        tokio_runtime: &Runtime,
+        job_scheduler: &Arc<dyn JobScheduler>,
+        cookie_store: Option<&Arc<dyn CookieStore>>,
+        event_bus: &Arc<EventBus>,
+    ) -> Result<(), InitError> {
+        let Some((interval, job)) = Self::cookie_auto_save_job(cookie_store, event_bus) else {
+            return Ok(());
         };
+        tokio_runtime
+            .block_on(job_scheduler.register(
+                COOKIE_AUTO_SAVE_JOB.to_string(),
+                JobSchedule::Interval(interval),
+                job,
+            ))
+            .map_err(|e| InitError::SchedulerInit(e.to_string()))
+    }
 
-        Ok((cookie_store.unwrap(), cookie_auto_save_handle.unwrap()))
+    fn cookie_auto_save_job(
+        cookie_store: Option<&Arc<dyn CookieStore>>,
+        event_bus: &Arc<EventBus>,
+    ) -> Option<(Duration, Arc<dyn Job>)> {
+        // memory and sqlite stores have nothing to save periodically
+        let file_backend_cookie_store = cookie_store?
+            .clone()
+            .downcast_arc::<FileBackedCookieStore>()?;
+        let interval = file_backend_cookie_store.auto_save_interval()?;
+        let event_bus = event_bus.clone();
+        let job = FnJob::new(move || {
+            let file_backend_cookie_store = file_backend_cookie_store.clone();
+            let event_bus = event_bus.clone();
+            Box::pin(async move {
+                file_backend_cookie_store.auto_save().await.map_err(|e| {
+                    event_bus.publish(RuntimeEvent::AutoSaveFailed {
+                        source: "cookie_store".to_string(),
+                        error: e.to_string(),
+                    });
+                    JobError::Failed(e.to_string())
+                })
+            })
+        });
+        Some((interval, Arc::new(job)))
     }
 
     async fn create_cookie_store(
         cookie_config: CookieConfig,
     ) -> Result<Arc<dyn CookieStore>, InitError> {
+        #[cfg(feature = "sqlite")]
+        if cookie_config.backend == CookieBackend::Sqlite {
+            let store = SqliteCookieStore::new(cookie_config)
+                .await
+                .map_err(|e| InitError::Configuration(e.to_string()))?;
+            return Ok(Arc::new(store));
+        }
+
+        if cookie_config.backend == CookieBackend::Memory {
+            let store = MemoryCookieStore::new(cookie_config)
+                .await
+                .map_err(|e| InitError::Configuration(e.to_string()))?;
+            return Ok(Arc::new(store));
+        }
+
         let store = FileBackedCookieStore::new(cookie_config)
             .await
             .map_err(|e| InitError::Configuration(e.to_string()))?;
@@ -361,47 +2303,115 @@ impl ServiceRuntime {
     }
 
     fn create_http_client(
-        http_config: HttpConfig,
+        mut http_config: HttpConfig,
         cookie_store: Option<Arc<dyn CookieStore>>,
-    ) -> Result<Arc<dyn HttpClient>, InitError> {
-        let backend = ReqwestBackend::with_parameters(http_config, cookie_store)
+        cookie_store_factory: Option<Arc<dyn CookieStoreFactory>>,
+        http_audit: Option<&Arc<HttpAuditTrail>>,
+    ) -> Result<Arc<ReloadableHttpClient>, InitError> {
+        if let Some(http_audit) = http_audit {
+            let mut loggers: Vec<Arc<dyn HttpLogger>> = vec![http_audit.clone()];
+            loggers.extend(http_config.logger.take());
+            http_config.logger = Some(Arc::new(CompositeHttpLogger::new(loggers)));
+        }
+        let backend =
+            ReloadableHttpClient::new(http_config, cookie_store, cookie_store_factory)
             .map_err(|e| InitError::HttpClientInit(e.to_string()))?;
 
         Ok(Arc::new(backend))
     }
 
-    fn create_storage_manager() -> Result<Arc<dyn StorageManager>, InitError> {
-        let backend = AsyncStorageManager::new();
-        Ok(Arc::new(backend))
+    fn create_storage_manager(
+        config: Option<&StorageConfig>,
+    ) -> Result<Arc<dyn StorageManager>, InitError> {
+        let backend = match config.and_then(|config| config.temp_root.clone()) {
+            Some(temp_root) => AsyncStorageManager::with_temp_root(temp_root),
+            None => AsyncStorageManager::new(),
+        };
+        let allowed_roots = config
+            .map(|config| config.allowed_roots.clone())
+            .unwrap_or_default();
+        Ok(Arc::new(backend.with_allowed_roots(allowed_roots)))
+    }
+
+    fn schedule_temp_cleanup(
+        tokio_runtime: &Runtime,
+        job_scheduler: &Arc<dyn JobScheduler>,
+        storage_manager: Arc<dyn StorageManager>,
+        config: Option<&StorageConfig>,
+    ) -> Result<(), InitError> {
+        let Some(max_age) = config.and_then(|config| config.temp_max_age) else {
+            return Ok(());
+        };
+        let job = FnJob::new(move || {
+            let storage_manager = storage_manager.clone();
+            Box::pin(async move {
+                storage_manager
+                    .cleanup_temp_files(max_age)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| JobError::Failed(e.to_string()))
+            })
+        });
+        tokio_runtime
+            .block_on(job_scheduler.register(
+                TEMP_CLEANUP_JOB.to_string(),
+                JobSchedule::Interval(Duration::from_secs(60 * 60)),
+                Arc::new(job),
+            ))
+            .map_err(|e| InitError::SchedulerInit(e.to_string()))
+    }
+
+    fn retrying_storage_manager(
+        storage_manager: Arc<dyn StorageManager>,
+        config: Option<&StorageConfig>,
+    ) -> Arc<dyn StorageManager> {
+        match config.and_then(|config| config.retry_policy.clone()) {
+            Some(policy) => Arc::new(RetryingStorageManager::new(storage_manager, policy)),
+            None => storage_manager,
+        }
+    }
+
+    // goes over the retrying one, so a retried read never re-runs the decryption
+    fn encrypting_storage_manager(
+        storage_manager: Arc<dyn StorageManager>,
+        config: Option<StorageConfig>,
+    ) -> Arc<dyn StorageManager> {
+        let Some(config) = config else {
+            return storage_manager;
+        };
+        match config.key_provider {
+            Some(key_provider) => {
+                Arc::new(EncryptedStorageManager::new(storage_manager, key_provider))
+            }
+            None => storage_manager,
+        }
     }
 
     async fn create_file_cache_factory(
-        mut config: FileCacheConfig,
+        config: FileCacheConfig,
         storage_manager: Arc<dyn StorageManager>,
+        job_scheduler: Arc<dyn JobScheduler>,
     ) -> Result<Arc<dyn FileCacheManagerFactory>, InitError> {
-        let channels = config.channels.take();
+        let channels = config.channels.clone();
 
         let factory = SingletonFileCacheManagerFactory::new(
             config,
             storage_manager,
-            |config, channel, storage_manager| {
+            job_scheduler,
+            |config, channel, channel_config, storage_manager| {
                 let path = format!("{}/{}", config.base_path, channel.name);
                 let manager = DefaultFileCacheManager::new(
                     path,
-                    config.auto_save_interval,
                     channel,
+                    channel_config,
                     storage_manager,
                 );
-                let manager = Arc::new(manager);
-
-                let _ = manager.clone().start_auto_save();
-                manager
+                Arc::new(manager)
             },
         );
         let factory = Arc::new(factory);
 
-        if channels.is_some() {
-            let channels = channels.unwrap();
+        if let Some(channels) = channels {
             for channel_config in channels {
                 let name = channel_config.name;
                 let extension = channel_config.extension;
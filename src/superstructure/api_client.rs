@@ -0,0 +1,262 @@
+use crate::domain::models::http_models::{
+    HttpClientError, HttpEndpoint, HttpEndpointBuilder, HttpMethod, HttpResponse, HttpRetryPolicy,
+};
+use crate::domain::traits::http_traits::{AuthProvider, HttpClient};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+// talks to one backend, every path is relative to the domain and every request carries the
+// default headers and the credentials of the auth provider
+pub struct ApiClient {
+    http_client: Arc<dyn HttpClient>,
+    domain: String,
+    default_headers: Vec<(String, String)>,
+    auth_provider: Option<Arc<dyn AuthProvider>>,
+    retry_policy: HttpRetryPolicy,
+    // None leaves the timeout to the http client
+    timeout: Option<Duration>,
+}
+
+impl ApiClient {
+    pub fn new(http_client: Arc<dyn HttpClient>, domain: impl Into<String>) -> Self {
+        Self {
+            http_client,
+            domain: domain.into(),
+            default_headers: Vec::new(),
+            auth_provider: None,
+            retry_policy: HttpRetryPolicy::default(),
+            timeout: None,
+        }
+    }
+
+    pub fn with_default_headers(mut self, default_headers: Vec<(String, String)>) -> Self {
+        self.default_headers = default_headers;
+        self
+    }
+
+    pub fn with_auth_provider(mut self, auth_provider: Arc<dyn AuthProvider>) -> Self {
+        self.auth_provider = Some(auth_provider);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: HttpRetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    // for requests the shortcuts do not cover, pass the result to execute
+    pub fn endpoint(&self, method: HttpMethod, path: impl Into<String>) -> HttpEndpointBuilder {
+        let builder = HttpEndpoint::builder(self.domain.clone(), path)
+            .method(method)
+            .headers(self.default_headers.clone());
+        match self.timeout {
+            Some(timeout) => builder.timeout(timeout),
+            None => builder,
+        }
+    }
+
+    // the credentials are asked for once per attempt, the last response is returned as it is
+    // once the attempts are used up
+    pub async fn execute(&self, endpoint: HttpEndpoint) -> Result<HttpResponse, HttpClientError> {
+        let max_attempts = self.retry_policy.attempts_for(&endpoint);
+        let mut backoff = self.retry_policy.initial_backoff;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let mut attempt = endpoint.clone();
+            if let Some(auth_provider) = &self.auth_provider {
+                attempt
+                    .headers
+                    .get_or_insert_with(Vec::new)
+                    .extend(auth_provider.headers().await?);
+            }
+            let result = self.http_client.execute(attempt).await;
+            let retryable = match &result {
+                Ok(response) => HttpRetryPolicy::is_retryable_status(response.status),
+                Err(e) => HttpRetryPolicy::is_retryable_error(e),
+            };
+            if attempts >= max_attempts || !retryable {
+                return result;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff = self.retry_policy.next_backoff(backoff);
+        }
+    }
+
+    pub async fn get(&self, path: impl Into<String>) -> Result<HttpResponse, HttpClientError> {
+        self.execute(self.endpoint(HttpMethod::Get, path).build())
+            .await
+    }
+
+    pub async fn delete(&self, path: impl Into<String>) -> Result<HttpResponse, HttpClientError> {
+        self.execute(self.endpoint(HttpMethod::Delete, path).build())
+            .await
+    }
+
+    pub async fn post_json<T: Serialize>(
+        &self,
+        path: impl Into<String>,
+        body: &T,
+    ) -> Result<HttpResponse, HttpClientError> {
+        self.send_json(HttpMethod::Post, path, body).await
+    }
+
+    pub async fn put_json<T: Serialize>(
+        &self,
+        path: impl Into<String>,
+        body: &T,
+    ) -> Result<HttpResponse, HttpClientError> {
+        self.send_json(HttpMethod::Put, path, body).await
+    }
+
+    pub async fn patch_json<T: Serialize>(
+        &self,
+        path: impl Into<String>,
+        body: &T,
+    ) -> Result<HttpResponse, HttpClientError> {
+        self.send_json(HttpMethod::Patch, path, body).await
+    }
+
+    async fn send_json<T: Serialize>(
+        &self,
+        method: HttpMethod,
+        path: impl Into<String>,
+        body: &T,
+    ) -> Result<HttpResponse, HttpClientError> {
+        let body =
+            serde_json::to_vec(body).map_err(|e| HttpClientError::Serialization(e.to_string()))?;
+        let endpoint = self
+            .endpoint(method, path)
+            .body(body)
+            .content_type("application/json")
+            .build();
+        self.execute(endpoint).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::http_models::{
+        HttpClientError, HttpEndpoint, HttpResponse, HttpRetryPolicy, HttpStreamResponse,
+    };
+    use crate::domain::traits::http_traits::{
+        AuthProvider, DecryptionProvider, EncryptionProvider, HttpClient,
+    };
+    use crate::superstructure::api_client::ApiClient;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    // answers 503 until the given number of requests was seen
+    struct FlakyServer {
+        failures: usize,
+        requests: Mutex<Vec<HttpEndpoint>>,
+    }
+
+    #[async_trait]
+    impl HttpClient for FlakyServer {
+        fn set_encryption_provider(&mut self, _: Arc<dyn EncryptionProvider>) {}
+        fn set_decryption_provider(&mut self, _: Arc<dyn DecryptionProvider>) {}
+        fn remove_encryption_provider(&mut self) -> Option<Arc<dyn EncryptionProvider>> {
+            None
+        }
+        fn remove_decryption_provider(&mut self) -> Option<Arc<dyn DecryptionProvider>> {
+            None
+        }
+
+        async fn execute(&self, endpoint: HttpEndpoint) -> Result<HttpResponse, HttpClientError> {
+            let mut requests = self.requests.lock();
+            requests.push(endpoint);
+            let status = if requests.len() > self.failures {
+                200
+            } else {
+                503
+            };
+            Ok(HttpResponse {
+                status,
+                headers: Vec::new(),
+                body: b"{\"id\":7}".to_vec(),
+                final_url: None,
+                redirects: Vec::new(),
+                trailers: Vec::new(),
+            })
+        }
+
+        async fn execute_stream(
+            &self,
+            _: HttpEndpoint,
+        ) -> Result<HttpStreamResponse, HttpClientError> {
+            Err(HttpClientError::Configuration("not supported".to_string()))
+        }
+    }
+
+    struct StaticToken;
+
+    #[async_trait]
+    impl AuthProvider for StaticToken {
+        async fn headers(&self) -> Result<Vec<(String, String)>, HttpClientError> {
+            Ok(vec![(
+                "Authorization".to_string(),
+                "Bearer token".to_string(),
+            )])
+        }
+    }
+
+    #[test]
+    fn test_requests_are_built_and_retried() {
+        let server = Arc::new(FlakyServer {
+            failures: 1,
+            requests: Mutex::new(Vec::new()),
+        });
+        let client = ApiClient::new(server.clone(), "https://api.example.com")
+            .with_default_headers(vec![("Accept".to_string(), "application/json".to_string())])
+            .with_auth_provider(Arc::new(StaticToken))
+            .with_retry_policy(HttpRetryPolicy {
+                initial_backoff: Duration::from_millis(1),
+                ..HttpRetryPolicy::default()
+            });
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let response = runtime.block_on(client.get("/items")).unwrap();
+        assert_eq!(response.status, 200);
+        let value: serde_json::Value = response.json().unwrap();
+        assert_eq!(value["id"], 7);
+        {
+            let requests = server.requests.lock();
+            assert_eq!(requests.len(), 2);
+            assert_eq!(requests[1].build_url(), "https://api.example.com/items");
+            assert_eq!(
+                requests[1].headers.as_ref().unwrap(),
+                &vec![
+                    ("Accept".to_string(), "application/json".to_string()),
+                    ("Authorization".to_string(), "Bearer token".to_string()),
+                ]
+            );
+        }
+
+        // a post may have taken effect, so the 503 is returned as it is
+        server.requests.lock().clear();
+        let response = runtime
+            .block_on(client.post_json("/items", &serde_json::json!({ "name": "a" })))
+            .unwrap();
+        assert_eq!(response.status, 503);
+        let requests = server.requests.lock();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].body.as_deref(), Some(&b"{\"name\":\"a\"}"[..]));
+        assert_eq!(
+            requests[0].content_type.as_deref(),
+            Some("application/json")
+        );
+    }
+}
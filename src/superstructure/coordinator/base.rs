@@ -161,7 +161,7 @@ impl StatusManager {
 
     pub fn update_status(&self) {
         let count = self.ongoing_request_count.load(Ordering::SeqCst);
-        if count <= 0 {
+        if count == 0 {
             self.change_status(RunnerStatus::Idle);
             return;
         }
@@ -206,9 +206,9 @@ impl RequestRetryer {
         }
         let strategy = strategy.as_ref().unwrap();
         match strategy {
-            RetryStrategy::RetryImmediately { max_retry } => max_retry.clone(),
-            RetryStrategy::RetryFixed { max_retry, .. } => max_retry.clone(),
-            RetryStrategy::RetryExponentialBackoff { max_retry, .. } => max_retry.clone(),
+            RetryStrategy::RetryImmediately { max_retry } => *max_retry,
+            RetryStrategy::RetryFixed { max_retry, .. } => *max_retry,
+            RetryStrategy::RetryExponentialBackoff { max_retry, .. } => *max_retry,
         }
     }
 
@@ -219,7 +219,7 @@ impl RequestRetryer {
         }
 
         let mut lock = self.retry_count.lock();
-        *lock = *lock + 1;
+        *lock += 1;
 
         let max_retry = max_retry.unwrap();
         *lock <= max_retry
@@ -232,11 +232,9 @@ impl RequestRetryer {
         }
         let strategy = strategy.as_ref().unwrap();
         match strategy {
-            RetryStrategy::RetryImmediately { .. } => {
-                return;
-            }
+            RetryStrategy::RetryImmediately { .. } => {}
             RetryStrategy::RetryFixed { delay, .. } => {
-                tokio::time::sleep(delay.clone()).await;
+                tokio::time::sleep(*delay).await;
             }
             RetryStrategy::RetryExponentialBackoff {
                 initial,
@@ -247,8 +245,8 @@ impl RequestRetryer {
                 let delay = {
                     let lock = self.retry_count.lock();
                     min(
-                        initial.clone() * base.powi(*lock as i32) as u32,
-                        max_delay.clone(),
+                        *initial * base.powi(*lock as i32) as u32,
+                        *max_delay,
                     )
                 };
                 let mut rng = rand::make_rng::<SmallRng>();
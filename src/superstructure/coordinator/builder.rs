@@ -114,26 +114,26 @@ impl BaseRunnerBuilder {
     }
 }
 
-impl Into<CoordinatorConfiguration> for CoordinatorConfigurationBuilder {
-    fn into(self) -> CoordinatorConfiguration {
-        self.build()
+impl From<CoordinatorConfigurationBuilder> for CoordinatorConfiguration {
+    fn from(val: CoordinatorConfigurationBuilder) -> Self {
+        val.build()
     }
 }
 
-impl Into<QueueConfiguration> for QueueConfigurationBuilder {
-    fn into(self) -> QueueConfiguration {
-        self.build()
+impl From<QueueConfigurationBuilder> for QueueConfiguration {
+    fn from(val: QueueConfigurationBuilder) -> Self {
+        val.build()
     }
 }
 
-impl Into<RunnerConfiguration> for RunnerConfigurationBuilder {
-    fn into(self) -> RunnerConfiguration {
-        self.build()
+impl From<RunnerConfigurationBuilder> for RunnerConfiguration {
+    fn from(val: RunnerConfigurationBuilder) -> Self {
+        val.build()
     }
 }
 
-impl Into<Request> for RequestBuilder {
-    fn into(self) -> Request {
-        self.build()
+impl From<RequestBuilder> for Request {
+    fn from(val: RequestBuilder) -> Self {
+        val.build()
     }
 }
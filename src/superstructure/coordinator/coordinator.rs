@@ -64,13 +64,12 @@ impl DefaultCoordinator {
             configuration.queue_configuration.clone(),
         ));
 
-        let arc_coordinator = Arc::new(Self {
+        Arc::new(Self {
             configuration,
             snapshot: Mutex::new(None),
             runner_discover: discover,
             queuer,
-        });
-        arc_coordinator
+        })
     }
 
     pub fn cycler_thread_entrypoint<T>(
@@ -140,7 +139,7 @@ impl Coordinator for DefaultCoordinator {
         let snapshot = Arc::new(CycleSnapshot { runner_snapshots });
 
         *self.snapshot.lock() = Some(snapshot.clone());
-        let _ = self.runner_discover.cycle_once(&snapshot)?;
+        self.runner_discover.cycle_once(&snapshot)?;
         Ok(snapshot)
     }
 
@@ -236,9 +235,7 @@ impl RunnerDiscover for DefaultRunnerDiscover {
                     return;
                 }
                 categories.as_ref().unwrap().iter().for_each(|category| {
-                    let current = all_is_available_map.get(category);
-                    if current.is_some() {
-                        let current = current.unwrap();
+                    if let Some(&current) = all_is_available_map.get(category) {
                         if !current && available {
                             all_is_available_map.insert(category.clone(), available);
                         }
@@ -263,7 +260,7 @@ impl RunnerDiscover for DefaultRunnerDiscover {
                     if waiter.is_none() {
                         return;
                     }
-                    waiter.unwrap().set(available.clone())
+                    waiter.unwrap().set(*available)
                 })
         }
 
@@ -273,7 +270,7 @@ impl RunnerDiscover for DefaultRunnerDiscover {
 
     fn discover_runner_by_category(
         &self,
-        category: &String,
+        category: &str,
         timeout: Duration,
     ) -> Result<Identifier, DiscoverError> {
         let mut waiter = match category.is_empty() {
@@ -283,14 +280,14 @@ impl RunnerDiscover for DefaultRunnerDiscover {
         if waiter.is_none() {
             waiter = self.category_notification_channels.get("omnipotence");
             if waiter.is_none() {
-                return Err(DiscoverError::CategoryNotExists(category.clone()));
+                return Err(DiscoverError::CategoryNotExists(category.to_string()));
             }
         }
         let waiter = waiter.unwrap();
         let _ = waiter.wait_timeout(true, timeout)?;
         waiter.reset(false);
 
-        let snapshot = self.snapshot_waiter.wait_timeout(timeout.clone())?;
+        let snapshot = self.snapshot_waiter.wait_timeout(timeout)?;
         self.snapshot_waiter.clear();
 
         let registry = RunnerRegistry::singleton().read();
@@ -311,7 +308,7 @@ impl RunnerDiscover for DefaultRunnerDiscover {
                 return Ok(identifier.clone());
             }
             let categories = categories.as_ref().unwrap();
-            if !categories.contains(category) {
+            if !categories.iter().any(|accepted| accepted == category) {
                 continue;
             }
             return Ok(identifier.clone());
@@ -402,13 +399,13 @@ impl Queuer for DefaultQueuer {
         let capacity = self.queue.capacity();
         let length = self.queue.len();
         if length >= capacity && reject_strategy == &RejectStrategy::Discard {
-            let result = self.queue.push(request, not_full_timeout.clone());
+            let result = self.queue.push(request, *not_full_timeout);
             return match result {
                 Ok(_) => Ok(()),
                 Err(_) => Err(QueuerError::WaitTimeout),
             };
         }
-        let result = self.queue.push(request, not_full_timeout.clone());
+        let result = self.queue.push(request, *not_full_timeout);
         match result {
             Ok(_) => Ok(()),
             Err(_) => Err(QueuerError::WaitTimeout),
@@ -510,6 +507,12 @@ impl RunnerWatcher for DefaultRunnerWatcher {
     }
 }
 
+impl Default for DefaultProgressListenerManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DefaultProgressListenerManager {
     pub fn new() -> Self {
         Self {
@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)]
 pub mod coordinator;
 pub mod registry;
 pub mod builder;
@@ -1,5 +1,5 @@
 use crate::domain::models::coordinator_models::{
-    CycleSnapshot, Identifier, RegistryError,
+    Identifier, RegistryError,
 };
 use crate::domain::traits::coordinator_traits::{PostRunner, Runner};
 use lazy_static::lazy_static;
@@ -23,6 +23,12 @@ pub struct RunnerRegistry {
     cached_post_runners_hash: u64,
 }
 
+impl Default for RunnerRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RunnerRegistry {
     pub fn singleton() -> &'static RwLock<RunnerRegistry> {
         &RUNNER_REGISTRY
@@ -115,9 +121,7 @@ impl RunnerRegistry {
         accepted_categories.iter().for_each(|category| {
             let vec = self.runners.get_mut(category);
             if vec.is_none() {
-                let mut vec = Vec::<Arc<dyn Runner>>::new();
-                vec.push(runner.clone());
-                self.runners.insert(category.clone(), vec);
+                self.runners.insert(category.clone(), vec![runner.clone()]);
                 return;
             }
             let vec = vec.unwrap();
@@ -142,9 +146,8 @@ impl RunnerRegistry {
         accepted_categories.iter().for_each(|category| {
             let vec = self.post_runners.get_mut(category);
             if vec.is_none() {
-                let mut vec = Vec::<Arc<dyn PostRunner>>::new();
-                vec.push(post_runner.clone());
-                self.post_runners.insert(category.clone(), vec);
+                self.post_runners
+                    .insert(category.clone(), vec![post_runner.clone()]);
                 return;
             }
             let vec = vec.unwrap();
@@ -205,11 +208,11 @@ impl RunnerRegistry {
     }
 
     pub fn all_runner_categories(&self) -> Vec<&String> {
-        self.runners.keys().map(|key| key).collect()
+        self.runners.keys().collect()
     }
 
     pub fn all_post_runner_categories(&self) -> Vec<&String> {
-        self.post_runners.keys().map(|key| key).collect()
+        self.post_runners.keys().collect()
     }
 
     pub fn find_runner_by_identifier(
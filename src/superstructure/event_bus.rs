@@ -0,0 +1,132 @@
+use crate::domain::models::event_models::RuntimeEvent;
+use crate::domain::models::file_cache_models::{CacheDiagnostic, CacheTask};
+use crate::domain::models::http_models::HttpClientError;
+use crate::domain::traits::cookie_traits::CookieStore;
+use crate::domain::traits::file_cache_traits::FileCacheObserver;
+use futures_util::StreamExt;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+// collects the notifications of every subsystem into one channel, publishing without
+// subscribers simply drops the event
+pub struct EventBus {
+    events: broadcast::Sender<RuntimeEvent>,
+    // unknown until the first request
+    online: Mutex<Option<bool>>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            events: broadcast::channel(256).0,
+            online: Mutex::new(None),
+        }
+    }
+
+    pub fn publish(&self, event: RuntimeEvent) {
+        let _ = self.events.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<RuntimeEvent> {
+        self.events.subscribe()
+    }
+
+    // any response means the network is reachable, only failing to reach it means it is not,
+    // the event is published when that changes
+    pub fn observe_http<T>(&self, result: &Result<T, HttpClientError>) {
+        let online = match result {
+            Ok(_) => true,
+            Err(HttpClientError::Network(_)) => false,
+            Err(_) => return,
+        };
+        let mut current = self.online.lock();
+        if current.replace(online) != Some(online) {
+            self.publish(RuntimeEvent::ConnectivityChanged { online });
+        }
+    }
+
+    pub fn forward_cookie_changes(
+        self: &Arc<Self>,
+        tokio_runtime: &Runtime,
+        cookie_store: &Arc<dyn CookieStore>,
+    ) {
+        let mut changes = cookie_store.subscribe();
+        let event_bus = self.clone();
+        tokio_runtime.spawn(async move {
+            while let Some(change) = changes.next().await {
+                event_bus.publish(RuntimeEvent::CookieChanged(change));
+            }
+        });
+    }
+
+    // a failed persist is the file cache auto-save failing, the other diagnostics are not
+    // forwarded
+    pub fn forward_cache_diagnostics(
+        self: &Arc<Self>,
+        tokio_runtime: &Runtime,
+        mut diagnostics: broadcast::Receiver<CacheDiagnostic>,
+    ) {
+        let event_bus = self.clone();
+        tokio_runtime.spawn(async move {
+            loop {
+                match diagnostics.recv().await {
+                    Ok(diagnostic) => {
+                        if matches!(diagnostic.task, CacheTask::Persist) {
+                            event_bus.publish(RuntimeEvent::AutoSaveFailed {
+                                source: diagnostic.channel,
+                                error: diagnostic.error,
+                            });
+                        }
+                    }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return,
+                }
+            }
+        });
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileCacheObserver for EventBus {
+    fn on_evict(&self, channel: &str, tag: &str, size: usize) {
+        self.publish(RuntimeEvent::CacheEvicted {
+            channel: channel.to_string(),
+            tag: tag.to_string(),
+            size,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::event_models::RuntimeEvent;
+    use crate::domain::models::http_models::HttpClientError;
+    use crate::superstructure::event_bus::EventBus;
+
+    #[test]
+    fn test_connectivity_is_published_on_change() {
+        let event_bus = EventBus::new();
+        let mut events = event_bus.subscribe();
+
+        event_bus.observe_http(&Ok::<(), HttpClientError>(()));
+        event_bus.observe_http(&Ok::<(), HttpClientError>(()));
+        event_bus.observe_http::<()>(&Err(HttpClientError::InvalidUrl("x".to_string())));
+        event_bus.observe_http::<()>(&Err(HttpClientError::Network("down".to_string())));
+
+        let mut online = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let RuntimeEvent::ConnectivityChanged { online: value } = event {
+                online.push(value);
+            }
+        }
+        assert_eq!(online, vec![true, false]);
+    }
+}
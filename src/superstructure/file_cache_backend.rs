@@ -1,28 +1,68 @@
-use crate::domain::models::file_cache_models::{CacheChannel, CacheError, CacheRecord};
-use crate::domain::models::storage_models::{ReadFile, WriteFile, WriteMode};
-use crate::domain::traits::file_cache_traits::{FileCacheManager, FileCacheManagerFactory};
+use crate::domain::models::file_cache_models::{
+    CacheChannel, CacheDiagnostic, CacheError, CacheRecord, CacheRecordFilter, CacheStats,
+    CacheTask, CompressionKind,
+};
+use crate::domain::models::scheduler_models::{JobError, JobSchedule};
+use crate::domain::models::storage_models::{EnsureMode, ReadFile, WriteFile, WriteMode};
+use crate::domain::traits::file_cache_traits::{
+    CacheFetcher, CacheMigration, FileCacheManager, FileCacheManagerFactory, FileCacheObserver,
+};
+use crate::domain::traits::scheduler_traits::JobScheduler;
 use crate::domain::traits::storage_traits::StorageManager;
+use crate::monitor::metrics_service::metrics;
 use crate::rkv::rkv_impl::RKV_SERVICE;
-use crate::service::config::FileCacheConfig;
+use crate::service::config::{FileCacheChannelConfig, FileCacheConfig};
+use crate::superstructure::job_scheduler::FnJob;
+use crate::utils::aead;
+use crate::utils::compression::{compress, compressed_writer, decompress, decompressed_reader};
+use crate::utils::dirty_flag::DirtyGuard;
+use crate::utils::time::now_millis;
+use aes_gcm::aead::KeyInit;
+use aes_gcm::Aes256Gcm;
 use async_trait::async_trait;
+use bytes::Bytes;
 use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use moka::future::Cache;
+use futures_util::{StreamExt, TryStreamExt};
+use futures_util::stream::BoxStream;
 use rkv::SingleStore;
 use rkv::backend::SafeModeDatabase;
+use std::collections::HashSet;
+use std::pin::Pin;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::time::Duration;
 use tokio::fs::{File, try_exists};
-use tokio::sync::{Mutex, RwLock};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{Mutex, RwLock, broadcast};
+use tokio_util::io::ReaderStream;
 use uuid::Uuid;
+use xxhash_rust::xxh3::{Xxh3, xxh3_64};
+
+// every channel saves through its own job, named FILE_CACHE_AUTO_SAVE_JOB:<channel>
+pub const FILE_CACHE_AUTO_SAVE_JOB: &str = "file_cache_auto_save";
 
 pub struct SingletonFileCacheManagerFactory<T>
 where
-    T: Fn(&FileCacheConfig, CacheChannel, Arc<dyn StorageManager>) -> Arc<dyn FileCacheManager>,
+    T: Fn(
+        &FileCacheConfig,
+        CacheChannel,
+        Option<&FileCacheChannelConfig>,
+        Arc<dyn StorageManager>,
+    ) -> Arc<dyn FileCacheManager>,
 {
     pub config: FileCacheConfig,
     map: DashMap<String, Arc<dyn FileCacheManager>>,
+    channel_configs: DashMap<String, FileCacheChannelConfig>,
+    observers: parking_lot::RwLock<Vec<Arc<dyn FileCacheObserver>>>,
+    create_lock: Mutex<()>,
+    diagnostics: broadcast::Sender<CacheDiagnostic>,
+    // replaces config.auto_save_interval once reconfigured
+    auto_save_interval: parking_lot::Mutex<Duration>,
     creator: T,
     storage_manager: Arc<dyn StorageManager>,
+    job_scheduler: Arc<dyn JobScheduler>,
     single_store: SingleStore<SafeModeDatabase>,
 }
 
@@ -31,52 +71,321 @@ pub struct DefaultFileCacheManager {
     path: String,
     extension: Option<String>,
     save_lock: Mutex<()>,
-    auto_save_interval: Duration,
+    default_ttl: Option<Duration>,
+    max_bytes: Option<u64>,
+    max_entries: Option<usize>,
+    compression: Option<CompressionKind>,
+    cipher: Option<Aes256Gcm>,
+    version: u32,
+    loaded_version: AtomicU32,
+    migration: Option<Arc<dyn CacheMigration>>,
+    memory: Option<Cache<String, Arc<Vec<u8>>>>,
+    memory_hits: AtomicU64,
+    memory_misses: AtomicU64,
+    observers: parking_lot::RwLock<Vec<Arc<dyn FileCacheObserver>>>,
     dirty: Arc<AtomicBool>,
-    map: DashMap<String, RwLock<CacheRecord>>,
+    last_persist: AtomicU64,
+    map: DashMap<String, Arc<RwLock<CacheRecord>>>,
+    in_flight: DashMap<String, Arc<Mutex<()>>>,
     storage_manager: Arc<dyn StorageManager>,
     single_store: SingleStore<SafeModeDatabase>,
 }
 
+// archive layout: the channel index first, then every data file under data/
+const ARCHIVE_INDEX: &str = "channel.rkyv";
+const ARCHIVE_DATA: &str = "data";
+
+fn write_archive(
+    path: &str,
+    index: &[u8],
+    files: Vec<(String, String)>,
+) -> std::io::Result<()> {
+    let temporary_path = format!("{}.{}.tmp", path, Uuid::new_v4());
+    let written = (|| {
+        let file = std::fs::File::create(&temporary_path)?;
+        let mut builder = tar::Builder::new(file);
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(index.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, ARCHIVE_INDEX, index)?;
+
+        for (source, name) in files {
+            builder.append_path_with_name(source, format!("{}/{}", ARCHIVE_DATA, name))?;
+        }
+        builder.into_inner()?.sync_all()
+    })();
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temporary_path);
+        return written;
+    }
+    std::fs::rename(&temporary_path, path)
+}
+
+// names read from an archive may only name an entry right inside the directory
+fn is_plain_name(name: &str) -> bool {
+    let mut components = std::path::Path::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(std::path::Component::Normal(_)), None)
+    )
+}
+
+// the name of a channel is a directory right under the base path
+pub(crate) fn check_channel_name(name: &str) -> Result<(), CacheError> {
+    if !is_plain_name(name) {
+        return Err(CacheError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+// only the index and the regular files directly under data/ are unpacked, the rest of the
+// archive is skipped
+fn unpack_archive(path: &str, staging: &str) -> std::io::Result<Vec<u8>> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = tar::Archive::new(file);
+    let mut index = None;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let entry_path = entry.path()?.to_string_lossy().to_string();
+        if entry_path == ARCHIVE_INDEX {
+            let mut bytes = Vec::new();
+            std::io::Read::read_to_end(&mut entry, &mut bytes)?;
+            index = Some(bytes);
+            continue;
+        }
+        let name = entry_path.strip_prefix(&format!("{}/", ARCHIVE_DATA));
+        if name.is_some_and(is_plain_name) {
+            entry.unpack_in(staging)?;
+        }
+    }
+    index.ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("{} is missing from the archive", ARCHIVE_INDEX),
+        )
+    })
+}
+
+// the random nonce is stored in front of the ciphertext
+fn encrypt(cipher: &Aes256Gcm, data: &[u8]) -> Result<Vec<u8>, CacheError> {
+    aead::seal(cipher, data).map_err(CacheError::Encryption)
+}
+
+fn decrypt(cipher: &Aes256Gcm, data: &[u8]) -> Result<Vec<u8>, CacheError> {
+    aead::open(cipher, data).map_err(CacheError::Encryption)
+}
+
+// checksums always cover the original bytes, whatever the channel stores on disk
+async fn copy_with_checksum<W>(
+    reader: &mut Pin<Box<dyn AsyncRead + Send>>,
+    writer: &mut W,
+) -> std::io::Result<(u64, u64)>
+where
+    W: AsyncWrite + Unpin + ?Sized,
+{
+    let mut hasher = Xxh3::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read]).await?;
+        size += read as u64;
+    }
+    writer.shutdown().await?;
+    Ok((size, hasher.digest()))
+}
+
+async fn file_checksum(
+    path: &str,
+    compression: Option<&CompressionKind>,
+) -> std::io::Result<u64> {
+    let file = File::open(path).await?;
+    let mut reader = decompressed_reader(compression, file);
+    let mut hasher = Xxh3::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.digest())
+}
+
 impl<T> SingletonFileCacheManagerFactory<T>
 where
-    T: Fn(&FileCacheConfig, CacheChannel, Arc<dyn StorageManager>) -> Arc<dyn FileCacheManager>,
+    T: Fn(
+        &FileCacheConfig,
+        CacheChannel,
+        Option<&FileCacheChannelConfig>,
+        Arc<dyn StorageManager>,
+    ) -> Arc<dyn FileCacheManager>,
 {
     pub fn new(
         config: FileCacheConfig,
         storage_manager: Arc<dyn StorageManager>,
+        job_scheduler: Arc<dyn JobScheduler>,
         creator: T,
     ) -> Self {
         let mut rkv_service = RKV_SERVICE.write().unwrap();
         let rkv_service = rkv_service.as_mut().unwrap();
         let store = rkv_service.init_db("file_cache").unwrap();
 
+        let channel_configs = DashMap::new();
+        config.channels.iter().flatten().for_each(|channel_config| {
+            channel_configs.insert(channel_config.name.clone(), channel_config.clone());
+        });
+
         Self {
+            auto_save_interval: parking_lot::Mutex::new(config.auto_save_interval),
             config,
             map: DashMap::new(),
+            channel_configs,
+            observers: parking_lot::RwLock::new(Vec::new()),
+            create_lock: Mutex::new(()),
+            diagnostics: broadcast::channel(64).0,
             creator,
             storage_manager,
+            job_scheduler,
             single_store: store,
         }
     }
 }
 
+impl<T> SingletonFileCacheManagerFactory<T>
+where
+    T: Fn(
+            &FileCacheConfig,
+            CacheChannel,
+            Option<&FileCacheChannelConfig>,
+            Arc<dyn StorageManager>,
+        ) -> Arc<dyn FileCacheManager>
+        + Send
+        + Sync
+        + 'static,
+{
+    // builds the manager of a channel that is not in the map yet, callers hold create_lock so
+    // two of them never race to register the same channel
+    async fn open_channel(
+        &self,
+        channel: CacheChannel,
+    ) -> Result<Arc<dyn FileCacheManager>, CacheError> {
+        let name = channel.name.clone();
+        let channel_config = self.channel_configs.get(&name).map(|entry| entry.clone());
+        let manager = (self.creator)(
+            &self.config,
+            channel,
+            channel_config.as_ref(),
+            self.storage_manager.clone(),
+        );
+        for observer in self.observers.read().iter() {
+            manager.observe(observer.clone());
+        }
+        // nothing writes to the channel before it is returned, so every leftover is an orphan
+        if let Err(e) = manager.recover_orphans().await {
+            let _ = self.diagnostics.send(CacheDiagnostic {
+                channel: name.clone(),
+                task: CacheTask::Recovery,
+                error: e.to_string(),
+                at: now_millis(),
+            });
+        }
+        manager.migrate().await?;
+        let interval = *self.auto_save_interval.lock();
+        self.schedule_auto_save(&name, manager.clone(), interval).await?;
+        self.map.insert(name, manager.clone());
+        Ok(manager)
+    }
+
+
+    async fn import_index(&self, index: Vec<u8>, staging: &str) -> Result<String, CacheError> {
+        let channel = CacheChannel::from_bytes(&index).map_err(CacheError::Serialization)?;
+        let name = channel.name.clone();
+        // the name and the extension end up in the paths of the channel
+        let extension_is_plain = channel
+            .extension
+            .as_ref()
+            .is_none_or(|extension| is_plain_name(&format!("{}.{}", name, extension)));
+        if !is_plain_name(&name) || !extension_is_plain {
+            return Err(CacheError::Serialization(format!(
+                "{} is not a valid channel name",
+                name
+            )));
+        }
+        let manager = self
+            .create_with_name(name.clone(), channel.extension.clone())
+            .await?;
+        if manager.version() != channel.version {
+            return Err(CacheError::IncompatibleVersion(name));
+        }
+
+        let source = format!("{}/{}", staging, ARCHIVE_DATA);
+        manager
+            .import_records(channel.records, channel.extension, &source)
+            .await?;
+        manager.persist().await?;
+        Ok(name)
+    }
+
+    async fn schedule_auto_save(
+        &self,
+        name: &str,
+        manager: Arc<dyn FileCacheManager>,
+        interval: Duration,
+    ) -> Result<(), CacheError> {
+        let diagnostics = self.diagnostics.clone();
+        let job = FnJob::new(move || {
+            let manager = manager.clone();
+            let diagnostics = diagnostics.clone();
+            Box::pin(async move {
+                manager
+                    .auto_save(&diagnostics)
+                    .await
+                    .map_err(|e| JobError::Failed(e.to_string()))
+            })
+        });
+        self.job_scheduler
+            .register(
+                auto_save_job_name(name),
+                JobSchedule::Interval(interval),
+                Arc::new(job),
+            )
+            .await
+            .map_err(|e| CacheError::ErrorForward(e.to_string()))
+    }
+}
+
+pub fn auto_save_job_name(channel: &str) -> String {
+    format!("{}:{}", FILE_CACHE_AUTO_SAVE_JOB, channel)
+}
+
 impl DefaultFileCacheManager {
     pub fn new(
         path: String,
-        auto_save_interval: Duration,
         channel: CacheChannel,
+        channel_config: Option<&FileCacheChannelConfig>,
         storage_manager: Arc<dyn StorageManager>,
     ) -> Self {
         let mut rkv_service = RKV_SERVICE.write().unwrap();
         let rkv_service = rkv_service.as_mut().unwrap();
         let store = rkv_service.init_db("file_cache").unwrap();
 
+        let loaded_version = channel.version;
         let records = channel.records;
-        let map: DashMap<String, RwLock<CacheRecord>> = DashMap::new();
+        let map: DashMap<String, Arc<RwLock<CacheRecord>>> = DashMap::new();
         records.into_iter().for_each(|record| {
             let tag = record.tag.clone();
-            map.insert(tag, RwLock::new(record));
+            map.insert(tag, Arc::new(RwLock::new(record)));
         });
 
         Self {
@@ -84,27 +393,155 @@ impl DefaultFileCacheManager {
             path,
             extension: channel.extension,
             save_lock: Mutex::new(()),
-            auto_save_interval,
+            default_ttl: channel_config.and_then(|channel_config| channel_config.default_ttl),
+            max_bytes: channel_config.and_then(|channel_config| channel_config.max_bytes),
+            max_entries: channel_config.and_then(|channel_config| channel_config.max_entries),
+            compression: channel_config
+                .and_then(|channel_config| channel_config.compression.clone()),
+            cipher: channel_config
+                .and_then(|channel_config| channel_config.encryption_key)
+                .map(|key| Aes256Gcm::new(&key.into())),
+            version: channel_config
+                .and_then(|channel_config| channel_config.schema_version)
+                .unwrap_or(loaded_version),
+            loaded_version: AtomicU32::new(loaded_version),
+            migration: channel_config.and_then(|channel_config| channel_config.migration.clone()),
+            memory: channel_config
+                .and_then(|channel_config| channel_config.memory_budget)
+                .map(|memory_budget| {
+                    Cache::builder()
+                        .max_capacity(memory_budget)
+                        .weigher(|_, data: &Arc<Vec<u8>>| data.len().min(u32::MAX as usize) as u32)
+                        .build()
+                }),
+            memory_hits: AtomicU64::new(0),
+            memory_misses: AtomicU64::new(0),
+            observers: parking_lot::RwLock::new(Vec::new()),
             dirty: Arc::new(AtomicBool::new(false)),
+            last_persist: AtomicU64::new(0),
             map,
+            in_flight: DashMap::new(),
             storage_manager,
             single_store: store,
         }
     }
     
-    fn build_path(&self, filename: &String) -> String {
-        if self.extension.is_some() {
-            return format!(
-                "{}/{}.{}",
-                self.path,
-                filename,
-                self.extension.as_ref().unwrap()
-            );
+    fn build_path(&self, filename: &str) -> String {
+        if let Some(extension) = &self.extension {
+            return format!("{}/{}.{}", self.path, filename, extension);
         }
 
         format!("{}/{}", self.path, filename)
     }
 
+    // the map guard is released before the record lock is awaited
+    fn entry(&self, tag: &str) -> Result<Arc<RwLock<CacheRecord>>, CacheError> {
+        self.map
+            .get(tag)
+            .map(|entry| entry.value().clone())
+            .ok_or(CacheError::TagNotExist(tag.to_string()))
+    }
+
+    async fn snapshot(&self) -> Vec<CacheRecord> {
+        let entries: Vec<Arc<RwLock<CacheRecord>>> =
+            self.map.iter().map(|entry| entry.value().clone()).collect();
+
+        let mut records = Vec::with_capacity(entries.len());
+        for entry in entries {
+            records.push(entry.read().await.clone());
+        }
+        records
+    }
+
+    fn expires_at(&self, ttl: Option<Duration>) -> Option<u64> {
+        ttl.or(self.default_ttl)
+            .map(|ttl| now_millis() + ttl.as_millis() as u64)
+    }
+
+    // compresses, then encrypts, according to the channel configuration
+    async fn encode(&self, bytes: &[u8]) -> Result<Vec<u8>, CacheError> {
+        let mut data = match &self.compression {
+            Some(compression) => compress(compression, bytes)
+                .await
+                .map_err(|e| CacheError::IO(e.to_string()))?,
+            None => bytes.to_vec(),
+        };
+        if let Some(cipher) = &self.cipher {
+            data = encrypt(cipher, &data)?;
+        }
+        Ok(data)
+    }
+
+    // decoding follows the record, a channel may hold entries written under an older config
+    async fn decode(&self, record: &CacheRecord, data: Vec<u8>) -> Result<Vec<u8>, CacheError> {
+        let mut data = data;
+        if record.encrypted {
+            if self.cipher.is_none() {
+                return Err(CacheError::Encryption(format!(
+                    "channel {} has no encryption key",
+                    self.name
+                )));
+            }
+            data = decrypt(self.cipher.as_ref().unwrap(), &data)
+                .map_err(|_| CacheError::Corrupted(record.tag.clone()))?;
+        }
+        if let Some(compression) = &record.compression {
+            data = decompress(compression, &data)
+                .await
+                .map_err(|_| CacheError::Corrupted(record.tag.clone()))?;
+        }
+        Ok(data)
+    }
+
+    async fn read_record(&self, record: &CacheRecord, path: String) -> Result<Vec<u8>, CacheError> {
+        let read_file = ReadFile::path(path);
+        let data = self
+            .storage_manager
+            .read(read_file)
+            .await
+            .map_err(CacheError::from)?;
+        let data = self.decode(record, data).await?;
+        if record.checksum.is_some_and(|checksum| checksum != xxh3_64(&data)) {
+            return Err(CacheError::Corrupted(record.tag.clone()));
+        }
+        Ok(data)
+    }
+
+    async fn forget(&self, tag: &str) {
+        if let Some(memory) = &self.memory {
+            memory.invalidate(tag).await;
+        }
+    }
+
+    // served from the memory tier when possible, the disk read fills it on a miss
+    async fn read_through(
+        &self,
+        record: &CacheRecord,
+        path: String,
+    ) -> Result<Arc<Vec<u8>>, CacheError> {
+        if self.memory.is_none() {
+            return Ok(Arc::new(self.read_record(record, path).await?));
+        }
+
+        let memory = self.memory.as_ref().unwrap();
+        if let Some(data) = memory.get(&record.tag).await {
+            self.memory_hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(data);
+        }
+        self.memory_misses.fetch_add(1, Ordering::Relaxed);
+
+        let data = Arc::new(self.read_record(record, path).await?);
+        memory.insert(record.tag.clone(), data.clone()).await;
+        Ok(data)
+    }
+
+    fn notify(&self, event: impl Fn(&dyn FileCacheObserver)) {
+        let observers = self.observers.read().clone();
+        for observer in observers.iter() {
+            event(observer.as_ref());
+        }
+    }
+
     fn make_dirty(&self) {
         self.dirty.store(true, Ordering::SeqCst);
     }
@@ -117,7 +554,7 @@ impl DefaultFileCacheManager {
         self.dirty.load(Ordering::SeqCst)
     }
 
-    async fn ensure_directory_exist(&self, directory: &String) -> Result<(), CacheError> {
+    async fn ensure_directory_exist(&self, directory: &str) -> Result<(), CacheError> {
         if !try_exists(directory)
             .await
             .map_err(|e| CacheError::IO(e.to_string()))?
@@ -129,42 +566,296 @@ impl DefaultFileCacheManager {
         Ok(())
     }
 
-    async fn ensure_file_exist(&self, filename: &String) -> Result<(), CacheError> {
-        if !try_exists(filename)
+    // a crash mid-write leaves only a .tmp file behind, never a torn entry
+    async fn write_atomically(&self, path: &str, bytes: &[u8]) -> Result<(), CacheError> {
+        let temporary_path = format!("{}.{}.tmp", path, Uuid::new_v4());
+        let write_file = WriteFile {
+            path: temporary_path.clone(),
+            mode: WriteMode::Truncate,
+            timeout: Duration::from_secs(60),
+            ensure_mode: Some(EnsureMode::SyncAll),
+            compression: None,
+            data: bytes,
+        };
+
+        let written = self.storage_manager.write(write_file).await;
+        if written.is_err() {
+            let _ = tokio::fs::remove_file(&temporary_path).await;
+            return written.map_err(CacheError::from);
+        }
+        tokio::fs::rename(&temporary_path, path)
+            .await
+            .map_err(|e| CacheError::IO(e.to_string()))
+    }
+
+    async fn write_record(
+        &self,
+        tag: String,
+        sentence: String,
+        bytes: &[u8],
+        ttl: Option<Duration>,
+    ) -> Result<(), CacheError> {
+        let checksum = Some(xxh3_64(bytes));
+        let compression = self.compression.clone();
+        let encrypted = self.cipher.is_some();
+        let encoded;
+        let stored = if compression.is_some() || encrypted {
+            encoded = self.encode(bytes).await?;
+            &encoded
+        } else {
+            bytes
+        };
+        if let Ok(entry) = self.entry(&tag) {
+            let mut record = entry.write().await;
+
+            let path = self.build_path(&record.filename);
+            self.ensure_directory_exist(&self.path).await?;
+
+            self.write_atomically(&path, stored).await?;
+            // forgotten under the write lock, reads fill the memory tier under the read lock so
+            // none of them can put the old data back
+            self.forget(&tag).await;
+            record.sentence = sentence;
+            record.size = bytes.len();
+            // the ttl starts once the data is on disk, like cached_at
+            record.expires_at = self.expires_at(ttl);
+            record.last_access = now_millis();
+            record.cached_at = now_millis();
+            record.checksum = checksum;
+            record.compression = compression;
+            record.encrypted = encrypted;
+            self.make_dirty();
+            self.notify(|observer| observer.on_cache(&self.name, &tag, bytes.len()));
+            return Ok(());
+        }
+
+        let filename = Uuid::new_v4().to_string();
+        let path = self.build_path(&filename);
+        self.ensure_directory_exist(&self.path).await?;
+
+        self.write_atomically(&path, stored).await?;
+        self.forget(&tag).await;
+        let record = CacheRecord {
+            tag: tag.clone(),
+            filename,
+            size: bytes.len(),
+            sentence,
+            expires_at: self.expires_at(ttl),
+            last_access: now_millis(),
+            cached_at: now_millis(),
+            checksum,
+            compression,
+            encrypted,
+            metadata: Vec::new(),
+            pinned: false,
+        };
+
+        self.insert_record(record).await;
+        self.make_dirty();
+        self.notify(|observer| observer.on_cache(&self.name, &tag, bytes.len()));
+        Ok(())
+    }
+
+    // a first write that finds the tag taken by another first write finished meanwhile
+    // replaces that record, its file is removed so none is left without a record
+    async fn insert_record(&self, record: CacheRecord) {
+        loop {
+            let entry = match self.map.entry(record.tag.clone()) {
+                Entry::Vacant(vacant) => {
+                    vacant.insert(Arc::new(RwLock::new(record)));
+                    return;
+                }
+                Entry::Occupied(occupied) => occupied.get().clone(),
+            };
+            let mut current = entry.write().await;
+            // removed while waiting for the lock, the record goes in on its own
+            let still_current = self
+                .map
+                .get(&record.tag)
+                .is_some_and(|found| Arc::ptr_eq(found.value(), &entry));
+            if !still_current {
+                continue;
+            }
+
+            self.forget(&record.tag).await;
+            let replaced = self.build_path(&current.filename);
+            *current = CacheRecord {
+                metadata: std::mem::take(&mut current.metadata),
+                pinned: current.pinned,
+                ..record
+            };
+            let _ = tokio::fs::remove_file(&replaced).await;
+            return;
+        }
+    }
+
+    async fn remove_record(&self, tag: &str) -> Result<Option<CacheRecord>, CacheError> {
+        let removed = self.map.remove(tag);
+        if removed.is_none() {
+            self.forget(tag).await;
+            return Ok(None);
+        }
+        let entry = removed.unwrap().1;
+        // forgotten under the write lock, see write_record
+        let record = entry.write().await;
+        self.forget(tag).await;
+        let record = record.clone();
+        let path = self.build_path(&record.filename);
+        if try_exists(&path)
             .await
             .map_err(|e| CacheError::IO(e.to_string()))?
         {
-            let file = File::create_new(filename)
+            tokio::fs::remove_file(&path)
                 .await
                 .map_err(|e| CacheError::IO(e.to_string()))?;
+        }
+        self.make_dirty();
+        Ok(Some(record))
+    }
+
+    async fn evict_record(&self, tag: &str) -> Result<bool, CacheError> {
+        let removed = self.remove_record(tag).await?;
+        if let Some(record) = &removed {
+            self.notify(|observer| observer.on_evict(&self.name, tag, record.size));
+            metrics(|registry| {
+                registry.increment_counter("file_cache.evictions", &[("channel", &self.name)], 1)
+            });
+        }
+        Ok(removed.is_some())
+    }
+
+    async fn fetch_record(&self, tag: &str) -> Result<Vec<u8>, CacheError> {
+        let entry = self.entry(tag)?;
+        let data = {
+            // held until the data is verified, write_record replaces the file under the write lock
+            let record = entry.read().await;
+            if record.is_expired(now_millis()) {
+                return Err(CacheError::Expired(tag.to_string()));
+            }
+            let filename = &record.filename;
+            let path = self.build_path(filename);
 
-            file.sync_all()
+            if !try_exists(&path)
                 .await
                 .map_err(|e| CacheError::IO(e.to_string()))?
+            {
+                return Err(CacheError::FileNotExist(path));
+            }
+
+            self.read_through(&record, path).await?
+        };
+        self.touch(&entry).await;
+        Ok(data.as_ref().clone())
+    }
+
+    // the access order decides what is evicted, so it is saved like any other change, the
+    // auto-save interval keeps reads from writing the index each time
+    async fn touch(&self, entry: &RwLock<CacheRecord>) {
+        entry.write().await.last_access = now_millis();
+        self.make_dirty();
+    }
+
+    async fn flush_record(&self, tag: &str) -> Result<bool, CacheError> {
+        let removed = self.remove_record(tag).await?;
+        if let Some(record) = &removed {
+            self.notify(|observer| observer.on_flush(&self.name, tag, record.size));
         }
-        Ok(())
+        Ok(removed.is_some())
     }
 
-    pub fn start_auto_save(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
-        let store = self.dirty.clone();
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(self.auto_save_interval);
-            loop {
-                interval.tick().await;
-                if store.load(Ordering::SeqCst) {
-                    if let Err(e) = self.persist().await {
-                        eprintln!("Failed to auto-save cache channel: {}", e);
-                    }
-                }
+    async fn get_or_put_locked(
+        &self,
+        tag: String,
+        sentence: String,
+        fetcher: CacheFetcher,
+    ) -> Result<Vec<u8>, CacheError> {
+        let stale = match self.should_update(&tag, &sentence).await {
+            Ok(stale) => stale,
+            Err(CacheError::TagNotExist(_)) => true,
+            Err(e) => return Err(e),
+        };
+        if !stale {
+            // an unreadable entry is refetched instead of failing the caller
+            if let Ok(data) = self.fetch(&tag).await {
+                return Ok(data);
             }
-        })
+        }
+
+        let bytes = fetcher().await?;
+        self.cache(tag, sentence, &bytes).await?;
+        Ok(bytes)
+    }
+
+    // an entry over the byte limit on its own can never fit the channel
+    fn too_large(&self, size: usize) -> bool {
+        self.max_bytes.is_some_and(|max_bytes| size as u64 > max_bytes)
+    }
+
+    // evicts least recently accessed records until the channel fits its limits
+    async fn enforce_limits(&self) -> Result<(), CacheError> {
+        if self.max_bytes.is_none() && self.max_entries.is_none() {
+            return Ok(());
+        }
+
+        let records = self.snapshot().await;
+        let mut total_bytes: u64 = records.iter().map(|record| record.size as u64).sum();
+        let mut total_entries = records.len();
+        let pinned: Vec<&CacheRecord> = records.iter().filter(|record| record.pinned).collect();
+        let pinned_bytes: u64 = pinned.iter().map(|record| record.size as u64).sum();
+        let pinned_entries = pinned.len();
+
+        // pinned records count against the limits but are never evicted, a limit the pinned
+        // records alone exceed cannot be met by evicting the others
+        let mut records: Vec<(String, usize, u64)> = records
+            .into_iter()
+            .filter(|record| !record.pinned)
+            .map(|record| (record.tag, record.size, record.last_access))
+            .collect();
+        records.sort_by_key(|(_, _, last_access)| *last_access);
+        for (tag, size, _) in records {
+            let over_bytes = self.max_bytes.is_some_and(|max_bytes| {
+                total_bytes > max_bytes && pinned_bytes <= max_bytes
+            });
+            let over_entries = self.max_entries.is_some_and(|max_entries| {
+                total_entries > max_entries && pinned_entries <= max_entries
+            });
+            if !over_bytes && !over_entries {
+                break;
+            }
+
+            if self.evict_record(&tag).await? {
+                total_bytes -= size as u64;
+                total_entries -= 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn report(
+        &self,
+        diagnostics: &broadcast::Sender<CacheDiagnostic>,
+        task: CacheTask,
+        error: &CacheError,
+    ) {
+        // nobody listening is not an error
+        let _ = diagnostics.send(CacheDiagnostic {
+            channel: self.name.clone(),
+            task,
+            error: error.to_string(),
+            at: now_millis(),
+        });
     }
 }
 
 #[async_trait]
 impl<T> FileCacheManagerFactory for SingletonFileCacheManagerFactory<T>
 where
-    T: Fn(&FileCacheConfig, CacheChannel, Arc<dyn StorageManager>) -> Arc<dyn FileCacheManager>
+    T: Fn(
+        &FileCacheConfig,
+        CacheChannel,
+        Option<&FileCacheChannelConfig>,
+        Arc<dyn StorageManager>,
+    ) -> Arc<dyn FileCacheManager>
         + Send
         + Sync
         + 'static,
@@ -174,11 +865,15 @@ where
         name: String,
         extension: Option<String>,
     ) -> Result<Arc<dyn FileCacheManager>, CacheError> {
-        if self.map.contains_key(&name) {
-            return Ok(self.map.get(&name).unwrap().clone());
+        if let Some(manager) = self.map.get(&name) {
+            return Ok(manager.clone());
+        }
+        let _guard = self.create_lock.lock().await;
+        if let Some(manager) = self.map.get(&name) {
+            return Ok(manager.clone());
         }
         let channel = self.create_channel(name, extension).await?;
-        self.create_with_channel(channel).await
+        self.open_channel(channel).await
     }
 
     async fn create_channel(
@@ -206,9 +901,15 @@ where
             .map_err(|e| CacheError::ErrorForward(e.to_string()))?;
         
         if channel.is_none() {
+            let version = self
+                .channel_configs
+                .get(&name)
+                .and_then(|channel_config| channel_config.schema_version)
+                .unwrap_or(0);
             let channel = CacheChannel {
                 name,
                 extension,
+                version,
                 records: Vec::new(),
             };
             return Ok(channel);
@@ -226,23 +927,161 @@ where
         &self,
         channel: CacheChannel,
     ) -> Result<Arc<dyn FileCacheManager>, CacheError> {
-        let name = channel.name.clone();
-        if self.map.contains_key(&name) {
-            return Ok(self.map.get(&name).unwrap().clone());
+        if let Some(manager) = self.map.get(&channel.name) {
+            return Ok(manager.clone());
         }
-        let manager = (self.creator)(&self.config, channel, self.storage_manager.clone());
-        self.map.insert(name, manager.clone());
+        let _guard = self.create_lock.lock().await;
+        if let Some(manager) = self.map.get(&channel.name) {
+            return Ok(manager.clone());
+        }
+        self.open_channel(channel).await
+    }
 
-        Ok(manager)
+    async fn create_with_options(
+        &self,
+        channel_config: FileCacheChannelConfig,
+    ) -> Result<Arc<dyn FileCacheManager>, CacheError> {
+        let name = channel_config.name.clone();
+        check_channel_name(&name)?;
+        if let Some(manager) = self.map.get(&name) {
+            return Ok(manager.clone());
+        }
+        let _guard = self.create_lock.lock().await;
+        if let Some(manager) = self.map.get(&name) {
+            return Ok(manager.clone());
+        }
+        let extension = channel_config.extension.clone();
+        self.channel_configs.insert(name.clone(), channel_config);
+        let channel = self.create_channel(name, extension).await?;
+        self.open_channel(channel).await
     }
 
-    async fn get_with_name(&self, name: &String) -> Result<Arc<dyn FileCacheManager>, CacheError> {
+    async fn get_with_name(&self, name: &str) -> Result<Arc<dyn FileCacheManager>, CacheError> {
         if !self.map.contains_key(name) {
-            return Err(CacheError::ManagerNotExist(name.clone()));
+            // only a name that stays a directory right under the base path is created
+            if self.config.auto_create_channels && is_plain_name(name) {
+                return self.create_with_name(name.to_string(), None).await;
+            }
+            return Err(CacheError::ManagerNotExist(name.to_string()));
         }
         let manager = self.map.get(name).unwrap();
         Ok(manager.clone())
     }
+
+    async fn delete_channel(&self, name: &str) -> Result<(), CacheError> {
+        check_channel_name(name)?;
+        let removed = self.map.remove(name);
+        if removed.is_none() {
+            return Err(CacheError::ManagerNotExist(name.to_string()));
+        }
+        self.channel_configs.remove(name);
+        let _ = self.job_scheduler.unregister(&auto_save_job_name(name));
+        removed.unwrap().1.destroy().await
+    }
+
+    async fn stats_all(&self) -> Result<Vec<CacheStats>, CacheError> {
+        let managers: Vec<Arc<dyn FileCacheManager>> =
+            self.map.iter().map(|entry| entry.value().clone()).collect();
+
+        let mut stats = Vec::new();
+        for manager in managers {
+            stats.push(manager.stats().await?);
+        }
+        stats.sort_by(|a, b| a.channel.cmp(&b.channel));
+        Ok(stats)
+    }
+
+    async fn clear_all(&self) -> Result<(), CacheError> {
+        let managers: Vec<Arc<dyn FileCacheManager>> =
+            self.map.iter().map(|entry| entry.value().clone()).collect();
+
+        for manager in managers {
+            manager.clear().await?;
+        }
+        Ok(())
+    }
+
+    async fn export_channel(&self, name: &str, path: &str) -> Result<(), CacheError> {
+        let manager = self.get_with_name(name).await?;
+        manager.export_archive(path).await
+    }
+
+    async fn import_channel(&self, path: &str) -> Result<String, CacheError> {
+        let staging = format!("{}/.import-{}", self.config.base_path, Uuid::new_v4());
+        let archive_path = path.to_string();
+        let staging_path = staging.clone();
+        let unpacked = tokio::task::spawn_blocking(move || {
+            std::fs::create_dir_all(&staging_path)?;
+            unpack_archive(&archive_path, &staging_path)
+        })
+        .await
+        .map_err(|e| CacheError::IO(e.to_string()));
+
+        let imported = match unpacked {
+            Ok(Ok(index)) => self.import_index(index, &staging).await,
+            Ok(Err(e)) => Err(CacheError::IO(e.to_string())),
+            Err(e) => Err(e),
+        };
+        let _ = tokio::fs::remove_dir_all(&staging).await;
+        imported
+    }
+
+    // applies to existing channels and to every channel created afterwards
+    fn add_observer(&self, observer: Arc<dyn FileCacheObserver>) {
+        self.observers.write().push(observer.clone());
+        for entry in self.map.iter() {
+            entry.value().observe(observer.clone());
+        }
+    }
+
+    fn subscribe_diagnostics(&self) -> broadcast::Receiver<CacheDiagnostic> {
+        self.diagnostics.subscribe()
+    }
+
+    async fn persist_all(&self) -> Result<(), CacheError> {
+        let managers: Vec<Arc<dyn FileCacheManager>> =
+            self.map.iter().map(|entry| entry.value().clone()).collect();
+        for manager in managers {
+            manager.persist().await?;
+        }
+        Ok(())
+    }
+
+    async fn set_auto_save_interval(&self, interval: Duration) -> Result<(), CacheError> {
+        *self.auto_save_interval.lock() = interval;
+        let managers: Vec<(String, Arc<dyn FileCacheManager>)> = self
+            .map
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        for (name, manager) in managers {
+            let job_name = auto_save_job_name(&name);
+            // a job paused on its own stays paused with the new interval
+            let paused = self
+                .job_scheduler
+                .jobs()
+                .iter()
+                .any(|job| job.name == job_name && job.paused);
+            let _ = self.job_scheduler.unregister(&job_name);
+            self.schedule_auto_save(&name, manager, interval).await?;
+            if paused {
+                self.job_scheduler
+                    .pause(&job_name)
+                    .map_err(|e| CacheError::ErrorForward(e.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+
+    // unregisters the auto-save jobs first so the final persist cannot race one
+    async fn shutdown(&self) -> Result<(), CacheError> {
+        for entry in self.map.iter() {
+            let _ = self
+                .job_scheduler
+                .unregister(&auto_save_job_name(entry.key()));
+        }
+        self.persist_all().await
+    }
 }
 
 #[async_trait]
@@ -251,76 +1090,121 @@ impl FileCacheManager for DefaultFileCacheManager {
         &self,
         tag: String,
         sentence: String,
-        bytes: &Vec<u8>,
+        bytes: &[u8],
     ) -> Result<(), CacheError> {
-        if self.map.contains_key(&tag) {
-            let entry = self.map.get_mut(&tag).ok_or(CacheError::TagNotExist(tag))?;
-            let mut record = entry
-                .try_write()
-                .map_err(|e| CacheError::Lock(e.to_string()))?;
+        self.cache_with_ttl(tag, sentence, bytes, None).await
+    }
 
-            let path = self.build_path(&record.filename);
-            self.ensure_directory_exist(&self.path).await?;
-            self.ensure_file_exist(&path).await?;
-
-            let write_file = WriteFile {
-                path,
-                mode: WriteMode::Cover,
-                timeout: Duration::from_secs(60),
-                ensure_mode: None,
-                data: bytes,
-            };
+    async fn cache_with_ttl(
+        &self,
+        tag: String,
+        sentence: String,
+        bytes: &[u8],
+        ttl: Option<Duration>,
+    ) -> Result<(), CacheError> {
+        // it would be the first to go once written
+        if self.too_large(bytes.len()) {
+            return Err(CacheError::TooLarge(tag));
+        }
+        self.write_record(tag, sentence, bytes, ttl).await?;
+        self.enforce_limits().await
+    }
 
-            return self
-                .storage_manager
-                .write(write_file)
+    async fn cache_stream(
+        &self,
+        tag: String,
+        sentence: String,
+        mut reader: Pin<Box<dyn AsyncRead + Send>>,
+    ) -> Result<(), CacheError> {
+        // authenticated encryption needs the whole payload, so encrypted channels buffer it
+        if self.cipher.is_some() {
+            let mut bytes = Vec::new();
+            reader
+                .read_to_end(&mut bytes)
                 .await
-                .inspect(|_| {
-                    record.sentence = sentence;
-                    record.size = bytes.len();
-                    self.make_dirty();
-                })
-                .map_err(|e| CacheError::from(e));
+                .map_err(|e| CacheError::IO(e.to_string()))?;
+            return self.cache_with_ttl(tag, sentence, &bytes, None).await;
         }
 
-        let filename = Uuid::new_v4().to_string();
+        let filename = match self.entry(&tag) {
+            Ok(entry) => entry.read().await.filename.clone(),
+            Err(_) => Uuid::new_v4().to_string(),
+        };
         let path = self.build_path(&filename);
         self.ensure_directory_exist(&self.path).await?;
-        self.ensure_file_exist(&path).await?;
 
-        let write_file = WriteFile {
-            path,
-            mode: WriteMode::Cover,
-            timeout: Duration::from_secs(60),
-            ensure_mode: None,
-            data: bytes,
+        // stream into a temporary file first so readers never see a partial entry
+        let temporary_path = format!("{}.{}.tmp", path, Uuid::new_v4());
+        let mut file = File::create(&temporary_path)
+            .await
+            .map_err(|e| CacheError::IO(e.to_string()))?;
+        let copied = {
+            let mut writer = compressed_writer(self.compression.as_ref(), &mut file);
+            copy_with_checksum(&mut reader, &mut writer).await
         };
+        let synced = file.sync_all().await;
+        drop(file);
+        if copied.is_err() || synced.is_err() {
+            let _ = tokio::fs::remove_file(&temporary_path).await;
+            let error = copied.err().or(synced.err()).unwrap();
+            return Err(CacheError::IO(error.to_string()));
+        }
+        let (size, checksum) = copied.unwrap();
+        let size = size as usize;
+        // the size of a stream is only known once it is written, the entry it replaces stays
+        if self.too_large(size) {
+            let _ = tokio::fs::remove_file(&temporary_path).await;
+            return Err(CacheError::TooLarge(tag));
+        }
 
-        self.storage_manager
-            .write(write_file)
-            .await
-            .inspect(|_| {
-                let record = CacheRecord {
-                    tag: tag.clone(),
-                    filename,
-                    size: bytes.len(),
-                    sentence,
-                };
-                
-                self.map.insert(tag, RwLock::new(record));
-                self.make_dirty();
-            })
-            .map_err(|e| CacheError::from(e))
+        let expires_at = self.expires_at(None);
+        let now = now_millis();
+        if let Ok(entry) = self.entry(&tag) {
+            // replaced and forgotten under the write lock, see write_record
+            let mut record = entry.write().await;
+            tokio::fs::rename(&temporary_path, &path)
+                .await
+                .map_err(|e| CacheError::IO(e.to_string()))?;
+            self.forget(&tag).await;
+            record.sentence = sentence;
+            record.size = size;
+            record.expires_at = expires_at;
+            record.last_access = now;
+            record.cached_at = now;
+            record.checksum = Some(checksum);
+            record.compression = self.compression.clone();
+            record.encrypted = false;
+        } else {
+            tokio::fs::rename(&temporary_path, &path)
+                .await
+                .map_err(|e| CacheError::IO(e.to_string()))?;
+            self.forget(&tag).await;
+            let record = CacheRecord {
+                tag: tag.clone(),
+                filename,
+                size,
+                sentence,
+                expires_at,
+                last_access: now,
+                cached_at: now,
+                checksum: Some(checksum),
+                compression: self.compression.clone(),
+                encrypted: false,
+                metadata: Vec::new(),
+                pinned: false,
+            };
+            self.insert_record(record).await;
+        }
+        self.make_dirty();
+        self.notify(|observer| observer.on_cache(&self.name, &tag, size));
+        self.enforce_limits().await
     }
 
-    async fn should_update(&self, tag: &String, sentence: &String) -> Result<bool, CacheError> {
-        let entry = self
-            .map
-            .get_mut(tag)
-            .ok_or(CacheError::TagNotExist(tag.clone()))?;
-        let record = entry
-            .try_write()
-            .map_err(|e| CacheError::Lock(e.to_string()))?;
+    async fn should_update(&self, tag: &str, sentence: &str) -> Result<bool, CacheError> {
+        let record = self.entry(tag)?.read().await.clone();
+        if record.is_expired(now_millis()) {
+            return Ok(true);
+        }
         let filename = &record.filename;
         if !try_exists(self.build_path(filename))
             .await
@@ -332,16 +1216,61 @@ impl FileCacheManager for DefaultFileCacheManager {
         Ok(record.sentence != *sentence)
     }
 
-    async fn fetch(&self, tag: &String) -> Result<Vec<u8>, CacheError> {
-        let entry = self
-            .map
-            .get_mut(tag)
-            .ok_or(CacheError::TagNotExist(tag.clone()))?;
-        let record = entry
-            .try_write()
-            .map_err(|e| CacheError::Lock(e.to_string()))?;
-        let filename = &record.filename;
-        let path = self.build_path(filename);
+    async fn remove_if_stale(&self, tag: &str, sentence: &str) -> Result<bool, CacheError> {
+        if !self.should_update(tag, sentence).await? {
+            return Ok(false);
+        }
+        self.flush_record(tag).await
+    }
+
+    async fn get_or_put(
+        &self,
+        tag: String,
+        sentence: String,
+        fetcher: CacheFetcher,
+    ) -> Result<Vec<u8>, CacheError> {
+        // concurrent callers for the same tag queue here, later ones see the fresh entry
+        let lock = self
+            .in_flight
+            .entry(tag.clone())
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone();
+        let guard = lock.lock().await;
+        let result = self.get_or_put_locked(tag.clone(), sentence, fetcher).await;
+        drop(guard);
+
+        self.in_flight.remove_if(&tag, |_, in_flight| {
+            Arc::ptr_eq(in_flight, &lock) && Arc::strong_count(in_flight) == 2
+        });
+        result
+    }
+
+    async fn fetch(&self, tag: &str) -> Result<Vec<u8>, CacheError> {
+        let result = self.fetch_record(tag).await;
+        let name = if result.is_ok() {
+            "file_cache.hits"
+        } else {
+            "file_cache.misses"
+        };
+        metrics(|registry| registry.increment_counter(name, &[("channel", &self.name)], 1));
+        result
+    }
+
+    async fn fetch_stream(
+        &self,
+        tag: &str,
+    ) -> Result<BoxStream<'static, Result<Bytes, CacheError>>, CacheError> {
+        let entry = self.entry(tag)?;
+        let mut record = entry.write().await;
+        if record.is_expired(now_millis()) {
+            return Err(CacheError::Expired(tag.to_string()));
+        }
+        record.last_access = now_millis();
+        self.make_dirty();
+        // the file is opened under the lock, write_record replaces it by a rename so the open
+        // file stays the one the record describes
+        let record = record.downgrade();
+        let path = self.build_path(&record.filename);
 
         if !try_exists(&path)
             .await
@@ -350,53 +1279,74 @@ impl FileCacheManager for DefaultFileCacheManager {
             return Err(CacheError::FileNotExist(path));
         }
 
-        let read_file = ReadFile::path(path);
-        self.storage_manager
-            .read(read_file)
+        let cached = match &self.memory {
+            Some(memory) => memory.get(tag).await,
+            None => None,
+        };
+        if cached.is_some() || record.encrypted {
+            let data = match cached {
+                Some(data) => {
+                    self.memory_hits.fetch_add(1, Ordering::Relaxed);
+                    data
+                }
+                None => self.read_through(&record, path).await?,
+            };
+            return Ok(Box::pin(futures_util::stream::once(async move {
+                Ok(Bytes::from(data.as_ref().clone()))
+            })));
+        }
+
+        let file = File::open(&path)
             .await
-            .map_err(|e| CacheError::from(e))
-    }
+            .map_err(|e| CacheError::IO(e.to_string()))?;
+        let record = record.clone();
+        let reader = decompressed_reader(record.compression.as_ref(), file);
+        let stream = ReaderStream::new(reader).map_err(|e| CacheError::IO(e.to_string()));
+        if record.checksum.is_none() {
+            return Ok(Box::pin(stream));
+        }
 
-    async fn flush(&self, tag: &String) -> Result<(), CacheError> {
-        // if !self.map.contains_key(tag) {
-        //     return Err(CacheError::TagNotExist(tag.clone()));
-        // }
-        //
-        // let record = self.map.remove(tag).unwrap();
-        // self.make_dirty();
-        //
-        // let record = record.1.into_inner();
-        // let path = self.build_path(&record.filename);
-        //
-        // if try_exists(&path)
-        //     .await
-        //     .map_err(|e| CacheError::IO(e.to_string()))?
-        // {
-        //     return tokio::fs::remove_file(path)
-        //         .await
-        //         .map_err(|e| CacheError::IO(e.to_string()));
-        // }
+        // the mismatch can only be known once the last chunk went out
+        let expected = record.checksum.unwrap();
+        let tag = tag.to_string();
+        let hasher = Arc::new(parking_lot::Mutex::new(Xxh3::new()));
+        let chunk_hasher = hasher.clone();
+        let verify = futures_util::stream::once(async move {
+            if hasher.lock().digest() != expected {
+                return Some(Err(CacheError::Corrupted(tag)));
+            }
+            None
+        })
+        .filter_map(|result| async move { result });
+        let stream = stream
+            .inspect_ok(move |chunk| chunk_hasher.lock().update(chunk))
+            .chain(verify);
+        Ok(Box::pin(stream))
+    }
 
+    async fn flush(&self, tag: &str) -> Result<(), CacheError> {
+        if !self.flush_record(tag).await? {
+            return Err(CacheError::TagNotExist(tag.to_string()));
+        }
         Ok(())
     }
 
     async fn persist(&self) -> Result<(), CacheError> {
-        if !self.is_dirty() {
+        let _guard = self.save_lock.lock().await;
+        // cleared before the snapshot, a change made while the index is written stays dirty,
+        // and a save cancelled or failed before the write puts the mark back
+        let dirty = DirtyGuard::take(&self.dirty);
+        if !dirty.was_dirty() {
             return Ok(());
         }
 
-        let _ = self.save_lock.lock();
-
-        let mut records: Vec<CacheRecord> = Vec::new();
-        for record in &self.map {
-            let record = record.read().await;
-            let record = record.clone();
-            records.push(record);
-        }
+        let records = self.snapshot().await;
+        let entries = records.len();
 
         let channel = CacheChannel {
             name: self.name.clone(),
             extension: self.extension.clone(),
+            version: self.version,
             records,
         };
 
@@ -405,7 +1355,9 @@ impl FileCacheManager for DefaultFileCacheManager {
         rkv_service
             .write_rkyv_cache_channel_data(&self.single_store, &self.name, &channel)
             .map_err(|e| CacheError::ErrorForward(e.to_string()))?;
-        self.make_clean();
+        dirty.written();
+        self.last_persist.store(now_millis(), Ordering::SeqCst);
+        self.notify(|observer| observer.on_persist(&self.name, entries));
         Ok(())
 
         // let bytes = rkyv::to_bytes::<Error>(&channel)
@@ -426,32 +1378,360 @@ impl FileCacheManager for DefaultFileCacheManager {
         // self.storage_manager
         //     .write(write_file)
         //     .await
-        //     .map_err(|e| CacheError::from(e))
+        //     .map_err(CacheError::from)
         //     .inspect(|_| {
         //         self.make_clean();
         //     })
     }
 
-    async fn record(&self, tag: &String) -> Result<CacheRecord, CacheError> {
-        let entry = self
-            .map
-            .get_mut(tag)
-            .ok_or(CacheError::TagNotExist(tag.clone()))?;
-        let record = entry
-            .try_write()
-            .map_err(|e| CacheError::Lock(e.to_string()))?;
-        let record = record.clone();
+    async fn clear(&self) -> Result<(), CacheError> {
+        let tags: Vec<String> = self.map.iter().map(|entry| entry.key().clone()).collect();
+        for tag in tags {
+            self.flush_record(&tag).await?;
+        }
+        self.make_dirty();
+        self.persist().await
+    }
+
+    async fn recover_orphans(&self) -> Result<usize, CacheError> {
+        if !try_exists(&self.path)
+            .await
+            .map_err(|e| CacheError::IO(e.to_string()))?
+        {
+            return Ok(0);
+        }
+
+        let known: HashSet<String> = self
+            .snapshot()
+            .await
+            .iter()
+            .map(|record| self.build_path(&record.filename))
+            .collect();
+
+        let mut directory = tokio::fs::read_dir(&self.path)
+            .await
+            .map_err(|e| CacheError::IO(e.to_string()))?;
+        let mut removed = 0;
+        while let Some(entry) = directory
+            .next_entry()
+            .await
+            .map_err(|e| CacheError::IO(e.to_string()))?
+        {
+            let is_file = entry
+                .file_type()
+                .await
+                .map(|file_type| file_type.is_file())
+                .unwrap_or(false);
+            if !is_file {
+                continue;
+            }
+
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let path = format!("{}/{}", self.path, file_name);
+            if file_name.ends_with(".tmp") || !known.contains(&path) {
+                tokio::fs::remove_file(entry.path())
+                    .await
+                    .map_err(|e| CacheError::IO(e.to_string()))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn auto_save(
+        &self,
+        diagnostics: &broadcast::Sender<CacheDiagnostic>,
+    ) -> Result<(), CacheError> {
+        let swept = self.sweep_expired().await.map(|_| ());
+        if let Err(e) = &swept {
+            self.report(diagnostics, CacheTask::Sweep, e);
+        }
+        // a channel without changes is not written
+        let persisted = self.persist().await;
+        if let Err(e) = &persisted {
+            self.report(diagnostics, CacheTask::Persist, e);
+        }
+        swept.and(persisted)
+    }
+
+    async fn destroy(&self) -> Result<(), CacheError> {
+        if let Some(memory) = &self.memory {
+            memory.invalidate_all();
+        }
+        self.map.clear();
+        self.make_clean();
+
+        if try_exists(&self.path)
+            .await
+            .map_err(|e| CacheError::IO(e.to_string()))?
+        {
+            tokio::fs::remove_dir_all(&self.path)
+                .await
+                .map_err(|e| CacheError::IO(e.to_string()))?;
+        }
+
+        let rkv_service = RKV_SERVICE.read().unwrap();
+        let rkv_service = rkv_service.as_ref().unwrap();
+        rkv_service
+            .delete_rkyv_cache_channel_data(&self.single_store, &self.name)
+            .map_err(|e| CacheError::ErrorForward(e.to_string()))
+    }
+
+    async fn migrate(&self) -> Result<(), CacheError> {
+        let from_version = self.loaded_version.load(Ordering::SeqCst);
+        let to_version = self.version;
+        if from_version == to_version {
+            return Ok(());
+        }
+
+        if self.migration.is_none() {
+            self.clear().await?;
+            self.loaded_version.store(to_version, Ordering::SeqCst);
+            return Ok(());
+        }
+
+        let migration = self.migration.as_ref().unwrap();
+        let now = now_millis();
+        for record in self.snapshot().await {
+            let path = self.build_path(&record.filename);
+            // unreadable entries cannot be migrated and are dropped
+            let migrated = match self.read_record(&record, path).await {
+                Ok(data) => migration.migrate(from_version, to_version, &record, data)?,
+                Err(_) => None,
+            };
+            if migrated.is_none() {
+                self.flush_record(&record.tag).await?;
+                continue;
+            }
+
+            let ttl = record
+                .expires_at
+                .map(|expires_at| Duration::from_millis(expires_at.saturating_sub(now)));
+            self.write_record(record.tag, record.sentence, &migrated.unwrap(), ttl)
+                .await?;
+        }
+
+        self.make_dirty();
+        self.persist().await?;
+        self.loaded_version.store(to_version, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn version(&self) -> u32 {
+        self.version
+    }
+
+    fn observe(&self, observer: Arc<dyn FileCacheObserver>) {
+        self.observers.write().push(observer);
+    }
+
+    async fn export_archive(&self, path: &str) -> Result<(), CacheError> {
+        let records = self.snapshot().await;
+        let files: Vec<(String, String)> = records
+            .iter()
+            .map(|record| {
+                let source = self.build_path(&record.filename);
+                let name = source
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or(&record.filename)
+                    .to_string();
+                (source, name)
+            })
+            .collect();
+
+        let channel = CacheChannel {
+            name: self.name.clone(),
+            extension: self.extension.clone(),
+            version: self.version,
+            records,
+        };
+        let index = channel.to_bytes().map_err(CacheError::Serialization)?;
+
+        let path = path.to_string();
+        tokio::task::spawn_blocking(move || write_archive(&path, &index, files))
+            .await
+            .map_err(|e| CacheError::IO(e.to_string()))?
+            .map_err(|e| CacheError::IO(e.to_string()))
+    }
+
+    async fn import_records(
+        &self,
+        records: Vec<CacheRecord>,
+        extension: Option<String>,
+        source: &str,
+    ) -> Result<usize, CacheError> {
+        self.ensure_directory_exist(&self.path).await?;
+
+        let mut imported = 0;
+        for record in records {
+            let source_name = match &extension {
+                Some(extension) => format!("{}.{}", record.filename, extension),
+                None => record.filename.clone(),
+            };
+            // the index comes from the archive, a filename reaching outside source is skipped
+            if !is_plain_name(&source_name) {
+                continue;
+            }
+            let source_path = format!("{}/{}", source, source_name);
+            if !try_exists(&source_path)
+                .await
+                .map_err(|e| CacheError::IO(e.to_string()))?
+            {
+                continue;
+            }
+
+            // imported entries replace local ones and get fresh filenames to avoid collisions
+            self.remove_record(&record.tag).await?;
+            let filename = Uuid::new_v4().to_string();
+            tokio::fs::rename(&source_path, self.build_path(&filename))
+                .await
+                .map_err(|e| CacheError::IO(e.to_string()))?;
+
+            let tag = record.tag.clone();
+            let record = CacheRecord { filename, ..record };
+            self.map.insert(tag, Arc::new(RwLock::new(record)));
+            imported += 1;
+        }
+        self.make_dirty();
+        Ok(imported)
+    }
+
+    async fn sweep_expired(&self) -> Result<usize, CacheError> {
+        let now = now_millis();
+        let expired_tags: Vec<String> = self
+            .snapshot()
+            .await
+            .into_iter()
+            .filter(|record| record.is_expired(now))
+            .map(|record| record.tag)
+            .collect();
+
+        let mut swept = 0;
+        for tag in expired_tags {
+            if self.evict_record(&tag).await? {
+                swept += 1;
+            }
+        }
+        Ok(swept)
+    }
+
+    async fn verify_all(&self, purge: bool) -> Result<Vec<String>, CacheError> {
+        let records = self.snapshot().await;
+
+        let mut corrupted = Vec::new();
+        for record in records {
+            let path = self.build_path(&record.filename);
+            let valid = if record.encrypted {
+                self.read_record(&record, path).await.is_ok()
+            } else {
+                let actual = file_checksum(&path, record.compression.as_ref()).await;
+                match (actual, record.checksum) {
+                    (Ok(actual), Some(expected)) => actual == expected,
+                    (Ok(_), None) => true,
+                    (Err(_), _) => false,
+                }
+            };
+            if !valid {
+                corrupted.push(record.tag);
+            }
+        }
+
+        if purge {
+            for tag in corrupted.iter() {
+                self.evict_record(tag).await?;
+            }
+        }
+        Ok(corrupted)
+    }
+
+    async fn record(&self, tag: &str) -> Result<CacheRecord, CacheError> {
+        let record = self.entry(tag)?.read().await.clone();
         Ok(record)
     }
 
-    async fn path(&self, tag: &String) -> Result<String, CacheError> {
-        let entry = self
-            .map
-            .get_mut(tag)
-            .ok_or(CacheError::TagNotExist(tag.clone()))?;
-        let record = entry
-            .try_write()
-            .map_err(|e| CacheError::Lock(e.to_string()))?;
+    async fn pin(&self, tag: &str) -> Result<(), CacheError> {
+        self.entry(tag)?.write().await.pinned = true;
+        self.make_dirty();
+        Ok(())
+    }
+
+    async fn unpin(&self, tag: &str) -> Result<(), CacheError> {
+        self.entry(tag)?.write().await.pinned = false;
+        self.make_dirty();
+        Ok(())
+    }
+
+    async fn update_metadata(
+        &self,
+        tag: &str,
+        metadata: Vec<(String, String)>,
+    ) -> Result<(), CacheError> {
+        self.entry(tag)?.write().await.merge_metadata(metadata);
+        self.make_dirty();
+        Ok(())
+    }
+
+    async fn stats(&self) -> Result<CacheStats, CacheError> {
+        let paths: Vec<String> = self
+            .snapshot()
+            .await
+            .iter()
+            .map(|record| self.build_path(&record.filename))
+            .collect();
+
+        let mut total_bytes = 0;
+        for path in paths.iter() {
+            // a record whose file vanished takes no space
+            if let Ok(metadata) = tokio::fs::metadata(path).await {
+                total_bytes += metadata.len();
+            }
+        }
+
+        let last_persist = self.last_persist.load(Ordering::SeqCst);
+        Ok(CacheStats {
+            channel: self.name.clone(),
+            entries: paths.len(),
+            total_bytes,
+            dirty: self.is_dirty(),
+            last_persist: if last_persist == 0 {
+                None
+            } else {
+                Some(last_persist)
+            },
+            memory_hits: self.memory_hits.load(Ordering::Relaxed),
+            memory_misses: self.memory_misses.load(Ordering::Relaxed),
+            memory_bytes: self
+                .memory
+                .as_ref()
+                .map(|memory| memory.weighted_size())
+                .unwrap_or(0),
+        })
+    }
+
+    async fn list(&self, filter: Option<CacheRecordFilter>) -> Result<Vec<CacheRecord>, CacheError> {
+        let now = now_millis();
+        let mut records: Vec<CacheRecord> = self
+            .snapshot()
+            .await
+            .into_iter()
+            .filter(|record| filter.as_ref().is_none_or(|filter| filter.matches(record, now)))
+            .collect();
+        records.sort_by(|a, b| a.tag.cmp(&b.tag));
+        Ok(records)
+    }
+
+    // the file is handed to readers outside the cache, such as a native player, so it has to
+    // hold the bytes as they were cached
+    async fn path(&self, tag: &str) -> Result<String, CacheError> {
+        let entry = self.entry(tag)?;
+        let record = entry.read().await.clone();
+        if record.is_expired(now_millis()) {
+            return Err(CacheError::Expired(tag.to_string()));
+        }
+        if record.compression.is_some() || record.encrypted {
+            return Err(CacheError::Encoded(tag.to_string()));
+        }
         let filename = &record.filename;
         let path = self.build_path(filename);
 
@@ -462,6 +1742,921 @@ impl FileCacheManager for DefaultFileCacheManager {
             return Err(CacheError::FileNotExist(path));
         }
 
+        // counts as an access so the file is not evicted while it is being read
+        self.touch(&entry).await;
         Ok(path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::file_cache_models::{
+        CacheChannel, CacheError, CacheRecord, CompressionKind, LegacyCacheChannel,
+        LegacyCacheRecord,
+    };
+    use crate::domain::models::scheduler_models::JobSchedule;
+    use crate::domain::traits::file_cache_traits::{
+        CacheMigration, FileCacheManager, FileCacheManagerFactory, FileCacheObserver,
+    };
+    use crate::domain::traits::scheduler_traits::JobScheduler;
+    use crate::infrastructure::storage::storage_backend::AsyncStorageManager;
+    use crate::rkv::rkv_impl::{RKV_SERVICE, initialize_rkv};
+    use crate::service::config::{FileCacheChannelConfig, FileCacheConfig, SchedulerConfig};
+    use crate::superstructure::file_cache_backend::{
+        DefaultFileCacheManager, SingletonFileCacheManagerFactory, auto_save_job_name,
+        unpack_archive,
+    };
+    use crate::superstructure::job_scheduler::DefaultJobScheduler;
+    use futures_util::TryStreamExt;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, LazyLock};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    macro_rules! await_test {
+        ($e:expr) => {
+            tokio_test::block_on($e)
+        };
+    }
+
+    // rkv is initialized once per process, so every test shares this directory
+    fn initialize_test_rkv() {
+        static DATABASES: LazyLock<TempDir> = LazyLock::new(|| tempfile::tempdir().unwrap());
+        initialize_rkv(DATABASES.path().to_string_lossy().to_string());
+    }
+
+    fn channel_config(name: &str) -> FileCacheChannelConfig {
+        FileCacheChannelConfig {
+            name: name.to_string(),
+            extension: None,
+            default_ttl: None,
+            max_bytes: None,
+            max_entries: None,
+            compression: None,
+            encryption_key: None,
+            memory_budget: None,
+            schema_version: None,
+            migration: None,
+        }
+    }
+
+    fn empty_channel(name: &str) -> CacheChannel {
+        CacheChannel {
+            name: name.to_string(),
+            extension: None,
+            version: 0,
+            records: Vec::new(),
+        }
+    }
+
+    // the channel lives in a directory named after it inside `directory`
+    fn manager(
+        directory: &TempDir,
+        channel: CacheChannel,
+        channel_config: &FileCacheChannelConfig,
+    ) -> DefaultFileCacheManager {
+        initialize_test_rkv();
+        let path = directory.path().join(&channel.name);
+        DefaultFileCacheManager::new(
+            path.to_string_lossy().to_string(),
+            channel,
+            Some(channel_config),
+            Arc::new(AsyncStorageManager::new()),
+        )
+    }
+
+    fn job_scheduler() -> Arc<dyn JobScheduler> {
+        let storage_manager = Arc::new(AsyncStorageManager::new());
+        let job_scheduler = await_test!(DefaultJobScheduler::new(
+            SchedulerConfig::default(),
+            storage_manager
+        ));
+        Arc::new(job_scheduler.unwrap())
+    }
+
+    fn factory(base_path: &str, auto_create_channels: bool) -> Arc<dyn FileCacheManagerFactory> {
+        factory_with_scheduler(base_path, auto_create_channels, job_scheduler())
+    }
+
+    fn factory_with_scheduler(
+        base_path: &str,
+        auto_create_channels: bool,
+        job_scheduler: Arc<dyn JobScheduler>,
+    ) -> Arc<dyn FileCacheManagerFactory> {
+        initialize_test_rkv();
+        let factory = SingletonFileCacheManagerFactory::new(
+            FileCacheConfig {
+                base_path: base_path.to_string(),
+                auto_save_interval: Duration::from_secs(60),
+                channels: None,
+                auto_create_channels,
+            },
+            Arc::new(AsyncStorageManager::new()),
+            job_scheduler,
+            |config, channel, channel_config, storage_manager| {
+                let path = format!("{}/{}", config.base_path, channel.name);
+                let manager: Arc<dyn FileCacheManager> = Arc::new(DefaultFileCacheManager::new(
+                    path,
+                    channel,
+                    channel_config,
+                    storage_manager,
+                ));
+                manager
+            },
+        );
+        Arc::new(factory)
+    }
+
+    #[test]
+    fn test_expired_entries_are_refused_and_swept() {
+        let directory = tempfile::tempdir().unwrap();
+        let manager = manager(&directory, empty_channel("ttl"), &channel_config("ttl"));
+        let tag = "tag".to_string();
+
+        await_test!(async {
+            manager
+                .cache_with_ttl(
+                    tag.clone(),
+                    "sentence".to_string(),
+                    b"data",
+                    Some(Duration::from_secs(1)),
+                )
+                .await
+                .unwrap();
+            assert_eq!(manager.fetch(&tag).await.unwrap(), b"data");
+
+            tokio::time::sleep(Duration::from_millis(1200)).await;
+            assert!(matches!(
+                manager.fetch(&tag).await,
+                Err(CacheError::Expired(_))
+            ));
+            assert_eq!(manager.sweep_expired().await.unwrap(), 1);
+            assert!(matches!(
+                manager.record(&tag).await,
+                Err(CacheError::TagNotExist(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_least_recently_used_entry_is_evicted() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut config = channel_config("lru");
+        config.max_entries = Some(2);
+        let manager = manager(&directory, empty_channel("lru"), &config);
+
+        await_test!(async {
+            for tag in ["first", "second"] {
+                manager
+                    .cache(tag.to_string(), "sentence".to_string(), b"data")
+                    .await
+                    .unwrap();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            manager.fetch("first").await.unwrap();
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            manager
+                .cache("third".to_string(), "sentence".to_string(), b"data")
+                .await
+                .unwrap();
+
+            assert!(manager.record("first").await.is_ok());
+            assert!(manager.record("second").await.is_err());
+            assert!(manager.record("third").await.is_ok());
+        });
+    }
+
+    #[test]
+    fn test_stream_round_trip() {
+        let directory = tempfile::tempdir().unwrap();
+        let manager = manager(
+            &directory,
+            empty_channel("stream"),
+            &channel_config("stream"),
+        );
+        let tag = "tag".to_string();
+        let data = "a streamed chunk\n".repeat(8192).into_bytes();
+
+        await_test!(async {
+            let reader = Box::pin(std::io::Cursor::new(data.clone()));
+            manager
+                .cache_stream(tag.clone(), "sentence".to_string(), reader)
+                .await
+                .unwrap();
+            let chunks: Vec<bytes::Bytes> = manager
+                .fetch_stream(&tag)
+                .await
+                .unwrap()
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(chunks.concat(), data);
+            assert_eq!(manager.record(&tag).await.unwrap().size, data.len());
+        });
+    }
+
+    #[test]
+    fn test_corrupted_file_is_reported() {
+        let directory = tempfile::tempdir().unwrap();
+        let manager = manager(
+            &directory,
+            empty_channel("corrupted"),
+            &channel_config("corrupted"),
+        );
+        let tag = "tag".to_string();
+
+        await_test!(async {
+            manager
+                .cache(tag.clone(), "sentence".to_string(), b"original")
+                .await
+                .unwrap();
+            let path = manager.path(&tag).await.unwrap();
+            tokio::fs::write(&path, b"tampered").await.unwrap();
+
+            assert!(matches!(
+                manager.fetch(&tag).await,
+                Err(CacheError::Corrupted(_))
+            ));
+            assert_eq!(manager.verify_all(false).await.unwrap(), vec![tag.clone()]);
+        });
+    }
+
+    #[test]
+    fn test_compressed_and_encrypted_round_trips() {
+        let directory = tempfile::tempdir().unwrap();
+        let data = "a compressible line\n".repeat(1024).into_bytes();
+        for (name, encrypted) in [("compressed", false), ("encrypted", true)] {
+            let mut config = channel_config(name);
+            if encrypted {
+                config.encryption_key = Some([3; 32]);
+            } else {
+                config.compression = Some(CompressionKind::Zstd);
+            }
+            let manager = manager(&directory, empty_channel(name), &config);
+            let tag = "tag".to_string();
+
+            await_test!(async {
+                manager
+                    .cache(tag.clone(), "sentence".to_string(), &data)
+                    .await
+                    .unwrap();
+                assert_eq!(manager.fetch(&tag).await.unwrap(), data);
+                assert!(matches!(
+                    manager.path(&tag).await,
+                    Err(CacheError::Encoded(_))
+                ));
+
+                let record = manager.record(&tag).await.unwrap();
+                assert_eq!(record.encrypted, encrypted);
+                let stored = tokio::fs::read(directory.path().join(name).join(record.filename))
+                    .await
+                    .unwrap();
+                assert_ne!(stored, data);
+                if !encrypted {
+                    assert!(stored.len() < data.len());
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn test_concurrent_get_or_put_fetches_once() {
+        let directory = tempfile::tempdir().unwrap();
+        let manager = manager(
+            &directory,
+            empty_channel("get_or_put"),
+            &channel_config("get_or_put"),
+        );
+        let fetched = Arc::new(AtomicUsize::new(0));
+        let get_or_put = || {
+            let fetched = fetched.clone();
+            manager.get_or_put(
+                "tag".to_string(),
+                "sentence".to_string(),
+                Box::new(move || {
+                    Box::pin(async move {
+                        fetched.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        Ok(b"data".to_vec())
+                    })
+                }),
+            )
+        };
+
+        await_test!(async {
+            let (first, second) = tokio::join!(get_or_put(), get_or_put());
+            assert_eq!(first.unwrap(), b"data");
+            assert_eq!(second.unwrap(), b"data");
+            assert_eq!(fetched.load(Ordering::SeqCst), 1);
+        });
+    }
+
+    #[test]
+    fn test_concurrent_access_never_fails_to_lock() {
+        let directory = tempfile::tempdir().unwrap();
+        let manager = manager(
+            &directory,
+            empty_channel("concurrent"),
+            &channel_config("concurrent"),
+        );
+        let tags: Vec<String> = (0..8).map(|i| format!("tag-{}", i)).collect();
+
+        await_test!(async {
+            for tag in tags.iter() {
+                manager
+                    .cache(tag.clone(), "sentence".to_string(), b"data")
+                    .await
+                    .unwrap();
+            }
+            let operations = tags.iter().flat_map(|tag| {
+                let manager = &manager;
+                [
+                    Box::pin(async move {
+                        manager
+                            .cache(tag.clone(), "sentence".to_string(), b"data")
+                            .await
+                    }) as Pin<Box<dyn Future<Output = Result<(), CacheError>>>>,
+                    Box::pin(async move { manager.fetch(tag).await.map(|_| ()) }),
+                    Box::pin(async move { manager.pin(tag).await }),
+                    Box::pin(async move { manager.list(None).await.map(|_| ()) }),
+                ]
+            });
+            for result in futures_util::future::join_all(operations).await {
+                assert!(!matches!(result, Err(CacheError::Lock(_))));
+                result.unwrap();
+            }
+        });
+    }
+
+    #[test]
+    fn test_reads_leave_the_access_order_to_be_saved() {
+        let directory = tempfile::tempdir().unwrap();
+        let name = "access_order";
+        let manager = manager(&directory, empty_channel(name), &channel_config(name));
+
+        await_test!(async {
+            manager
+                .cache("tag".to_string(), "sentence".to_string(), b"data")
+                .await
+                .unwrap();
+            manager.persist().await.unwrap();
+            assert!(!manager.is_dirty());
+
+            manager.fetch("tag").await.unwrap();
+            assert!(manager.is_dirty());
+            manager.persist().await.unwrap();
+
+            manager.path("tag").await.unwrap();
+            assert!(manager.is_dirty());
+        });
+    }
+
+    #[test]
+    fn test_pinned_entries_survive_eviction() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut config = channel_config("pinned");
+        config.max_entries = Some(1);
+        let manager = manager(&directory, empty_channel("pinned"), &config);
+        let pinned = "pinned".to_string();
+
+        await_test!(async {
+            manager
+                .cache_with_ttl(
+                    pinned.clone(),
+                    "sentence".to_string(),
+                    b"offline",
+                    Some(Duration::from_millis(10)),
+                )
+                .await
+                .unwrap();
+            manager.pin(&pinned).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            manager
+                .cache("other".to_string(), "sentence".to_string(), b"data")
+                .await
+                .unwrap();
+
+            assert_eq!(manager.sweep_expired().await.unwrap(), 0);
+            assert_eq!(manager.fetch(&pinned).await.unwrap(), b"offline");
+
+            manager.unpin(&pinned).await.unwrap();
+            assert_eq!(manager.sweep_expired().await.unwrap(), 1);
+            assert!(manager.record(&pinned).await.is_err());
+        });
+    }
+
+    #[test]
+    fn test_unknown_channels_are_created_on_first_use() {
+        let directory = tempfile::tempdir().unwrap();
+        let factory = factory(&directory.path().to_string_lossy(), true);
+        let name = "auto_create";
+
+        await_test!(async {
+            let (first, second) =
+                tokio::join!(factory.get_with_name(name), factory.get_with_name(name));
+            let (first, second) = (first.unwrap(), second.unwrap());
+            assert!(Arc::ptr_eq(&first, &second));
+
+            first
+                .cache("tag".to_string(), "sentence".to_string(), b"data")
+                .await
+                .unwrap();
+            assert_eq!(second.fetch("tag").await.unwrap(), b"data");
+
+            for name in ["..", "", "a/b"] {
+                let result = factory.get_with_name(name).await;
+                assert!(matches!(result, Err(CacheError::ManagerNotExist(_))));
+            }
+            factory.shutdown().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn test_concurrent_creates_end_up_with_one_manager() {
+        let directory = tempfile::tempdir().unwrap();
+        let factory = factory(&directory.path().to_string_lossy(), false);
+        let name = "created_twice";
+
+        await_test!(async {
+            let (first, second, third) = tokio::join!(
+                factory.create_with_options(channel_config(name)),
+                factory.create_with_name(name.to_string(), None),
+                factory.create_with_options(channel_config(name)),
+            );
+            let (first, second, third) = (first.unwrap(), second.unwrap(), third.unwrap());
+            assert!(Arc::ptr_eq(&first, &second));
+            assert!(Arc::ptr_eq(&first, &third));
+            assert!(Arc::ptr_eq(&first, &factory.get_with_name(name).await.unwrap()));
+            factory.shutdown().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn test_channels_save_through_scheduler_jobs() {
+        let directory = tempfile::tempdir().unwrap();
+        let job_scheduler = job_scheduler();
+        let factory = factory_with_scheduler(
+            &directory.path().to_string_lossy(),
+            true,
+            job_scheduler.clone(),
+        );
+        let job_name = auto_save_job_name("scheduled");
+        let schedule = |job_scheduler: &Arc<dyn JobScheduler>| {
+            job_scheduler
+                .jobs()
+                .into_iter()
+                .find(|job| job.name == job_name)
+                .map(|job| (job.schedule, job.paused))
+        };
+
+        await_test!(async {
+            factory.get_with_name("scheduled").await.unwrap();
+            assert!(matches!(
+                schedule(&job_scheduler),
+                Some((JobSchedule::Interval(interval), false)) if interval == Duration::from_secs(60)
+            ));
+
+            job_scheduler.pause(&job_name).unwrap();
+            factory
+                .set_auto_save_interval(Duration::from_secs(5))
+                .await
+                .unwrap();
+            assert!(matches!(
+                schedule(&job_scheduler),
+                Some((JobSchedule::Interval(interval), true)) if interval == Duration::from_secs(5)
+            ));
+
+            factory.delete_channel("scheduled").await.unwrap();
+            assert!(schedule(&job_scheduler).is_none());
+            factory.shutdown().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn test_cancelled_save_leaves_the_channel_dirty() {
+        let directory = tempfile::tempdir().unwrap();
+        let name = "cancelled_save";
+        let manager = Arc::new(manager(
+            &directory,
+            empty_channel(name),
+            &channel_config(name),
+        ));
+
+        await_test!(async {
+            manager
+                .cache("tag".to_string(), "sentence".to_string(), b"data")
+                .await
+                .unwrap();
+
+            // the held record keeps the save waiting in its snapshot until it is aborted
+            let record = manager.map.get("tag").unwrap().value().clone();
+            let held = record.write().await;
+            let save = tokio::spawn({
+                let manager = manager.clone();
+                async move { manager.persist().await }
+            });
+            for _ in 0..10 {
+                tokio::task::yield_now().await;
+            }
+            save.abort();
+            assert!(save.await.unwrap_err().is_cancelled());
+            drop(held);
+            assert!(manager.is_dirty());
+
+            manager.persist().await.unwrap();
+            assert!(!manager.is_dirty());
+            let rkv_service = RKV_SERVICE.read().unwrap();
+            let channel = rkv_service
+                .as_ref()
+                .unwrap()
+                .read_rkyv_cache_channel_data(&manager.single_store, name)
+                .unwrap()
+                .unwrap();
+            assert!(channel.records.iter().any(|record| record.tag == "tag"));
+        });
+    }
+
+    struct Uppercase;
+
+    impl CacheMigration for Uppercase {
+        fn migrate(
+            &self,
+            from_version: u32,
+            to_version: u32,
+            _record: &CacheRecord,
+            data: Vec<u8>,
+        ) -> Result<Option<Vec<u8>>, CacheError> {
+            assert_eq!((from_version, to_version), (0, 1));
+            Ok(Some(data.to_ascii_uppercase()))
+        }
+    }
+
+    #[test]
+    fn test_legacy_channel_is_migrated() {
+        let directory = tempfile::tempdir().unwrap();
+        let legacy = LegacyCacheChannel {
+            name: "legacy".to_string(),
+            extension: None,
+            records: vec![LegacyCacheRecord {
+                tag: "tag".to_string(),
+                filename: "file".to_string(),
+                size: 6,
+                sentence: "sentence".to_string(),
+            }],
+        };
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&legacy).unwrap();
+        let channel = CacheChannel::from_bytes(&bytes).unwrap();
+
+        let mut config = channel_config("legacy");
+        config.schema_version = Some(1);
+        config.migration = Some(Arc::new(Uppercase));
+        await_test!(async {
+            let path = directory.path().join("legacy");
+            tokio::fs::create_dir_all(&path).await.unwrap();
+            tokio::fs::write(path.join("file"), b"stored")
+                .await
+                .unwrap();
+
+            let manager = manager(&directory, channel, &config);
+            manager.migrate().await.unwrap();
+            assert_eq!(manager.fetch("tag").await.unwrap(), b"STORED");
+            let record = manager.record("tag").await.unwrap();
+            assert!(record.checksum.is_some());
+        });
+    }
+
+    #[test]
+    fn test_archive_round_trip_keeps_to_its_directories() {
+        let directory = tempfile::tempdir().unwrap();
+        let archive = directory.path().join("archive.tar");
+        let archive = archive.to_str().unwrap();
+        let staging = directory.path().join("staging");
+        let data = b"archived".to_vec();
+
+        await_test!(async {
+            let source = manager(
+                &directory,
+                empty_channel("archive"),
+                &channel_config("archive"),
+            );
+            source
+                .cache("tag".to_string(), "sentence".to_string(), &data)
+                .await
+                .unwrap();
+            source.export_archive(archive).await.unwrap();
+
+            tokio::fs::create_dir_all(&staging).await.unwrap();
+            let index = unpack_archive(archive, staging.to_str().unwrap()).unwrap();
+            let channel = CacheChannel::from_bytes(&index).unwrap();
+            assert_eq!(channel.records.len(), 1);
+
+            let target = manager(
+                &directory,
+                empty_channel("archive_target"),
+                &channel_config("archive_target"),
+            );
+            let mut records = channel.records.clone();
+            let mut escaping = records[0].clone();
+            escaping.tag = "escaping".to_string();
+            escaping.filename = "../outside".to_string();
+            records.push(escaping);
+            tokio::fs::write(staging.join("outside"), b"outside")
+                .await
+                .unwrap();
+
+            let source_dir = staging.join("data");
+            let imported = target
+                .import_records(records, None, source_dir.to_str().unwrap())
+                .await
+                .unwrap();
+            assert_eq!(imported, 1);
+            assert_eq!(target.fetch("tag").await.unwrap(), data);
+            assert!(target.fetch("escaping").await.is_err());
+            assert!(
+                tokio::fs::try_exists(staging.join("outside"))
+                    .await
+                    .unwrap()
+            );
+        });
+    }
+
+    #[test]
+    fn test_unpack_skips_entries_outside_data() {
+        let directory = tempfile::tempdir().unwrap();
+        let archive = directory.path().join("unpack.tar");
+        let staging = directory.path().join("staging");
+
+        let file = std::fs::File::create(&archive).unwrap();
+        let mut builder = tar::Builder::new(file);
+        let entries = [
+            "channel.rkyv",
+            "data/file",
+            "data/nested/file",
+            "other/file",
+        ];
+        for entry in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(4);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, entry, &b"data"[..])
+                .unwrap();
+        }
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        header.set_cksum();
+        builder
+            .append_link(&mut header, "data/link", "/etc/passwd")
+            .unwrap();
+        builder.into_inner().unwrap();
+
+        std::fs::create_dir_all(&staging).unwrap();
+        let index = unpack_archive(archive.to_str().unwrap(), staging.to_str().unwrap());
+        assert_eq!(index.unwrap(), b"data");
+        assert!(staging.join("data/file").exists());
+        assert!(!staging.join("data/nested").exists());
+        assert!(!staging.join("other").exists());
+        assert!(std::fs::symlink_metadata(staging.join("data/link")).is_err());
+    }
+
+    #[test]
+    fn test_fetch_while_rewritten_is_never_corrupted() {
+        let directory = tempfile::tempdir().unwrap();
+        let manager = manager(
+            &directory,
+            empty_channel("rewrite"),
+            &channel_config("rewrite"),
+        );
+        let tag = "tag".to_string();
+
+        await_test!(async {
+            manager
+                .cache(tag.clone(), "sentence".to_string(), &b"0".repeat(4096))
+                .await
+                .unwrap();
+            let writes = async {
+                for i in 1..50u8 {
+                    let data = vec![b'0' + i % 10; 4096];
+                    manager
+                        .cache(tag.clone(), "sentence".to_string(), &data)
+                        .await
+                        .unwrap();
+                }
+            };
+            let reads = async {
+                for _ in 0..50 {
+                    let data = manager.fetch(&tag).await.unwrap();
+                    assert!(data.iter().all(|byte| *byte == data[0]));
+                    tokio::task::yield_now().await;
+                }
+            };
+            tokio::join!(writes, reads);
+        });
+    }
+
+    #[test]
+    fn test_concurrent_first_writes_leave_one_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let name = "first_writes";
+        let manager = manager(&directory, empty_channel(name), &channel_config(name));
+
+        await_test!(async {
+            let writes = (0..4).map(|i| {
+                let manager = &manager;
+                async move {
+                    manager
+                        .cache("tag".to_string(), "sentence".to_string(), &[i; 8])
+                        .await
+                }
+            });
+            for result in futures_util::future::join_all(writes).await {
+                result.unwrap();
+            }
+
+            let fetched = manager.fetch("tag").await.unwrap();
+            assert!((0..4).any(|i| fetched == [i; 8]));
+            let mut entries = tokio::fs::read_dir(directory.path().join(name)).await.unwrap();
+            let mut files = 0;
+            while entries.next_entry().await.unwrap().is_some() {
+                files += 1;
+            }
+            assert_eq!(files, 1);
+        });
+    }
+
+    #[test]
+    fn test_orphans_are_recovered_before_the_channel_is_returned() {
+        let directory = tempfile::tempdir().unwrap();
+        let factory = factory(&directory.path().to_string_lossy(), false);
+        let channel_path = directory.path().join("orphans");
+
+        await_test!(async {
+            tokio::fs::create_dir_all(&channel_path).await.unwrap();
+            for orphan in ["file.tmp", "unknown"] {
+                tokio::fs::write(channel_path.join(orphan), b"orphan")
+                    .await
+                    .unwrap();
+            }
+
+            let manager = factory
+                .create_with_name("orphans".to_string(), None)
+                .await
+                .unwrap();
+            let mut entries = tokio::fs::read_dir(&channel_path).await.unwrap();
+            assert!(entries.next_entry().await.unwrap().is_none());
+
+            manager
+                .cache("tag".to_string(), "sentence".to_string(), b"data")
+                .await
+                .unwrap();
+            assert_eq!(manager.fetch("tag").await.unwrap(), b"data");
+            factory.shutdown().await.unwrap();
+        });
+    }
+
+    #[test]
+    fn test_memory_tier_never_keeps_replaced_data() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut config = channel_config("memory");
+        config.memory_budget = Some(1024 * 1024);
+        let manager = manager(&directory, empty_channel("memory"), &config);
+        let tag = "tag".to_string();
+
+        await_test!(async {
+            for i in 0..20u8 {
+                let data = vec![i; 1024];
+                let write = manager.cache(tag.clone(), "sentence".to_string(), &data);
+                let read = manager.fetch(&tag);
+                let (written, _) = tokio::join!(write, read);
+                written.unwrap();
+                assert_eq!(manager.fetch(&tag).await.unwrap(), data);
+            }
+            let stats = manager.stats().await.unwrap();
+            assert!(stats.memory_hits > 0);
+        });
+    }
+
+    #[test]
+    fn test_limits_reject_oversized_entries_and_spare_the_rest_past_pinned() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut config = channel_config("limits");
+        config.max_bytes = Some(100);
+        // pinned records that alone are over the byte limit
+        let records = (0..3)
+            .map(|i| {
+                let mut record = CacheRecord::from(LegacyCacheRecord {
+                    tag: format!("pinned-{}", i),
+                    filename: format!("pinned-{}", i),
+                    size: 50,
+                    sentence: "sentence".to_string(),
+                });
+                record.pinned = true;
+                record
+            })
+            .collect();
+        let mut channel = empty_channel("limits");
+        channel.records = records;
+        let manager = manager(&directory, channel, &config);
+
+        await_test!(async {
+            let oversized = manager
+                .cache("oversized".to_string(), "sentence".to_string(), &[0; 101])
+                .await;
+            assert!(matches!(oversized, Err(CacheError::TooLarge(_))));
+            assert!(manager.record("oversized").await.is_err());
+
+            for tag in ["first", "second"] {
+                manager
+                    .cache(tag.to_string(), "sentence".to_string(), &[0; 10])
+                    .await
+                    .unwrap();
+            }
+            assert!(manager.record("first").await.is_ok());
+            assert!(manager.record("second").await.is_ok());
+        });
+    }
+
+    #[test]
+    fn test_oversized_stream_leaves_the_previous_entry() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut config = channel_config("oversized_stream");
+        config.max_bytes = Some(100);
+        let manager = manager(&directory, empty_channel("oversized_stream"), &config);
+        let tag = "tag".to_string();
+
+        await_test!(async {
+            manager
+                .cache(tag.clone(), "sentence".to_string(), b"previous")
+                .await
+                .unwrap();
+            let reader = Box::pin(std::io::Cursor::new(vec![0u8; 101]));
+            let result = manager
+                .cache_stream(tag.clone(), "sentence".to_string(), reader)
+                .await;
+            assert!(matches!(result, Err(CacheError::TooLarge(_))));
+            assert_eq!(manager.fetch(&tag).await.unwrap(), b"previous");
+        });
+    }
+
+    #[derive(Default)]
+    struct FlushRecorder {
+        flushed: parking_lot::Mutex<Vec<(String, usize)>>,
+    }
+
+    impl FileCacheObserver for FlushRecorder {
+        fn on_flush(&self, _channel: &str, tag: &str, size: usize) {
+            self.flushed.lock().push((tag.to_string(), size));
+        }
+    }
+
+    #[test]
+    fn test_flush_removes_the_file_and_notifies() {
+        let directory = tempfile::tempdir().unwrap();
+        let manager = manager(&directory, empty_channel("flush"), &channel_config("flush"));
+        let recorder = Arc::new(FlushRecorder::default());
+        manager.observe(recorder.clone());
+        let tag = "tag".to_string();
+
+        await_test!(async {
+            manager
+                .cache(tag.clone(), "sentence".to_string(), b"data")
+                .await
+                .unwrap();
+            let path = manager.path(&tag).await.unwrap();
+            manager.flush(&tag).await.unwrap();
+
+            assert!(!tokio::fs::try_exists(&path).await.unwrap());
+            assert!(manager.record(&tag).await.is_err());
+            assert_eq!(*recorder.flushed.lock(), vec![(tag.clone(), 4)]);
+            assert!(matches!(
+                manager.flush(&tag).await,
+                Err(CacheError::TagNotExist(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_channel_names_outside_the_base_path_are_refused() {
+        let directory = tempfile::tempdir().unwrap();
+        let base_path = directory.path().join("cache");
+        let factory = factory(&base_path.to_string_lossy(), false);
+        let kept = directory.path().join("kept");
+
+        await_test!(async {
+            tokio::fs::create_dir_all(&base_path).await.unwrap();
+            tokio::fs::write(&kept, b"kept").await.unwrap();
+
+            for name in ["..", "", ".", "a/b", "/tmp"] {
+                let created = factory.create_with_options(channel_config(name)).await;
+                assert!(matches!(created, Err(CacheError::InvalidName(_))));
+                let deleted = factory.delete_channel(name).await;
+                assert!(matches!(deleted, Err(CacheError::InvalidName(_))));
+            }
+            assert_eq!(tokio::fs::read(&kept).await.unwrap(), b"kept");
+        });
+    }
+}
@@ -0,0 +1,254 @@
+use crate::domain::models::file_cache_models::{CacheError, CacheRecordFilter};
+use crate::domain::models::http_models::{HttpAuditRecord, HttpLogRecord};
+use crate::domain::traits::file_cache_traits::FileCacheManager;
+use crate::domain::traits::http_traits::HttpLogger;
+use crate::infrastructure::http::http_logger::DefaultHttpLogger;
+use crate::service::config::HttpAuditConfig;
+use crate::utils::time::now_millis;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+
+const SEGMENT_PREFIX: &str = "segment-";
+// a segment is rewritten as a whole whenever records are added to it
+const SEGMENT_SENTENCE: &str = "http_audit";
+
+// padded so the tags sort in the order the segments were started
+fn segment_tag(index: u64) -> String {
+    format!("{}{:010}", SEGMENT_PREFIX, index)
+}
+
+fn segment_index(tag: &str) -> Option<u64> {
+    tag.strip_prefix(SEGMENT_PREFIX)?.parse().ok()
+}
+
+async fn stored_segments(
+    cache_manager: &Arc<dyn FileCacheManager>,
+) -> Result<Vec<u64>, CacheError> {
+    let filter = CacheRecordFilter {
+        tag_prefix: Some(SEGMENT_PREFIX.to_string()),
+        ..CacheRecordFilter::default()
+    };
+    let mut indexes: Vec<u64> = cache_manager
+        .list(Some(filter))
+        .await?
+        .iter()
+        .filter_map(|record| segment_index(&record.tag))
+        .collect();
+    indexes.sort();
+    Ok(indexes)
+}
+
+// the lines of the current segment and the indexes of the stored ones, the last index is the
+// current segment
+struct Segments {
+    records_per_segment: usize,
+    max_segments: usize,
+    stored: VecDeque<u64>,
+    current: Vec<u8>,
+    records: usize,
+}
+
+impl Segments {
+    fn new(config: &HttpAuditConfig, mut stored: VecDeque<u64>, current: Vec<u8>) -> Self {
+        if stored.is_empty() {
+            stored.push_back(0);
+        }
+        let records = current.iter().filter(|byte| **byte == b'\n').count();
+        Self {
+            records_per_segment: config.records_per_segment,
+            max_segments: config.max_segments,
+            stored,
+            current,
+            records,
+        }
+    }
+
+    fn current_tag(&self) -> String {
+        segment_tag(*self.stored.back().unwrap())
+    }
+
+    fn is_full(&self) -> bool {
+        self.records >= self.records_per_segment
+    }
+
+    fn push(&mut self, line: Vec<u8>) {
+        self.current.extend(line);
+        self.current.push(b'\n');
+        self.records += 1;
+    }
+
+    // starts the next segment, returns the ones that fell out of the rotation
+    fn rotate(&mut self) -> Vec<u64> {
+        let next = self.stored.back().unwrap() + 1;
+        self.stored.push_back(next);
+        self.current.clear();
+        self.records = 0;
+        let mut removed = Vec::new();
+        while self.stored.len() > self.max_segments.max(1) {
+            removed.push(self.stored.pop_front().unwrap());
+        }
+        removed
+    }
+}
+
+async fn restore(
+    cache_manager: &Arc<dyn FileCacheManager>,
+    config: &HttpAuditConfig,
+) -> Result<Segments, CacheError> {
+    let stored = stored_segments(cache_manager).await?;
+    let current = match stored.last() {
+        Some(index) => cache_manager.fetch(&segment_tag(*index)).await?,
+        None => Vec::new(),
+    };
+    Ok(Segments::new(config, stored.into(), current))
+}
+
+async fn store(cache_manager: &Arc<dyn FileCacheManager>, segments: &Segments) {
+    let result = cache_manager
+        .cache(
+            segments.current_tag(),
+            SEGMENT_SENTENCE.to_string(),
+            &segments.current,
+        )
+        .await;
+    if let Err(e) = result {
+        tracing::warn!("Failed to write the http audit trail: {}", e);
+    }
+}
+
+async fn write_segments(
+    cache_manager: Arc<dyn FileCacheManager>,
+    config: HttpAuditConfig,
+    mut receiver: mpsc::UnboundedReceiver<HttpAuditRecord>,
+) {
+    let mut segments = match restore(&cache_manager, &config).await {
+        Ok(segments) => segments,
+        Err(e) => {
+            tracing::warn!("Failed to restore the http audit trail: {}", e);
+            Segments::new(&config, VecDeque::new(), Vec::new())
+        }
+    };
+    let mut removed = Vec::new();
+    if segments.is_full() {
+        removed = segments.rotate();
+    }
+    loop {
+        for index in removed.drain(..) {
+            let _ = cache_manager.flush(&segment_tag(index)).await;
+        }
+        let Some(record) = receiver.recv().await else {
+            return;
+        };
+        // whatever arrived meanwhile is written at once
+        let mut batch = vec![record];
+        while let Ok(record) = receiver.try_recv() {
+            batch.push(record);
+        }
+        for record in batch {
+            // plain fields always serialize
+            segments.push(serde_json::to_vec(&record).unwrap());
+            if segments.is_full() {
+                store(&cache_manager, &segments).await;
+                removed.extend(segments.rotate());
+            }
+        }
+        if segments.records > 0 {
+            store(&cache_manager, &segments).await;
+        }
+    }
+}
+
+// keeps a compact record of every request so what the app did can be reconstructed later,
+// the records are written in the background in the order they were logged
+pub struct HttpAuditTrail {
+    cache_manager: Arc<dyn FileCacheManager>,
+    sender: mpsc::UnboundedSender<HttpAuditRecord>,
+    // only used to redact the urls
+    redactor: DefaultHttpLogger,
+}
+
+impl HttpAuditTrail {
+    pub fn start(
+        tokio_runtime: &Runtime,
+        cache_manager: Arc<dyn FileCacheManager>,
+        config: HttpAuditConfig,
+    ) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio_runtime.spawn(write_segments(cache_manager.clone(), config, receiver));
+        Self {
+            cache_manager,
+            sender,
+            redactor: DefaultHttpLogger::default(),
+        }
+    }
+
+    // oldest first, the records not yet written are left out
+    pub async fn records(&self) -> Result<Vec<HttpAuditRecord>, CacheError> {
+        let mut records = Vec::new();
+        for index in stored_segments(&self.cache_manager).await? {
+            let bytes = match self.cache_manager.fetch(&segment_tag(index)).await {
+                Ok(bytes) => bytes,
+                // rotated away meanwhile
+                Err(CacheError::TagNotExist(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            records.extend(
+                bytes
+                    .split(|byte| *byte == b'\n')
+                    .filter_map(|line| serde_json::from_slice::<HttpAuditRecord>(line).ok()),
+            );
+        }
+        Ok(records)
+    }
+}
+
+impl HttpLogger for HttpAuditTrail {
+    fn log(&self, record: &HttpLogRecord) {
+        let duration_millis = record.duration.as_millis() as u64;
+        let audit_record = HttpAuditRecord {
+            timestamp: now_millis().saturating_sub(duration_millis),
+            method: record.method.as_str().to_string(),
+            url: self.redactor.redact_url(&record.url),
+            status: record.status,
+            duration_millis,
+            request_size: record.request_size,
+            response_size: record.response_size,
+            error: record.error.clone(),
+        };
+        // the writer only stops with the tokio runtime
+        let _ = self.sender.send(audit_record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::service::config::HttpAuditConfig;
+    use crate::superstructure::http_audit::{Segments, segment_index, segment_tag};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_segments_are_rotated() {
+        assert_eq!(segment_index(&segment_tag(12)), Some(12));
+        assert!(segment_tag(9) < segment_tag(10));
+
+        let config = HttpAuditConfig {
+            channel: "audit".to_string(),
+            records_per_segment: 2,
+            max_segments: 2,
+        };
+        let mut segments = Segments::new(&config, VecDeque::from(vec![3]), b"{}\n".to_vec());
+        assert!(!segments.is_full());
+        segments.push(b"{}".to_vec());
+        assert!(segments.is_full());
+        assert!(segments.rotate().is_empty());
+        assert_eq!(segments.current_tag(), segment_tag(4));
+        assert!(segments.current.is_empty());
+
+        segments.push(b"{}".to_vec());
+        segments.push(b"{}".to_vec());
+        assert_eq!(segments.rotate(), vec![3]);
+        assert_eq!(segments.current_tag(), segment_tag(5));
+    }
+}
@@ -0,0 +1,361 @@
+use crate::domain::models::scheduler_models::{JobError, JobInfo, JobSchedule};
+use crate::domain::models::storage_models::{ReadFile, StorageError};
+use crate::domain::traits::scheduler_traits::{Job, JobScheduler};
+use crate::domain::traits::storage_traits::StorageManager;
+use crate::monitor::crash_service::guard_task;
+use crate::service::config::SchedulerConfig;
+use crate::utils::cron::CronSchedule;
+use crate::utils::snapshot_file::SnapshotFile;
+use crate::utils::time::now_millis;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use futures_util::FutureExt;
+use futures_util::future::BoxFuture;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::{Notify, Semaphore};
+use tokio::task::JoinHandle;
+
+// adapts a closure, e.g. FnJob::new(move || Box::pin(async move { ... }))
+pub struct FnJob<F> {
+    f: F,
+}
+
+impl<F> FnJob<F>
+where
+    F: Fn() -> BoxFuture<'static, Result<(), JobError>> + Send + Sync + 'static,
+{
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+}
+
+#[async_trait]
+impl<F> Job for FnJob<F>
+where
+    F: Fn() -> BoxFuture<'static, Result<(), JobError>> + Send + Sync + 'static,
+{
+    async fn run(&self) -> Result<(), JobError> {
+        (self.f)().await
+    }
+}
+
+struct JobEntry {
+    schedule: JobSchedule,
+    cron: Option<CronSchedule>,
+    job: Arc<dyn Job>,
+    paused: AtomicBool,
+    running: AtomicBool,
+    run_requested: AtomicBool,
+    last_run: Mutex<Option<u64>>,
+    next_run: Mutex<Option<u64>>,
+    last_error: Mutex<Option<String>>,
+    wake: Notify,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl JobEntry {
+    // a job that never ran is due at once, a missed cron run is caught up once
+    fn due_at(&self, now: u64) -> Option<u64> {
+        let last_run = *self.last_run.lock();
+        match (&self.schedule, &self.cron) {
+            (JobSchedule::Interval(interval), _) => match last_run {
+                Some(last_run) => Some(last_run + interval.as_millis() as u64),
+                None => Some(now),
+            },
+            (JobSchedule::Cron(_), Some(cron)) => cron.next_after(last_run.unwrap_or(now)),
+            (JobSchedule::Cron(_), None) => None,
+        }
+    }
+}
+
+struct SchedulerState {
+    state_file: SnapshotFile,
+    permits: Semaphore,
+    paused: AtomicBool,
+    last_runs: Mutex<HashMap<String, u64>>,
+}
+
+impl SchedulerState {
+    async fn persist(&self) -> Result<(), JobError> {
+        self.state_file
+            .write(|| self.last_runs.lock().clone())
+            .await
+            .map_err(JobError::Persistence)
+    }
+}
+
+async fn drive(name: String, entry: Arc<JobEntry>, state: Arc<SchedulerState>) {
+    loop {
+        let now = now_millis();
+        let due_at = entry.due_at(now);
+        *entry.next_run.lock() = due_at;
+
+        if !entry.run_requested.swap(false, Ordering::SeqCst) {
+            let paused = entry.paused.load(Ordering::SeqCst) || state.paused.load(Ordering::SeqCst);
+            let Some(due_at) = due_at.filter(|_| !paused) else {
+                entry.wake.notified().await;
+                continue;
+            };
+            let delay = Duration::from_millis(due_at.saturating_sub(now));
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                // paused, resumed or asked to run, the schedule is looked at again
+                _ = entry.wake.notified() => continue,
+            }
+        }
+
+        let Ok(_permit) = state.permits.acquire().await else {
+            return;
+        };
+        let started = now_millis();
+        entry.running.store(true, Ordering::SeqCst);
+        let result = match AssertUnwindSafe(entry.job.run()).catch_unwind().await {
+            Ok(result) => result,
+            Err(_) => Err(JobError::Failed("job panicked".to_string())),
+        };
+        entry.running.store(false, Ordering::SeqCst);
+
+        *entry.last_error.lock() = result.err().map(|e| e.to_string());
+        *entry.last_run.lock() = Some(started);
+        state.last_runs.lock().insert(name.clone(), started);
+        if let Err(e) = state.persist().await {
+            tracing::warn!("Failed to persist the state of job {}: {}", name, e);
+        }
+    }
+}
+
+pub struct DefaultJobScheduler {
+    jobs: DashMap<String, Arc<JobEntry>>,
+    state: Arc<SchedulerState>,
+}
+
+impl DefaultJobScheduler {
+    pub async fn new(
+        config: SchedulerConfig,
+        storage_manager: Arc<dyn StorageManager>,
+    ) -> Result<Self, JobError> {
+        let last_runs = match &config.state_path {
+            Some(state_path) => match storage_manager
+                .read(ReadFile::path(state_path.clone()))
+                .await
+            {
+                // an unreadable state only means every job is due once
+                Ok(data) => serde_json::from_slice(&data).unwrap_or_default(),
+                Err(StorageError::NotExist(_)) => HashMap::new(),
+                Err(e) => return Err(JobError::Persistence(e.to_string())),
+            },
+            None => HashMap::new(),
+        };
+
+        Ok(Self {
+            jobs: DashMap::new(),
+            state: Arc::new(SchedulerState {
+                state_file: SnapshotFile::new(storage_manager, config.state_path),
+                permits: Semaphore::new(config.max_concurrent_jobs.max(1)),
+                paused: AtomicBool::new(false),
+                last_runs: Mutex::new(last_runs),
+            }),
+        })
+    }
+
+    fn entry(&self, name: &str) -> Result<Arc<JobEntry>, JobError> {
+        self.jobs
+            .get(name)
+            .map(|entry| entry.value().clone())
+            .ok_or_else(|| JobError::NotExist(name.to_string()))
+    }
+}
+
+#[async_trait]
+impl JobScheduler for DefaultJobScheduler {
+    async fn register(
+        &self,
+        name: String,
+        schedule: JobSchedule,
+        job: Arc<dyn Job>,
+    ) -> Result<(), JobError> {
+        let cron = match &schedule {
+            JobSchedule::Interval(interval) if interval.is_zero() => {
+                return Err(JobError::InvalidSchedule("zero interval".to_string()));
+            }
+            JobSchedule::Interval(_) => None,
+            JobSchedule::Cron(expression) => {
+                Some(CronSchedule::parse(expression).map_err(JobError::InvalidSchedule)?)
+            }
+        };
+
+        let vacant = match self.jobs.entry(name.clone()) {
+            Entry::Occupied(_) => return Err(JobError::AlreadyExists(name)),
+            Entry::Vacant(vacant) => vacant,
+        };
+        let entry = Arc::new(JobEntry {
+            schedule,
+            cron,
+            job,
+            paused: AtomicBool::new(false),
+            running: AtomicBool::new(false),
+            run_requested: AtomicBool::new(false),
+            last_run: Mutex::new(self.state.last_runs.lock().get(&name).copied()),
+            next_run: Mutex::new(None),
+            last_error: Mutex::new(None),
+            wake: Notify::new(),
+            task: Mutex::new(None),
+        });
+        let task = tokio::spawn(guard_task(
+            format!("job:{}", name),
+            drive(name, entry.clone(), self.state.clone()),
+        ));
+        entry.task.lock().replace(task);
+        vacant.insert(entry);
+        Ok(())
+    }
+
+    fn unregister(&self, name: &str) -> Result<(), JobError> {
+        let (_, entry) = self
+            .jobs
+            .remove(name)
+            .ok_or_else(|| JobError::NotExist(name.to_string()))?;
+        if let Some(task) = entry.task.lock().take() {
+            task.abort();
+        }
+        Ok(())
+    }
+
+    fn pause(&self, name: &str) -> Result<(), JobError> {
+        let entry = self.entry(name)?;
+        entry.paused.store(true, Ordering::SeqCst);
+        entry.wake.notify_one();
+        Ok(())
+    }
+
+    fn resume(&self, name: &str) -> Result<(), JobError> {
+        let entry = self.entry(name)?;
+        entry.paused.store(false, Ordering::SeqCst);
+        entry.wake.notify_one();
+        Ok(())
+    }
+
+    fn pause_all(&self) {
+        self.state.paused.store(true, Ordering::SeqCst);
+        self.jobs.iter().for_each(|entry| entry.wake.notify_one());
+    }
+
+    fn resume_all(&self) {
+        self.state.paused.store(false, Ordering::SeqCst);
+        self.jobs.iter().for_each(|entry| entry.wake.notify_one());
+    }
+
+    fn run_now(&self, name: &str) -> Result<(), JobError> {
+        let entry = self.entry(name)?;
+        entry.run_requested.store(true, Ordering::SeqCst);
+        entry.wake.notify_one();
+        Ok(())
+    }
+
+    fn jobs(&self) -> Vec<JobInfo> {
+        let paused = self.state.paused.load(Ordering::SeqCst);
+        self.jobs
+            .iter()
+            .map(|entry| JobInfo {
+                name: entry.key().clone(),
+                schedule: entry.schedule.clone(),
+                paused: paused || entry.paused.load(Ordering::SeqCst),
+                running: entry.running.load(Ordering::SeqCst),
+                last_run: *entry.last_run.lock(),
+                next_run: *entry.next_run.lock(),
+                last_error: entry.last_error.lock().clone(),
+            })
+            .collect()
+    }
+
+    async fn shutdown(&self) -> Result<(), JobError> {
+        self.jobs.iter().for_each(|entry| {
+            if let Some(task) = entry.task.lock().take() {
+                task.abort();
+            }
+        });
+        self.state.persist().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::scheduler_models::{JobError, JobSchedule};
+    use crate::domain::traits::scheduler_traits::JobScheduler;
+    use crate::infrastructure::storage::storage_backend::AsyncStorageManager;
+    use crate::service::config::SchedulerConfig;
+    use crate::superstructure::job_scheduler::{DefaultJobScheduler, FnJob};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn test_interval_job_pause_and_persisted_last_run() {
+        let state_path = std::env::temp_dir()
+            .join(format!("strawberry_jobs_{}.json", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        let config = SchedulerConfig {
+            state_path: Some(state_path.clone()),
+            max_concurrent_jobs: 1,
+        };
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        runtime.block_on(async {
+            let _ = tokio::fs::remove_file(&state_path).await;
+            let storage_manager = Arc::new(AsyncStorageManager::new());
+            let scheduler = DefaultJobScheduler::new(config.clone(), storage_manager.clone())
+                .await
+                .unwrap();
+            let runs = Arc::new(AtomicUsize::new(0));
+            let counter = runs.clone();
+            let job = FnJob::new(move || {
+                let counter = counter.clone();
+                Box::pin(async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Ok::<_, JobError>(())
+                })
+            });
+            scheduler
+                .register(
+                    "count".to_string(),
+                    JobSchedule::Interval(Duration::from_millis(20)),
+                    Arc::new(job),
+                )
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(110)).await;
+            assert!(runs.load(Ordering::SeqCst) >= 2);
+
+            scheduler.pause("count").unwrap();
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            let paused_runs = runs.load(Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            assert_eq!(runs.load(Ordering::SeqCst), paused_runs);
+            scheduler.shutdown().await.unwrap();
+
+            let restored = DefaultJobScheduler::new(config, storage_manager)
+                .await
+                .unwrap();
+            let never = FnJob::new(|| Box::pin(async { Ok::<_, JobError>(()) }));
+            restored
+                .register(
+                    "count".to_string(),
+                    JobSchedule::Interval(Duration::from_secs(3600)),
+                    Arc::new(never),
+                )
+                .await
+                .unwrap();
+            let info = restored.jobs().pop().unwrap();
+            assert!(info.last_run.is_some());
+            restored.shutdown().await.unwrap();
+            let _ = tokio::fs::remove_file(&state_path).await;
+        });
+    }
+}
@@ -0,0 +1,197 @@
+use crate::domain::models::http_models::{
+    HttpClientError, HttpEndpoint, HttpResponse, LongPollOptions,
+};
+use crate::domain::traits::http_traits::HttpClient;
+use futures_util::stream::BoxStream;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+struct LongPoll {
+    client: Arc<dyn HttpClient>,
+    endpoint: HttpEndpoint,
+    options: LongPollOptions,
+    token: CancellationToken,
+    delay: Duration,
+    backoff: Duration,
+    errors: u32,
+}
+
+impl LongPoll {
+    fn polled(&mut self) {
+        self.delay = self.options.poll_interval;
+        self.backoff = self.options.initial_backoff;
+        self.errors = 0;
+    }
+
+    // false once max_consecutive_errors is reached
+    fn failed(&mut self) -> bool {
+        self.errors += 1;
+        self.delay = self.backoff;
+        self.backoff = self.backoff.saturating_mul(2).min(self.options.max_backoff);
+        self.options
+            .max_consecutive_errors
+            .is_none_or(|max_consecutive_errors| self.errors < max_consecutive_errors)
+    }
+
+    async fn next(mut self) -> Option<(Result<HttpResponse, HttpClientError>, Option<Self>)> {
+        loop {
+            if !self.delay.is_zero() {
+                tokio::select! {
+                    biased;
+                    _ = self.token.cancelled() => return None,
+                    _ = tokio::time::sleep(self.delay) => {}
+                }
+            }
+            // a cancelled token wins over a response that is ready at the same time
+            let result = tokio::select! {
+                biased;
+                _ = self.token.cancelled() => return None,
+                result = self.client.execute(self.endpoint.clone()) => result,
+            };
+            let error = match result {
+                // the server had nothing to say before one of the sides gave up waiting
+                Ok(response) if response.status == 204 => {
+                    self.polled();
+                    continue;
+                }
+                Err(HttpClientError::Timeout(_)) => {
+                    self.polled();
+                    continue;
+                }
+                Ok(response) if response.is_success() => {
+                    self.polled();
+                    return Some((Ok(response), Some(self)));
+                }
+                Ok(response) => HttpClientError::Status(response.status),
+                Err(e) => e,
+            };
+            return if self.failed() {
+                Some((Err(error), Some(self)))
+            } else {
+                Some((Err(error), None))
+            };
+        }
+    }
+}
+
+// repeats the request until the token is cancelled and yields every response with news, a
+// 204 or a timed out request is polled again, failures and statuses other than 2xx are
+// yielded and retried after the backoff, the stream ends with the error that reached
+// max_consecutive_errors
+pub fn long_poll(
+    client: Arc<dyn HttpClient>,
+    endpoint: HttpEndpoint,
+    options: LongPollOptions,
+    token: CancellationToken,
+) -> BoxStream<'static, Result<HttpResponse, HttpClientError>> {
+    let state = LongPoll {
+        client,
+        endpoint,
+        backoff: options.initial_backoff,
+        options,
+        token,
+        delay: Duration::ZERO,
+        errors: 0,
+    };
+    Box::pin(futures_util::stream::unfold(
+        Some(state),
+        |state| async move { state?.next().await },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::http_models::{
+        HttpClientError, HttpEndpoint, HttpResponse, HttpStreamResponse, LongPollOptions,
+    };
+    use crate::domain::traits::http_traits::{DecryptionProvider, EncryptionProvider, HttpClient};
+    use crate::superstructure::long_poll::long_poll;
+    use async_trait::async_trait;
+    use futures_util::StreamExt;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio_util::sync::CancellationToken;
+
+    // answers with the scripted statuses, then fails to connect
+    struct ScriptedServer {
+        statuses: Mutex<Vec<u16>>,
+    }
+
+    #[async_trait]
+    impl HttpClient for ScriptedServer {
+        fn set_encryption_provider(&mut self, _: Arc<dyn EncryptionProvider>) {}
+        fn set_decryption_provider(&mut self, _: Arc<dyn DecryptionProvider>) {}
+        fn remove_encryption_provider(&mut self) -> Option<Arc<dyn EncryptionProvider>> {
+            None
+        }
+        fn remove_decryption_provider(&mut self) -> Option<Arc<dyn DecryptionProvider>> {
+            None
+        }
+
+        async fn execute(&self, _: HttpEndpoint) -> Result<HttpResponse, HttpClientError> {
+            let mut statuses = self.statuses.lock();
+            if statuses.is_empty() {
+                return Err(HttpClientError::Network("unreachable".to_string()));
+            }
+            Ok(HttpResponse {
+                status: statuses.remove(0),
+                headers: Vec::new(),
+                body: Vec::new(),
+                final_url: None,
+                redirects: Vec::new(),
+                trailers: Vec::new(),
+            })
+        }
+
+        async fn execute_stream(
+            &self,
+            _: HttpEndpoint,
+        ) -> Result<HttpStreamResponse, HttpClientError> {
+            Err(HttpClientError::Configuration("not supported".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_long_poll() {
+        let server = Arc::new(ScriptedServer {
+            statuses: Mutex::new(vec![200, 204, 204, 503, 200]),
+        });
+        let options = LongPollOptions {
+            initial_backoff: Duration::from_millis(1),
+            max_consecutive_errors: Some(2),
+            ..LongPollOptions::default()
+        };
+        let endpoint = HttpEndpoint::builder("https://example.com", "/events").build();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        let results: Vec<Result<u16, String>> = runtime.block_on(
+            long_poll(server, endpoint.clone(), options, CancellationToken::new())
+                .map(|result| {
+                    result
+                        .map(|response| response.status)
+                        .map_err(|e| e.to_string())
+                })
+                .collect(),
+        );
+        assert_eq!(
+            results,
+            vec![
+                Ok(200),
+                Err("Unexpected status 503".to_string()),
+                Ok(200),
+                Err("Network error: unreachable".to_string()),
+                Err("Network error: unreachable".to_string()),
+            ]
+        );
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let server = Arc::new(ScriptedServer {
+            statuses: Mutex::new(vec![200]),
+        });
+        let mut stream = long_poll(server, endpoint, LongPollOptions::default(), token);
+        assert!(runtime.block_on(stream.next()).is_none());
+    }
+}
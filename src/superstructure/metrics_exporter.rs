@@ -0,0 +1,118 @@
+use crate::domain::models::http_models::{HttpEndpoint, HttpMethod};
+use crate::domain::models::metrics_models::MetricsError;
+use crate::domain::models::scheduler_models::JobError;
+use crate::domain::models::storage_models::{WriteFile, WriteMode};
+use crate::domain::traits::http_traits::HttpClient;
+use crate::domain::traits::metrics_traits::MetricsRegistry;
+use crate::domain::traits::scheduler_traits::Job;
+use crate::domain::traits::storage_traits::StorageManager;
+use crate::service::config::MetricsExportTarget;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+// runs on the job scheduler and sends a JSON snapshot to the configured target each time
+pub struct MetricsExportJob {
+    registry: Arc<dyn MetricsRegistry>,
+    target: MetricsExportTarget,
+    storage_manager: Arc<dyn StorageManager>,
+    http_client: Option<Arc<dyn HttpClient>>,
+}
+
+impl MetricsExportJob {
+    pub fn new(
+        registry: Arc<dyn MetricsRegistry>,
+        target: MetricsExportTarget,
+        storage_manager: Arc<dyn StorageManager>,
+        http_client: Option<Arc<dyn HttpClient>>,
+    ) -> Self {
+        Self {
+            registry,
+            target,
+            storage_manager,
+            http_client,
+        }
+    }
+
+    pub async fn export(&self) -> Result<(), MetricsError> {
+        let snapshot = self.registry.snapshot();
+        let data = serde_json::to_vec(&snapshot)
+            .map_err(|e| MetricsError::Serialization(e.to_string()))?;
+
+        match &self.target {
+            MetricsExportTarget::File(path) => {
+                let mut request = WriteFile::path(path.clone(), &data);
+                request.mode = WriteMode::Atomic;
+                self.storage_manager
+                    .write(request)
+                    .await
+                    .map_err(|e| MetricsError::Storage(e.to_string()))
+            }
+            MetricsExportTarget::Http { url, headers } => {
+                let Some(http_client) = &self.http_client else {
+                    return Err(MetricsError::Http(
+                        "http client is not configured".to_string(),
+                    ));
+                };
+                let endpoint = HttpEndpoint::builder(url.clone(), "")
+                    .method(HttpMethod::Post)
+                    .body(data)
+                    .headers(headers.clone())
+                    .content_type("application/json")
+                    .build();
+                let response = http_client
+                    .execute(endpoint)
+                    .await
+                    .map_err(|e| MetricsError::Http(e.to_string()))?;
+                if !(200..=299).contains(&response.status) {
+                    return Err(MetricsError::Status(response.status));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Job for MetricsExportJob {
+    async fn run(&self) -> Result<(), JobError> {
+        self.export()
+            .await
+            .map_err(|e| JobError::Failed(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::traits::metrics_traits::MetricsRegistry;
+    use crate::infrastructure::metrics::metrics_backend::DefaultMetricsRegistry;
+    use crate::infrastructure::storage::storage_backend::AsyncStorageManager;
+    use crate::service::config::MetricsExportTarget;
+    use crate::superstructure::metrics_exporter::MetricsExportJob;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_export_to_file() {
+        let path = std::env::temp_dir()
+            .join(format!("strawberry_metrics_{}.json", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        let registry = Arc::new(DefaultMetricsRegistry::new(vec![10.0]));
+        registry.increment_counter("http.requests", &[("method", "GET")], 2);
+        let job = MetricsExportJob::new(
+            registry,
+            MetricsExportTarget::File(path.clone()),
+            Arc::new(AsyncStorageManager::new()),
+            None,
+        );
+
+        tokio_test::block_on(async {
+            job.export().await.unwrap();
+            let data = tokio::fs::read(&path).await.unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&data).unwrap();
+            assert_eq!(json["counters"][0]["name"], "http.requests");
+            assert_eq!(json["counters"][0]["labels"][0][1], "GET");
+            assert_eq!(json["counters"][0]["value"], 2);
+            let _ = tokio::fs::remove_file(&path).await;
+        });
+    }
+}
@@ -1,2 +1,11 @@
 pub mod file_cache_backend;
-pub mod coordinator;
\ No newline at end of file
+pub mod coordinator;
+pub mod job_scheduler;
+pub mod download_manager;
+pub mod upload_manager;
+pub mod metrics_exporter;
+pub mod task_registry;
+pub mod event_bus;
+pub mod api_client;
+pub mod long_poll;
+pub mod http_audit;
\ No newline at end of file
@@ -0,0 +1,314 @@
+use crate::domain::models::event_models::RuntimeEvent;
+use crate::domain::models::task_models::{TaskError, TaskInfo, TaskOutcome};
+use crate::monitor::crash_service::guard_task;
+use crate::superstructure::event_bus::EventBus;
+use crate::utils::time::now_millis;
+use dashmap::DashMap;
+use futures_util::FutureExt;
+use parking_lot::Mutex;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use tokio::runtime::Runtime;
+use tokio::task::{AbortHandle, JoinHandle};
+
+struct TaskEntry {
+    name: String,
+    started: u64,
+    // set right after spawning, the task may already be gone by then, a cancel that comes
+    // first is remembered and applied once the handle is set
+    abort: Mutex<Abort>,
+}
+
+#[derive(Default)]
+struct Abort {
+    handle: Option<AbortHandle>,
+    requested: bool,
+}
+
+impl Abort {
+    fn request(&mut self) {
+        self.requested = true;
+        if let Some(handle) = &self.handle {
+            handle.abort();
+        }
+    }
+
+    fn set(&mut self, handle: AbortHandle) {
+        if self.requested {
+            handle.abort();
+        }
+        self.handle = Some(handle);
+    }
+}
+
+// removes the entry however the task ends, finished, panicked or aborted, a task dropped
+// before its outcome was set has been aborted
+struct Deregister {
+    tasks: Arc<DashMap<u64, TaskEntry>>,
+    event_bus: Arc<EventBus>,
+    id: u64,
+    name: String,
+    outcome: TaskOutcome,
+}
+
+impl Drop for Deregister {
+    fn drop(&mut self) {
+        self.tasks.remove(&self.id);
+        self.event_bus.publish(RuntimeEvent::TaskFinished {
+            id: self.id,
+            name: std::mem::take(&mut self.name),
+            outcome: self.outcome.clone(),
+        });
+    }
+}
+
+pub struct TaskHandle<T> {
+    pub id: u64,
+    pub name: String,
+    join: JoinHandle<T>,
+}
+
+impl<T> TaskHandle<T> {
+    pub fn cancel(&self) {
+        self.join.abort();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.join.is_finished()
+    }
+}
+
+impl<T> Future for TaskHandle<T> {
+    type Output = Result<T, TaskError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let id = self.id;
+        Pin::new(&mut self.join).poll(cx).map(|result| {
+            result.map_err(|e| {
+                if e.is_cancelled() {
+                    TaskError::Cancelled(id)
+                } else {
+                    TaskError::Panicked(id, e.to_string())
+                }
+            })
+        })
+    }
+}
+
+// tracks the work spawned through the runtime until it ends
+pub struct TaskRegistry {
+    next_id: AtomicU64,
+    tasks: Arc<DashMap<u64, TaskEntry>>,
+    // ids handed out before their task is spawned, true once cancelled
+    reserved: DashMap<u64, bool>,
+    event_bus: Arc<EventBus>,
+}
+
+impl TaskRegistry {
+    pub fn new() -> Self {
+        Self::with_event_bus(Arc::new(EventBus::new()))
+    }
+
+    // every task announces how it ended on the event bus
+    pub fn with_event_bus(event_bus: Arc<EventBus>) -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+            tasks: Arc::new(DashMap::new()),
+            reserved: DashMap::new(),
+            event_bus,
+        }
+    }
+
+    // an id for a task spawned later with spawn_reserved, cancelling it before then cancels
+    // the task as soon as it is spawned
+    pub fn reserve(&self) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.reserved.insert(id, false);
+        id
+    }
+
+    // for a reserved id nothing is going to be spawned under
+    pub fn release(&self, id: u64) {
+        self.reserved.remove(&id);
+    }
+
+    pub fn spawn<F>(&self, runtime: &Runtime, name: String, future: F) -> TaskHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.spawn_reserved(runtime, self.reserve(), name, future)
+    }
+
+    pub fn spawn_reserved<F>(
+        &self,
+        runtime: &Runtime,
+        id: u64,
+        name: String,
+        future: F,
+    ) -> TaskHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.tasks.insert(
+            id,
+            TaskEntry {
+                name: name.clone(),
+                started: now_millis(),
+                abort: Mutex::new(Abort::default()),
+            },
+        );
+        // listed before the reservation goes, so a cancel always finds one of them
+        if let Some((_, true)) = self.reserved.remove(&id)
+            && let Some(entry) = self.tasks.get(&id)
+        {
+            entry.abort.lock().request();
+        }
+
+        let mut deregister = Deregister {
+            tasks: self.tasks.clone(),
+            event_bus: self.event_bus.clone(),
+            id,
+            name: name.clone(),
+            outcome: TaskOutcome::Cancelled,
+        };
+        let guarded = AssertUnwindSafe(guard_task(name.clone(), future));
+        let join = runtime.spawn(async move {
+            match guarded.catch_unwind().await {
+                Ok(output) => {
+                    deregister.outcome = TaskOutcome::Completed;
+                    drop(deregister);
+                    output
+                }
+                Err(payload) => {
+                    deregister.outcome = TaskOutcome::Panicked;
+                    drop(deregister);
+                    std::panic::resume_unwind(payload)
+                }
+            }
+        });
+        if let Some(entry) = self.tasks.get(&id) {
+            entry.abort.lock().set(join.abort_handle());
+        }
+        TaskHandle { id, name, join }
+    }
+
+    // sorted by id, so the oldest task comes first
+    pub fn list(&self) -> Vec<TaskInfo> {
+        let mut tasks: Vec<TaskInfo> = self
+            .tasks
+            .iter()
+            .map(|entry| TaskInfo {
+                id: *entry.key(),
+                name: entry.name.clone(),
+                started: entry.started,
+            })
+            .collect();
+        tasks.sort_by_key(|task| task.id);
+        tasks
+    }
+
+    pub fn cancel(&self, id: u64) -> Result<(), TaskError> {
+        if let Some(mut cancelled) = self.reserved.get_mut(&id) {
+            *cancelled = true;
+            return Ok(());
+        }
+        let Some(entry) = self.tasks.get(&id) else {
+            return Err(TaskError::NotExist(id));
+        };
+        entry.abort.lock().request();
+        Ok(())
+    }
+
+    pub fn cancel_all(&self) {
+        for mut cancelled in self.reserved.iter_mut() {
+            *cancelled = true;
+        }
+        for entry in self.tasks.iter() {
+            entry.abort.lock().request();
+        }
+    }
+}
+
+impl Default for TaskRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::event_models::RuntimeEvent;
+    use crate::domain::models::task_models::{TaskError, TaskOutcome};
+    use crate::superstructure::event_bus::EventBus;
+    use crate::superstructure::task_registry::TaskRegistry;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_tasks_are_listed_until_they_end() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let event_bus = Arc::new(EventBus::new());
+        let mut events = event_bus.subscribe();
+        let registry = TaskRegistry::with_event_bus(event_bus);
+
+        let quick = registry.spawn(&runtime, "quick".to_string(), async { 7 });
+        let slow = registry.spawn(&runtime, "slow".to_string(), async {
+            tokio::time::sleep(Duration::from_secs(3600)).await
+        });
+        assert_eq!(runtime.block_on(quick).unwrap(), 7);
+
+        let tasks = registry.list();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, slow.id);
+        assert_eq!(tasks[0].name, "slow");
+
+        registry.cancel(slow.id).unwrap();
+        let id = slow.id;
+        assert!(matches!(
+            runtime.block_on(slow),
+            Err(TaskError::Cancelled(cancelled)) if cancelled == id
+        ));
+        assert!(registry.list().is_empty());
+        assert!(matches!(registry.cancel(id), Err(TaskError::NotExist(_))));
+
+        let mut outcomes = Vec::new();
+        while let Ok(RuntimeEvent::TaskFinished { name, outcome, .. }) = events.try_recv() {
+            outcomes.push((name, outcome));
+        }
+        assert_eq!(
+            outcomes,
+            vec![
+                ("quick".to_string(), TaskOutcome::Completed),
+                ("slow".to_string(), TaskOutcome::Cancelled),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cancelling_a_reserved_id_cancels_its_task() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let registry = TaskRegistry::new();
+
+        let id = registry.reserve();
+        assert!(registry.list().is_empty());
+        registry.cancel(id).unwrap();
+        let cancelled = registry.spawn_reserved(&runtime, id, "reserved".to_string(), async {
+            tokio::time::sleep(Duration::from_secs(3600)).await
+        });
+        assert_eq!(cancelled.id, id);
+        assert!(matches!(
+            runtime.block_on(cancelled),
+            Err(TaskError::Cancelled(cancelled)) if cancelled == id
+        ));
+
+        let id = registry.reserve();
+        let completed = registry.spawn_reserved(&runtime, id, "reserved".to_string(), async { 7 });
+        assert_eq!(runtime.block_on(completed).unwrap(), 7);
+        assert!(matches!(registry.cancel(id), Err(TaskError::NotExist(_))));
+    }
+}
@@ -0,0 +1,845 @@
+use crate::domain::models::http_models::{HttpClientError, HttpEndpoint, HttpMethod, HttpResponse};
+use crate::domain::models::storage_models::{ReadFile, StorageError};
+use crate::domain::models::upload_models::{
+    UploadError, UploadEvent, UploadProtocol, UploadRequest, UploadState, UploadTask,
+};
+use crate::domain::traits::http_traits::HttpClient;
+use crate::domain::traits::storage_traits::StorageManager;
+use crate::domain::traits::upload_traits::UploadManager;
+use crate::monitor::crash_service::guard_task;
+use crate::service::config::UploadConfig;
+use crate::superstructure::download_manager::header;
+use crate::utils::snapshot_file::SnapshotFile;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+use uuid::Uuid;
+
+const TUS_VERSION: &str = "1.0.0";
+
+enum TransferOutcome {
+    Completed,
+    Interrupted,
+}
+
+// the offset after "bytes=0-99", nothing was received without the header
+fn received_offset(headers: &[(String, String)]) -> Option<u64> {
+    header(headers, "range")?
+        .trim()
+        .strip_prefix("bytes=")?
+        .rsplit_once('-')
+        .and_then(|(_, end)| end.trim().parse::<u64>().ok())
+        .map(|end| end + 1)
+}
+
+fn upload_offset(headers: &[(String, String)]) -> Result<u64, UploadError> {
+    header(headers, "upload-offset")
+        .and_then(|offset| offset.trim().parse().ok())
+        .ok_or_else(|| UploadError::Protocol("missing Upload-Offset header".to_string()))
+}
+
+// quotes are percent encoded the way browsers do it for form data
+fn quoted(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn http_error(e: HttpClientError) -> UploadError {
+    match e {
+        HttpClientError::Network(_) | HttpClientError::Timeout(_) => {
+            UploadError::Network(e.to_string())
+        }
+        _ => UploadError::Http(e.to_string()),
+    }
+}
+
+fn storage_error(e: StorageError) -> UploadError {
+    UploadError::Storage(e.to_string())
+}
+
+fn is_retryable(e: &UploadError) -> bool {
+    match e {
+        UploadError::Network(_) => true,
+        // a tus offset conflict, the retry asks the server for its offset again
+        UploadError::Status(409) => true,
+        UploadError::Status(status) => *status >= 500 || *status == 429,
+        _ => false,
+    }
+}
+
+struct UploadManagerInner {
+    config: UploadConfig,
+    http_client: Arc<dyn HttpClient>,
+    storage_manager: Arc<dyn StorageManager>,
+    tasks: Mutex<HashMap<String, UploadTask>>,
+    active: DashMap<String, CancellationToken>,
+    events: broadcast::Sender<UploadEvent>,
+    // set on shutdown, nothing new is started afterwards
+    closed: AtomicBool,
+    state_file: SnapshotFile,
+}
+
+pub struct DefaultUploadManager {
+    inner: Arc<UploadManagerInner>,
+}
+
+impl UploadManagerInner {
+    fn task(&self, id: &str) -> Result<UploadTask, UploadError> {
+        self.tasks
+            .lock()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| UploadError::NotExist(id.to_string()))
+    }
+
+    fn update<F>(&self, id: &str, f: F) -> Option<UploadTask>
+    where
+        F: FnOnce(&mut UploadTask),
+    {
+        let mut tasks = self.tasks.lock();
+        let task = tasks.get_mut(id)?;
+        f(task);
+        let _ = self.events.send(UploadEvent::from(&*task));
+        Some(task.clone())
+    }
+
+    async fn persist(&self) {
+        let result = self
+            .state_file
+            .write(|| {
+                self.tasks
+                    .lock()
+                    .values()
+                    .cloned()
+                    .collect::<Vec<UploadTask>>()
+            })
+            .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to persist the upload queue: {}", e);
+        }
+    }
+
+    // starts the queued uploads with the highest priority while slots are free
+    fn dispatch(self: &Arc<Self>) {
+        if self.closed.load(Ordering::SeqCst) {
+            return;
+        }
+        let mut tasks = self.tasks.lock();
+        while self.active.len() < self.config.max_concurrent_uploads.max(1) {
+            let next = tasks
+                .values_mut()
+                .filter(|task| task.state == UploadState::Queued)
+                .max_by(|a, b| {
+                    a.priority
+                        .cmp(&b.priority)
+                        .then_with(|| b.sequence.cmp(&a.sequence))
+                });
+            let Some(task) = next else {
+                return;
+            };
+            task.state = UploadState::Running;
+            task.error = None;
+            let _ = self.events.send(UploadEvent::from(&*task));
+
+            let token = CancellationToken::new();
+            self.active.insert(task.id.clone(), token.clone());
+            tokio::spawn(guard_task(
+                format!("upload:{}", task.id),
+                self.clone().run(task.id.clone(), token),
+            ));
+        }
+    }
+
+    async fn run(self: Arc<Self>, id: String, token: CancellationToken) {
+        let mut attempts = 0;
+        let mut backoff = self.config.initial_backoff;
+        let outcome = loop {
+            attempts += 1;
+            self.update(&id, |task| task.attempts = attempts);
+            match self.transfer(&id, &token).await {
+                Err(e) if is_retryable(&e) && attempts < self.config.max_attempts => {
+                    tokio::select! {
+                        _ = tokio::time::sleep(backoff) => {}
+                        _ = token.cancelled() => break Ok(TransferOutcome::Interrupted),
+                    }
+                    backoff = (backoff * 2).min(self.config.max_backoff);
+                }
+                result => break result,
+            }
+        };
+        self.active.remove(&id);
+
+        match outcome {
+            Ok(TransferOutcome::Completed) => {
+                self.update(&id, |task| task.state = UploadState::Completed);
+            }
+            // pause and cancel already changed the state, shutdown leaves it running
+            Ok(TransferOutcome::Interrupted) => {}
+            Err(e) => {
+                self.update(&id, |task| {
+                    task.state = UploadState::Failed;
+                    task.error = Some(e.to_string());
+                });
+            }
+        }
+        self.persist().await;
+        self.dispatch();
+    }
+
+    fn endpoint(
+        &self,
+        url: &str,
+        method: HttpMethod,
+        headers: Vec<(String, String)>,
+        body: Option<Vec<u8>>,
+        content_type: Option<String>,
+    ) -> HttpEndpoint {
+        let mut builder = HttpEndpoint::builder(url, "")
+            .method(method)
+            .timeout(self.config.request_timeout)
+            .headers(headers);
+        if let Some(body) = body {
+            builder = builder.body(body);
+        }
+        if let Some(content_type) = content_type {
+            builder = builder.content_type(content_type);
+        }
+        builder.build()
+    }
+
+    // None when the upload was paused, cancelled or shut down meanwhile
+    async fn send(
+        &self,
+        endpoint: HttpEndpoint,
+        token: &CancellationToken,
+    ) -> Result<Option<HttpResponse>, UploadError> {
+        tokio::select! {
+            response = self.http_client.execute(endpoint) => response.map(Some).map_err(http_error),
+            _ = token.cancelled() => Ok(None),
+        }
+    }
+
+    async fn progress(&self, id: &str, uploaded: u64) {
+        self.update(id, |task| task.uploaded = uploaded);
+        self.persist().await;
+    }
+
+    fn complete(&self, id: &str, size: u64, response: HttpResponse) -> TransferOutcome {
+        self.update(id, |task| {
+            task.uploaded = size;
+            task.response_status = Some(response.status);
+            task.response_body = Some(response.body);
+        });
+        TransferOutcome::Completed
+    }
+
+    async fn read_chunk(
+        &self,
+        task: &UploadTask,
+        offset: u64,
+        len: u64,
+    ) -> Result<Vec<u8>, UploadError> {
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        self.storage_manager
+            .read_range(task.source_path.clone(), offset, len)
+            .await
+            .map_err(storage_error)
+    }
+
+    async fn transfer(
+        &self,
+        id: &str,
+        token: &CancellationToken,
+    ) -> Result<TransferOutcome, UploadError> {
+        let size = self
+            .storage_manager
+            .metadata(self.task(id)?.source_path)
+            .await
+            .map_err(storage_error)?
+            .size;
+        // a changed source cannot be resumed, what the server has is stale
+        let task = self
+            .update(id, |task| {
+                if task.total.is_some_and(|total| total != size) {
+                    task.uploaded = 0;
+                    task.upload_url = None;
+                }
+                task.total = Some(size);
+            })
+            .ok_or_else(|| UploadError::NotExist(id.to_string()))?;
+
+        match task.protocol.clone() {
+            UploadProtocol::Multipart {
+                field_name,
+                file_name,
+            } => {
+                self.multipart(&task, size, &field_name, file_name, token)
+                    .await
+            }
+            UploadProtocol::ChunkedRange { chunk_size } => {
+                self.chunked_range(&task, size, chunk_size.max(1), token)
+                    .await
+            }
+            UploadProtocol::Tus { chunk_size } => {
+                self.tus(&task, size, chunk_size.max(1), token).await
+            }
+        }
+    }
+
+    async fn multipart(
+        &self,
+        task: &UploadTask,
+        size: u64,
+        field_name: &str,
+        file_name: Option<String>,
+        token: &CancellationToken,
+    ) -> Result<TransferOutcome, UploadError> {
+        let data = self
+            .storage_manager
+            .read(ReadFile::path(task.source_path.clone()))
+            .await
+            .map_err(storage_error)?;
+        let file_name = file_name.unwrap_or_else(|| {
+            Path::new(&task.source_path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "file".to_string())
+        });
+        let boundary = format!("strawberry-{}", Uuid::new_v4().simple());
+
+        let mut body = Vec::with_capacity(data.len() + 256);
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+                boundary,
+                quoted(field_name),
+                quoted(&file_name)
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&data);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+        let endpoint = self.endpoint(
+            &task.url,
+            HttpMethod::Post,
+            task.headers.clone(),
+            Some(body),
+            Some(format!("multipart/form-data; boundary={}", boundary)),
+        );
+        let Some(response) = self.send(endpoint, token).await? else {
+            return Ok(TransferOutcome::Interrupted);
+        };
+        if !(200..=299).contains(&response.status) {
+            return Err(UploadError::Status(response.status));
+        }
+        Ok(self.complete(&task.id, size, response))
+    }
+
+    async fn chunked_range(
+        &self,
+        task: &UploadTask,
+        size: u64,
+        chunk_size: u64,
+        token: &CancellationToken,
+    ) -> Result<TransferOutcome, UploadError> {
+        let mut offset = 0;
+        if task.uploaded > 0 {
+            // asks what the server kept, a 2xx means it already has everything
+            let mut headers = task.headers.clone();
+            headers.push(("Content-Range".to_string(), format!("bytes */{}", size)));
+            let endpoint =
+                self.endpoint(&task.url, HttpMethod::Put, headers, Some(Vec::new()), None);
+            let Some(response) = self.send(endpoint, token).await? else {
+                return Ok(TransferOutcome::Interrupted);
+            };
+            match response.status {
+                200..=299 => return Ok(self.complete(&task.id, size, response)),
+                308 => offset = received_offset(&response.headers).unwrap_or(0),
+                status => return Err(UploadError::Status(status)),
+            }
+            self.progress(&task.id, offset).await;
+        }
+
+        loop {
+            if offset > size {
+                return Err(UploadError::Protocol(format!(
+                    "server reported {} of {} bytes",
+                    offset, size
+                )));
+            }
+            let len = chunk_size.min(size - offset);
+            let data = self.read_chunk(task, offset, len).await?;
+            let range = if len == 0 {
+                format!("bytes */{}", size)
+            } else {
+                format!("bytes {}-{}/{}", offset, offset + len - 1, size)
+            };
+            let mut headers = task.headers.clone();
+            headers.push(("Content-Range".to_string(), range));
+            let endpoint = self.endpoint(
+                &task.url,
+                HttpMethod::Put,
+                headers,
+                Some(data),
+                Some("application/octet-stream".to_string()),
+            );
+            let Some(response) = self.send(endpoint, token).await? else {
+                return Ok(TransferOutcome::Interrupted);
+            };
+
+            let end = offset + len;
+            let next = match response.status {
+                200..=299 if end == size => return Ok(self.complete(&task.id, size, response)),
+                200..=299 => end,
+                308 => received_offset(&response.headers).unwrap_or(end),
+                status => return Err(UploadError::Status(status)),
+            };
+            // guards against a server that keeps answering 308 without taking anything
+            if next <= offset && len == 0 {
+                return Err(UploadError::Protocol(
+                    "server did not finish the upload".to_string(),
+                ));
+            }
+            offset = next;
+            self.progress(&task.id, offset).await;
+        }
+    }
+
+    async fn tus(
+        &self,
+        task: &UploadTask,
+        size: u64,
+        chunk_size: u64,
+        token: &CancellationToken,
+    ) -> Result<TransferOutcome, UploadError> {
+        let tus_headers = || {
+            let mut headers = task.headers.clone();
+            headers.push(("Tus-Resumable".to_string(), TUS_VERSION.to_string()));
+            headers
+        };
+
+        let mut last_response = None;
+        let mut offset = 0;
+        let mut upload_url = task.upload_url.clone();
+        if let Some(location) = &upload_url {
+            let endpoint = self.endpoint(location, HttpMethod::Head, tus_headers(), None, None);
+            let Some(response) = self.send(endpoint, token).await? else {
+                return Ok(TransferOutcome::Interrupted);
+            };
+            match response.status {
+                200..=299 => offset = upload_offset(&response.headers)?,
+                // the upload expired or was removed on the server, it is created again
+                403 | 404 | 410 => upload_url = None,
+                status => return Err(UploadError::Status(status)),
+            }
+            last_response = Some(response);
+        }
+
+        let upload_url = match upload_url {
+            Some(upload_url) => upload_url,
+            None => {
+                let mut headers = tus_headers();
+                headers.push(("Upload-Length".to_string(), size.to_string()));
+                let endpoint = self.endpoint(&task.url, HttpMethod::Post, headers, None, None);
+                let Some(response) = self.send(endpoint, token).await? else {
+                    return Ok(TransferOutcome::Interrupted);
+                };
+                if !(200..=299).contains(&response.status) {
+                    return Err(UploadError::Status(response.status));
+                }
+                let location = header(&response.headers, "location")
+                    .ok_or_else(|| UploadError::Protocol("missing Location header".to_string()))?;
+                let upload_url = Url::parse(&task.url)
+                    .and_then(|base| base.join(location))
+                    .map_err(|e| UploadError::Protocol(e.to_string()))?
+                    .to_string();
+                self.update(&task.id, |task| task.upload_url = Some(upload_url.clone()));
+                offset = 0;
+                last_response = Some(response);
+                upload_url
+            }
+        };
+        self.progress(&task.id, offset).await;
+
+        while offset < size {
+            let len = chunk_size.min(size - offset);
+            let data = self.read_chunk(task, offset, len).await?;
+            let mut headers = tus_headers();
+            headers.push(("Upload-Offset".to_string(), offset.to_string()));
+            let endpoint = self.endpoint(
+                &upload_url,
+                HttpMethod::Patch,
+                headers,
+                Some(data),
+                Some("application/offset+octet-stream".to_string()),
+            );
+            let Some(response) = self.send(endpoint, token).await? else {
+                return Ok(TransferOutcome::Interrupted);
+            };
+            if !(200..=299).contains(&response.status) {
+                return Err(UploadError::Status(response.status));
+            }
+            let next = upload_offset(&response.headers).unwrap_or(offset + len);
+            if next <= offset {
+                return Err(UploadError::Protocol(
+                    "server did not accept the chunk".to_string(),
+                ));
+            }
+            offset = next;
+            last_response = Some(response);
+            self.progress(&task.id, offset).await;
+        }
+
+        let response = last_response
+            .ok_or_else(|| UploadError::Protocol("server did not respond".to_string()))?;
+        Ok(self.complete(&task.id, size, response))
+    }
+}
+
+impl DefaultUploadManager {
+    pub async fn new(
+        config: UploadConfig,
+        http_client: Arc<dyn HttpClient>,
+        storage_manager: Arc<dyn StorageManager>,
+    ) -> Result<Self, UploadError> {
+        let mut tasks = HashMap::new();
+        if let Some(state_path) = &config.state_path {
+            match storage_manager
+                .read(ReadFile::path(state_path.clone()))
+                .await
+            {
+                Ok(data) => {
+                    let stored: Vec<UploadTask> = serde_json::from_slice(&data)
+                        .map_err(|e| UploadError::Persistence(e.to_string()))?;
+                    for mut task in stored {
+                        if task.state == UploadState::Running {
+                            task.state = UploadState::Queued;
+                        }
+                        tasks.insert(task.id.clone(), task);
+                    }
+                }
+                Err(StorageError::NotExist(_)) => {}
+                Err(e) => return Err(UploadError::Persistence(e.to_string())),
+            }
+        }
+
+        let inner = Arc::new(UploadManagerInner {
+            state_file: SnapshotFile::new(storage_manager.clone(), config.state_path.clone()),
+            config,
+            http_client,
+            storage_manager,
+            tasks: Mutex::new(tasks),
+            active: DashMap::new(),
+            events: broadcast::channel(256).0,
+            closed: AtomicBool::new(false),
+        });
+        inner.dispatch();
+        Ok(Self { inner })
+    }
+}
+
+#[async_trait]
+impl UploadManager for DefaultUploadManager {
+    async fn enqueue(&self, request: UploadRequest) -> Result<String, UploadError> {
+        let id = Uuid::new_v4().to_string();
+        {
+            let mut tasks = self.inner.tasks.lock();
+            let sequence = tasks
+                .values()
+                .map(|task| task.sequence + 1)
+                .max()
+                .unwrap_or(0);
+            let task = UploadTask {
+                id: id.clone(),
+                url: request.url,
+                source_path: request.source_path,
+                protocol: request.protocol,
+                priority: request.priority,
+                headers: request.headers,
+                state: UploadState::Queued,
+                uploaded: 0,
+                total: None,
+                attempts: 0,
+                error: None,
+                upload_url: None,
+                response_status: None,
+                response_body: None,
+                sequence,
+            };
+            let _ = self.inner.events.send(UploadEvent::from(&task));
+            tasks.insert(id.clone(), task);
+        }
+        self.inner.persist().await;
+        self.inner.dispatch();
+        Ok(id)
+    }
+
+    async fn pause(&self, id: &str) -> Result<(), UploadError> {
+        let task = self.inner.task(id)?;
+        if !matches!(task.state, UploadState::Queued | UploadState::Running) {
+            return Err(UploadError::InvalidState {
+                id: id.to_string(),
+                action: "paused".to_string(),
+                state: task.state,
+            });
+        }
+        self.inner
+            .update(id, |task| task.state = UploadState::Paused);
+        if let Some(token) = self.inner.active.get(id) {
+            token.cancel();
+        }
+        self.inner.persist().await;
+        Ok(())
+    }
+
+    async fn resume(&self, id: &str) -> Result<(), UploadError> {
+        let task = self.inner.task(id)?;
+        if !matches!(task.state, UploadState::Paused | UploadState::Failed) {
+            return Err(UploadError::InvalidState {
+                id: id.to_string(),
+                action: "resumed".to_string(),
+                state: task.state,
+            });
+        }
+        // an interrupted transfer may still be winding down, it is queued again afterwards
+        self.inner.update(id, |task| {
+            task.state = UploadState::Queued;
+            task.attempts = 0;
+        });
+        self.inner.persist().await;
+        if !self.inner.active.contains_key(id) {
+            self.inner.dispatch();
+        }
+        Ok(())
+    }
+
+    async fn cancel(&self, id: &str) -> Result<(), UploadError> {
+        let task = self.inner.task(id)?;
+        if matches!(task.state, UploadState::Completed | UploadState::Cancelled) {
+            return Err(UploadError::InvalidState {
+                id: id.to_string(),
+                action: "cancelled".to_string(),
+                state: task.state,
+            });
+        }
+        self.inner
+            .update(id, |task| task.state = UploadState::Cancelled);
+        if let Some(token) = self.inner.active.get(id) {
+            token.cancel();
+        }
+        self.inner.persist().await;
+        Ok(())
+    }
+
+    async fn remove(&self, id: &str) -> Result<(), UploadError> {
+        let task = self.inner.task(id)?;
+        if matches!(
+            task.state,
+            UploadState::Queued | UploadState::Running | UploadState::Paused
+        ) {
+            return Err(UploadError::InvalidState {
+                id: id.to_string(),
+                action: "removed".to_string(),
+                state: task.state,
+            });
+        }
+        self.inner.tasks.lock().remove(id);
+        self.inner.persist().await;
+        Ok(())
+    }
+
+    fn get(&self, id: &str) -> Option<UploadTask> {
+        self.inner.tasks.lock().get(id).cloned()
+    }
+
+    fn list(&self) -> Vec<UploadTask> {
+        let mut tasks: Vec<UploadTask> = self.inner.tasks.lock().values().cloned().collect();
+        tasks.sort_by_key(|task| task.sequence);
+        tasks
+    }
+
+    fn events(&self) -> broadcast::Receiver<UploadEvent> {
+        self.inner.events.subscribe()
+    }
+
+    async fn shutdown(&self) -> Result<(), UploadError> {
+        self.inner.closed.store(true, Ordering::SeqCst);
+        self.inner.active.iter().for_each(|token| token.cancel());
+        self.inner.persist().await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::models::http_models::{
+        HttpClientError, HttpEndpoint, HttpMethod, HttpResponse, HttpStreamResponse,
+    };
+    use crate::domain::models::upload_models::UploadState;
+    use crate::domain::traits::http_traits::{DecryptionProvider, EncryptionProvider, HttpClient};
+    use crate::domain::traits::upload_traits::UploadManager;
+    use crate::infrastructure::storage::storage_backend::AsyncStorageManager;
+    use crate::service::config::UploadConfig;
+    use crate::superstructure::upload_manager::DefaultUploadManager;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    // a tus server that already holds the first bytes of an upload
+    struct TusServer {
+        received: Mutex<Vec<u8>>,
+        requests: Mutex<Vec<String>>,
+    }
+
+    fn header(endpoint: &HttpEndpoint, name: &str) -> Option<String> {
+        endpoint
+            .headers
+            .as_ref()?
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    }
+
+    #[async_trait]
+    impl HttpClient for TusServer {
+        fn set_encryption_provider(&mut self, _: Arc<dyn EncryptionProvider>) {}
+        fn set_decryption_provider(&mut self, _: Arc<dyn DecryptionProvider>) {}
+        fn remove_encryption_provider(&mut self) -> Option<Arc<dyn EncryptionProvider>> {
+            None
+        }
+        fn remove_decryption_provider(&mut self) -> Option<Arc<dyn DecryptionProvider>> {
+            None
+        }
+
+        async fn execute(&self, endpoint: HttpEndpoint) -> Result<HttpResponse, HttpClientError> {
+            let mut received = self.received.lock();
+            let (status, method) = match endpoint.method {
+                HttpMethod::Post => {
+                    received.clear();
+                    return Ok(HttpResponse {
+                        status: 201,
+                        headers: vec![("location".to_string(), "/files/1".to_string())],
+                        body: Vec::new(),
+                        final_url: None,
+                        redirects: Vec::new(),
+                        trailers: Vec::new(),
+                    });
+                }
+                HttpMethod::Head => (200, "HEAD"),
+                HttpMethod::Patch => {
+                    let offset: usize =
+                        header(&endpoint, "Upload-Offset").unwrap().parse().unwrap();
+                    assert_eq!(offset, received.len());
+                    received.extend_from_slice(endpoint.body.as_ref().unwrap());
+                    (204, "PATCH")
+                }
+                _ => (405, "OTHER"),
+            };
+            self.requests
+                .lock()
+                .push(format!("{} {}", method, endpoint.domain));
+            Ok(HttpResponse {
+                status,
+                headers: vec![("upload-offset".to_string(), received.len().to_string())],
+                body: Vec::new(),
+                final_url: None,
+                redirects: Vec::new(),
+                trailers: Vec::new(),
+            })
+        }
+
+        async fn execute_stream(
+            &self,
+            _: HttpEndpoint,
+        ) -> Result<HttpStreamResponse, HttpClientError> {
+            Err(HttpClientError::Configuration("not used".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_tus_upload_resumes_from_server_offset() {
+        let base = std::env::temp_dir().join(format!("strawberry_upload_{}", std::process::id()));
+        let source_path = base.with_extension("bin").to_string_lossy().to_string();
+        let state_path = base.with_extension("json").to_string_lossy().to_string();
+        let body: Vec<u8> = (0..100u8).collect();
+        let server = Arc::new(TusServer {
+            received: Mutex::new(body[..40].to_vec()),
+            requests: Mutex::new(Vec::new()),
+        });
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        runtime.block_on(async {
+            tokio::fs::write(&source_path, &body).await.unwrap();
+            // a queue left behind by a previous run
+            let stored = serde_json::json!([{
+                "id": "upload",
+                "url": "http://localhost/files",
+                "source_path": source_path,
+                "protocol": { "Tus": { "chunk_size": 25 } },
+                "priority": 0,
+                "headers": [],
+                "state": "Running",
+                "uploaded": 40,
+                "total": 100,
+                "attempts": 1,
+                "error": null,
+                "upload_url": "http://localhost/files/1",
+                "response_status": null,
+                "response_body": null,
+                "sequence": 0
+            }]);
+            tokio::fs::write(&state_path, stored.to_string())
+                .await
+                .unwrap();
+
+            let config = UploadConfig {
+                state_path: Some(state_path.clone()),
+                ..Default::default()
+            };
+            let manager = DefaultUploadManager::new(
+                config,
+                server.clone(),
+                Arc::new(AsyncStorageManager::new()),
+            )
+            .await
+            .unwrap();
+            let mut events = manager.events();
+            if manager.get("upload").unwrap().state != UploadState::Completed {
+                loop {
+                    let event = events.recv().await.unwrap();
+                    if event.state == UploadState::Completed {
+                        break;
+                    }
+                }
+            }
+
+            assert_eq!(*server.received.lock(), body);
+            assert_eq!(
+                *server.requests.lock(),
+                vec![
+                    "HEAD http://localhost/files/1".to_string(),
+                    "PATCH http://localhost/files/1".to_string(),
+                    "PATCH http://localhost/files/1".to_string(),
+                    "PATCH http://localhost/files/1".to_string(),
+                ]
+            );
+            let task = manager.get("upload").unwrap();
+            assert_eq!(task.uploaded, 100);
+            assert_eq!(task.response_status, Some(204));
+            let _ = tokio::fs::remove_file(&source_path).await;
+            let _ = tokio::fs::remove_file(&state_path).await;
+        });
+    }
+}
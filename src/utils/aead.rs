@@ -0,0 +1,24 @@
+use aes_gcm::aead::{Aead, AeadCore, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+
+const NONCE_LENGTH: usize = 12;
+
+// the random nonce is stored in front of the ciphertext
+pub fn seal(cipher: &Aes256Gcm, data: &[u8]) -> Result<Vec<u8>, String> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, data).map_err(|e| e.to_string())?;
+
+    let mut sealed = nonce.to_vec();
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+pub fn open(cipher: &Aes256Gcm, data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < NONCE_LENGTH {
+        return Err("ciphertext is too short".to_string());
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|e| e.to_string())
+}
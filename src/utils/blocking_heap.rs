@@ -27,6 +27,10 @@ impl<T: Ord> BlockingHeap<T> {
         self.inner.lock().len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().is_empty()
+    }
+
     pub fn push(&self, item: T, timeout: Duration) -> Result<(), T> {
         let mut heap = self.inner.lock();
         while heap.len() >= self.capacity {
@@ -0,0 +1,22 @@
+use futures_util::stream::BoxStream;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::broadcast::error::RecvError;
+
+pub fn broadcast_stream<T>(receiver: Receiver<T>) -> BoxStream<'static, T>
+where
+    T: Clone + Send + 'static,
+{
+    Box::pin(futures_util::stream::unfold(
+        receiver,
+        |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(item) => return Some((item, receiver)),
+                    // slow subscribers skip what they missed instead of ending the stream
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    ))
+}
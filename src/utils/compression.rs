@@ -0,0 +1,50 @@
+use crate::domain::models::file_cache_models::CompressionKind;
+use async_compression::tokio::bufread::{GzipDecoder, ZstdDecoder};
+use async_compression::tokio::write::{GzipEncoder, ZstdEncoder};
+use std::pin::Pin;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+pub fn compressed_writer<'a, W>(
+    compression: Option<&CompressionKind>,
+    writer: W,
+) -> Box<dyn AsyncWrite + Send + Unpin + 'a>
+where
+    W: AsyncWrite + Send + Unpin + 'a,
+{
+    match compression {
+        Some(CompressionKind::Zstd) => Box::new(ZstdEncoder::new(writer)),
+        Some(CompressionKind::Gzip) => Box::new(GzipEncoder::new(writer)),
+        None => Box::new(writer),
+    }
+}
+
+pub fn decompressed_reader<'a, R>(
+    compression: Option<&CompressionKind>,
+    reader: R,
+) -> Pin<Box<dyn AsyncRead + Send + 'a>>
+where
+    R: AsyncRead + Send + Unpin + 'a,
+{
+    match compression {
+        Some(CompressionKind::Zstd) => Box::pin(ZstdDecoder::new(BufReader::new(reader))),
+        Some(CompressionKind::Gzip) => Box::pin(GzipDecoder::new(BufReader::new(reader))),
+        None => Box::pin(reader),
+    }
+}
+
+pub async fn compress(compression: &CompressionKind, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut compressed = Vec::new();
+    let mut writer = compressed_writer(Some(compression), &mut compressed);
+    writer.write_all(bytes).await?;
+    writer.shutdown().await?;
+    drop(writer);
+    Ok(compressed)
+}
+
+pub async fn decompress(compression: &CompressionKind, data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    decompressed_reader(Some(compression), data)
+        .read_to_end(&mut decompressed)
+        .await?;
+    Ok(decompressed)
+}
@@ -0,0 +1,199 @@
+// five field cron expressions, minute hour day-of-month month day-of-week, evaluated in UTC.
+// fields accept *, numbers, a-b ranges, a,b lists and /n steps, sunday is 0 or 7
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    // when both day fields are restricted a day matching either of them runs
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+const MINUTE_MILLIS: u64 = 60 * 1000;
+const DAY_MINUTES: u64 = 24 * 60;
+// an expression that never matches within this many days is treated as never due
+const SEARCH_DAYS: u64 = 366 * 5;
+
+fn parse_field(field: &str, min: u64, max: u64) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u64 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step in {}", part))?;
+                if step == 0 {
+                    return Err(format!("zero step in {}", part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            let start = start
+                .parse()
+                .map_err(|_| format!("invalid value in {}", part))?;
+            let end = end
+                .parse()
+                .map_err(|_| format!("invalid value in {}", part))?;
+            (start, end)
+        } else {
+            let value = range
+                .parse()
+                .map_err(|_| format!("invalid value in {}", part))?;
+            // 5/15 starts at 5 and runs to the end of the field
+            if step > 1 {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("{} is out of range {}-{}", part, min, max));
+        }
+        let mut value = start;
+        while value <= end {
+            mask |= 1 << value;
+            value += step;
+        }
+    }
+    Ok(mask)
+}
+
+// year, month and day of a day count since 1970-01-01
+pub(crate) fn civil_date(days: u64) -> (u64, u64, u64) {
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year as u64, month as u64, day as u64)
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("expected 5 fields but got {}", fields.len()));
+        }
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            any_day_of_month: fields[2] == "*",
+            any_day_of_week: fields[4] == "*",
+        })
+    }
+
+    fn matches_day(&self, days: u64) -> bool {
+        let (_, month, day) = civil_date(days);
+        if self.months & (1 << month) == 0 {
+            return false;
+        }
+        // 1970-01-01 was a thursday
+        let weekday = (days + 4) % 7;
+        let day_of_month = self.days_of_month & (1 << day) != 0;
+        let day_of_week = self.days_of_week & (1 << weekday) != 0;
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+
+    // first matching minute strictly after the given unix time, in milliseconds
+    pub fn next_after(&self, after_millis: u64) -> Option<u64> {
+        let mut minute = after_millis / MINUTE_MILLIS + 1;
+        let last_day = minute / DAY_MINUTES + SEARCH_DAYS;
+        while minute / DAY_MINUTES <= last_day {
+            let days = minute / DAY_MINUTES;
+            if !self.matches_day(days) {
+                minute = (days + 1) * DAY_MINUTES;
+                continue;
+            }
+            let hour = minute % DAY_MINUTES / 60;
+            if self.hours & (1 << hour) == 0 {
+                minute = (minute / 60 + 1) * 60;
+                continue;
+            }
+            if self.minutes & (1 << (minute % 60)) == 0 {
+                minute += 1;
+                continue;
+            }
+            return Some(minute * MINUTE_MILLIS);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CronSchedule;
+
+    // 2024-01-01T00:00:00Z, a monday
+    const NEW_YEAR: u64 = 1704067200000;
+    const MINUTE: u64 = 60 * 1000;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+
+    #[test]
+    fn test_next_after() {
+        let every_quarter = CronSchedule::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            every_quarter.next_after(NEW_YEAR),
+            Some(NEW_YEAR + 15 * MINUTE)
+        );
+
+        let daily = CronSchedule::parse("30 3 * * *").unwrap();
+        assert_eq!(
+            daily.next_after(NEW_YEAR),
+            Some(NEW_YEAR + 3 * HOUR + 30 * MINUTE)
+        );
+        assert_eq!(
+            daily.next_after(NEW_YEAR + 4 * HOUR),
+            Some(NEW_YEAR + DAY + 3 * HOUR + 30 * MINUTE)
+        );
+
+        let sundays = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert_eq!(sundays.next_after(NEW_YEAR), Some(NEW_YEAR + 6 * DAY));
+
+        let leap_day = CronSchedule::parse("0 12 29 2 *").unwrap();
+        assert_eq!(
+            leap_day.next_after(NEW_YEAR),
+            Some(NEW_YEAR + 59 * DAY + 12 * HOUR)
+        );
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        assert!(CronSchedule::parse("* * * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("a * * * *").is_err());
+        assert_eq!(
+            CronSchedule::parse("0 0 30 2 *")
+                .unwrap()
+                .next_after(NEW_YEAR),
+            None
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// takes the dirty mark for one save and puts it back when the save is dropped before its write
+// finished, a save that fails or is cancelled leaves the changes for the next one
+pub struct DirtyGuard<'a> {
+    dirty: &'a AtomicBool,
+    was_dirty: bool,
+}
+
+impl<'a> DirtyGuard<'a> {
+    pub fn take(dirty: &'a AtomicBool) -> Self {
+        let was_dirty = dirty.swap(false, Ordering::SeqCst);
+        Self { dirty, was_dirty }
+    }
+
+    pub fn was_dirty(&self) -> bool {
+        self.was_dirty
+    }
+
+    pub fn written(mut self) {
+        self.was_dirty = false;
+    }
+}
+
+impl Drop for DirtyGuard<'_> {
+    fn drop(&mut self) {
+        if self.was_dirty {
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+    }
+}
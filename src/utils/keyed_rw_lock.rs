@@ -1,13 +1,19 @@
 use dashmap::DashMap;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicI32, Ordering};
-use tokio::sync::{RwLock};
+use tokio::sync::{OwnedRwLockReadGuard, RwLock};
 
 pub struct KeyedRwLock<T> {
     cumulative_cleanup: AtomicI32,
     locks: DashMap<String, Arc<RwLock<T>>>,
 }
 
+impl<T> Default for KeyedRwLock<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> KeyedRwLock<T> {
     pub fn new() -> Self {
         Self {
@@ -31,6 +37,22 @@ impl<T> KeyedRwLock<T> {
         operation(&guard)
     }
 
+    // for a read that outlives the call, the lock is held until the guard is dropped
+    pub async fn read_owned(&self, id: &str) -> OwnedRwLockReadGuard<T>
+    where
+        T: Default,
+    {
+        self.cumulate_cleanup();
+
+        let lock = self
+            .locks
+            .entry(id.to_string())
+            .or_insert_with(|| Arc::new(RwLock::new(T::default())))
+            .value()
+            .clone();
+        lock.read_owned().await
+    }
+
     pub async fn write<F, R>(&self, id: &str, operation: F) -> R
     where
         F: FnOnce(&mut T) -> R,
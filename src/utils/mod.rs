@@ -4,3 +4,11 @@ pub mod progress_reader;
 pub mod stream_with_callback;
 pub mod waiter;
 pub mod blocking_heap;
+pub mod public_suffix;
+pub mod broadcast_stream;
+pub mod aead;
+pub mod compression;
+pub mod cron;
+pub mod snapshot_file;
+pub mod time;
+pub mod dirty_flag;
@@ -0,0 +1,125 @@
+use lazy_static::lazy_static;
+use std::collections::HashSet;
+
+// the list of publicsuffix.org with its private section, CookieConfig.public_suffix_list_path
+// takes a newer copy
+const BUILTIN_PUBLIC_SUFFIXES: &str = include_str!("public_suffix_list.dat");
+
+lazy_static! {
+    static ref BUILTIN: PublicSuffixList = PublicSuffixList::parse(BUILTIN_PUBLIC_SUFFIXES);
+}
+
+#[derive(Clone)]
+pub struct PublicSuffixList {
+    rules: HashSet<String>,
+    wildcards: HashSet<String>,
+    exceptions: HashSet<String>,
+}
+
+impl PublicSuffixList {
+    pub fn parse(content: &str) -> Self {
+        let mut rules = HashSet::new();
+        let mut wildcards = HashSet::new();
+        let mut exceptions = HashSet::new();
+
+        for line in content.lines() {
+            let rule = line.split_whitespace().next();
+            if rule.is_none() {
+                continue;
+            }
+            let rule = rule.unwrap().to_lowercase();
+            if rule.starts_with("//") {
+                continue;
+            }
+            // cookie domains arrive in punycode, the list writes internationalized rules in
+            // unicode
+            let rule = to_ascii(&rule);
+
+            if let Some(exception) = rule.strip_prefix('!') {
+                exceptions.insert(exception.to_string());
+            } else if let Some(wildcard) = rule.strip_prefix("*.") {
+                wildcards.insert(wildcard.to_string());
+            } else {
+                rules.insert(rule);
+            }
+        }
+
+        Self {
+            rules,
+            wildcards,
+            exceptions,
+        }
+    }
+
+    pub fn builtin() -> Self {
+        BUILTIN.clone()
+    }
+
+    pub fn is_public_suffix(&self, domain: &str) -> bool {
+        let domain = domain.trim_matches('.').to_lowercase();
+        if domain.is_empty() {
+            return true;
+        }
+        if self.exceptions.contains(&domain) {
+            return false;
+        }
+        if self.rules.contains(&domain) {
+            return true;
+        }
+
+        let parent = domain.split_once('.').map(|(_, parent)| parent);
+        if parent.is_some() && self.wildcards.contains(parent.unwrap()) {
+            return true;
+        }
+        false
+    }
+}
+
+fn to_ascii(rule: &str) -> String {
+    let (prefix, domain) = match rule.strip_prefix("*.") {
+        Some(domain) => ("*.", domain),
+        None => match rule.strip_prefix('!') {
+            Some(domain) => ("!", domain),
+            None => ("", rule),
+        },
+    };
+    if domain.is_ascii() {
+        return rule.to_string();
+    }
+    match url::Host::parse(domain) {
+        Ok(url::Host::Domain(domain)) => format!("{}{}", prefix, domain),
+        _ => rule.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PublicSuffixList;
+
+    #[test]
+    fn test_builtin_public_suffixes() {
+        let list = PublicSuffixList::builtin();
+        assert!(list.is_public_suffix("com"));
+        assert!(list.is_public_suffix(".co.uk"));
+        assert!(list.is_public_suffix("COM.CN"));
+        assert!(!list.is_public_suffix("example.com"));
+        assert!(!list.is_public_suffix("bbc.co.uk"));
+        assert!(!list.is_public_suffix("localhost"));
+
+        // multi-label, private section and internationalized rules
+        assert!(list.is_public_suffix("s3.amazonaws.com"));
+        assert!(list.is_public_suffix("blogspot.com"));
+        assert!(list.is_public_suffix("xn--55qx5d.cn"));
+        assert!(list.is_public_suffix("anything.kawasaki.jp"));
+        assert!(!list.is_public_suffix("city.kawasaki.jp"));
+        assert!(!list.is_public_suffix("example.github.io"));
+    }
+
+    #[test]
+    fn test_wildcard_and_exception_rules() {
+        let list = PublicSuffixList::parse("// comment\n*.ck\n!www.ck\n");
+        assert!(list.is_public_suffix("anything.ck"));
+        assert!(!list.is_public_suffix("www.ck"));
+        assert!(!list.is_public_suffix("example.anything.ck"));
+    }
+}
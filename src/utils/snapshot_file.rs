@@ -0,0 +1,42 @@
+use crate::domain::models::storage_models::{WriteFile, WriteMode};
+use crate::domain::traits::storage_traits::StorageManager;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// a state file rewritten from a snapshot of the state on every change, the snapshots are taken
+// and written one at a time so an older one never wins
+pub struct SnapshotFile {
+    storage_manager: Arc<dyn StorageManager>,
+    // None keeps the state in memory only
+    path: Option<String>,
+    lock: Mutex<()>,
+}
+
+impl SnapshotFile {
+    pub fn new(storage_manager: Arc<dyn StorageManager>, path: Option<String>) -> Self {
+        Self {
+            storage_manager,
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub async fn write<T, F>(&self, snapshot: F) -> Result<(), String>
+    where
+        T: Serialize,
+        F: FnOnce() -> T,
+    {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let _guard = self.lock.lock().await;
+        let data = serde_json::to_vec(&snapshot()).map_err(|e| e.to_string())?;
+        let mut request = WriteFile::path(path.clone(), &data);
+        request.mode = WriteMode::Atomic;
+        self.storage_manager
+            .write(request)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
@@ -1,4 +1,4 @@
-use futures_util::stream::{Stream, StreamExt};
+use futures_util::stream::Stream;
 use pin_project::pin_project;
 use std::pin::Pin;
 use std::task::{Context, Poll};
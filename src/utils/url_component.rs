@@ -77,9 +77,9 @@ pub fn extract_domain(url_str: &str) -> Result<String, UrlParseError> {
         url_str.to_string()
     };
 
-    let parsed = Url::parse(&url_to_parse).map_err(|e| UrlParseError::ParseError)?;
+    let parsed = Url::parse(&url_to_parse).map_err(|_e| UrlParseError::ParseError)?;
 
-    let host = parsed.host_str().ok_or_else(|| UrlParseError::NoHost)?;
+    let host = parsed.host_str().ok_or(UrlParseError::NoHost)?;
 
     if let Ok(ip_addr) = host.parse::<IpAddr>() {
         return Ok(match ip_addr {
@@ -97,8 +97,8 @@ pub fn extract_domain(url_str: &str) -> Result<String, UrlParseError> {
 fn normalize_domain(host: &str) -> String {
     let host_lower = host.to_lowercase();
 
-    if host_lower.starts_with("www.") {
-        host_lower[4..].to_string()
+    if let Some(stripped) = host_lower.strip_prefix("www.") {
+        stripped.to_string()
     } else {
         host_lower
     }
@@ -19,6 +19,12 @@ pub struct OptionWaiter<T> {
     condvar: Condvar,
 }
 
+impl<T> Default for OptionWaiter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> OptionWaiter<T> {
     pub fn new() -> Self {
         Self {
@@ -3,18 +3,14 @@ use quote::{format_ident, quote};
 use syn::{GenericArgument, ItemStruct, PathArguments, Type, TypePath, parse_macro_input};
 
 fn strip_single_wrapper<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
-    if let Type::Path(TypePath { qself: _none, path }) = ty {
-        if let Some(last_segment) = path.segments.last() {
-            if last_segment.ident == wrapper {
-                if let PathArguments::AngleBracketed(ref args) = last_segment.arguments {
-                    if args.args.len() == 1 {
-                        if let GenericArgument::Type(inner_ty) = &args.args[0] {
-                            return Some(inner_ty);
-                        }
-                    }
-                }
-            }
-        }
+    if let Type::Path(TypePath { qself: _none, path }) = ty
+        && let Some(last_segment) = path.segments.last()
+        && last_segment.ident == wrapper
+        && let PathArguments::AngleBracketed(ref args) = last_segment.arguments
+        && args.args.len() == 1
+        && let GenericArgument::Type(inner_ty) = &args.args[0]
+    {
+        return Some(inner_ty);
     }
     None
 }